@@ -1,39 +1,34 @@
 extern crate bindgen;
-use std::path::Path;
+use std::env;
+use std::path::{Path, PathBuf};
 
 fn main() {
-    pkg_config::Config::new().atleast_version("1.2");
+    println!("cargo:rerun-if-env-changed=TSKIT_LIB_DIR");
+    println!("cargo:rerun-if-env-changed=TSKIT_INCLUDE_DIR");
 
-    let src = [
-        "subprojects/tskit/tskit/convert.c",
-        "subprojects/tskit/tskit/core.c",
-        "subprojects/tskit/tskit/genotypes.c",
-        "subprojects/tskit/tskit/haplotype_matching.c",
-        "subprojects/tskit/tskit/stats.c",
-        "subprojects/tskit/tskit/tables.c",
-        "subprojects/tskit/tskit/trees.c",
-        "subprojects/kastore/kastore.c",
-    ];
-
-    let tskit_path = Path::new("subprojects/tskit/");
-    let kastore_path = Path::new("subprojects/kastore/");
-    let mut builder = cc::Build::new();
-    let build = builder
-        .files(src.iter())
-        .include(tskit_path)
-        .include(kastore_path)
-        .flag("-Wno-unused-parameter");
-    build.compile("tskit");
+    // The "system-tskit" feature links to an installed libtskit
+    // instead of compiling the copy in subprojects/.
+    // Documentation builds on docs.rs have no libtskit to link to,
+    // so they always use the vendored copy.
+    let include_paths = if env::var_os("CARGO_FEATURE_SYSTEM_TSKIT").is_some()
+        && env::var_os("DOCS_RS").is_none()
+    {
+        link_system_tskit()
+    } else {
+        build_vendored_tskit()
+    };
 
     // The bindgen::Builder is the main entry point
     // to bindgen, and lets you build up options for
     // the resulting bindings.
-    let bindings = bindgen::Builder::default()
+    let mut builder = bindgen::Builder::default()
         // The input header we would like to generate
         // bindings for.
-        .header("wrapper.h")
-        .clang_arg("-Isubprojects/tskit")
-        .clang_arg("-Isubprojects/kastore")
+        .header("wrapper.h");
+    for path in &include_paths {
+        builder = builder.clang_arg(format!("-I{}", path.display()));
+    }
+    let bindings = builder
         .allowlist_type("tsk.*")
         .allowlist_function("tsk.*")
         .allowlist_type("TSK_.*")
@@ -51,8 +46,70 @@ fn main() {
         .expect("Unable to generate bindings");
 
     // Write the bindings to the $OUT_DIR/bindings.rs file.
-    let out_path = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
     bindings
         .write_to_file(out_path.join("auto_bindings.rs"))
         .expect("Couldn't write bindings!");
 }
+
+/// Compile the C code in subprojects/ into the crate
+/// and return the paths to its headers.
+fn build_vendored_tskit() -> Vec<PathBuf> {
+    let src = [
+        "subprojects/tskit/tskit/convert.c",
+        "subprojects/tskit/tskit/core.c",
+        "subprojects/tskit/tskit/genotypes.c",
+        "subprojects/tskit/tskit/haplotype_matching.c",
+        "subprojects/tskit/tskit/stats.c",
+        "subprojects/tskit/tskit/tables.c",
+        "subprojects/tskit/tskit/trees.c",
+        "subprojects/kastore/kastore.c",
+    ];
+
+    let tskit_path = Path::new("subprojects/tskit/");
+    let kastore_path = Path::new("subprojects/kastore/");
+    let mut builder = cc::Build::new();
+    let build = builder
+        .files(src.iter())
+        .include(tskit_path)
+        .include(kastore_path)
+        .flag("-Wno-unused-parameter");
+    build.compile("tskit");
+
+    vec![tskit_path.to_path_buf(), kastore_path.to_path_buf()]
+}
+
+/// Link to an installed libtskit and return the paths to its headers.
+///
+/// If `TSKIT_LIB_DIR` is set, the library is found there and the
+/// headers in `TSKIT_INCLUDE_DIR`.
+/// A static libtskit does not contain kastore, which must then be
+/// installed alongside it.
+/// Otherwise, pkg-config is used to find the library.
+fn link_system_tskit() -> Vec<PathBuf> {
+    match env::var_os("TSKIT_LIB_DIR") {
+        Some(lib_dir) => {
+            let lib_dir = Path::new(&lib_dir);
+            println!("cargo:rustc-link-search=native={}", lib_dir.display());
+            let shared = ["libtskit.so", "libtskit.dylib", "tskit.dll"]
+                .iter()
+                .any(|name| lib_dir.join(name).exists());
+            if !shared && lib_dir.join("libtskit.a").exists() {
+                println!("cargo:rustc-link-lib=static=tskit");
+                println!("cargo:rustc-link-lib=kastore");
+            } else {
+                println!("cargo:rustc-link-lib=tskit");
+            }
+            env::var_os("TSKIT_INCLUDE_DIR")
+                .map(|dir| vec![PathBuf::from(dir)])
+                .unwrap_or_default()
+        }
+        None => {
+            pkg_config::Config::new()
+                .atleast_version("1.1")
+                .probe("tskit")
+                .expect("the system-tskit feature requires libtskit >= 1.1; set TSKIT_LIB_DIR or make it visible to pkg-config")
+                .include_paths
+        }
+    }
+}
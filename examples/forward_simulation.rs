@@ -23,6 +23,7 @@ use rand::rngs::StdRng;
 use rand::Rng;
 use rand::SeedableRng;
 use rand_distr::{Exp, Uniform};
+use tskit::prelude::*;
 
 struct SimParams {
     pub popsize: u32,
@@ -168,8 +169,8 @@ impl SimParams {
 
 #[derive(Copy, Clone)]
 struct Diploid {
-    node0: tskit::NodeId,
-    node1: tskit::NodeId,
+    node0: NodeId,
+    node1: NodeId,
 }
 
 struct Parents {
@@ -203,7 +204,7 @@ fn death_and_parents(
     }
 }
 
-fn mendel(pnodes: &mut (tskit::NodeId, tskit::NodeId), rng: &mut StdRng) {
+fn mendel(pnodes: &mut (NodeId, NodeId), rng: &mut StdRng) {
     let x: f64 = rng.gen();
     match x.partial_cmp(&0.5) {
         Some(std::cmp::Ordering::Less) => {
@@ -216,9 +217,9 @@ fn mendel(pnodes: &mut (tskit::NodeId, tskit::NodeId), rng: &mut StdRng) {
 
 fn crossover_and_record_edges_details(
     parent: Diploid,
-    offspring_node: tskit::NodeId,
+    offspring_node: NodeId,
     params: &SimParams,
-    tables: &mut tskit::TableCollection,
+    tables: &mut TableCollection,
     rng: &mut StdRng,
 ) {
     let mut pnodes = (parent.node0, parent.node1);
@@ -271,9 +272,9 @@ fn crossover_and_record_edges_details(
 
 fn crossover_and_record_edges(
     parents: &Parents,
-    offspring_nodes: (tskit::NodeId, tskit::NodeId),
+    offspring_nodes: (NodeId, NodeId),
     params: &SimParams,
-    tables: &mut tskit::TableCollection,
+    tables: &mut TableCollection,
     rng: &mut StdRng,
 ) {
     crossover_and_record_edges_details(parents.parent0, offspring_nodes.0, params, tables, rng);
@@ -284,31 +285,27 @@ fn births(
     parents: &[Parents],
     params: &SimParams,
     birth_time: u32,
-    tables: &mut tskit::TableCollection,
+    tables: &mut TableCollection,
     alive: &mut [Diploid],
     rng: &mut StdRng,
 ) {
     for p in parents {
         // Register the two nodes for our offspring
         let node0 = match tables.add_node(
-            0,                         // flags
-            birth_time as f64,         // time
-            tskit::PopulationId::NULL, // population
+            0,                  // flags
+            birth_time as f64,  // time
+            PopulationId::NULL, // population
             // individual
-            tskit::IndividualId::NULL,
-        ) {
-            Ok(x) => x,
-            Err(e) => panic!("{}", e),
-        };
-        let node1 = match tables.add_node(
-            0,
-            birth_time as f64,
-            tskit::PopulationId::NULL,
-            tskit::IndividualId::NULL,
+            IndividualId::NULL,
         ) {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
         };
+        let node1 =
+            match tables.add_node(0, birth_time as f64, PopulationId::NULL, IndividualId::NULL) {
+                Ok(x) => x,
+                Err(e) => panic!("{}", e),
+            };
 
         // Replace a dead individual
         // with our newborn.
@@ -318,7 +315,7 @@ fn births(
     }
 }
 
-fn simplify(alive: &mut [Diploid], tables: &mut tskit::TableCollection) {
+fn simplify(alive: &mut [Diploid], tables: &mut TableCollection) {
     let mut samples = vec![];
     for a in alive.iter() {
         assert!(a.node0 != a.node1);
@@ -326,23 +323,19 @@ fn simplify(alive: &mut [Diploid], tables: &mut tskit::TableCollection) {
         samples.push(a.node1);
     }
 
-    match tables.full_sort(tskit::TableSortOptions::default()) {
+    match tables.full_sort(TableSortOptions::default()) {
         Ok(_) => (),
         Err(e) => panic!("{}", e),
     }
 
-    match tables.simplify(
-        &samples,
-        tskit::SimplificationOptions::KEEP_INPUT_ROOTS,
-        true,
-    ) {
+    match tables.simplify(&samples, SimplificationOptions::KEEP_INPUT_ROOTS, true) {
         Ok(x) => match x {
             Some(idmap) => {
                 for a in alive.iter_mut() {
                     a.node0 = idmap[usize::from(a.node0)];
-                    assert!(a.node0 != tskit::NodeId::NULL);
+                    assert!(a.node0 != NodeId::NULL);
                     a.node1 = idmap[usize::from(a.node1)];
-                    assert!(a.node1 != tskit::NodeId::NULL);
+                    assert!(a.node1 != NodeId::NULL);
                 }
             }
             None => panic!("Unexpected None"),
@@ -351,8 +344,8 @@ fn simplify(alive: &mut [Diploid], tables: &mut tskit::TableCollection) {
     };
 }
 
-fn runsim(params: &SimParams) -> tskit::TableCollection {
-    let mut tables = match tskit::TableCollection::new(params.genome_length) {
+fn runsim(params: &SimParams) -> TableCollection {
+    let mut tables = match TableCollection::new(params.genome_length) {
         Ok(x) => x,
         Err(e) => panic!("{}", e),
     };
@@ -364,8 +357,8 @@ fn runsim(params: &SimParams) -> tskit::TableCollection {
         let node0 = match tables.add_node(
             0,
             params.nsteps as f64,
-            tskit::PopulationId::NULL,
-            tskit::IndividualId::NULL,
+            PopulationId::NULL,
+            IndividualId::NULL,
         ) {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
@@ -373,8 +366,8 @@ fn runsim(params: &SimParams) -> tskit::TableCollection {
         let node1 = match tables.add_node(
             0,
             params.nsteps as f64,
-            tskit::PopulationId::NULL,
-            tskit::IndividualId::NULL,
+            PopulationId::NULL,
+            IndividualId::NULL,
         ) {
             Ok(x) => x,
             Err(e) => panic!("{}", e),
@@ -412,10 +405,10 @@ fn main() {
 
     let tables = runsim(&params);
     let treeseq = tables
-        .tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES)
+        .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
         .unwrap();
     treeseq
-        .dump(&params.treefile, tskit::TableOutputOptions::default())
+        .dump(&params.treefile, TableOutputOptions::default())
         .unwrap();
 }
 
@@ -2,17 +2,17 @@ use clap::{Arg, Command};
 use tskit::prelude::*;
 
 // "Manual" traversal from samples to root
-fn traverse_upwards(tree: &tskit::Tree) {
+fn traverse_upwards(tree: &Tree) {
     for &s in tree.sample_nodes() {
         let mut u = s;
-        while u != tskit::NodeId::NULL {
+        while u != NodeId::NULL {
             u = tree.parent(u).unwrap();
         }
     }
 }
 
 // Iterate from each node up to its root.
-fn traverse_upwards_with_iterator(tree: &tskit::Tree) {
+fn traverse_upwards_with_iterator(tree: &Tree) {
     for &s in tree.sample_nodes() {
         // _steps_to_root counts the number of steps,
         // including the starting node s.
@@ -20,8 +20,8 @@ fn traverse_upwards_with_iterator(tree: &tskit::Tree) {
     }
 }
 
-fn preorder_traversal(tree: &tskit::Tree) {
-    for _ in tree.traverse_nodes(tskit::NodeTraversalOrder::Preorder) {}
+fn preorder_traversal(tree: &Tree) {
+    for _ in tree.traverse_nodes(NodeTraversalOrder::Preorder) {}
 }
 
 fn main() {
@@ -37,9 +37,9 @@ fn main() {
 
     let treefile: String = matches.value_of_t_or_exit("treefile");
 
-    let treeseq = tskit::TreeSequence::load(&treefile).unwrap();
+    let treeseq = TreeSequence::load(&treefile).unwrap();
 
-    let mut tree_iterator = treeseq.tree_iterator(tskit::TreeFlags::default()).unwrap();
+    let mut tree_iterator = treeseq.tree_iterator(TreeFlags::default()).unwrap();
 
     while let Some(tree) = tree_iterator.next() {
         traverse_upwards(tree);
@@ -16,6 +16,28 @@ macro_rules! handle_tsk_return_value {
     }};
 }
 
+/// Borrow column `$column` of `$table`, a reference to a C table
+/// (or table collection) that is valid for the duration of the borrow.
+///
+/// The length defaults to the number of rows.
+/// `@bytes` views a `char` column as bytes.
+macro_rules! table_column {
+    ($table: expr, $column: ident) => {
+        table_column!($table, $column, $table.num_rows)
+    };
+    ($table: expr, $column: ident, $length: expr) => {{
+        let table = $table;
+        // Safety: a valid table holds `$length` values in the column.
+        unsafe { $crate::util::column_slice(table, table.$column, $length) }
+    }};
+    (@bytes $table: expr, $column: ident, $length: expr) => {{
+        let table = $table;
+        let data: *const libc::c_char = table.$column;
+        // Safety: as above, and u8 has the layout of c_char.
+        unsafe { $crate::util::column_slice(table, data.cast::<u8>(), $length) }
+    }};
+}
+
 macro_rules! panic_on_tskit_error {
     ($code: expr) => {
         if $code < 0 {
@@ -46,7 +68,7 @@ macro_rules! unsafe_tsk_column_access {
 
 macro_rules! unsafe_tsk_ragged_column_access {
     ($i: expr, $lo: expr, $hi: expr, $array: expr, $offset_array: expr, $offset_array_len: expr) => {{
-        let i = $crate::SizeType::try_from($i)?;
+        let i = $crate::SizeType::try_from($i).map_err(|_| TskitError::IndexError)?;
         if $i < $lo || i >= $hi {
             Err(TskitError::IndexError {})
         } else if $offset_array_len == 0 {
@@ -71,7 +93,7 @@ macro_rules! unsafe_tsk_ragged_column_access {
     }};
 
     ($i: expr, $lo: expr, $hi: expr, $array: expr, $offset_array: expr, $offset_array_len: expr, $output_id_type: ty) => {{
-        let i = $crate::SizeType::try_from($i)?;
+        let i = $crate::SizeType::try_from($i).map_err(|_| TskitError::IndexError)?;
         if $i < $lo || i >= $hi {
             Err(TskitError::IndexError {})
         } else if $offset_array_len == 0 {
@@ -102,7 +124,7 @@ macro_rules! unsafe_tsk_ragged_column_access {
 #[allow(unused_macros)]
 macro_rules! unsafe_tsk_ragged_char_column_access {
     ($i: expr, $lo: expr, $hi: expr, $array: expr, $offset_array: expr, $offset_array_len: expr) => {{
-        let i = $crate::SizeType::try_from($i)?;
+        let i = $crate::SizeType::try_from($i).map_err(|_| TskitError::IndexError)?;
         if $i < $lo || i >= $hi {
             Err(TskitError::IndexError {})
         } else if $offset_array_len == 0 {
@@ -128,27 +150,42 @@ macro_rules! unsafe_tsk_ragged_char_column_access {
 }
 
 macro_rules! metadata_to_vector {
-    ($outer: ident, $table: expr, $row: expr) => {
+    ($outer: ident, $table: expr, $row: expr) => {{
+        let row = $row;
         $crate::metadata::char_column_to_slice(
             $outer,
             $table.metadata,
             $table.metadata_offset,
-            $row,
+            row,
             $table.num_rows,
             $table.metadata_length,
         )
-    };
+        .map_err(|e| match e {
+            $crate::TskitError::IndexError => $crate::TskitError::MetadataRowOutOfRange {
+                row,
+                num_rows: $table.num_rows.into(),
+            },
+            e => e,
+        })
+    }};
 }
 
 macro_rules! decode_metadata_row {
-    ($T: ty, $buffer: expr) => {
+    (@decode $T: ty, $row: expr, $buffer: expr) => {
         match $buffer {
             None => Ok(None),
-            Some(v) => Ok(Some(<$T as $crate::metadata::MetadataRoundtrip>::decode(
-                &v,
-            )?)),
+            Some(v) => match <$T as $crate::metadata::MetadataRoundtrip>::decode(&v) {
+                Ok(md) => Ok(Some(md)),
+                Err(value) => Err($crate::TskitError::MetadataDecodeError { row: $row, value }),
+            },
         }
     };
+    ($T: ty, $buffer: expr) => {
+        decode_metadata_row!(@decode $T, None, $buffer)
+    };
+    ($T: ty, $row: expr, $buffer: expr) => {
+        decode_metadata_row!(@decode $T, Some($row), $buffer)
+    };
 }
 
 macro_rules! table_row_decode_metadata {
@@ -187,14 +224,7 @@ macro_rules! err_if_not_tracking_samples {
 // as it applies $row is out of range.
 macro_rules! table_row_access {
     ($row: expr, $table: expr, $row_fn: ident) => {
-        if $row < 0 {
-            Err(TskitError::IndexError)
-        } else {
-            match $row_fn($table, $row) {
-                Some(x) => Ok(x),
-                None => Err(TskitError::IndexError),
-            }
-        }
+        $row_fn($table, $row).ok_or(TskitError::IndexError)
     };
 }
 
@@ -231,6 +261,30 @@ macro_rules! impl_id_traits {
             pub fn is_null(&self) -> bool {
                 *self == Self::NULL
             }
+
+            /// Return `None` if `self` is `NULL` and `Some(self)` otherwise.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// let n = tskit::NodeId::from(3);
+            /// assert_eq!(n.to_option(), Some(n));
+            /// assert!(tskit::NodeId::NULL.to_option().is_none());
+            /// ```
+            pub fn to_option(self) -> Option<Self> {
+                if self.is_null() {
+                    None
+                } else {
+                    Some(self)
+                }
+            }
+        }
+
+        /// `None` is converted to `NULL`.
+        impl From<Option<$idtype>> for $idtype {
+            fn from(value: Option<$idtype>) -> Self {
+                value.unwrap_or(Self::NULL)
+            }
         }
 
         impl std::fmt::Display for $idtype {
@@ -422,6 +476,89 @@ macro_rules! impl_f64_newtypes {
                 self.0.div_assign(&rhs.0)
             }
         }
+
+        impl $type {
+            /// The value `0.0`.
+            pub const ZERO: Self = Self(0.0);
+        }
+
+        impl std::ops::Add<f64> for $type {
+            type Output = Self;
+
+            fn add(self, rhs: f64) -> Self::Output {
+                Self(self.0 + rhs)
+            }
+        }
+
+        impl std::ops::AddAssign<f64> for $type {
+            fn add_assign(&mut self, rhs: f64) {
+                self.0 += rhs
+            }
+        }
+
+        impl std::ops::Sub<f64> for $type {
+            type Output = Self;
+
+            fn sub(self, rhs: f64) -> Self::Output {
+                Self(self.0 - rhs)
+            }
+        }
+
+        impl std::ops::SubAssign<f64> for $type {
+            fn sub_assign(&mut self, rhs: f64) {
+                self.0 -= rhs
+            }
+        }
+
+        impl std::ops::Mul<f64> for $type {
+            type Output = Self;
+
+            fn mul(self, rhs: f64) -> Self::Output {
+                Self(self.0 * rhs)
+            }
+        }
+
+        impl std::ops::Mul<$type> for f64 {
+            type Output = $type;
+
+            fn mul(self, rhs: $type) -> Self::Output {
+                rhs * self
+            }
+        }
+
+        impl std::ops::MulAssign<f64> for $type {
+            fn mul_assign(&mut self, rhs: f64) {
+                self.0 *= rhs
+            }
+        }
+
+        impl std::ops::Div<f64> for $type {
+            type Output = Self;
+
+            fn div(self, rhs: f64) -> Self::Output {
+                Self(self.0 / rhs)
+            }
+        }
+
+        impl std::ops::DivAssign<f64> for $type {
+            fn div_assign(&mut self, rhs: f64) {
+                self.0 /= rhs
+            }
+        }
+
+        impl std::ops::Neg for $type {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self(-self.0)
+            }
+        }
+
+        impl std::iter::Sum for $type {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::ZERO, |a, b| a + b)
+            }
+        }
     };
 }
 
@@ -457,6 +594,184 @@ macro_rules! impl_time_position_arithmetic {
     };
 }
 
+/// Inherent versions of the [`TableAccess`](crate::TableAccess) functions,
+/// so that they can be called without importing the trait.
+macro_rules! impl_table_access_inherent {
+    ($type: ty) => {
+        impl $type {
+            /// Get reference to the [``EdgeTable``](crate::EdgeTable).
+            pub fn edges(&self) -> $crate::EdgeTable<'_> {
+                <Self as $crate::TableAccess>::edges(self)
+            }
+
+            /// Return an iterator over the edges.
+            pub fn edges_iter(
+                &self,
+            ) -> Box<dyn Iterator<Item = $crate::edge_table::EdgeTableRow> + '_> {
+                <Self as $crate::TableAccess>::edges_iter(self)
+            }
+
+            /// Get reference to the [``NodeTable``](crate::NodeTable).
+            pub fn nodes(&self) -> $crate::NodeTable<'_> {
+                <Self as $crate::TableAccess>::nodes(self)
+            }
+
+            /// Return an iterator over the nodes.
+            pub fn nodes_iter(
+                &self,
+            ) -> Box<dyn Iterator<Item = $crate::node_table::NodeTableRow> + '_> {
+                <Self as $crate::TableAccess>::nodes_iter(self)
+            }
+
+            /// Get reference to the [``MutationTable``](crate::MutationTable).
+            pub fn mutations(&self) -> $crate::MutationTable<'_> {
+                <Self as $crate::TableAccess>::mutations(self)
+            }
+
+            /// Return an iterator over the mutations.
+            pub fn mutations_iter(
+                &self,
+            ) -> Box<dyn Iterator<Item = $crate::mutation_table::MutationTableRow> + '_> {
+                <Self as $crate::TableAccess>::mutations_iter(self)
+            }
+
+            /// Get reference to the [``SiteTable``](crate::SiteTable).
+            pub fn sites(&self) -> $crate::SiteTable<'_> {
+                <Self as $crate::TableAccess>::sites(self)
+            }
+
+            /// Return an iterator over the sites.
+            pub fn sites_iter(
+                &self,
+            ) -> Box<dyn Iterator<Item = $crate::site_table::SiteTableRow> + '_> {
+                <Self as $crate::TableAccess>::sites_iter(self)
+            }
+
+            /// Get reference to the [``PopulationTable``](crate::PopulationTable).
+            pub fn populations(&self) -> $crate::PopulationTable<'_> {
+                <Self as $crate::TableAccess>::populations(self)
+            }
+
+            /// Return an iterator over the populations.
+            pub fn populations_iter(
+                &self,
+            ) -> Box<dyn Iterator<Item = $crate::population_table::PopulationTableRow> + '_> {
+                <Self as $crate::TableAccess>::populations_iter(self)
+            }
+
+            /// Get reference to the [``MigrationTable``](crate::MigrationTable).
+            pub fn migrations(&self) -> $crate::MigrationTable<'_> {
+                <Self as $crate::TableAccess>::migrations(self)
+            }
+
+            /// Return an iterator over the migrations.
+            pub fn migrations_iter(
+                &self,
+            ) -> Box<dyn Iterator<Item = $crate::migration_table::MigrationTableRow> + '_> {
+                <Self as $crate::TableAccess>::migrations_iter(self)
+            }
+
+            /// Get reference to the [``IndividualTable``](crate::IndividualTable).
+            pub fn individuals(&self) -> $crate::IndividualTable<'_> {
+                <Self as $crate::TableAccess>::individuals(self)
+            }
+
+            /// Return an iterator over the individuals.
+            pub fn individuals_iter(
+                &self,
+            ) -> Box<dyn Iterator<Item = $crate::individual_table::IndividualTableRow> + '_> {
+                <Self as $crate::TableAccess>::individuals_iter(self)
+            }
+
+            #[cfg(any(feature = "provenance", doc))]
+            /// Get reference to the [``ProvenanceTable``](crate::provenance::ProvenanceTable)
+            pub fn provenances(&self) -> $crate::provenance::ProvenanceTable<'_> {
+                <Self as $crate::TableAccess>::provenances(self)
+            }
+
+            #[cfg(any(feature = "provenance", doc))]
+            /// Return an iterator over provenances
+            pub fn provenances_iter(
+                &self,
+            ) -> Box<dyn Iterator<Item = $crate::provenance::ProvenanceTableRow> + '_> {
+                <Self as $crate::TableAccess>::provenances_iter(self)
+            }
+        }
+    };
+}
+
+macro_rules! impl_display_for_flag_types {
+    ($flagstype: ty) => {
+        /// Lists the names of the set flags, separated by ` | `.
+        /// Set bits without a name are written in hexadecimal.
+        impl std::fmt::Display for $flagstype {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                // bitflags writes the flag names in the Debug output
+                write!(f, "{:?}", self)
+            }
+        }
+    };
+    (@user $flagstype: ty) => {
+        /// Lists the names of the set flags reserved for tskit,
+        /// separated by ` | `.
+        /// User bits are written together in hexadecimal.
+        impl std::fmt::Display for $flagstype {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                let reserved = Self::from_bits_truncate(self.reserved_bits());
+                match self.user_bits() {
+                    0 => write!(f, "{:?}", reserved),
+                    user if reserved.is_empty() => write!(f, "{:#x}", user),
+                    user => write!(f, "{:?} | {:#x}", reserved, user),
+                }
+            }
+        }
+    };
+}
+
+macro_rules! impl_from_str_for_flag_types {
+    ($flagstype: ident, [$($name: ident),*]) => {
+        /// Parses flag names separated by `|`, which is the format
+        #[doc = concat!("written by the [`Display`](std::fmt::Display) implementation of [`", stringify!($flagstype), "`].")]
+        ///
+        /// Whitespace around names is ignored.
+        /// `NONE` adds no flags and hexadecimal values such as
+        /// `0x100000` give bits directly.
+        ///
+        /// # Errors
+        ///
+        /// [`TskitError::ValueError`](crate::TskitError::ValueError) if a name
+        /// is unknown or a hexadecimal value has bits that the type does not allow.
+        impl std::str::FromStr for $flagstype {
+            type Err = $crate::TskitError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let mut flags = Self::empty();
+                for token in s.split('|').map(str::trim) {
+                    flags |= match token {
+                        "NONE" => Self::empty(),
+                        $(stringify!($name) => Self::$name,)*
+                        _ => match token
+                            .strip_prefix("0x")
+                            .and_then(|hex| $crate::RawFlags::from_str_radix(hex, 16).ok())
+                            .and_then(Self::from_bits)
+                        {
+                            Some(flags) => flags,
+                            None => {
+                                return Err($crate::TskitError::ValueError {
+                                    got: token.to_string(),
+                                    expected: concat!("a flag of ", stringify!($flagstype))
+                                        .to_string(),
+                                })
+                            }
+                        },
+                    };
+                }
+                Ok(flags)
+            }
+        }
+    };
+}
+
 macro_rules! impl_from_for_flag_types {
     ($flagstype: ty) => {
         impl From<$crate::RawFlags> for $flagstype {
@@ -465,6 +780,13 @@ macro_rules! impl_from_for_flag_types {
             }
         }
     };
+    (@user $flagstype: ty) => {
+        impl From<$crate::RawFlags> for $flagstype {
+            fn from(value: $crate::RawFlags) -> Self {
+                <$flagstype>::from_bits_retaining_user(value)
+            }
+        }
+    };
 }
 
 macro_rules! impl_flags {
@@ -480,6 +802,139 @@ macro_rules! impl_flags {
     };
 }
 
+/// Like `bitflags!`, but also defines a flag for each of
+/// the bits 16 to 31 that the tskit C API leaves to users.
+macro_rules! bitflags_with_user_bits {
+    (
+        $(#[$outer: meta])*
+        pub struct $flagstype: ident : $t: ty {
+            $($(#[$inner: ident $($args: tt)*])* const $name: ident = $value: expr;)*
+        }
+    ) => {
+        bitflags::bitflags! {
+            $(#[$outer])*
+            pub struct $flagstype : $t {
+                $($(#[$inner $($args)*])* const $name = $value;)*
+                /// Bit 16, free for user-defined values.
+                const USER_BIT_16 = 1 << 16;
+                /// Bit 17, free for user-defined values.
+                const USER_BIT_17 = 1 << 17;
+                /// Bit 18, free for user-defined values.
+                const USER_BIT_18 = 1 << 18;
+                /// Bit 19, free for user-defined values.
+                const USER_BIT_19 = 1 << 19;
+                /// Bit 20, free for user-defined values.
+                const USER_BIT_20 = 1 << 20;
+                /// Bit 21, free for user-defined values.
+                const USER_BIT_21 = 1 << 21;
+                /// Bit 22, free for user-defined values.
+                const USER_BIT_22 = 1 << 22;
+                /// Bit 23, free for user-defined values.
+                const USER_BIT_23 = 1 << 23;
+                /// Bit 24, free for user-defined values.
+                const USER_BIT_24 = 1 << 24;
+                /// Bit 25, free for user-defined values.
+                const USER_BIT_25 = 1 << 25;
+                /// Bit 26, free for user-defined values.
+                const USER_BIT_26 = 1 << 26;
+                /// Bit 27, free for user-defined values.
+                const USER_BIT_27 = 1 << 27;
+                /// Bit 28, free for user-defined values.
+                const USER_BIT_28 = 1 << 28;
+                /// Bit 29, free for user-defined values.
+                const USER_BIT_29 = 1 << 29;
+                /// Bit 30, free for user-defined values.
+                const USER_BIT_30 = 1 << 30;
+                /// Bit 31, free for user-defined values.
+                const USER_BIT_31 = 1 << 31;
+            }
+        }
+    };
+}
+
+macro_rules! impl_user_bits_for_flag_types {
+    ($flagstype: ident) => {
+        impl $flagstype {
+            /// The bits that client code may use for its own flags.
+            ///
+            /// The tskit C API reserves the lower 16 bits for the library,
+            /// leaving bits 16 to 31 for users.
+            pub const USER_BITS: $crate::RawFlags = !0xffff;
+
+            /// Create flags from `bits`, keeping the bits in
+            /// [`USER_BITS`](Self::USER_BITS) and dropping bits in the
+            /// reserved range that tskit does not define.
+            ///
+            /// This is the conversion used by `From<RawFlags>`.
+            pub const fn from_bits_retaining_user(bits: $crate::RawFlags) -> Self {
+                Self::from_bits_truncate(bits)
+            }
+
+            /// Returns `true` if no bits are set in the reserved range
+            /// other than those of flags defined by tskit.
+            ///
+            /// Bits in [`USER_BITS`](Self::USER_BITS) are always valid.
+            /// Only [`from_bits_unchecked`](Self::from_bits_unchecked)
+            /// or the column slices of a table can create invalid flags.
+            pub fn is_valid(&self) -> bool {
+                Self::from_bits(self.bits()).is_some()
+            }
+
+            /// The bits of `self` in [`USER_BITS`](Self::USER_BITS).
+            pub fn user_bits(&self) -> $crate::RawFlags {
+                self.bits() & Self::USER_BITS
+            }
+
+            /// The bits of `self` in the range reserved for tskit.
+            pub fn reserved_bits(&self) -> $crate::RawFlags {
+                self.bits() & !Self::USER_BITS
+            }
+
+            /// Returns `true` if user bit `bit` is set.
+            ///
+            /// # Errors
+            ///
+            /// [`TskitError::RangeError`](crate::TskitError::RangeError)
+            /// if `bit` is not in `16..32`.
+            pub fn user_bit(&self, bit: u32) -> Result<bool, $crate::TskitError> {
+                Ok(self.bits() & Self::user_bit_mask(bit)? != 0)
+            }
+
+            /// Set user bit `bit`.
+            ///
+            /// # Errors
+            ///
+            /// [`TskitError::RangeError`](crate::TskitError::RangeError)
+            /// if `bit` is not in `16..32`.
+            pub fn set_user_bit(&mut self, bit: u32) -> Result<(), $crate::TskitError> {
+                *self = Self::from(self.bits() | Self::user_bit_mask(bit)?);
+                Ok(())
+            }
+
+            /// Unset user bit `bit`.
+            ///
+            /// # Errors
+            ///
+            /// [`TskitError::RangeError`](crate::TskitError::RangeError)
+            /// if `bit` is not in `16..32`.
+            pub fn clear_user_bit(&mut self, bit: u32) -> Result<(), $crate::TskitError> {
+                *self = Self::from(self.bits() & !Self::user_bit_mask(bit)?);
+                Ok(())
+            }
+
+            fn user_bit_mask(bit: u32) -> Result<$crate::RawFlags, $crate::TskitError> {
+                match (1 as $crate::RawFlags).checked_shl(bit) {
+                    Some(mask) if mask & Self::USER_BITS != 0 => Ok(mask),
+                    _ => Err($crate::TskitError::RangeError(format!(
+                        "user bit {} is not in 16..32",
+                        bit
+                    ))),
+                }
+            }
+        }
+    };
+}
+
 /// Convenience macro to handle implementing
 /// [`crate::metadata::MetadataRoundtrip`]
 #[macro_export]
@@ -492,8 +947,83 @@ macro_rules! handle_metadata_return {
     };
 }
 
+macro_rules! impl_send_sync_for_table_view {
+    ($view: ident) => {
+        // Safety: a view has no methods that modify the C table,
+        // and the table collection or owned table that it
+        // borrows from is itself `Sync`.
+        unsafe impl Send for $view<'_> {}
+        unsafe impl Sync for $view<'_> {}
+    };
+}
+
+macro_rules! impl_table_equality {
+    ($view: ident, $equals: ident) => {
+        impl $view<'_> {
+            /// Return `true` if `self` contains the same data as `other`,
+            /// and `false` otherwise.
+            ///
+            /// Of the [`TableEqualityOptions`](crate::TableEqualityOptions),
+            /// only `IGNORE_METADATA` applies to a single table,
+            /// except for provenance tables, where only
+            /// `IGNORE_TIMESTAMPS` applies.
+            pub fn equals<O: Into<$crate::TableEqualityOptions>>(
+                &self,
+                other: &$view<'_>,
+                options: O,
+            ) -> bool {
+                unsafe {
+                    $crate::bindings::$equals(self.table_, other.table_, options.into().bits())
+                }
+            }
+        }
+
+        impl<'b> PartialEq<$view<'b>> for $view<'_> {
+            fn eq(&self, other: &$view<'b>) -> bool {
+                self.equals(other, $crate::TableEqualityOptions::default())
+            }
+        }
+    };
+}
+
+macro_rules! impl_table_columns {
+    ($view: ident, $kind: ident,
+     [$(($name: ident, $coltype: ident)),*],
+     [$(($ragged: ident, $raggedtype: ident, $length: ident)),*]) => {
+        impl $crate::TableColumns for $view<'_> {
+            fn table_kind(&self) -> $crate::types::TableKind {
+                $crate::types::TableKind::$kind
+            }
+
+            fn columns(&self) -> Vec<$crate::types::ColumnInfo> {
+                let num_rows = $crate::SizeType::from(self.table_.num_rows);
+                vec![
+                    $($crate::types::ColumnInfo {
+                        name: stringify!($name),
+                        column_type: $crate::types::ColumnType::$coltype,
+                        len: num_rows,
+                        offsets_len: None,
+                    },)*
+                    $($crate::types::ColumnInfo {
+                        name: stringify!($ragged),
+                        column_type: $crate::types::ColumnType::$raggedtype,
+                        len: self.table_.$length.into(),
+                        offsets_len: Some(num_rows + 1),
+                    },)*
+                ]
+            }
+        }
+    };
+}
+
 macro_rules! build_owned_tables {
-    ($name: ty, $deref: ident, $llname: ty, $init: ident, $free: ident, $clear: expr) => {
+    ($name: ty, $deref: ident, $llname: ty, $init: ident, $free: ident, $clear: expr, $truncate: expr) => {
+        // Safety: the C table is owned exclusively by this type,
+        // the tskit C library keeps no global or thread-local state,
+        // and only methods taking `&mut self` modify the table.
+        unsafe impl Send for $name {}
+        unsafe impl Sync for $name {}
+
         impl $name {
             fn new() -> Self {
                 let temp = unsafe { libc::malloc(std::mem::size_of::<$llname>()) as *mut $llname };
@@ -512,6 +1042,20 @@ macro_rules! build_owned_tables {
                 let rv = unsafe { $clear(self.as_mut_ptr()) };
                 handle_tsk_return_value!(rv)
             }
+
+            /// Truncate the table to the first `num_rows` rows.
+            ///
+            /// # Errors
+            ///
+            /// [`TskitError`](crate::TskitError) if `num_rows` is
+            /// greater than the current number of rows.
+            pub fn truncate<N: Into<$crate::SizeType>>(
+                &mut self,
+                num_rows: N,
+            ) -> $crate::TskReturnValue {
+                let rv = unsafe { $truncate(self.as_mut_ptr(), num_rows.into().into()) };
+                handle_tsk_return_value!(rv)
+            }
         }
 
         impl Default for $name {
@@ -520,6 +1064,12 @@ macro_rules! build_owned_tables {
             }
         }
 
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                **self == **other
+            }
+        }
+
         impl std::ops::Deref for $name {
             type Target = $deref<'static>;
 
@@ -1025,22 +1575,1079 @@ macro_rules! migration_table_add_row_with_metadata {
     };
 }
 
-#[cfg(any(doc, feature = "provenance"))]
-macro_rules! provenance_table_add_row {
+macro_rules! node_table_update_row_details {
+    ($row: ident, $flags: ident, $time: ident, $population: ident, $individual: ident, $metadata: expr, $metadata_len: expr, $table: expr) => {{
+        let rv = unsafe {
+            $crate::bindings::tsk_node_table_update_row(
+                &mut $table,
+                $row.into().0,
+                $flags.into().bits(),
+                $time.into().0,
+                $population.into().0,
+                $individual.into().0,
+                $metadata,
+                $metadata_len,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }};
+}
+macro_rules! node_table_update_row {
     ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
         $(#[$attr])*
-        pub fn $name(&mut $self, record: &str) -> Result<$crate::ProvenanceId, $crate::TskitError> {
-            let timestamp = humantime::format_rfc3339(std::time::SystemTime::now()).to_string();
-            let rv = unsafe {
-                $crate::bindings::tsk_provenance_table_add_row(
-                    &mut $table,
-                    timestamp.as_ptr() as *mut i8,
-                    timestamp.len() as tsk_size_t,
-                    record.as_ptr() as *mut i8,
-                    record.len() as tsk_size_t,
-                )
-            };
-            handle_tsk_return_value!(rv, rv.into())
+        pub fn $name<ROW,F,T,P,I>(
+            &mut $self,
+            row: ROW,
+            flags: F,
+            time: T,
+            population: P,
+            individual: I,
+        ) -> $crate::TskReturnValue
+        where
+            ROW: Into<$crate::NodeId>,
+            F: Into<$crate::NodeFlags>,
+            T: Into<$crate::Time>,
+            P: Into<$crate::PopulationId>,
+            I: Into<$crate::IndividualId>,
+        {
+            node_table_update_row_details!(row, flags, time, population, individual, std::ptr::null(), 0, $table)
+        }
+    };
+}
+macro_rules! node_table_update_row_with_metadata {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name<ROW,F,T,P,I,M>(
+            &mut $self,
+            row: ROW,
+            flags: F,
+            time: T,
+            population: P,
+            individual: I,
+            metadata: &M,
+        ) -> $crate::TskReturnValue
+        where
+            ROW: Into<$crate::NodeId>,
+            F: Into<$crate::NodeFlags>,
+            T: Into<$crate::Time>,
+            P: Into<$crate::PopulationId>,
+            I: Into<$crate::IndividualId>,
+            M: $crate::metadata::NodeMetadata,
+        {
+            let md = $crate::metadata::EncodedMetadata::new(metadata)?;
+            node_table_update_row_details!(row, flags, time, population, individual, md.as_ptr(), md.len().into(), $table)
+        }
+    };
+}
+macro_rules! edge_table_update_row_details {
+    ($row: ident, $left: ident, $right: ident, $parent: ident, $child: ident, $metadata: expr, $metadata_len: expr, $table: expr) => {{
+        let rv = unsafe {
+            $crate::bindings::tsk_edge_table_update_row(
+                &mut $table,
+                $row.into().0,
+                $left.into().0,
+                $right.into().0,
+                $parent.into().0,
+                $child.into().0,
+                $metadata,
+                $metadata_len,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }};
+}
+macro_rules! edge_table_update_row {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name<ROW,L,R,P,C>(
+            &mut $self,
+            row: ROW,
+            left: L,
+            right: R,
+            parent: P,
+            child: C,
+        ) -> $crate::TskReturnValue
+        where
+            ROW: Into<$crate::EdgeId>,
+            L: Into<$crate::Position>,
+            R: Into<$crate::Position>,
+            P: Into<$crate::NodeId>,
+            C: Into<$crate::NodeId>,
+        {
+            edge_table_update_row_details!(row, left, right, parent, child, std::ptr::null(), 0, $table)
+        }
+    };
+}
+macro_rules! edge_table_update_row_with_metadata {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name<ROW,L,R,P,C,M>(
+            &mut $self,
+            row: ROW,
+            left: L,
+            right: R,
+            parent: P,
+            child: C,
+            metadata: &M,
+        ) -> $crate::TskReturnValue
+        where
+            ROW: Into<$crate::EdgeId>,
+            L: Into<$crate::Position>,
+            R: Into<$crate::Position>,
+            P: Into<$crate::NodeId>,
+            C: Into<$crate::NodeId>,
+            M: $crate::metadata::EdgeMetadata,
+        {
+            let md = $crate::metadata::EncodedMetadata::new(metadata)?;
+            edge_table_update_row_details!(row, left, right, parent, child, md.as_ptr(), md.len().into(), $table)
+        }
+    };
+}
+macro_rules! individual_table_update_row_details {
+    ($row: ident, $flags: ident, $location: ident, $parents: ident, $metadata: expr, $metadata_len: expr, $table: expr) => {{
+        let rv = unsafe {
+            $crate::bindings::tsk_individual_table_update_row(
+                &mut $table,
+                $row.into().0,
+                $flags.into().bits(),
+                $location.get_slice().as_ptr().cast::<f64>(),
+                $location.get_slice().len() as $crate::bindings::tsk_size_t,
+                $parents
+                    .get_slice()
+                    .as_ptr()
+                    .cast::<$crate::bindings::tsk_id_t>(),
+                $parents.get_slice().len() as $crate::bindings::tsk_size_t,
+                $metadata,
+                $metadata_len,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }};
+}
+macro_rules! individual_table_update_row {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name<ROW,F,L,P>(
+            &mut $self,
+            row: ROW,
+            flags: F,
+            location: L,
+            parents: P,
+        ) -> $crate::TskReturnValue
+        where
+            ROW: Into<$crate::IndividualId>,
+            F: Into<$crate::IndividualFlags>,
+            L: $crate::IndividualLocation,
+            P: $crate::IndividualParents,
+        {
+            individual_table_update_row_details!(row, flags, location, parents, std::ptr::null(), 0, $table)
+        }
+    };
+}
+macro_rules! individual_table_update_row_with_metadata {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name<ROW,F,L,P,M>(
+            &mut $self,
+            row: ROW,
+            flags: F,
+            location: L,
+            parents: P,
+            metadata: &M,
+        ) -> $crate::TskReturnValue
+        where
+            ROW: Into<$crate::IndividualId>,
+            F: Into<$crate::IndividualFlags>,
+            L: $crate::IndividualLocation,
+            P: $crate::IndividualParents,
+            M: $crate::metadata::IndividualMetadata,
+        {
+            let md = $crate::metadata::EncodedMetadata::new(metadata)?;
+            individual_table_update_row_details!(row, flags, location, parents, md.as_ptr(), md.len().into(), $table)
+        }
+    };
+}
+macro_rules! site_table_update_row_details {
+    ($row: ident, $position: ident, $ancestral_state: ident, $metadata: expr, $metadata_len: expr, $table: expr) => {{
+        let astate = process_state_input!($ancestral_state);
+        let rv = unsafe {
+            $crate::bindings::tsk_site_table_update_row(
+                &mut $table,
+                $row.into().0,
+                $position.into().0,
+                astate.0,
+                astate.1,
+                $metadata,
+                $metadata_len,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }};
+}
+macro_rules! site_table_update_row {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name<ROW,P>(
+            &mut $self,
+            row: ROW,
+            position: P,
+            ancestral_state: Option<&[u8]>,
+        ) -> $crate::TskReturnValue
+        where
+            ROW: Into<$crate::SiteId>,
+            P: Into<$crate::Position>,
+        {
+            site_table_update_row_details!(row, position, ancestral_state, std::ptr::null(), 0, $table)
+        }
+    };
+}
+macro_rules! site_table_update_row_with_metadata {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name<ROW,P,M>(
+            &mut $self,
+            row: ROW,
+            position: P,
+            ancestral_state: Option<&[u8]>,
+            metadata: &M,
+        ) -> $crate::TskReturnValue
+        where
+            ROW: Into<$crate::SiteId>,
+            P: Into<$crate::Position>,
+            M: $crate::metadata::SiteMetadata,
+        {
+            let md = $crate::metadata::EncodedMetadata::new(metadata)?;
+            site_table_update_row_details!(row, position, ancestral_state, md.as_ptr(), md.len().into(), $table)
+        }
+    };
+}
+macro_rules! mutation_table_update_row_details {
+    ($row: ident, $site: ident, $node: ident, $parent: ident, $time: ident, $derived_state: ident, $metadata: expr, $metadata_len: expr, $table: expr) => {{
+        let dstate = process_state_input!($derived_state);
+        let rv = unsafe {
+            $crate::bindings::tsk_mutation_table_update_row(
+                &mut $table,
+                $row.into().0,
+                $site.into().0,
+                $node.into().0,
+                $parent.into().0,
+                $time.into().0,
+                dstate.0,
+                dstate.1,
+                $metadata,
+                $metadata_len,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }};
+}
+macro_rules! mutation_table_update_row {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name<ROW,S,N,P,T>(
+            &mut $self,
+            row: ROW,
+            site: S,
+            node: N,
+            parent: P,
+            time: T,
+            derived_state: Option<&[u8]>,
+        ) -> $crate::TskReturnValue
+        where
+            ROW: Into<$crate::MutationId>,
+            S: Into<$crate::SiteId>,
+            N: Into<$crate::NodeId>,
+            P: Into<$crate::MutationId>,
+            T: Into<$crate::Time>,
+        {
+            mutation_table_update_row_details!(row, site, node, parent, time, derived_state, std::ptr::null(), 0, $table)
+        }
+    };
+}
+macro_rules! mutation_table_update_row_with_metadata {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        #[allow(clippy::too_many_arguments)]
+        pub fn $name<ROW,S,N,P,T,M>(
+            &mut $self,
+            row: ROW,
+            site: S,
+            node: N,
+            parent: P,
+            time: T,
+            derived_state: Option<&[u8]>,
+            metadata: &M,
+        ) -> $crate::TskReturnValue
+        where
+            ROW: Into<$crate::MutationId>,
+            S: Into<$crate::SiteId>,
+            N: Into<$crate::NodeId>,
+            P: Into<$crate::MutationId>,
+            T: Into<$crate::Time>,
+            M: $crate::metadata::MutationMetadata,
+        {
+            let md = $crate::metadata::EncodedMetadata::new(metadata)?;
+            mutation_table_update_row_details!(row, site, node, parent, time, derived_state, md.as_ptr(), md.len().into(), $table)
+        }
+    };
+}
+macro_rules! migration_table_update_row_details {
+    ($row: ident, $span: ident, $node: ident, $source_dest: ident, $time: ident, $metadata: expr, $metadata_len: expr, $table: expr) => {{
+        let rv = unsafe {
+            $crate::bindings::tsk_migration_table_update_row(
+                &mut $table,
+                $row.into().0,
+                $span.0.into().0,
+                $span.1.into().0,
+                $node.into().0,
+                $source_dest.0.into().0,
+                $source_dest.1.into().0,
+                $time.into().0,
+                $metadata,
+                $metadata_len,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }};
+}
+macro_rules! migration_table_update_row {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name<ROW,LEFT,RIGHT,N,SOURCE,DEST,T>(
+            &mut $self,
+            row: ROW,
+            span: (LEFT, RIGHT),
+            node: N,
+            source_dest: (SOURCE, DEST),
+            time: T,
+        ) -> $crate::TskReturnValue
+        where
+            ROW: Into<$crate::MigrationId>,
+            LEFT: Into<$crate::Position>,
+            RIGHT: Into<$crate::Position>,
+            N: Into<$crate::NodeId>,
+            SOURCE: Into<$crate::PopulationId>,
+            DEST: Into<$crate::PopulationId>,
+            T: Into<$crate::Time>,
+        {
+            migration_table_update_row_details!(row, span, node, source_dest, time, std::ptr::null(), 0, $table)
+        }
+    };
+}
+macro_rules! migration_table_update_row_with_metadata {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name<ROW,LEFT,RIGHT,N,SOURCE,DEST,T,M>(
+            &mut $self,
+            row: ROW,
+            span: (LEFT, RIGHT),
+            node: N,
+            source_dest: (SOURCE, DEST),
+            time: T,
+            metadata: &M,
+        ) -> $crate::TskReturnValue
+        where
+            ROW: Into<$crate::MigrationId>,
+            LEFT: Into<$crate::Position>,
+            RIGHT: Into<$crate::Position>,
+            N: Into<$crate::NodeId>,
+            SOURCE: Into<$crate::PopulationId>,
+            DEST: Into<$crate::PopulationId>,
+            T: Into<$crate::Time>,
+            M: $crate::metadata::MigrationMetadata,
+        {
+            let md = $crate::metadata::EncodedMetadata::new(metadata)?;
+            migration_table_update_row_details!(row, span, node, source_dest, time, md.as_ptr(), md.len().into(), $table)
+        }
+    };
+}
+macro_rules! population_table_update_row_details {
+    ($row: ident, $metadata: expr, $metadata_len: expr, $table: expr) => {{
+        let rv = unsafe {
+            $crate::bindings::tsk_population_table_update_row(
+                &mut $table,
+                $row.into().0,
+                $metadata,
+                $metadata_len,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }};
+}
+macro_rules! population_table_update_row {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name<ROW>(
+            &mut $self,
+            row: ROW,
+        ) -> $crate::TskReturnValue
+        where
+            ROW: Into<$crate::PopulationId>,
+        {
+            population_table_update_row_details!(row, std::ptr::null(), 0, $table)
+        }
+    };
+}
+macro_rules! population_table_update_row_with_metadata {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name<ROW,M>(
+            &mut $self,
+            row: ROW,
+            metadata: &M,
+        ) -> $crate::TskReturnValue
+        where
+            ROW: Into<$crate::PopulationId>,
+            M: $crate::metadata::PopulationMetadata,
+        {
+            let md = $crate::metadata::EncodedMetadata::new(metadata)?;
+            population_table_update_row_details!(row, md.as_ptr(), md.len().into(), $table)
+        }
+    };
+}
+#[cfg(any(doc, feature = "provenance"))]
+macro_rules! provenance_table_add_row {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name(&mut $self, record: &str) -> Result<$crate::ProvenanceId, $crate::TskitError> {
+            let timestamp = humantime::format_rfc3339_micros(std::time::SystemTime::now()).to_string();
+            let rv = unsafe {
+                $crate::bindings::tsk_provenance_table_add_row(
+                    &mut $table,
+                    timestamp.as_ptr() as *mut i8,
+                    timestamp.len() as tsk_size_t,
+                    record.as_ptr() as *mut i8,
+                    record.len() as tsk_size_t,
+                )
+            };
+            handle_tsk_return_value!(rv, rv.into())
+        }
+    };
+}
+
+#[cfg(any(doc, feature = "provenance"))]
+macro_rules! provenance_table_add_row_with_timestamp {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name(&mut $self, timestamp: &str, record: &str) -> Result<$crate::ProvenanceId, $crate::TskitError> {
+            let rv = unsafe {
+                $crate::bindings::tsk_provenance_table_add_row(
+                    &mut $table,
+                    timestamp.as_ptr() as *mut i8,
+                    timestamp.len() as tsk_size_t,
+                    record.as_ptr() as *mut i8,
+                    record.len() as tsk_size_t,
+                )
+            };
+            handle_tsk_return_value!(rv, rv.into())
+        }
+    };
+}
+
+macro_rules! node_table_set_columns {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name(
+            &mut $self,
+            flags: &[$crate::NodeFlags],
+            time: &[$crate::Time],
+            population: &[$crate::PopulationId],
+            individual: &[$crate::IndividualId],
+        ) -> $crate::TskReturnValue {
+            let num_rows = $crate::util::num_rows_from_column_lengths(&[
+                flags.len(),
+                time.len(),
+                population.len(),
+                individual.len(),
+            ])?;
+            let rv = unsafe {
+                $crate::bindings::tsk_node_table_set_columns(
+                    &mut $table,
+                    num_rows,
+                    flags.as_ptr().cast::<$crate::bindings::tsk_flags_t>(),
+                    time.as_ptr().cast::<f64>(),
+                    population.as_ptr().cast::<$crate::bindings::tsk_id_t>(),
+                    individual.as_ptr().cast::<$crate::bindings::tsk_id_t>(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                )
+            };
+            handle_tsk_return_value!(rv)
+        }
+    };
+}
+
+macro_rules! edge_table_set_columns {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name(
+            &mut $self,
+            left: &[$crate::Position],
+            right: &[$crate::Position],
+            parent: &[$crate::NodeId],
+            child: &[$crate::NodeId],
+        ) -> $crate::TskReturnValue {
+            let num_rows = $crate::util::num_rows_from_column_lengths(&[
+                left.len(),
+                right.len(),
+                parent.len(),
+                child.len(),
+            ])?;
+            let rv = unsafe {
+                $crate::bindings::tsk_edge_table_set_columns(
+                    &mut $table,
+                    num_rows,
+                    left.as_ptr().cast::<f64>(),
+                    right.as_ptr().cast::<f64>(),
+                    parent.as_ptr().cast::<$crate::bindings::tsk_id_t>(),
+                    child.as_ptr().cast::<$crate::bindings::tsk_id_t>(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                )
+            };
+            handle_tsk_return_value!(rv)
+        }
+    };
+}
+
+macro_rules! individual_table_set_columns {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name<L, P>(
+            &mut $self,
+            flags: &[$crate::IndividualFlags],
+            location: &[L],
+            parents: &[P],
+        ) -> $crate::TskReturnValue
+        where
+            L: $crate::IndividualLocation,
+            P: $crate::IndividualParents,
+        {
+            let num_rows = $crate::util::num_rows_from_column_lengths(&[
+                flags.len(),
+                location.len(),
+                parents.len(),
+            ])?;
+            let (location, location_offset) =
+                $crate::util::flatten_ragged_column(location.iter().map(|l| l.get_slice()));
+            let (parents, parents_offset) =
+                $crate::util::flatten_ragged_column(parents.iter().map(|p| p.get_slice()));
+            let rv = unsafe {
+                $crate::bindings::tsk_individual_table_set_columns(
+                    &mut $table,
+                    num_rows,
+                    flags.as_ptr().cast::<$crate::bindings::tsk_flags_t>(),
+                    location.as_ptr().cast::<f64>(),
+                    location_offset.as_ptr(),
+                    parents.as_ptr().cast::<$crate::bindings::tsk_id_t>(),
+                    parents_offset.as_ptr(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                )
+            };
+            handle_tsk_return_value!(rv)
+        }
+    };
+}
+
+macro_rules! site_table_set_columns {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name(
+            &mut $self,
+            position: &[$crate::Position],
+            ancestral_state: &[Option<&[u8]>],
+        ) -> $crate::TskReturnValue {
+            let num_rows = $crate::util::num_rows_from_column_lengths(&[
+                position.len(),
+                ancestral_state.len(),
+            ])?;
+            let (ancestral_state, ancestral_state_offset) = $crate::util::flatten_ragged_column(
+                ancestral_state.iter().map(|a| a.unwrap_or(&[])),
+            );
+            let rv = unsafe {
+                $crate::bindings::tsk_site_table_set_columns(
+                    &mut $table,
+                    num_rows,
+                    position.as_ptr().cast::<f64>(),
+                    ancestral_state.as_ptr().cast::<i8>(),
+                    ancestral_state_offset.as_ptr(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                )
+            };
+            handle_tsk_return_value!(rv)
+        }
+    };
+}
+
+macro_rules! mutation_table_set_columns {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name(
+            &mut $self,
+            site: &[$crate::SiteId],
+            node: &[$crate::NodeId],
+            parent: &[$crate::MutationId],
+            time: &[$crate::Time],
+            derived_state: &[Option<&[u8]>],
+        ) -> $crate::TskReturnValue {
+            let num_rows = $crate::util::num_rows_from_column_lengths(&[
+                site.len(),
+                node.len(),
+                parent.len(),
+                time.len(),
+                derived_state.len(),
+            ])?;
+            let (derived_state, derived_state_offset) = $crate::util::flatten_ragged_column(
+                derived_state.iter().map(|d| d.unwrap_or(&[])),
+            );
+            let rv = unsafe {
+                $crate::bindings::tsk_mutation_table_set_columns(
+                    &mut $table,
+                    num_rows,
+                    site.as_ptr().cast::<$crate::bindings::tsk_id_t>(),
+                    node.as_ptr().cast::<$crate::bindings::tsk_id_t>(),
+                    parent.as_ptr().cast::<$crate::bindings::tsk_id_t>(),
+                    time.as_ptr().cast::<f64>(),
+                    derived_state.as_ptr().cast::<i8>(),
+                    derived_state_offset.as_ptr(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                )
+            };
+            handle_tsk_return_value!(rv)
+        }
+    };
+}
+
+macro_rules! migration_table_set_columns {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name(
+            &mut $self,
+            span: (&[$crate::Position], &[$crate::Position]),
+            node: &[$crate::NodeId],
+            source_dest: (&[$crate::PopulationId], &[$crate::PopulationId]),
+            time: &[$crate::Time],
+        ) -> $crate::TskReturnValue {
+            let num_rows = $crate::util::num_rows_from_column_lengths(&[
+                span.0.len(),
+                span.1.len(),
+                node.len(),
+                source_dest.0.len(),
+                source_dest.1.len(),
+                time.len(),
+            ])?;
+            let rv = unsafe {
+                $crate::bindings::tsk_migration_table_set_columns(
+                    &mut $table,
+                    num_rows,
+                    span.0.as_ptr().cast::<f64>(),
+                    span.1.as_ptr().cast::<f64>(),
+                    node.as_ptr().cast::<$crate::bindings::tsk_id_t>(),
+                    source_dest.0.as_ptr().cast::<$crate::bindings::tsk_id_t>(),
+                    source_dest.1.as_ptr().cast::<$crate::bindings::tsk_id_t>(),
+                    time.as_ptr().cast::<f64>(),
+                    std::ptr::null(),
+                    std::ptr::null(),
+                )
+            };
+            handle_tsk_return_value!(rv)
+        }
+    };
+}
+
+macro_rules! table_set_max_increment {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr, $function: path) => {
+        $(#[$attr])*
+        pub fn $name<N: Into<$crate::SizeType>>(&mut $self, increment: N) -> $crate::TskReturnValue {
+            let rv = unsafe { $function(&mut $table, increment.into().into()) };
+            handle_tsk_return_value!(rv)
+        }
+    };
+}
+
+macro_rules! table_packset_metadata {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr) => {
+        $(#[$attr])*
+        pub fn $name<M: AsRef<[u8]>>(&mut $self, metadata: &[M]) -> $crate::TskReturnValue {
+            let num_rows = $crate::SizeType::from($table.num_rows);
+            if $crate::SizeType::from(metadata.len()) != num_rows {
+                return Err($crate::TskitError::ValueError {
+                    got: format!("{} metadata rows", metadata.len()),
+                    expected: format!("{} metadata rows", num_rows),
+                });
+            }
+            let (encoded, offsets) =
+                $crate::util::flatten_ragged_column(metadata.iter().map(|m| m.as_ref()));
+            let length: $crate::bindings::tsk_size_t = $crate::SizeType::from(encoded.len()).into();
+            if length > $table.max_metadata_length {
+                // The buffer is owned by the C library, so it must
+                // be reallocated by the C library.
+                let buffer = unsafe {
+                    $crate::bindings::tsk_realloc($table.metadata.cast::<libc::c_void>(), length)
+                };
+                if buffer.is_null() {
+                    return Err($crate::TskitError::ErrorCode {
+                        code: $crate::bindings::TSK_ERR_NO_MEMORY,
+                    });
+                }
+                $table.metadata = buffer.cast::<libc::c_char>();
+                $table.max_metadata_length = length;
+            }
+            // SAFETY: the metadata buffer holds at least length bytes and
+            // the offset buffer holds at least num_rows + 1 values.
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    encoded.as_ptr().cast::<libc::c_char>(),
+                    $table.metadata,
+                    encoded.len(),
+                );
+                std::ptr::copy_nonoverlapping(
+                    offsets.as_ptr(),
+                    $table.metadata_offset,
+                    offsets.len(),
+                );
+            }
+            $table.metadata_length = length;
+            Ok(0)
+        }
+    };
+}
+
+macro_rules! table_migrate_metadata {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $view: expr, $packset: ident, $set_schema: ident, $marker: ident) => {
+        $(#[$attr])*
+        pub fn $name<Old, New, F>(
+            &mut $self,
+            old_schema: &str,
+            new_schema: &str,
+            mut convert: F,
+        ) -> $crate::TskReturnValue
+        where
+            Old: $crate::metadata::MetadataRoundtrip,
+            New: $crate::metadata::$marker,
+            F: FnMut(Old) -> New,
+        {
+            let encoded = {
+                let view = $view;
+                let current = view.metadata_schema().unwrap_or("");
+                if current != old_schema {
+                    return Err($crate::TskitError::ValueError {
+                        got: format!("metadata schema {:?}", current),
+                        expected: format!("metadata schema {:?}", old_schema),
+                    });
+                }
+                let mut encoded = vec![];
+                for metadata in view.metadata_iter::<Old>() {
+                    match metadata? {
+                        Some(old) => encoded.push($crate::metadata::MetadataRoundtrip::encode(
+                            &convert(old),
+                        )?),
+                        None => encoded.push(vec![]),
+                    }
+                }
+                encoded
+            };
+            $self.$packset(&encoded)?;
+            $self.$set_schema(new_schema)
+        }
+    };
+}
+
+macro_rules! build_table_metadata_schema_getter {
+    () => {
+        /// Return the metadata schema of the table.
+        ///
+        /// Returns `None` if there is no schema or if the schema
+        /// is not valid UTF-8.
+        pub fn metadata_schema(&self) -> Option<&str> {
+            unsafe {
+                $crate::metadata::char_array_to_slice(
+                    self,
+                    self.table_.metadata_schema,
+                    self.table_.metadata_schema_length,
+                )
+            }
+            .and_then(|s| std::str::from_utf8(s).ok())
+        }
+
+        /// Parse the metadata schema of the table.
+        ///
+        /// # Errors
+        ///
+        /// * [`TskitError::MissingMetadataSchema`](crate::TskitError::MissingMetadataSchema)
+        ///   if the table has no schema.
+        /// * [`TskitError::MetadataError`](crate::TskitError::MetadataError)
+        ///   if the schema is invalid.
+        #[cfg(feature = "serde_json")]
+        pub fn parse_metadata_schema(
+            &self,
+        ) -> Result<$crate::metadata::MetadataSchema, $crate::TskitError> {
+            match self.metadata_schema() {
+                Some(schema) => Ok($crate::metadata::MetadataSchema::parse(schema)?),
+                None => Err($crate::TskitError::MissingMetadataSchema),
+            }
+        }
+    };
+}
+
+macro_rules! build_table_metadata_bytes_access {
+    ($idtype: ty $(; $(#[$metadata_attr: meta])*)?) => {
+        /// Return the encoded metadata of row `row`.
+        ///
+        /// The bytes are borrowed from the table, so nothing is allocated.
+        /// Returns `Ok(None)` if the row has no metadata.
+        ///
+        /// # Errors
+        ///
+        /// [`TskitError::MetadataRowOutOfRange`](crate::TskitError::MetadataRowOutOfRange)
+        /// if `row` is out of range.
+        pub fn metadata_bytes<I: Into<$idtype>>(
+            &self,
+            row: I,
+        ) -> Result<Option<&[u8]>, $crate::TskitError> {
+            let table_ref = self.table_;
+            metadata_to_vector!(self, table_ref, row.into().0)
+        }
+
+        /// Return an iterator decoding the metadata of each row, in order.
+        ///
+        /// Each item is the result of decoding a row's metadata,
+        /// which is `Ok(None)` for rows without metadata.
+        pub fn metadata_iter<T: $crate::metadata::MetadataRoundtrip>(
+            &self,
+        ) -> impl Iterator<Item = Result<Option<T>, $crate::TskitError>> + '_ {
+            let num_rows = $crate::tsk_id_t::try_from(self.table_.num_rows).unwrap();
+            (0..num_rows).map(move |pos| {
+                let buffer = self.metadata_bytes(pos)?;
+                decode_metadata_row!(T, pos, buffer)
+            })
+        }
+
+        /// Decode the metadata of row `row`.
+        ///
+        /// # Returns
+        ///
+        /// * `Ok(Some(T))` if the row has metadata that decode successfully.
+        /// * `Ok(None)` if the row has no metadata.
+        ///
+        /// See [`metadata`](crate::metadata#reading-metadata) for the
+        /// conventions shared by all metadata getters.
+        ///
+        /// # Errors
+        ///
+        /// * [`TskitError::MetadataRowOutOfRange`](crate::TskitError::MetadataRowOutOfRange)
+        ///   if `row` is out of range.
+        /// * [`TskitError::MetadataDecodeError`](crate::TskitError::MetadataDecodeError)
+        ///   if decoding fails.
+        $($(#[$metadata_attr])*)?
+        pub fn metadata<T: $crate::metadata::MetadataRoundtrip>(
+            &self,
+            row: impl Into<$idtype>,
+        ) -> Result<Option<T>, $crate::TskitError> {
+            let row = row.into();
+            decode_metadata_row!(T, row.0, self.metadata_bytes(row)?)
+        }
+
+        /// Decode the metadata of row `row`, treating a row
+        /// without metadata as an error.
+        ///
+        /// # Errors
+        ///
+        /// * [`TskitError::MetadataRowOutOfRange`](crate::TskitError::MetadataRowOutOfRange)
+        ///   if `row` is out of range.
+        /// * [`TskitError::UnexpectedEmptyMetadata`](crate::TskitError::UnexpectedEmptyMetadata)
+        ///   if the row has no metadata.
+        /// * [`TskitError::MetadataDecodeError`](crate::TskitError::MetadataDecodeError)
+        ///   if decoding fails.
+        pub fn metadata_or_err<T: $crate::metadata::MetadataRoundtrip, I: Into<$idtype>>(
+            &self,
+            row: I,
+        ) -> Result<T, $crate::TskitError> {
+            let row = row.into();
+            self.metadata(row)?
+                .ok_or($crate::TskitError::UnexpectedEmptyMetadata { row: Some(row.0) })
+        }
+    };
+}
+
+macro_rules! build_table_row_views {
+    ($idtype: ty, $view: ident, $make_view: ident) => {
+        /// Return a view of row `row`, or `None` if `row` is out of range.
+        ///
+        /// The view borrows its data from the table, so nothing is copied.
+        pub fn get<I: Into<$idtype>>(&self, row: I) -> Option<$crate::$view<'_>> {
+            let row = row.into().0;
+            match $crate::SizeType::try_from(row) {
+                Ok(r) if r < self.num_rows() => Some($make_view(self, row)),
+                _ => None,
+            }
+        }
+
+        /// Return an [`Iterator`]
+        #[doc = concat!("over [`", stringify!($view), "`](crate::", stringify!($view), ")s of the rows of the table.")]
+        ///
+        /// Unlike the rows returned by `iter`, the views borrow their data,
+        /// including metadata, from the table, so iteration does not allocate.
+        /// Convert a view with `into` to get an owned row.
+        pub fn iter_views(&self) -> impl Iterator<Item = $crate::$view<'_>> + '_ {
+            // Table sizes fit in tsk_id_t
+            let num_rows = $crate::tsk_id_t::try_from(self.table_.num_rows).unwrap();
+            (0..num_rows).map(move |pos| $make_view(self, pos))
+        }
+    };
+}
+
+macro_rules! impl_row_view_into_row {
+    ($view: ident, $row: ident, [$($field: ident),*], [$($ragged: ident),*]) => {
+        impl From<$view<'_>> for $row {
+            fn from(view: $view<'_>) -> Self {
+                Self {
+                    $($field: view.$field,)*
+                    $($ragged: view.$ragged.map(|v| v.to_vec()),)*
+                }
+            }
+        }
+    };
+}
+
+macro_rules! impl_row_view_metadata {
+    ($view: ident) => {
+        impl $view<'_> {
+            /// Decode the metadata of the row.
+            ///
+            /// # Errors
+            ///
+            /// [`TskitError::MetadataDecodeError`](crate::TskitError::MetadataDecodeError)
+            /// if decoding fails.
+            pub fn decode_metadata<T: $crate::metadata::MetadataRoundtrip>(
+                &self,
+            ) -> Result<Option<T>, $crate::TskitError> {
+                decode_metadata_row!(T, self.id.0, self.metadata)
+            }
+        }
+    };
+}
+
+macro_rules! impl_table_into_iterator {
+    ($table: ident, $owned: ident, $row: ty, $make_row: ident) => {
+        impl<'a> IntoIterator for &'a $table<'a> {
+            type Item = $row;
+            type IntoIter = $crate::table_iterator::TableIterator<&'a $table<'a>>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                $crate::table_iterator::make_table_iterator(self)
+            }
+        }
+
+        impl<'a> IntoIterator for $table<'a> {
+            type Item = $row;
+            type IntoIter = $crate::table_iterator::TableIterator<$table<'a>>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                $crate::table_iterator::make_table_iterator(self)
+            }
+        }
+
+        impl<'a> IntoIterator for &'a $owned {
+            type Item = $row;
+            type IntoIter = $crate::table_iterator::TableIterator<&'a $table<'a>>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                $crate::table_iterator::make_table_iterator::<&$table>(self)
+            }
+        }
+
+        impl IntoIterator for $owned {
+            type Item = $row;
+            type IntoIter = $crate::table_iterator::TableIterator<$owned>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                $crate::table_iterator::make_table_iterator(self)
+            }
+        }
+
+        impl Iterator for $crate::table_iterator::TableIterator<$owned> {
+            type Item = $row;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                let rv = $make_row(&self.table, self.pos);
+                self.pos += 1;
+                rv
+            }
+        }
+    };
+}
+
+macro_rules! table_set_metadata_schema {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr, $function: path) => {
+        $(#[$attr])*
+        pub fn $name<S: AsRef<str>>(&mut $self, schema: S) -> $crate::TskReturnValue {
+            let schema = schema.as_ref();
+            let rv = unsafe {
+                $function(
+                    &mut $table,
+                    schema.as_ptr().cast::<libc::c_char>(),
+                    schema.len() as $crate::bindings::tsk_size_t,
+                )
+            };
+            handle_tsk_return_value!(rv)
+        }
+    };
+}
+
+macro_rules! table_collection_takeset_table {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $field: ident, $owned: ty) => {
+        $(#[$attr])*
+        pub fn $name(&mut $self, mut table: $owned) -> TskReturnValue {
+            // SAFETY: both tables are initialized, and tskit tables
+            // do not contain pointers to themselves, so swapping them
+            // transfers ownership of their columns.
+            // The previous table is freed when table is dropped.
+            unsafe { std::ptr::swap(&mut $self.inner.$field, table.as_mut_ptr()) };
+            let rv = unsafe { ll_bindings::tsk_table_collection_drop_index($self.as_mut_ptr(), 0) };
+            handle_tsk_return_value!(rv)
+        }
+    };
+}
+
+macro_rules! table_column_slice_mut {
+    ($(#[$attr:meta])* => $name: ident, $self: ident, $table: expr, $column: ident, $cast: ty) => {
+        $(#[$attr])*
+        pub fn $name(&mut $self) -> &mut [$cast] {
+            // The columns of empty tables may not be allocated.
+            if $table.num_rows == 0 {
+                return &mut [];
+            }
+            // SAFETY: the column holds num_rows values that have
+            // the same layout as $cast.
+            unsafe {
+                std::slice::from_raw_parts_mut(
+                    $table.$column.cast::<$cast>(),
+                    usize::try_from($table.num_rows).unwrap(),
+                )
+            }
+        }
+    };
+}
+
+macro_rules! build_table_column_slice_getter {
+    ($(#[$attr:meta])* => $column: ident, $name: ident, $cast: ty) => {
+        $(#[$attr])*
+        pub fn $name(&self) -> &[$cast] {
+            if self.table_.num_rows == 0 {
+                return &[];
+            }
+            // SAFETY: $cast is repr(transparent) wrapper
+            // of the type stored in the column.
+            unsafe {
+                std::slice::from_raw_parts(
+                    self.table_.$column.cast::<$cast>(),
+                    usize::try_from(self.table_.num_rows).unwrap(),
+                )
+            }
         }
     };
 }
@@ -1051,7 +2658,8 @@ macro_rules! build_owned_table_type {
     $tskname: ident,
     $tskinit: ident,
     $tskfree: ident,
-    $tskclear: expr) => {
+    $tskclear: expr,
+    $tsktruncate: expr) => {
         $(#[$attr])*
         pub struct $name {
             table: mbox::MBox<$crate::bindings::$tskname>,
@@ -1063,7 +2671,8 @@ macro_rules! build_owned_table_type {
             $crate::bindings::$tskname,
             $tskinit,
             $tskfree,
-            $tskclear
+            $tskclear,
+            $tsktruncate
         );
     };
 }
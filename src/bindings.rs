@@ -20,6 +20,36 @@
 //! }
 //! ```
 //!
+//! # Using the safe types with the C API
+//!
+//! The safe types give pointers to their C objects, so the
+//! functions in this module can be called on them:
+//!
+//! * [`TableCollection`](crate::TableCollection), [`TreeSequence`](crate::TreeSequence),
+//!   and [`Tree`](crate::Tree) implement [`TskitTypeAccess`](crate::TskitTypeAccess).
+//! * The owned tables, such as [`OwnedNodeTable`](crate::OwnedNodeTable),
+//!   have `as_ptr` and `as_mut_ptr`.
+//! * The table views, such as [`NodeTable`](crate::NodeTable), have `as_ptr`.
+//!
+//! Error codes returned by the C functions can be turned into a
+//! [`TskitError`](crate::TskitError) with
+//! [`TskitError::ErrorCode`](crate::TskitError::ErrorCode).
+//!
+//! ```
+//! use tskit::TskitTypeAccess;
+//! let mut tables = tskit::TableCollection::new(10.).unwrap();
+//! tables.add_node(0, 0.0, -1, -1).unwrap();
+//! let has_reference = unsafe {
+//!     tskit::bindings::tsk_table_collection_has_reference_sequence(tables.as_ptr())
+//! };
+//! assert!(!has_reference);
+//! let num_rows = unsafe { (*tables.nodes().as_ptr()).num_rows };
+//! assert_eq!(num_rows, 1);
+//! let rv = unsafe { tskit::bindings::tsk_table_collection_clear(tables.as_mut_ptr(), 0) };
+//! assert_eq!(rv, 0);
+//! assert_eq!(tables.nodes().num_rows(), 0);
+//! ```
+//!
 //! The best source for documentation will be the [tskit docs](https://tskit.readthedocs.io).
 //! Those docs describe the most important parts of the C API.
 //! This module contains the same types/functions with the same names.
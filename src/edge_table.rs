@@ -6,6 +6,7 @@ use crate::{EdgeId, NodeId};
 use ll_bindings::{tsk_edge_table_free, tsk_edge_table_init};
 
 /// Row of an [`EdgeTable`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EdgeTableRow {
     pub id: EdgeId,
     pub left: Position,
@@ -26,11 +27,59 @@ impl PartialEq for EdgeTableRow {
     }
 }
 
+impl std::fmt::Display for EdgeTableRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "id: {}, left: {}, right: {}, parent: {}, child: {}, metadata: {}",
+            self.id,
+            self.left,
+            self.right,
+            self.parent,
+            self.child,
+            crate::util::DisplayMetadata(&self.metadata),
+        )
+    }
+}
+
+/// A view of a row of a [`EdgeTable`].
+///
+/// Unlike [`EdgeTableRow`], the view borrows its data from the table.
+/// Views are returned by [`EdgeTable::get`] and [`EdgeTable::iter_views`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EdgeTableRowView<'a> {
+    pub id: crate::EdgeId,
+    pub left: crate::Position,
+    pub right: crate::Position,
+    pub parent: crate::NodeId,
+    pub child: crate::NodeId,
+    pub metadata: Option<&'a [u8]>,
+}
+
+impl_row_view_metadata!(EdgeTableRowView);
+impl_row_view_into_row!(
+    EdgeTableRowView,
+    EdgeTableRow,
+    [id, left, right, parent, child],
+    [metadata]
+);
+
+fn make_edge_table_row_view<'a>(table: &'a EdgeTable<'_>, pos: tsk_id_t) -> EdgeTableRowView<'a> {
+    // pos is in range, so neither the indexing nor the unwraps can fail
+    let index = usize::try_from(pos).unwrap();
+    EdgeTableRowView {
+        id: pos.into(),
+        left: table.left_slice()[index],
+        right: table.right_slice()[index],
+        parent: table.parent_slice()[index],
+        child: table.child_slice()[index],
+        metadata: table.metadata_bytes(pos).unwrap(),
+    }
+}
+
 fn make_edge_table_row(table: &EdgeTable, pos: tsk_id_t) -> Option<EdgeTableRow> {
-    // panic is okay here, as we are handling a bad
-    // input value before we first call this to
-    // set up the iterator
-    let p = crate::SizeType::try_from(pos).unwrap();
+    // A negative pos is not a row, so there is nothing to return.
+    let p = crate::SizeType::try_from(pos).ok()?;
     if p < table.num_rows() {
         let table_ref = table.table_;
         let rv = EdgeTableRow {
@@ -70,6 +119,8 @@ impl<'a> Iterator for EdgeTableIterator<'a> {
     }
 }
 
+impl_table_into_iterator!(EdgeTable, OwnedEdgeTable, EdgeTableRow, make_edge_table_row);
+
 /// An immutable view of an edge table.
 ///
 /// These are not created directly.
@@ -80,23 +131,60 @@ pub struct EdgeTable<'a> {
     table_: &'a ll_bindings::tsk_edge_table_t,
 }
 
+impl_send_sync_for_table_view!(EdgeTable);
+impl_table_columns!(
+    EdgeTable,
+    Edges,
+    [
+        (left, Float64),
+        (right, Float64),
+        (parent, Int32),
+        (child, Int32)
+    ],
+    [(metadata, UInt8, metadata_length)]
+);
+impl_table_equality!(EdgeTable, tsk_edge_table_equals);
+
 impl<'a> EdgeTable<'a> {
     pub(crate) fn new_from_table(edges: &'a ll_bindings::tsk_edge_table_t) -> Self {
         EdgeTable { table_: edges }
     }
 
+    /// Return a pointer to the underlying C table, for use
+    /// with the functions in [`bindings`](crate::bindings).
+    pub fn as_ptr(&self) -> *const ll_bindings::tsk_edge_table_t {
+        self.table_
+    }
+
     /// Return the number of rows
-    pub fn num_rows(&'a self) -> crate::SizeType {
+    pub fn num_rows(&self) -> crate::SizeType {
         self.table_.num_rows.into()
     }
 
+    /// Return the number of bytes used by the data in this table.
+    pub fn nbytes(&self) -> usize {
+        let t = self.table_;
+        crate::util::column_nbytes::<f64>(t.num_rows)
+            + crate::util::column_nbytes::<f64>(t.num_rows)
+            + crate::util::column_nbytes::<crate::tsk_id_t>(t.num_rows)
+            + crate::util::column_nbytes::<crate::tsk_id_t>(t.num_rows)
+            + crate::util::ragged_column_nbytes::<u8>(t.metadata_length, t.num_rows)
+            + crate::util::column_nbytes::<u8>(t.metadata_schema_length)
+    }
+
+    build_table_metadata_schema_getter!();
+
+    build_table_metadata_bytes_access!(EdgeId);
+
+    build_table_row_views!(EdgeId, EdgeTableRowView, make_edge_table_row_view);
+
     /// Return the ``parent`` value from row ``row`` of the table.
     ///
     /// # Errors
     ///
     /// Will return [``IndexError``](crate::TskitError::IndexError)
     /// if ``row`` is out of range.
-    pub fn parent<E: Into<EdgeId> + Copy>(&'a self, row: E) -> Result<NodeId, TskitError> {
+    pub fn parent<E: Into<EdgeId> + Copy>(&self, row: E) -> Result<NodeId, TskitError> {
         unsafe_tsk_column_access!(row.into().0, 0, self.num_rows(), self.table_.parent, NodeId)
     }
 
@@ -106,7 +194,7 @@ impl<'a> EdgeTable<'a> {
     ///
     /// Will return [``IndexError``](crate::TskitError::IndexError)
     /// if ``row`` is out of range.
-    pub fn child<E: Into<EdgeId> + Copy>(&'a self, row: E) -> Result<NodeId, TskitError> {
+    pub fn child<E: Into<EdgeId> + Copy>(&self, row: E) -> Result<NodeId, TskitError> {
         unsafe_tsk_column_access!(row.into().0, 0, self.num_rows(), self.table_.child, NodeId)
     }
 
@@ -116,7 +204,7 @@ impl<'a> EdgeTable<'a> {
     ///
     /// Will return [``IndexError``](crate::TskitError::IndexError)
     /// if ``row`` is out of range.
-    pub fn left<E: Into<EdgeId> + Copy>(&'a self, row: E) -> Result<Position, TskitError> {
+    pub fn left<E: Into<EdgeId> + Copy>(&self, row: E) -> Result<Position, TskitError> {
         match unsafe_tsk_column_access!(row.into().0, 0, self.num_rows(), self.table_.left) {
             Ok(p) => Ok(p.into()),
             Err(e) => Err(e),
@@ -129,21 +217,25 @@ impl<'a> EdgeTable<'a> {
     ///
     /// Will return [``IndexError``](crate::TskitError::IndexError)
     /// if ``row`` is out of range.
-    pub fn right<E: Into<EdgeId> + Copy>(&'a self, row: E) -> Result<Position, TskitError> {
+    pub fn right<E: Into<EdgeId> + Copy>(&self, row: E) -> Result<Position, TskitError> {
         match unsafe_tsk_column_access!(row.into().0, 0, self.num_rows(), self.table_.right) {
             Ok(p) => Ok(p.into()),
             Err(e) => Err(e),
         }
     }
 
-    pub fn metadata<T: metadata::MetadataRoundtrip>(
-        &'a self,
-        row: EdgeId,
-    ) -> Result<Option<T>, TskitError> {
-        let table_ref = self.table_;
-        let buffer = metadata_to_vector!(self, table_ref, row.0)?;
-        decode_metadata_row!(T, buffer)
-    }
+    build_table_column_slice_getter!(
+        /// Get the left column as a slice
+        => left, left_slice, crate::Position);
+    build_table_column_slice_getter!(
+        /// Get the right column as a slice
+        => right, right_slice, crate::Position);
+    build_table_column_slice_getter!(
+        /// Get the parent column as a slice
+        => parent, parent_slice, crate::NodeId);
+    build_table_column_slice_getter!(
+        /// Get the child column as a slice
+        => child, child_slice, crate::NodeId);
 
     /// Return an iterator over rows of the table.
     /// The value of the iterator is [`EdgeTableRow`].
@@ -152,6 +244,51 @@ impl<'a> EdgeTable<'a> {
         crate::table_iterator::make_table_iterator::<&EdgeTable<'a>>(self)
     }
 
+    /// Return an iterator over groups of rows sharing the same parent.
+    ///
+    /// Each item is a tuple of the parent and an iterator over
+    /// the [`EdgeTableRow`]s having that parent.
+    ///
+    /// Groups are formed from contiguous runs of rows with equal
+    /// parent values, so the table should be sorted, as it is after
+    /// [`TableCollection::full_sort`](crate::TableCollection::full_sort).
+    /// The table is not checked for sortedness.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut edges = tskit::OwnedEdgeTable::default();
+    /// edges.add_row(0., 1., 2, 0).unwrap();
+    /// edges.add_row(0., 1., 2, 1).unwrap();
+    /// edges.add_row(0., 1., 3, 2).unwrap();
+    /// let groups = edges
+    ///     .iter_by_parent()
+    ///     .map(|(parent, rows)| (parent, rows.map(|row| row.child).collect::<Vec<_>>()))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(groups.len(), 2);
+    /// assert_eq!(groups[0], (2.into(), vec![0.into(), 1.into()]));
+    /// assert_eq!(groups[1], (3.into(), vec![2.into()]));
+    /// ```
+    pub fn iter_by_parent(
+        &self,
+    ) -> impl Iterator<Item = (NodeId, impl Iterator<Item = EdgeTableRow> + '_)> + '_ {
+        let parents = self.parent_slice();
+        let mut start = 0;
+        std::iter::from_fn(move || {
+            let parent = *parents.get(start)?;
+            let stop = parents[start..]
+                .iter()
+                .position(|&p| p != parent)
+                .map_or(parents.len(), |n| start + n);
+            let rows = (start..stop).map(move |row| {
+                // row is in range, so the unwrap cannot fail
+                make_edge_table_row(self, row as tsk_id_t).unwrap()
+            });
+            start = stop;
+            Some((parent, rows))
+        })
+    }
+
     /// Return row `r` of the table.
     ///
     /// # Parameters
@@ -170,6 +307,154 @@ impl<'a> EdgeTable<'a> {
     }
 }
 
+/// Builder for adding a row to an edge table.
+///
+/// The interval, parent, and child must be set.
+/// The row is checked before it is passed to the C library,
+/// so that common mistakes give descriptive errors:
+///
+/// * the interval must be finite with `0 <= left < right`,
+/// * neither the parent nor the child may be [`NodeId::NULL`],
+/// * the parent and child must differ.
+///
+/// Obtained from [`OwnedEdgeTable::add_row_defaults`]
+/// or [`TableCollection::add_edge_defaults`](crate::TableCollection::add_edge_defaults).
+///
+/// # Examples
+///
+/// ```
+/// let mut edges = tskit::OwnedEdgeTable::default();
+/// let id = edges
+///     .add_row_defaults()
+///     .interval(0., 10.)
+///     .parent(0)
+///     .child(1)
+///     .build()
+///     .unwrap();
+/// assert_eq!(edges.right(id).unwrap(), 10.);
+///
+/// // Forgetting the child is an error
+/// assert!(edges.add_row_defaults().interval(0., 10.).parent(0).build().is_err());
+/// // So is an empty interval
+/// assert!(edges
+///     .add_row_defaults()
+///     .interval(5., 5.)
+///     .parent(0)
+///     .child(1)
+///     .build()
+///     .is_err());
+/// ```
+#[must_use = "the row is not added until build is called"]
+pub struct EdgeRowBuilder<'a> {
+    table: &'a mut ll_bindings::tsk_edge_table_t,
+    interval: Option<(Position, Position)>,
+    parent: NodeId,
+    child: NodeId,
+    metadata: Result<Option<metadata::EncodedMetadata>, TskitError>,
+}
+
+impl<'a> EdgeRowBuilder<'a> {
+    pub(crate) fn new(table: &'a mut ll_bindings::tsk_edge_table_t) -> Self {
+        Self {
+            table,
+            interval: None,
+            parent: NodeId::NULL,
+            child: NodeId::NULL,
+            metadata: Ok(None),
+        }
+    }
+
+    /// Set the left and right coordinates of the edge.
+    pub fn interval<L: Into<Position>, R: Into<Position>>(mut self, left: L, right: R) -> Self {
+        self.interval = Some((left.into(), right.into()));
+        self
+    }
+
+    /// Set the parent node.
+    pub fn parent<N: Into<NodeId>>(mut self, parent: N) -> Self {
+        self.parent = parent.into();
+        self
+    }
+
+    /// Set the child node.
+    pub fn child<N: Into<NodeId>>(mut self, child: N) -> Self {
+        self.child = child.into();
+        self
+    }
+
+    /// Set the metadata of the edge.
+    ///
+    /// Encoding errors are reported by [`EdgeRowBuilder::build`].
+    pub fn metadata<M: metadata::EdgeMetadata>(mut self, metadata: &M) -> Self {
+        self.metadata = metadata::EncodedMetadata::new(metadata)
+            .map(Some)
+            .map_err(TskitError::from);
+        self
+    }
+
+    fn validate(&self) -> Result<(Position, Position), TskitError> {
+        let (left, right) = self.interval.ok_or_else(|| TskitError::ValueError {
+            got: "no interval".to_string(),
+            expected: "the left and right coordinates of the edge".to_string(),
+        })?;
+        if !(f64::from(left).is_finite() && f64::from(right).is_finite() && left >= 0.0)
+            || left >= right
+        {
+            return Err(TskitError::ValueError {
+                got: format!("edge interval [{}, {})", f64::from(left), f64::from(right)),
+                expected: "finite coordinates with 0 <= left < right".to_string(),
+            });
+        }
+        for (name, node) in [("parent", self.parent), ("child", self.child)] {
+            if node.is_null() {
+                return Err(TskitError::ValueError {
+                    got: format!("a NULL {}", name),
+                    expected: format!("a valid {} node", name),
+                });
+            }
+        }
+        if self.parent == self.child {
+            return Err(TskitError::ValueError {
+                got: format!("parent and child both equal to {}", self.parent),
+                expected: "different parent and child nodes".to_string(),
+            });
+        }
+        Ok((left, right))
+    }
+
+    /// Check the row and add it to the table.
+    ///
+    /// # Returns
+    ///
+    /// The id of the new edge.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if the row is not valid.
+    /// * [`TskitError::MetadataError`] if encoding the metadata failed.
+    /// * [`TskitError`] if the C library returns an error code.
+    pub fn build(self) -> Result<EdgeId, TskitError> {
+        let (left, right) = self.validate()?;
+        let md = self.metadata?;
+        let (md_ptr, md_len) = match &md {
+            Some(md) => (md.as_ptr(), md.len().into()),
+            None => (std::ptr::null(), 0),
+        };
+        let rv = unsafe {
+            ll_bindings::tsk_edge_table_add_row(
+                self.table,
+                left.0,
+                right.0,
+                self.parent.0,
+                self.child.0,
+                md_ptr,
+                md_len,
+            )
+        };
+        handle_tsk_return_value!(rv, rv.into())
+    }
+}
+
 build_owned_table_type!(
     /// A standalone edge table that owns its data.
     ///
@@ -222,10 +507,160 @@ build_owned_table_type!(
     tsk_edge_table_t,
     tsk_edge_table_init,
     tsk_edge_table_free,
-    crate::bindings::tsk_edge_table_clear
+    crate::bindings::tsk_edge_table_clear,
+    crate::bindings::tsk_edge_table_truncate
 );
 
 impl OwnedEdgeTable {
     edge_table_add_row!(=> add_row, self, *self.table);
     edge_table_add_row_with_metadata!(=> add_row_with_metadata, self, *self.table);
+
+    /// Start adding a row that is checked before insertion.
+    ///
+    /// See [`EdgeRowBuilder`] for details.
+    pub fn add_row_defaults(&mut self) -> EdgeRowBuilder<'_> {
+        EdgeRowBuilder::new(&mut self.table)
+    }
+    table_set_metadata_schema!(
+    /// Set the metadata schema of the table.
+    => set_metadata_schema, self, *self.table, ll_bindings::tsk_edge_table_set_metadata_schema);
+    edge_table_update_row!(
+    /// Replace the contents of row `row`.
+    ///
+    /// The row's metadata are cleared.
+    => update_row, self, *self.table);
+    edge_table_update_row_with_metadata!(
+    /// Replace the contents of row `row`, including its metadata.
+    => update_row_with_metadata, self, *self.table);
+
+    /// Replace the metadata of row `row`, keeping its other values.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`](crate::TskitError::IndexError) if `row` is out of range.
+    /// * [`TskitError::MetadataError`](crate::TskitError::MetadataError) if encoding fails.
+    pub fn set_metadata<R: Into<crate::EdgeId> + Copy, M: crate::metadata::EdgeMetadata>(
+        &mut self,
+        row: R,
+        metadata: &M,
+    ) -> crate::TskReturnValue {
+        let r = self.get(row).ok_or(crate::TskitError::IndexError)?;
+        let (id, left, right, parent, child) = (r.id, r.left, r.right, r.parent, r.child);
+        self.update_row_with_metadata(id, left, right, parent, child, metadata)
+    }
+    edge_table_set_columns!(
+    /// Replace the contents of the table with the
+    /// left, right, parent, and child columns.
+    ///
+    /// All rows are loaded with a single call to the C API.
+    /// Metadata are cleared.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`](crate::TskitError::ValueError) if the columns
+    /// do not all have the same length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::{NodeId, OwnedEdgeTable, Position};
+    ///
+    /// let mut edges = OwnedEdgeTable::default();
+    /// let left = vec![Position::from(0.); 2];
+    /// let right = vec![Position::from(10.); 2];
+    /// let parent = vec![NodeId::from(0); 2];
+    /// let child = vec![NodeId::from(1), NodeId::from(2)];
+    /// edges.set_columns(&left, &right, &parent, &child).unwrap();
+    /// assert_eq!(edges.num_rows(), 2);
+    /// assert_eq!(edges.child(1).unwrap(), 2);
+    ///
+    /// // All columns must have the same length
+    /// assert!(edges.set_columns(&left, &right, &parent, &child[..1]).is_err());
+    /// ```
+    => set_columns, self, *self.table);
+
+    /// Create a table from columns.
+    ///
+    /// See [`OwnedEdgeTable::set_columns`] for details.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`](crate::TskitError::ValueError) if the columns
+    /// do not all have the same length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::{NodeId, OwnedEdgeTable, Position};
+    ///
+    /// let left = vec![Position::from(0.); 2];
+    /// let right = vec![Position::from(10.); 2];
+    /// let parent = vec![NodeId::from(0); 2];
+    /// let child = vec![NodeId::from(1), NodeId::from(2)];
+    /// let edges = OwnedEdgeTable::from_columns(&left, &right, &parent, &child).unwrap();
+    /// assert_eq!(edges.num_rows(), 2);
+    ///
+    /// assert!(OwnedEdgeTable::from_columns(&left, &right, &parent, &child[1..]).is_err());
+    /// ```
+    pub fn from_columns(
+        left: &[crate::Position],
+        right: &[crate::Position],
+        parent: &[crate::NodeId],
+        child: &[crate::NodeId],
+    ) -> Result<Self, crate::TskitError> {
+        let mut table = Self::default();
+        table.set_columns(left, right, parent, child)?;
+        Ok(table)
+    }
+
+    table_packset_metadata!(
+    /// Replace the metadata column of the table.
+    ///
+    /// See [`OwnedNodeTable::packset_metadata`](crate::OwnedNodeTable::packset_metadata).
+    => packset_metadata, self, *self.table);
+
+    table_migrate_metadata!(
+    /// Re-encode the metadata of every row with a new schema.
+    ///
+    /// See [`OwnedNodeTable::migrate_metadata`](crate::OwnedNodeTable::migrate_metadata).
+    => migrate_metadata, self, &*self, packset_metadata, set_metadata_schema, EdgeMetadata);
+
+    table_column_slice_mut!(
+    /// Mutable access to the left column.
+    => left_array_mut, self, *self.table, left, crate::Position);
+
+    table_column_slice_mut!(
+    /// Mutable access to the right column.
+    => right_array_mut, self, *self.table, right, crate::Position);
+
+    table_set_max_increment!(
+    /// Set the number of rows by which the table grows
+    /// when it runs out of space.
+    ///
+    /// See [`OwnedNodeTable::set_max_rows_increment`](crate::OwnedNodeTable::set_max_rows_increment).
+    => set_max_rows_increment, self, *self.table, ll_bindings::tsk_edge_table_set_max_rows_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the metadata
+    /// column grows when it runs out of space.
+    => set_max_metadata_length_increment, self, *self.table, ll_bindings::tsk_edge_table_set_max_metadata_length_increment);
+
+    /// Merge adjacent edges with the same parent and child.
+    ///
+    /// See [`TableCollection::squash_edges`](crate::TableCollection::squash_edges).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut edges = tskit::OwnedEdgeTable::default();
+    /// edges.add_row(0., 5., 0, 1).unwrap();
+    /// edges.add_row(0., 5., 0, 2).unwrap();
+    /// edges.add_row(5., 10., 0, 1).unwrap();
+    /// edges.squash().unwrap();
+    /// assert_eq!(edges.num_rows(), 2);
+    /// ```
+    pub fn squash(&mut self) -> crate::TskReturnValue {
+        let rv = unsafe { ll_bindings::tsk_edge_table_squash(self.as_mut_ptr()) };
+        handle_tsk_return_value!(rv)
+    }
 }
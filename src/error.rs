@@ -31,6 +31,136 @@ pub enum TskitError {
         #[from]
         value: crate::metadata::MetadataError,
     },
+    /// A table has no metadata schema where one is required.
+    #[error("the table has no metadata schema")]
+    MissingMetadataSchema,
+    /// The metadata of a row could not be decoded.
+    ///
+    /// `row` is `None` for the top-level metadata of a
+    /// table collection or tree sequence.
+    #[error("could not decode {}: {}", describe_metadata_row(row), *value)]
+    MetadataDecodeError {
+        row: Option<crate::tsk_id_t>,
+        /// The error returned when decoding
+        #[source]
+        value: crate::metadata::MetadataError,
+    },
+    /// The metadata of a row out of range were requested.
+    #[error("metadata row {} is out of range for a table with {} rows", *row, *num_rows)]
+    MetadataRowOutOfRange {
+        row: crate::tsk_id_t,
+        num_rows: crate::SizeType,
+    },
+    /// A row has no metadata where metadata are required.
+    ///
+    /// `row` is `None` for the top-level metadata of a
+    /// table collection or tree sequence.
+    #[error("{} is empty", describe_metadata_row(row))]
+    UnexpectedEmptyMetadata { row: Option<crate::tsk_id_t> },
+    /// Times with different declared units were compared or combined.
+    ///
+    /// See [`TimeWithUnits`](crate::TimeWithUnits).
+    #[error("time units differ: {} and {}", *left, *right)]
+    TimeUnitsMismatch { left: String, right: String },
+    /// A redirection of [``std::io::Error``]
+    #[error("{}", *.0)]
+    IOError(#[from] std::io::Error),
+}
+
+macro_rules! impl_error_code_predicates {
+    ($($(#[$attr:meta])* $name: ident => [$($code: ident),+];)+) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(&self) -> bool {
+                matches!(self.code(), Some(code) if [$(crate::bindings::$code),+].contains(&code))
+            }
+        )+
+    };
+}
+
+impl TskitError {
+    /// The `tsk_err_*` code of an error returned by the tskit C library,
+    /// or `None` for other errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// tables.add_edge(0., 10., 0, 1).unwrap();
+    /// // The edge refers to nodes that do not exist.
+    /// let e = tables.build_index().and_then(|_| {
+    ///     tables
+    ///         .tree_sequence(tskit::TreeSequenceFlags::default())
+    ///         .map(|_| 0)
+    /// });
+    /// let e = e.unwrap_err();
+    /// assert_eq!(e.code(), Some(tskit::bindings::TSK_ERR_NODE_OUT_OF_BOUNDS));
+    /// assert!(e.is_out_of_bounds());
+    /// assert!(!e.is_unsorted_edges());
+    /// ```
+    pub fn code(&self) -> Option<i32> {
+        match self {
+            TskitError::ErrorCode { code } => Some(*code),
+            _ => None,
+        }
+    }
+
+    /// Return `true` if the error comes from the kastore library,
+    /// which tskit uses for file input and output.
+    pub fn is_kastore_error(&self) -> bool {
+        match self.code() {
+            Some(code) => unsafe { crate::bindings::tsk_is_kas_error(code) },
+            None => false,
+        }
+    }
+
+    impl_error_code_predicates! {
+        /// Return `true` if the edge table is not sorted as required.
+        is_unsorted_edges => [
+            TSK_ERR_EDGES_NOT_SORTED_PARENT_TIME,
+            TSK_ERR_EDGES_NONCONTIGUOUS_PARENTS,
+            TSK_ERR_EDGES_NOT_SORTED_CHILD,
+            TSK_ERR_EDGES_NOT_SORTED_LEFT
+        ];
+        /// Return `true` if the site table is not sorted by position.
+        is_unsorted_sites => [TSK_ERR_UNSORTED_SITES];
+        /// Return `true` if the mutation table is not sorted as required.
+        is_unsorted_mutations => [TSK_ERR_UNSORTED_MUTATIONS, TSK_ERR_MUTATION_PARENT_AFTER_CHILD];
+        /// Return `true` if individuals are not sorted before their parents.
+        is_unsorted_individuals => [TSK_ERR_UNSORTED_INDIVIDUALS];
+        /// Return `true` if the migration table is not sorted by time.
+        is_unsorted_migrations => [TSK_ERR_UNSORTED_MIGRATIONS];
+        /// Return `true` if the tables need an index that is missing or invalid.
+        ///
+        /// See [`TableCollection::build_index`](crate::TableCollection::build_index).
+        is_not_indexed => [TSK_ERR_TABLES_NOT_INDEXED, TSK_ERR_TABLES_BAD_INDEXES];
+        /// Return `true` if an id refers to a row that does not exist.
+        is_out_of_bounds => [
+            TSK_ERR_NODE_OUT_OF_BOUNDS,
+            TSK_ERR_EDGE_OUT_OF_BOUNDS,
+            TSK_ERR_POPULATION_OUT_OF_BOUNDS,
+            TSK_ERR_SITE_OUT_OF_BOUNDS,
+            TSK_ERR_MUTATION_OUT_OF_BOUNDS,
+            TSK_ERR_MIGRATION_OUT_OF_BOUNDS,
+            TSK_ERR_INDIVIDUAL_OUT_OF_BOUNDS,
+            TSK_ERR_PROVENANCE_OUT_OF_BOUNDS
+        ];
+        /// Return `true` if a parameter value is invalid.
+        is_bad_param_value => [TSK_ERR_BAD_PARAM_VALUE];
+        /// Return `true` if the tskit C library failed to allocate memory.
+        is_no_memory => [TSK_ERR_NO_MEMORY];
+        /// Return `true` if a table or column has grown too large.
+        is_overflow => [TSK_ERR_TABLE_OVERFLOW, TSK_ERR_COLUMN_OVERFLOW, TSK_ERR_TREE_OVERFLOW];
+        /// Return `true` if reading or writing a file failed.
+        is_io => [TSK_ERR_IO, TSK_ERR_FILE_FORMAT, TSK_ERR_FILE_VERSION_TOO_OLD, TSK_ERR_FILE_VERSION_TOO_NEW];
+    }
+}
+
+fn describe_metadata_row(row: &Option<crate::tsk_id_t>) -> String {
+    match row {
+        Some(row) => format!("the metadata of row {}", row),
+        None => "the top-level metadata".to_string(),
+    }
 }
 
 /// Takes the return code from a tskit
@@ -136,4 +266,28 @@ mod test {
             panic!();
         }
     }
+
+    #[test]
+    fn test_error_code_predicates() {
+        let e = mock_error().unwrap_err();
+        assert_eq!(
+            e.code(),
+            Some(crate::bindings::TSK_ERR_INDIVIDUAL_OUT_OF_BOUNDS)
+        );
+        assert!(e.is_out_of_bounds());
+        assert!(!e.is_unsorted_edges());
+        assert!(!e.is_kastore_error());
+        assert!(!TskitError::IndexError.is_out_of_bounds());
+        assert!(TskitError::IndexError.code().is_none());
+
+        let mut tables = crate::TableCollection::new(10.).unwrap();
+        let p0 = tables.add_node(0, 1.0, -1, -1).unwrap();
+        let p1 = tables.add_node(0, 2.0, -1, -1).unwrap();
+        let c = tables.add_node(0, 0.0, -1, -1).unwrap();
+        tables.add_edge(0., 10., p1, c).unwrap();
+        tables.add_edge(0., 10., p0, c).unwrap();
+        let e = tables.build_index().unwrap_err();
+        assert!(e.is_unsorted_edges());
+        assert!(!e.is_not_indexed());
+    }
 }
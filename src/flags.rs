@@ -38,6 +38,16 @@ bitflags! {
     /// assert!(flags.contains(SO::KEEP_UNARY));
     /// assert!(!flags.contains(SO::FILTER_POPULATIONS));
     /// ```
+    ///
+    /// ## Parsing from a string
+    ///
+    /// ```
+    /// use tskit::SimplificationOptions as SO;
+    /// let flags = "KEEP_UNARY | FILTER_SITES".parse::<SO>().unwrap();
+    /// assert_eq!(flags, SO::KEEP_UNARY | SO::FILTER_SITES);
+    /// assert_eq!(flags.to_string().parse::<SO>().unwrap(), flags);
+    /// assert!("KEEP_BINARY".parse::<SO>().is_err());
+    /// ```
     #[derive(Default)]
     #[repr(transparent)]
     pub struct SimplificationOptions: RawFlags {
@@ -117,6 +127,49 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Modify behavior of [`crate::TableCollection::subset`]
+    /// and [`crate::TableCollection::canonicalise`].
+    #[derive(Default)]
+    #[repr(transparent)]
+    pub struct SubsetFlags : RawFlags {
+        /// Default behavior.
+        const NONE = 0;
+        /// Do not remove or reorder populations.
+        /// Ignored by [`crate::TableCollection::canonicalise`].
+        const NO_CHANGE_POPULATIONS = ll_bindings::TSK_SUBSET_NO_CHANGE_POPULATIONS;
+        /// Retain populations, individuals, and sites
+        /// that are not referenced by any retained row.
+        const KEEP_UNREFERENCED = ll_bindings::TSK_SUBSET_KEEP_UNREFERENCED;
+    }
+}
+
+bitflags! {
+    /// Modify behavior of [`crate::TableCollection::union`].
+    #[derive(Default)]
+    #[repr(transparent)]
+    pub struct UnionFlags : RawFlags {
+        /// Default behavior.
+        const NONE = 0;
+        /// Do not check that the shared portions of the
+        /// two table collections are equal.
+        const NO_CHECK_SHARED = ll_bindings::TSK_UNION_NO_CHECK_SHARED;
+        /// Nodes added to `self` keep their population ids from `other`
+        /// instead of being assigned to new populations.
+        const NO_ADD_POP = ll_bindings::TSK_UNION_NO_ADD_POP;
+    }
+}
+
+bitflags! {
+    /// Modify behavior of [`crate::TableCollection::compute_mutation_parents`].
+    #[derive(Default)]
+    #[repr(transparent)]
+    pub struct MutationParentsFlags : RawFlags {
+        /// Default behavior.
+        const NONE = 0;
+    }
+}
+
 bitflags! {
     /// Specify the behavior of iterating over [`Tree`] objects.
     /// See [`TreeSequence::tree_iterator`].
@@ -190,9 +243,10 @@ bitflags! {
     }
 }
 
-bitflags! {
+bitflags_with_user_bits! {
     #[derive(Default)]
     #[repr(transparent)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
     /// Node flags
     pub struct NodeFlags : RawFlags {
         /// Default (empty)
@@ -202,9 +256,10 @@ bitflags! {
     }
 }
 
-bitflags! {
+bitflags_with_user_bits! {
     #[derive(Default)]
     #[repr(transparent)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
     /// Individual flags
     pub struct IndividualFlags : RawFlags {
         /// Default (empty)
@@ -221,6 +276,9 @@ impl_flags!(TreeFlags);
 impl_flags!(IndividualTableSortOptions);
 impl_flags!(TableIntegrityCheckFlags);
 impl_flags!(TableOutputOptions);
+impl_flags!(MutationParentsFlags);
+impl_flags!(SubsetFlags);
+impl_flags!(UnionFlags);
 
 impl_from_for_flag_types!(SimplificationOptions);
 impl_from_for_flag_types!(TableClearOptions);
@@ -231,24 +289,79 @@ impl_from_for_flag_types!(TreeFlags);
 impl_from_for_flag_types!(IndividualTableSortOptions);
 impl_from_for_flag_types!(TableIntegrityCheckFlags);
 impl_from_for_flag_types!(TableOutputOptions);
+impl_from_for_flag_types!(MutationParentsFlags);
+impl_from_for_flag_types!(SubsetFlags);
+impl_from_for_flag_types!(UnionFlags);
+impl_from_for_flag_types!(@user NodeFlags);
+impl_from_for_flag_types!(@user IndividualFlags);
 
-impl From<RawFlags> for NodeFlags {
-    fn from(flags: RawFlags) -> Self {
-        // Safety: node flags can contain user-defined values.
-        // It is an error on the user's part to define flags
-        // in the first 16 bits, as per the C API docs.
-        unsafe { Self::from_bits_unchecked(flags) }
-    }
-}
+impl_display_for_flag_types!(SimplificationOptions);
+impl_display_for_flag_types!(TableClearOptions);
+impl_display_for_flag_types!(TableEqualityOptions);
+impl_display_for_flag_types!(TreeSequenceFlags);
+impl_display_for_flag_types!(TableSortOptions);
+impl_display_for_flag_types!(TreeFlags);
+impl_display_for_flag_types!(IndividualTableSortOptions);
+impl_display_for_flag_types!(TableIntegrityCheckFlags);
+impl_display_for_flag_types!(TableOutputOptions);
+impl_display_for_flag_types!(MutationParentsFlags);
+impl_display_for_flag_types!(SubsetFlags);
+impl_display_for_flag_types!(UnionFlags);
+impl_display_for_flag_types!(@user NodeFlags);
+impl_display_for_flag_types!(@user IndividualFlags);
 
-impl From<RawFlags> for IndividualFlags {
-    fn from(flags: RawFlags) -> Self {
-        // Safety: node flags can contain user-defined values.
-        // It is an error on the user's part to define flags
-        // in the first 16 bits, as per the C API docs.
-        unsafe { Self::from_bits_unchecked(flags) }
-    }
-}
+impl_from_str_for_flag_types!(
+    SimplificationOptions,
+    [
+        FILTER_SITES,
+        FILTER_POPULATIONS,
+        FILTER_INDIVIDUALS,
+        REDUCE_TO_SITE_TOPOLOGY,
+        KEEP_UNARY,
+        KEEP_INPUT_ROOTS,
+        KEEP_UNARY_IN_INDIVIDUALS
+    ]
+);
+impl_from_str_for_flag_types!(
+    TableClearOptions,
+    [
+        CLEAR_METADATA_SCHEMAS,
+        CLEAR_TS_METADATA_SCHEMAS,
+        CLEAR_PROVENANCE
+    ]
+);
+impl_from_str_for_flag_types!(
+    TableEqualityOptions,
+    [
+        IGNORE_METADATA,
+        IGNORE_TS_METADATA,
+        IGNORE_PROVENANCE,
+        IGNORE_TIMESTAMPS
+    ]
+);
+impl_from_str_for_flag_types!(TreeSequenceFlags, [BUILD_INDEXES]);
+impl_from_str_for_flag_types!(TableSortOptions, [NO_CHECK_INTEGRITY]);
+impl_from_str_for_flag_types!(TreeFlags, [SAMPLE_LISTS, NO_SAMPLE_COUNTS]);
+impl_from_str_for_flag_types!(IndividualTableSortOptions, []);
+impl_from_str_for_flag_types!(
+    TableIntegrityCheckFlags,
+    [
+        CHECK_EDGE_ORDERING,
+        CHECK_SITE_ORDERING,
+        CHECK_SITE_DUPLICATES,
+        CHECK_MUTATION_ORDERING,
+        CHECK_INDIVIDUAL_ORDERING,
+        CHECK_MIGRATION_ORDERING,
+        CHECK_INDEXES,
+        CHECK_TREES
+    ]
+);
+impl_from_str_for_flag_types!(TableOutputOptions, []);
+impl_from_str_for_flag_types!(MutationParentsFlags, []);
+impl_from_str_for_flag_types!(SubsetFlags, [NO_CHANGE_POPULATIONS, KEEP_UNREFERENCED]);
+impl_from_str_for_flag_types!(UnionFlags, [NO_CHECK_SHARED, NO_ADD_POP]);
+impl_from_str_for_flag_types!(NodeFlags, [IS_SAMPLE]);
+impl_from_str_for_flag_types!(IndividualFlags, []);
 
 impl NodeFlags {
     /// Create a new flags instance with `IS_SAMPLE` set.
@@ -256,13 +369,6 @@ impl NodeFlags {
         Self::IS_SAMPLE
     }
 
-    /// We do not enforce valid flags in the library.
-    /// This function will return `true` if any bits
-    /// are set that do not correspond to allowed flags.
-    pub fn is_valid(&self) -> bool {
-        true
-    }
-
     /// Returns `true` if flags contains `IS_SAMPLE`,
     /// and `false` otherwise.
     pub fn is_sample(&self) -> bool {
@@ -270,14 +376,8 @@ impl NodeFlags {
     }
 }
 
-impl IndividualFlags {
-    /// We do not enforce valid flags in the library.
-    /// This function will return `true` if any bits
-    /// are set that do not correspond to allowed flags.
-    pub fn is_valid(&self) -> bool {
-        true
-    }
-}
+impl_user_bits_for_flag_types!(NodeFlags);
+impl_user_bits_for_flag_types!(IndividualFlags);
 
 #[cfg(test)]
 mod tests {
@@ -294,4 +394,94 @@ mod tests {
         let n = NodeFlags::new_sample();
         assert!(n.is_sample());
     }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(NodeFlags::new_sample().to_string(), "IS_SAMPLE");
+        assert_eq!(NodeFlags::default().to_string(), "NONE");
+        assert_eq!(
+            NodeFlags::from(NodeFlags::IS_SAMPLE.bits() | (1 << 20)).to_string(),
+            "IS_SAMPLE | 0x100000"
+        );
+        assert_eq!(
+            (SimplificationOptions::FILTER_SITES | SimplificationOptions::KEEP_INPUT_ROOTS)
+                .to_string(),
+            "FILTER_SITES | KEEP_INPUT_ROOTS"
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            "KEEP_UNARY|FILTER_SITES"
+                .parse::<SimplificationOptions>()
+                .unwrap(),
+            SimplificationOptions::KEEP_UNARY | SimplificationOptions::FILTER_SITES
+        );
+        assert_eq!("NONE".parse::<TreeFlags>().unwrap(), TreeFlags::default());
+        assert_eq!(
+            " IS_SAMPLE ".parse::<NodeFlags>().unwrap(),
+            NodeFlags::IS_SAMPLE
+        );
+        assert!("".parse::<TreeFlags>().is_err());
+        assert!("IS_SAMPLE".parse::<TreeFlags>().is_err());
+        assert!("0xzz".parse::<TreeFlags>().is_err());
+
+        // Unnamed bits are only accepted where the type keeps them.
+        let flags = NodeFlags::from(NodeFlags::IS_SAMPLE.bits() | (1 << 20));
+        assert_eq!(flags.to_string().parse::<NodeFlags>().unwrap(), flags);
+        assert!("0x100000".parse::<SimplificationOptions>().is_err());
+
+        for flags in [
+            TableEqualityOptions::all(),
+            TableEqualityOptions::empty(),
+            TableEqualityOptions::IGNORE_METADATA,
+        ] {
+            assert_eq!(
+                flags.to_string().parse::<TableEqualityOptions>().unwrap(),
+                flags
+            );
+        }
+    }
+
+    #[test]
+    fn test_user_bits() {
+        let mut flags = NodeFlags::new_sample();
+        flags.set_user_bit(16).unwrap();
+        flags.set_user_bit(31).unwrap();
+        assert!(flags.is_sample());
+        assert!(flags.is_valid());
+        assert!(flags.user_bit(16).unwrap());
+        assert!(!flags.user_bit(17).unwrap());
+        assert_eq!(flags.user_bits(), 1 << 16 | 1 << 31);
+        assert_eq!(flags.reserved_bits(), NodeFlags::IS_SAMPLE.bits());
+        flags.clear_user_bit(31).unwrap();
+        assert_eq!(flags.user_bits(), 1 << 16);
+
+        for bit in [0, 15, 32, u32::MAX] {
+            assert!(flags.set_user_bit(bit).is_err());
+            assert!(flags.user_bit(bit).is_err());
+        }
+
+        let mut flags = IndividualFlags::default();
+        flags.set_user_bit(20).unwrap();
+        assert!(flags.is_valid());
+        assert_eq!(IndividualFlags::from(1 | 1 << 20).bits(), 1 << 20);
+        assert!(IndividualFlags::from(1).is_valid());
+
+        let raw = NodeFlags::IS_SAMPLE.bits() << 1 | 1 << 31;
+        let flags = NodeFlags::from_bits_retaining_user(raw);
+        assert_eq!(flags, NodeFlags::from(raw));
+        assert_eq!(flags, NodeFlags::USER_BIT_31);
+        assert!(flags.is_valid());
+        assert_eq!(flags.to_string(), "0x80000000");
+        assert_eq!("0x80000000".parse::<NodeFlags>().unwrap(), flags);
+        assert!("0x2".parse::<NodeFlags>().is_err());
+        assert_eq!(NodeFlags::USER_BITS, IndividualFlags::USER_BITS);
+        assert_eq!(
+            NodeFlags::all().bits() & NodeFlags::USER_BITS,
+            NodeFlags::USER_BITS
+        );
+        assert_eq!(IndividualFlags::all().bits(), IndividualFlags::USER_BITS);
+    }
 }
@@ -7,6 +7,7 @@ use crate::{tsk_id_t, tsk_size_t, TskitError};
 use ll_bindings::{tsk_individual_table_free, tsk_individual_table_init};
 
 /// Row of a [`IndividualTable`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IndividualTableRow {
     pub id: IndividualId,
     pub flags: IndividualFlags,
@@ -42,6 +43,61 @@ impl PartialEq for IndividualTableRow {
     }
 }
 
+impl std::fmt::Display for IndividualTableRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "id: {}, flags: {}, location: {}, parents: {}, metadata: {}",
+            self.id,
+            self.flags,
+            crate::util::DisplayRagged(&self.location),
+            crate::util::DisplayRagged(&self.parents),
+            crate::util::DisplayMetadata(&self.metadata),
+        )
+    }
+}
+
+impl IndividualTableRow {
+    /// The location of the individual.
+    ///
+    /// An empty slice is returned if there is no location.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(1.).unwrap();
+    /// tables.add_individual(0, [0.5, 1.5], None).unwrap();
+    /// tables.add_individual(0, None, None).unwrap();
+    /// let rows = tables.individuals().iter().collect::<Vec<_>>();
+    /// assert_eq!(rows[0].location_slice(), &[0.5, 1.5]);
+    /// assert!(rows[1].location_slice().is_empty());
+    /// ```
+    pub fn location_slice(&self) -> &[Location] {
+        self.location.as_deref().unwrap_or(&[])
+    }
+
+    /// The parents of the individual.
+    ///
+    /// An empty slice is returned if there are no parents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(1.).unwrap();
+    /// tables.add_individual(0, None, None).unwrap();
+    /// let parents = [tskit::IndividualId::from(0), tskit::IndividualId::NULL];
+    /// tables.add_individual(0, None, &parents[..]).unwrap();
+    /// let row = tables.individuals().row(1).unwrap();
+    /// assert_eq!(row.parents_slice(), &parents);
+    /// assert!(tables.individuals().row(0).unwrap().parents_slice().is_empty());
+    /// ```
+    pub fn parents_slice(&self) -> &[IndividualId] {
+        self.parents.as_deref().unwrap_or(&[])
+    }
+}
+
 /// An immutable view of a individual table.
 ///
 /// These are not created directly.
@@ -51,11 +107,58 @@ pub struct IndividualTable<'a> {
     table_: &'a ll_bindings::tsk_individual_table_t,
 }
 
+impl_send_sync_for_table_view!(IndividualTable);
+impl_table_columns!(
+    IndividualTable,
+    Individuals,
+    [(flags, UInt32)],
+    [
+        (location, Float64, location_length),
+        (parents, Int32, parents_length),
+        (metadata, UInt8, metadata_length)
+    ]
+);
+impl_table_equality!(IndividualTable, tsk_individual_table_equals);
+
+/// A view of a row of a [`IndividualTable`].
+///
+/// Unlike [`IndividualTableRow`], the view borrows its data from the table.
+/// Views are returned by [`IndividualTable::get`] and [`IndividualTable::iter_views`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IndividualTableRowView<'a> {
+    pub id: crate::IndividualId,
+    pub flags: crate::IndividualFlags,
+    pub location: Option<&'a [crate::Location]>,
+    pub parents: Option<&'a [crate::IndividualId]>,
+    pub metadata: Option<&'a [u8]>,
+}
+
+impl_row_view_metadata!(IndividualTableRowView);
+impl_row_view_into_row!(
+    IndividualTableRowView,
+    IndividualTableRow,
+    [id, flags],
+    [location, parents, metadata]
+);
+
+fn make_individual_table_row_view<'a>(
+    table: &'a IndividualTable<'_>,
+    pos: tsk_id_t,
+) -> IndividualTableRowView<'a> {
+    // pos is in range, so neither the indexing nor the unwraps can fail
+    let index = usize::try_from(pos).unwrap();
+    IndividualTableRowView {
+        id: pos.into(),
+        flags: table.flags_slice()[index],
+        location: table.location(pos).unwrap(),
+        parents: table.parents(pos).unwrap(),
+        metadata: table.metadata_bytes(pos).unwrap(),
+    }
+}
+
 fn make_individual_table_row(table: &IndividualTable, pos: tsk_id_t) -> Option<IndividualTableRow> {
-    // panic is okay here, as we are handling a bad
-    // input value before we first call this to
-    // set up the iterator
-    let p = crate::SizeType::try_from(pos).unwrap();
+    // A negative pos is not a row, so there is nothing to return.
+    let p = crate::SizeType::try_from(pos).ok()?;
     if p < table.num_rows() {
         let table_ref = table.table_;
         let rv = IndividualTableRow {
@@ -96,6 +199,13 @@ impl<'a> Iterator for IndividualTableIterator<'a> {
     }
 }
 
+impl_table_into_iterator!(
+    IndividualTable,
+    OwnedIndividualTable,
+    IndividualTableRow,
+    make_individual_table_row
+);
+
 impl<'a> IndividualTable<'a> {
     pub(crate) fn new_from_table(individuals: &'a ll_bindings::tsk_individual_table_t) -> Self {
         IndividualTable {
@@ -103,11 +213,107 @@ impl<'a> IndividualTable<'a> {
         }
     }
 
+    /// Return a pointer to the underlying C table, for use
+    /// with the functions in [`bindings`](crate::bindings).
+    pub fn as_ptr(&self) -> *const ll_bindings::tsk_individual_table_t {
+        self.table_
+    }
+
     /// Return the number of rows
-    pub fn num_rows(&'a self) -> crate::SizeType {
+    pub fn num_rows(&self) -> crate::SizeType {
         self.table_.num_rows.into()
     }
 
+    /// Return the number of bytes used by the data in this table.
+    pub fn nbytes(&self) -> usize {
+        let t = self.table_;
+        crate::util::column_nbytes::<crate::tsk_flags_t>(t.num_rows)
+            + crate::util::ragged_column_nbytes::<f64>(t.location_length, t.num_rows)
+            + crate::util::ragged_column_nbytes::<crate::tsk_id_t>(t.parents_length, t.num_rows)
+            + crate::util::ragged_column_nbytes::<u8>(t.metadata_length, t.num_rows)
+            + crate::util::column_nbytes::<u8>(t.metadata_schema_length)
+    }
+
+    build_table_metadata_schema_getter!();
+
+    build_table_metadata_bytes_access!(
+        IndividualId;
+        ///
+        /// # Examples
+        ///
+        /// For all examples, this is our metadata type.
+        /// We will add all instances with a value of `x = 1`.
+        ///
+        /// ```
+        /// # #[cfg(feature = "derive")] {
+        /// #[derive(serde::Serialize, serde::Deserialize, tskit::metadata::IndividualMetadata)]
+        /// #[serializer("serde_json")]
+        /// struct IndividualMetadata {
+        ///    x: i32,
+        /// }
+        /// # }
+        /// ```
+        ///
+        /// ## Without matches
+        ///
+        /// ```
+        /// # #[cfg(feature = "derive")] {
+        /// # use tskit::TableAccess;
+        /// # let mut tables = tskit::TableCollection::new(100.).unwrap();
+        /// # #[derive(serde::Serialize, serde::Deserialize, tskit::metadata::IndividualMetadata)]
+        /// # #[serializer("serde_json")]
+        /// # struct IndividualMetadata {
+        /// #    x: i32,
+        /// # }
+        /// # let metadata = IndividualMetadata{x: 1};
+        /// # assert!(tables.add_individual_with_metadata(0, None, None,
+        /// #                                             &metadata).is_ok());
+        /// // We know the metadata are here, so we unwrap the Result and the Option
+        /// let decoded = tables.individuals().metadata::<IndividualMetadata>(0).unwrap().unwrap();
+        /// assert_eq!(decoded.x, 1);
+        /// # }
+        /// ```
+        ///
+        /// ## Checking for errors and absence of metadata
+        ///
+        /// Handling both the possibility of error and optional metadata leads to some verbosity:
+        ///
+        /// ```
+        /// # #[cfg(feature = "derive")] {
+        /// # use tskit::TableAccess;
+        /// # let mut tables = tskit::TableCollection::new(100.).unwrap();
+        /// # #[derive(serde::Serialize, serde::Deserialize, tskit::metadata::IndividualMetadata)]
+        /// # #[serializer("serde_json")]
+        /// # struct IndividualMetadata {
+        /// #     x: i32,
+        /// # }
+        /// # let metadata = IndividualMetadata { x: 1 };
+        /// # assert!(tables
+        /// #     .add_individual_with_metadata(0, None, None, &metadata)
+        /// #     .is_ok());
+        /// // First, check the Result.
+        /// let decoded_option = match tables
+        ///     .individuals()
+        ///     .metadata::<IndividualMetadata>(0)
+        /// {
+        ///     Ok(metadata_option) => metadata_option,
+        ///     Err(e) => panic!("error: {:?}", e),
+        /// };
+        /// // Now, check the contents of the Option
+        /// match decoded_option {
+        ///     Some(metadata) => assert_eq!(metadata.x, 1),
+        ///     None => panic!("we expected Some(metadata)?"),
+        /// }
+        /// # }
+        /// ```
+    );
+
+    build_table_row_views!(
+        IndividualId,
+        IndividualTableRowView,
+        make_individual_table_row_view
+    );
+
     /// Return the flags for a given row.
     ///
     /// # Errors
@@ -163,94 +369,9 @@ impl<'a> IndividualTable<'a> {
         )
     }
 
-    /// Return the metadata for a given row.
-    ///
-    /// # Returns
-    ///
-    /// The result type is `Option<T>`
-    /// where `T`: [`tskit::metadata::IndividualMetadata`](crate::metadata::IndividualMetadata).
-    /// `Some(T)` if there is metadata.  `None` if the metadata field is empty for a given
-    /// row.
-    ///
-    /// # Errors
-    ///
-    /// * [`TskitError::IndexError`] if `row` is out of range.
-    ///
-    /// # Examples
-    ///
-    /// For all examples, this is our metadata type.
-    /// We will add all instances with a value of `x = 1`.
-    ///
-    /// ```
-    /// # #[cfg(feature = "derive")] {
-    /// #[derive(serde::Serialize, serde::Deserialize, tskit::metadata::IndividualMetadata)]
-    /// #[serializer("serde_json")]
-    /// struct IndividualMetadata {
-    ///    x: i32,
-    /// }
-    /// # }
-    /// ```
-    ///
-    /// ## Without matches
-    ///
-    /// ```
-    /// # #[cfg(feature = "derive")] {
-    /// # use tskit::TableAccess;
-    /// # let mut tables = tskit::TableCollection::new(100.).unwrap();
-    /// # #[derive(serde::Serialize, serde::Deserialize, tskit::metadata::IndividualMetadata)]
-    /// # #[serializer("serde_json")]
-    /// # struct IndividualMetadata {
-    /// #    x: i32,
-    /// # }
-    /// # let metadata = IndividualMetadata{x: 1};
-    /// # assert!(tables.add_individual_with_metadata(0, None, None,
-    /// #                                             &metadata).is_ok());
-    /// // We know the metadata are here, so we unwrap the Result and the Option
-    /// let decoded = tables.individuals().metadata::<IndividualMetadata>(0.into()).unwrap().unwrap();
-    /// assert_eq!(decoded.x, 1);
-    /// # }
-    /// ```
-    ///
-    /// ## Checking for errors and absence of metadata
-    ///
-    /// Handling both the possibility of error and optional metadata leads to some verbosity:
-    ///
-    /// ```
-    /// # #[cfg(feature = "derive")] {
-    /// # use tskit::TableAccess;
-    /// # let mut tables = tskit::TableCollection::new(100.).unwrap();
-    /// # #[derive(serde::Serialize, serde::Deserialize, tskit::metadata::IndividualMetadata)]
-    /// # #[serializer("serde_json")]
-    /// # struct IndividualMetadata {
-    /// #     x: i32,
-    /// # }
-    /// # let metadata = IndividualMetadata { x: 1 };
-    /// # assert!(tables
-    /// #     .add_individual_with_metadata(0, None, None, &metadata)
-    /// #     .is_ok());
-    /// // First, check the Result.
-    /// let decoded_option = match tables
-    ///     .individuals()
-    ///     .metadata::<IndividualMetadata>(0.into())
-    /// {
-    ///     Ok(metadata_option) => metadata_option,
-    ///     Err(e) => panic!("error: {:?}", e),
-    /// };
-    /// // Now, check the contents of the Option
-    /// match decoded_option {
-    ///     Some(metadata) => assert_eq!(metadata.x, 1),
-    ///     None => panic!("we expected Some(metadata)?"),
-    /// }
-    /// # }
-    /// ```
-    pub fn metadata<T: metadata::MetadataRoundtrip>(
-        &'a self,
-        row: IndividualId,
-    ) -> Result<Option<T>, TskitError> {
-        let table_ref = self.table_;
-        let buffer = metadata_to_vector!(self, table_ref, row.0)?;
-        decode_metadata_row!(T, buffer)
-    }
+    build_table_column_slice_getter!(
+        /// Get the flags column as a slice
+        => flags, flags_slice, crate::IndividualFlags);
 
     /// Return an iterator over rows of the table.
     /// The value of the iterator is [`IndividualTableRow`].
@@ -280,6 +401,146 @@ impl<'a> IndividualTable<'a> {
     }
 }
 
+/// Builder for adding a row to an individual table.
+///
+/// By default, the individual has no flags, location,
+/// parents, or metadata.
+///
+/// The row is checked before it is passed to the C library,
+/// so that common mistakes give descriptive errors:
+///
+/// * locations must be finite,
+/// * parents must be valid ids or [`IndividualId::NULL`],
+/// * an individual cannot be its own parent.
+///
+/// Obtained from [`OwnedIndividualTable::add_row_defaults`]
+/// or [`TableCollection::add_individual_defaults`](crate::TableCollection::add_individual_defaults).
+///
+/// # Examples
+///
+/// ```
+/// let mut individuals = tskit::OwnedIndividualTable::default();
+/// let parent = individuals.add_row_defaults().location(&[0.5]).build().unwrap();
+/// let child = individuals
+///     .add_row_defaults()
+///     .parents(&[parent, tskit::IndividualId::NULL])
+///     .build()
+///     .unwrap();
+/// assert_eq!(
+///     individuals.parents(child).unwrap().unwrap(),
+///     [parent, tskit::IndividualId::NULL]
+/// );
+///
+/// // An individual cannot be its own parent
+/// assert!(individuals.add_row_defaults().parents(&[2]).build().is_err());
+/// ```
+#[must_use = "the row is not added until build is called"]
+pub struct IndividualRowBuilder<'a> {
+    table: &'a mut ll_bindings::tsk_individual_table_t,
+    flags: IndividualFlags,
+    location: Vec<Location>,
+    parents: Vec<IndividualId>,
+    metadata: Result<Option<metadata::EncodedMetadata>, TskitError>,
+}
+
+impl<'a> IndividualRowBuilder<'a> {
+    pub(crate) fn new(table: &'a mut ll_bindings::tsk_individual_table_t) -> Self {
+        Self {
+            table,
+            flags: IndividualFlags::default(),
+            location: vec![],
+            parents: vec![],
+            metadata: Ok(None),
+        }
+    }
+
+    /// Set the individual flags.
+    pub fn flags<F: Into<IndividualFlags>>(mut self, flags: F) -> Self {
+        self.flags = flags.into();
+        self
+    }
+
+    /// Set the location of the individual.
+    pub fn location<L: crate::IndividualLocation + ?Sized>(mut self, location: &L) -> Self {
+        self.location = location.get_slice().to_vec();
+        self
+    }
+
+    /// Set the parents of the individual.
+    pub fn parents<P: crate::IndividualParents + ?Sized>(mut self, parents: &P) -> Self {
+        self.parents = parents.get_slice().to_vec();
+        self
+    }
+
+    /// Set the metadata of the individual.
+    ///
+    /// Encoding errors are reported by [`IndividualRowBuilder::build`].
+    pub fn metadata<M: metadata::IndividualMetadata>(mut self, metadata: &M) -> Self {
+        self.metadata = metadata::EncodedMetadata::new(metadata)
+            .map(Some)
+            .map_err(TskitError::from);
+        self
+    }
+
+    fn validate(&self) -> Result<(), TskitError> {
+        if let Some(x) = self.location.iter().find(|x| !f64::from(**x).is_finite()) {
+            return Err(TskitError::ValueError {
+                got: format!("location {}", f64::from(*x)),
+                expected: "finite locations".to_string(),
+            });
+        }
+        let id = self.table.num_rows as tsk_id_t;
+        for &parent in &self.parents {
+            if parent < IndividualId::NULL {
+                return Err(TskitError::ValueError {
+                    got: format!("parent {}", parent),
+                    expected: "a valid individual id or IndividualId::NULL".to_string(),
+                });
+            }
+            if parent == id {
+                return Err(TskitError::ValueError {
+                    got: format!("individual {} as its own parent", id),
+                    expected: "parents other than the new individual".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the row and add it to the table.
+    ///
+    /// # Returns
+    ///
+    /// The id of the new individual.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if the row is not valid.
+    /// * [`TskitError::MetadataError`] if encoding the metadata failed.
+    /// * [`TskitError`] if the C library returns an error code.
+    pub fn build(self) -> Result<IndividualId, TskitError> {
+        self.validate()?;
+        let md = self.metadata?;
+        let (md_ptr, md_len) = match &md {
+            Some(md) => (md.as_ptr(), md.len().into()),
+            None => (std::ptr::null(), 0),
+        };
+        let rv = unsafe {
+            ll_bindings::tsk_individual_table_add_row(
+                self.table,
+                self.flags.bits(),
+                self.location.as_ptr().cast::<f64>(),
+                self.location.len() as tsk_size_t,
+                self.parents.as_ptr().cast::<tsk_id_t>(),
+                self.parents.len() as tsk_size_t,
+                md_ptr,
+                md_len,
+            )
+        };
+        handle_tsk_return_value!(rv, rv.into())
+    }
+}
+
 build_owned_table_type!(
     /// A standalone individual table that owns its data.
     ///
@@ -329,10 +590,122 @@ build_owned_table_type!(
     tsk_individual_table_t,
     tsk_individual_table_init,
     tsk_individual_table_free,
-    crate::bindings::tsk_individual_table_clear
+    crate::bindings::tsk_individual_table_clear,
+    crate::bindings::tsk_individual_table_truncate
 );
 
 impl OwnedIndividualTable {
     individual_table_add_row!(=> add_row, self, *self.table);
     individual_table_add_row_with_metadata!(=> add_row_with_metadata, self, *self.table);
+
+    /// Start adding a row whose columns take default values.
+    ///
+    /// See [`IndividualRowBuilder`] for details.
+    pub fn add_row_defaults(&mut self) -> IndividualRowBuilder<'_> {
+        IndividualRowBuilder::new(&mut self.table)
+    }
+    table_set_metadata_schema!(
+    /// Set the metadata schema of the table.
+    => set_metadata_schema, self, *self.table, ll_bindings::tsk_individual_table_set_metadata_schema);
+    individual_table_update_row!(
+    /// Replace the contents of row `row`.
+    ///
+    /// The row's metadata are cleared.
+    => update_row, self, *self.table);
+    individual_table_update_row_with_metadata!(
+    /// Replace the contents of row `row`, including its metadata.
+    => update_row_with_metadata, self, *self.table);
+
+    /// Replace the metadata of row `row`, keeping its other values.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`](crate::TskitError::IndexError) if `row` is out of range.
+    /// * [`TskitError::MetadataError`](crate::TskitError::MetadataError) if encoding fails.
+    pub fn set_metadata<
+        R: Into<crate::IndividualId> + Copy,
+        M: crate::metadata::IndividualMetadata,
+    >(
+        &mut self,
+        row: R,
+        metadata: &M,
+    ) -> crate::TskReturnValue {
+        let r = self.get(row).ok_or(crate::TskitError::IndexError)?;
+        let (id, flags) = (r.id, r.flags);
+        let location = r.location.map(<[_]>::to_vec).unwrap_or_default();
+        let parents = r.parents.map(<[_]>::to_vec).unwrap_or_default();
+        self.update_row_with_metadata(id, flags, location, parents, metadata)
+    }
+    individual_table_set_columns!(
+    /// Replace the contents of the table with the
+    /// flags, location, and parents columns.
+    ///
+    /// All rows are loaded with a single call to the C API.
+    /// Metadata are cleared.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`](crate::TskitError::ValueError) if the columns
+    /// do not all have the same length.
+    => set_columns, self, *self.table);
+
+    /// Create a table from columns.
+    ///
+    /// See [`OwnedIndividualTable::set_columns`] for details.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`](crate::TskitError::ValueError) if the columns
+    /// do not all have the same length.
+    pub fn from_columns<L, P>(
+        flags: &[crate::IndividualFlags],
+        location: &[L],
+        parents: &[P],
+    ) -> Result<Self, crate::TskitError>
+    where
+        L: crate::IndividualLocation,
+        P: crate::IndividualParents,
+    {
+        let mut table = Self::default();
+        table.set_columns(flags, location, parents)?;
+        Ok(table)
+    }
+
+    table_packset_metadata!(
+    /// Replace the metadata column of the table.
+    ///
+    /// See [`OwnedNodeTable::packset_metadata`](crate::OwnedNodeTable::packset_metadata).
+    => packset_metadata, self, *self.table);
+
+    table_migrate_metadata!(
+    /// Re-encode the metadata of every row with a new schema.
+    ///
+    /// See [`OwnedNodeTable::migrate_metadata`](crate::OwnedNodeTable::migrate_metadata).
+    => migrate_metadata, self, &*self, packset_metadata, set_metadata_schema, IndividualMetadata);
+
+    table_column_slice_mut!(
+    /// Mutable access to the flags column.
+    => flags_array_mut, self, *self.table, flags, crate::IndividualFlags);
+
+    table_set_max_increment!(
+    /// Set the number of rows by which the table grows
+    /// when it runs out of space.
+    ///
+    /// See [`OwnedNodeTable::set_max_rows_increment`](crate::OwnedNodeTable::set_max_rows_increment).
+    => set_max_rows_increment, self, *self.table, ll_bindings::tsk_individual_table_set_max_rows_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the metadata
+    /// column grows when it runs out of space.
+    => set_max_metadata_length_increment, self, *self.table, ll_bindings::tsk_individual_table_set_max_metadata_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the location
+    /// column grows when it runs out of space.
+    => set_max_location_length_increment, self, *self.table, ll_bindings::tsk_individual_table_set_max_location_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the parents
+    /// column grows when it runs out of space.
+    => set_max_parents_length_increment, self, *self.table, ll_bindings::tsk_individual_table_set_max_parents_length_increment);
 }
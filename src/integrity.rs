@@ -0,0 +1,502 @@
+//! Locating the rows that fail integrity checks.
+//!
+//! The `C` API only reports an error code.
+//! The functions here repeat its per-row checks, in the same order,
+//! to find the table and row responsible for that code.
+
+use crate::bindings as ll_bindings;
+use crate::types::TableKind;
+use crate::SizeType;
+use ll_bindings::{tsk_id_t, TSK_NULL};
+
+/// The result of a failed call to
+/// [`TableCollection::check_integrity_report`](crate::TableCollection::check_integrity_report).
+///
+/// # Examples
+///
+/// ```
+/// let mut tables = tskit::TableCollection::new(10.).unwrap();
+/// tables.add_node(0, 1.0, -1, -1).unwrap();
+/// tables.add_node(0, 0.0, -1, -1).unwrap();
+/// tables.add_edge(0., 10., 0, 1).unwrap();
+/// // Node 2 does not exist
+/// tables.add_edge(0., 10., 0, 2).unwrap();
+/// let report = tables
+///     .check_integrity_report(tskit::TableIntegrityCheckFlags::default())
+///     .unwrap_err();
+/// assert_eq!(report.table, Some(tskit::types::TableKind::Edges));
+/// assert_eq!(report.row.map(u64::from), Some(1));
+/// assert_eq!(report.code, tskit::bindings::TSK_ERR_NODE_OUT_OF_BOUNDS);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// The error code returned by the `C` API.
+    pub code: i32,
+    /// The table that failed the check, if known.
+    pub table: Option<TableKind>,
+    /// The first row of `table` that failed the check, if known.
+    pub row: Option<SizeType>,
+}
+
+impl IntegrityReport {
+    /// The error message describing the failed constraint.
+    pub fn message(&self) -> String {
+        crate::error::get_tskit_error_message(self.code)
+    }
+}
+
+impl std::fmt::Display for IntegrityReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match (self.table, self.row) {
+            (Some(table), Some(row)) => write!(
+                f,
+                "{} table, row {}: {}",
+                table,
+                u64::from(row),
+                self.message()
+            ),
+            (Some(table), None) => write!(f, "{} table: {}", table, self.message()),
+            _ => write!(f, "{}", self.message()),
+        }
+    }
+}
+
+impl std::error::Error for IntegrityReport {}
+
+impl From<IntegrityReport> for crate::TskitError {
+    fn from(report: IntegrityReport) -> Self {
+        crate::TskitError::ErrorCode { code: report.code }
+    }
+}
+
+fn in_bounds(id: tsk_id_t, n: usize) -> bool {
+    id >= 0 && (id as usize) < n
+}
+
+fn in_bounds_or_null(id: tsk_id_t, n: usize) -> bool {
+    id == TSK_NULL || in_bounds(id, n)
+}
+
+type RowCheck = Option<(usize, i32)>;
+
+fn check_nodes(
+    tables: &ll_bindings::tsk_table_collection_t,
+    check_population_refs: bool,
+) -> RowCheck {
+    let nodes = &tables.nodes;
+    let time = table_column!(nodes, time);
+    let population = table_column!(nodes, population);
+    let individual = table_column!(nodes, individual);
+    let num_populations = tables.populations.num_rows as usize;
+    let num_individuals = tables.individuals.num_rows as usize;
+    for j in 0..time.len() {
+        if !time[j].is_finite() {
+            return Some((j, ll_bindings::TSK_ERR_TIME_NONFINITE));
+        }
+        if check_population_refs && !in_bounds_or_null(population[j], num_populations) {
+            return Some((j, ll_bindings::TSK_ERR_POPULATION_OUT_OF_BOUNDS));
+        }
+        if !in_bounds_or_null(individual[j], num_individuals) {
+            return Some((j, ll_bindings::TSK_ERR_INDIVIDUAL_OUT_OF_BOUNDS));
+        }
+    }
+    None
+}
+
+fn check_edges(tables: &ll_bindings::tsk_table_collection_t, check_ordering: bool) -> RowCheck {
+    let edges = &tables.edges;
+    let left = table_column!(edges, left);
+    let right = table_column!(edges, right);
+    let parent = table_column!(edges, parent);
+    let child = table_column!(edges, child);
+    let time = table_column!(&tables.nodes, time);
+    let num_nodes = time.len();
+    let mut parent_seen = vec![false; num_nodes];
+    for j in 0..left.len() {
+        let (p, c) = (parent[j], child[j]);
+        if p == TSK_NULL {
+            return Some((j, ll_bindings::TSK_ERR_NULL_PARENT));
+        }
+        if !in_bounds(p, num_nodes) {
+            return Some((j, ll_bindings::TSK_ERR_NODE_OUT_OF_BOUNDS));
+        }
+        if c == TSK_NULL {
+            return Some((j, ll_bindings::TSK_ERR_NULL_CHILD));
+        }
+        if !in_bounds(c, num_nodes) {
+            return Some((j, ll_bindings::TSK_ERR_NODE_OUT_OF_BOUNDS));
+        }
+        if !(left[j].is_finite() && right[j].is_finite()) {
+            return Some((j, ll_bindings::TSK_ERR_GENOME_COORDS_NONFINITE));
+        }
+        if left[j] < 0. {
+            return Some((j, ll_bindings::TSK_ERR_LEFT_LESS_ZERO));
+        }
+        if right[j] > tables.sequence_length {
+            return Some((j, ll_bindings::TSK_ERR_RIGHT_GREATER_SEQ_LENGTH));
+        }
+        if left[j] >= right[j] {
+            return Some((j, ll_bindings::TSK_ERR_BAD_EDGE_INTERVAL));
+        }
+        let (p, c) = (p as usize, c as usize);
+        if time[c] >= time[p] {
+            return Some((j, ll_bindings::TSK_ERR_BAD_NODE_TIME_ORDERING));
+        }
+        if check_ordering {
+            if parent_seen[p] {
+                return Some((j, ll_bindings::TSK_ERR_EDGES_NONCONTIGUOUS_PARENTS));
+            }
+            if j > 0 {
+                let last_parent = parent[j - 1] as usize;
+                if time[p] < time[last_parent] {
+                    return Some((j, ll_bindings::TSK_ERR_EDGES_NOT_SORTED_PARENT_TIME));
+                }
+                if time[p] == time[last_parent] {
+                    if p == last_parent {
+                        if child[j] < child[j - 1] {
+                            return Some((j, ll_bindings::TSK_ERR_EDGES_NOT_SORTED_CHILD));
+                        }
+                        if child[j] == child[j - 1] {
+                            if left[j] == left[j - 1] {
+                                return Some((j, ll_bindings::TSK_ERR_DUPLICATE_EDGES));
+                            } else if left[j] < left[j - 1] {
+                                return Some((j, ll_bindings::TSK_ERR_EDGES_NOT_SORTED_LEFT));
+                            }
+                        }
+                    } else {
+                        parent_seen[last_parent] = true;
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+fn check_sites(
+    tables: &ll_bindings::tsk_table_collection_t,
+    check_ordering: bool,
+    check_duplicates: bool,
+) -> RowCheck {
+    let position = table_column!(&tables.sites, position);
+    for j in 0..position.len() {
+        if !position[j].is_finite() || position[j] < 0. || position[j] >= tables.sequence_length {
+            return Some((j, ll_bindings::TSK_ERR_BAD_SITE_POSITION));
+        }
+        if j > 0 {
+            if check_duplicates && position[j - 1] == position[j] {
+                return Some((j, ll_bindings::TSK_ERR_DUPLICATE_SITE_POSITION));
+            }
+            if check_ordering && position[j - 1] > position[j] {
+                return Some((j, ll_bindings::TSK_ERR_UNSORTED_SITES));
+            }
+        }
+    }
+    None
+}
+
+fn check_mutations(tables: &ll_bindings::tsk_table_collection_t, check_ordering: bool) -> RowCheck {
+    let mutations = &tables.mutations;
+    let site = table_column!(mutations, site);
+    let node = table_column!(mutations, node);
+    let parent = table_column!(mutations, parent);
+    let time = table_column!(mutations, time);
+    let node_time = table_column!(&tables.nodes, time);
+    let num_sites = tables.sites.num_rows as usize;
+    let mut last_known_time = f64::INFINITY;
+    let mut num_known_times = 0;
+    let mut num_unknown_times = 0;
+    for j in 0..site.len() {
+        if !in_bounds(site[j], num_sites) {
+            return Some((j, ll_bindings::TSK_ERR_SITE_OUT_OF_BOUNDS));
+        }
+        if !in_bounds(node[j], node_time.len()) {
+            return Some((j, ll_bindings::TSK_ERR_NODE_OUT_OF_BOUNDS));
+        }
+        let p = parent[j];
+        if !in_bounds_or_null(p, site.len()) {
+            return Some((j, ll_bindings::TSK_ERR_MUTATION_OUT_OF_BOUNDS));
+        }
+        if p == j as tsk_id_t {
+            return Some((j, ll_bindings::TSK_ERR_MUTATION_PARENT_EQUAL));
+        }
+        let unknown_time = unsafe { ll_bindings::tsk_is_unknown_time(time[j]) };
+        if !unknown_time {
+            if !time[j].is_finite() {
+                return Some((j, ll_bindings::TSK_ERR_TIME_NONFINITE));
+            }
+            if time[j] < node_time[node[j] as usize] {
+                return Some((j, ll_bindings::TSK_ERR_MUTATION_TIME_YOUNGER_THAN_NODE));
+            }
+        }
+        if j > 0 && site[j - 1] != site[j] {
+            last_known_time = f64::INFINITY;
+            num_known_times = 0;
+            num_unknown_times = 0;
+        }
+        if unknown_time {
+            num_unknown_times += 1;
+        } else {
+            num_known_times += 1;
+        }
+        if num_unknown_times > 0 && num_known_times > 0 {
+            return Some((
+                j,
+                ll_bindings::TSK_ERR_MUTATION_TIME_HAS_BOTH_KNOWN_AND_UNKNOWN,
+            ));
+        }
+        if p != TSK_NULL {
+            if site[p as usize] != site[j] {
+                return Some((j, ll_bindings::TSK_ERR_MUTATION_PARENT_DIFFERENT_SITE));
+            }
+            if !unknown_time && time[j] > time[p as usize] {
+                return Some((
+                    j,
+                    ll_bindings::TSK_ERR_MUTATION_TIME_OLDER_THAN_PARENT_MUTATION,
+                ));
+            }
+        }
+        if check_ordering {
+            if j > 0 && site[j - 1] > site[j] {
+                return Some((j, ll_bindings::TSK_ERR_UNSORTED_MUTATIONS));
+            }
+            if p != TSK_NULL && p > j as tsk_id_t {
+                return Some((j, ll_bindings::TSK_ERR_MUTATION_PARENT_AFTER_CHILD));
+            }
+            if !unknown_time {
+                if time[j] > last_known_time {
+                    return Some((j, ll_bindings::TSK_ERR_UNSORTED_MUTATIONS));
+                }
+                last_known_time = time[j];
+            }
+        }
+    }
+    None
+}
+
+fn check_migrations(
+    tables: &ll_bindings::tsk_table_collection_t,
+    check_population_refs: bool,
+    check_ordering: bool,
+) -> RowCheck {
+    let migrations = &tables.migrations;
+    let left = table_column!(migrations, left);
+    let right = table_column!(migrations, right);
+    let node = table_column!(migrations, node);
+    let source = table_column!(migrations, source);
+    let dest = table_column!(migrations, dest);
+    let time = table_column!(migrations, time);
+    let num_nodes = tables.nodes.num_rows as usize;
+    let num_populations = tables.populations.num_rows as usize;
+    for j in 0..node.len() {
+        if !in_bounds(node[j], num_nodes) {
+            return Some((j, ll_bindings::TSK_ERR_NODE_OUT_OF_BOUNDS));
+        }
+        if check_population_refs
+            && !(in_bounds(source[j], num_populations) && in_bounds(dest[j], num_populations))
+        {
+            return Some((j, ll_bindings::TSK_ERR_POPULATION_OUT_OF_BOUNDS));
+        }
+        if !time[j].is_finite() {
+            return Some((j, ll_bindings::TSK_ERR_TIME_NONFINITE));
+        }
+        if check_ordering && j > 0 && time[j - 1] > time[j] {
+            return Some((j, ll_bindings::TSK_ERR_UNSORTED_MIGRATIONS));
+        }
+        if !(left[j].is_finite() && right[j].is_finite()) {
+            return Some((j, ll_bindings::TSK_ERR_GENOME_COORDS_NONFINITE));
+        }
+        if left[j] < 0. {
+            return Some((j, ll_bindings::TSK_ERR_LEFT_LESS_ZERO));
+        }
+        if right[j] > tables.sequence_length {
+            return Some((j, ll_bindings::TSK_ERR_RIGHT_GREATER_SEQ_LENGTH));
+        }
+        if left[j] >= right[j] {
+            return Some((j, ll_bindings::TSK_ERR_BAD_EDGE_INTERVAL));
+        }
+    }
+    None
+}
+
+fn check_individuals(
+    tables: &ll_bindings::tsk_table_collection_t,
+    check_ordering: bool,
+) -> RowCheck {
+    let individuals = &tables.individuals;
+    let num_individuals = individuals.num_rows as usize;
+    let offsets = table_column!(individuals, parents_offset, individuals.num_rows + 1);
+    let parents = table_column!(individuals, parents, individuals.parents_length);
+    for j in 0..num_individuals {
+        let row_parents = parents.get(offsets[j] as usize..offsets[j + 1] as usize)?;
+        for &p in row_parents {
+            if !in_bounds_or_null(p, num_individuals) {
+                return Some((j, ll_bindings::TSK_ERR_INDIVIDUAL_OUT_OF_BOUNDS));
+            }
+            if p == j as tsk_id_t {
+                return Some((j, ll_bindings::TSK_ERR_INDIVIDUAL_SELF_PARENT));
+            }
+            if check_ordering && p != TSK_NULL && p >= j as tsk_id_t {
+                return Some((j, ll_bindings::TSK_ERR_UNSORTED_INDIVIDUALS));
+            }
+        }
+    }
+    None
+}
+
+/// Build the report for an error `code` returned by
+/// `tsk_table_collection_check_integrity` when called with `options`.
+pub(crate) fn integrity_report(
+    tables: &ll_bindings::tsk_table_collection_t,
+    options: ll_bindings::tsk_flags_t,
+    code: i32,
+) -> IntegrityReport {
+    let mut options = options;
+    if options & ll_bindings::TSK_CHECK_TREES != 0 {
+        options |= ll_bindings::TSK_CHECK_EDGE_ORDERING
+            | ll_bindings::TSK_CHECK_SITE_ORDERING
+            | ll_bindings::TSK_CHECK_SITE_DUPLICATES
+            | ll_bindings::TSK_CHECK_MUTATION_ORDERING
+            | ll_bindings::TSK_CHECK_MIGRATION_ORDERING
+            | ll_bindings::TSK_CHECK_INDEXES;
+    }
+    let has = |flag: ll_bindings::tsk_flags_t| options & flag != 0;
+    let check_population_refs = !has(ll_bindings::TSK_NO_CHECK_POPULATION_REFS);
+
+    let mut report = IntegrityReport {
+        code,
+        table: None,
+        row: None,
+    };
+    if tables.sequence_length <= 0. || code == ll_bindings::TSK_ERR_BAD_OFFSET {
+        return report;
+    }
+    let checks: [(TableKind, &dyn Fn() -> RowCheck); 6] = [
+        (TableKind::Nodes, &|| {
+            check_nodes(tables, check_population_refs)
+        }),
+        (TableKind::Edges, &|| {
+            check_edges(tables, has(ll_bindings::TSK_CHECK_EDGE_ORDERING))
+        }),
+        (TableKind::Sites, &|| {
+            check_sites(
+                tables,
+                has(ll_bindings::TSK_CHECK_SITE_ORDERING),
+                has(ll_bindings::TSK_CHECK_SITE_DUPLICATES),
+            )
+        }),
+        (TableKind::Mutations, &|| {
+            check_mutations(tables, has(ll_bindings::TSK_CHECK_MUTATION_ORDERING))
+        }),
+        (TableKind::Migrations, &|| {
+            check_migrations(
+                tables,
+                check_population_refs,
+                has(ll_bindings::TSK_CHECK_MIGRATION_ORDERING),
+            )
+        }),
+        (TableKind::Individuals, &|| {
+            check_individuals(tables, has(ll_bindings::TSK_CHECK_INDIVIDUAL_ORDERING))
+        }),
+    ];
+    for (table, check) in checks {
+        if let Some((row, row_code)) = check() {
+            // Trust the C library if our checks disagree with it.
+            if row_code == code {
+                report.table = Some(table);
+                report.row = Some(SizeType::from(row));
+            }
+            return report;
+        }
+    }
+    match code {
+        ll_bindings::TSK_ERR_TABLES_NOT_INDEXED
+        | ll_bindings::TSK_ERR_TABLES_BAD_INDEXES
+        | ll_bindings::TSK_ERR_BAD_EDGES_CONTRADICTORY_CHILDREN => {
+            report.table = Some(TableKind::Edges)
+        }
+        _ => (),
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::make_small_table_collection_two_trees;
+    use crate::types::TableKind;
+    use crate::{TableCollection, TableIntegrityCheckFlags};
+
+    fn report(tables: &TableCollection, flags: TableIntegrityCheckFlags) -> super::IntegrityReport {
+        let report = tables.check_integrity_report(flags).unwrap_err();
+        assert_eq!(
+            report.code,
+            match tables.check_integrity(flags) {
+                Err(crate::TskitError::ErrorCode { code }) => code,
+                _ => panic!("expected an error code"),
+            }
+        );
+        report
+    }
+
+    #[test]
+    fn test_valid_tables() {
+        let tables = make_small_table_collection_two_trees();
+        assert_eq!(
+            tables
+                .check_integrity_report(TableIntegrityCheckFlags::CHECK_TREES)
+                .unwrap(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_edge_ordering() {
+        let mut tables = make_small_table_collection_two_trees();
+        tables.add_edge(0., 1000., 0, 1).unwrap();
+        assert!(tables
+            .check_integrity_report(TableIntegrityCheckFlags::default())
+            .is_ok());
+        let r = report(&tables, TableIntegrityCheckFlags::CHECK_EDGE_ORDERING);
+        assert_eq!(r.table, Some(TableKind::Edges));
+        assert_eq!(
+            r.row.map(u64::from),
+            Some(u64::from(tables.edges().num_rows()) - 1)
+        );
+    }
+
+    #[test]
+    fn test_mutations() {
+        let mut tables = make_small_table_collection_two_trees();
+        tables.add_site(1., None).unwrap();
+        tables.add_mutation(0, 5, -1, 0.0, None).unwrap();
+        tables.add_mutation(0, 5, 3, 0.0, None).unwrap();
+        let r = report(&tables, TableIntegrityCheckFlags::default());
+        assert_eq!(r.table, Some(TableKind::Mutations));
+        assert_eq!(r.row.map(u64::from), Some(1));
+        assert_eq!(r.code, crate::bindings::TSK_ERR_MUTATION_OUT_OF_BOUNDS);
+    }
+
+    #[test]
+    fn test_individuals() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables.add_individual(0, None, None).unwrap();
+        tables.add_individual(0, None, [2]).unwrap();
+        tables.add_individual(0, None, None).unwrap();
+        assert!(tables
+            .check_integrity_report(TableIntegrityCheckFlags::default())
+            .is_ok());
+        let r = report(&tables, TableIntegrityCheckFlags::CHECK_INDIVIDUAL_ORDERING);
+        assert_eq!(r.table, Some(TableKind::Individuals));
+        assert_eq!(r.row.map(u64::from), Some(1));
+    }
+
+    #[test]
+    fn test_no_row() {
+        let mut tables = make_small_table_collection_two_trees();
+        tables.drop_index().unwrap();
+        let r = report(&tables, TableIntegrityCheckFlags::CHECK_INDEXES);
+        assert_eq!(r.table, Some(TableKind::Edges));
+        assert!(r.row.is_none());
+        assert_eq!(r.to_string(), format!("edge table: {}", r.message()));
+    }
+}
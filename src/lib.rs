@@ -19,6 +19,11 @@
 //!
 //! * The types listed above handle all the memory management!
 //! * All array accesses are range-checked.
+//!   Row and node ids are never trusted: accessors given an id that is
+//!   negative or out of range return [`TskitError::IndexError`] rather
+//!   than panicking, so ids from untrusted input can be passed directly.
+//!   (Metadata getters return the more detailed
+//!   [`TskitError::MetadataRowOutOfRange`], and `get` methods return `None`.)
 //! * Object lifetimes are clear:
 //!     * Creating a tree sequence moves/consumes a table collection.
 //!     * Tree lifetimes are tied to that of the parent tree sequence.
@@ -45,6 +50,18 @@
 //!
 //! * `provenance`
 //!     * Enables [`provenance`]
+//! * `serde`
+//!     * Implements `serde::Serialize` and `serde::Deserialize` for
+//!       [`TableCollection`], the table row types, the id types, the flag types,
+//!       [`Time`], [`Position`], and [`Location`].
+//!     * Enables the `struct` metadata codec,
+//!       [`metadata::struct_encode`] and [`metadata::struct_decode`].
+//! * `serde_json`
+//!     * Enables [`metadata::MetadataSchema`] for parsing metadata schemas
+//!       and validating metadata.
+//!     * Implements [`metadata::MetadataRoundtrip`] for `serde_json::Value`,
+//!       supporting the "permissive_json" convention.
+//!       This feature is also enabled by `derive` and `provenance`.
 //! * `derive` enables the following derive macros:
 //!     * [`crate::metadata::MutationMetadata`]
 //!     * [`crate::metadata::IndividualMetadata`]
@@ -53,9 +70,16 @@
 //!     * [`crate::metadata::NodeMetadata`]
 //!     * [`crate::metadata::MigrationMetadata`]
 //!     * [`crate::metadata::PopulationMetadata`]
+//!     * [`crate::metadata::TreeSequenceMetadata`]
 //!
 //!     To see these derive macros in action, take a look
 //!     [`here`](metadata).
+//! * `system-tskit` links to an installed `libtskit` instead of
+//!   compiling the copy of the C library included with this crate.
+//!   The library is found with `pkg-config`, or in the directory given
+//!   by the `TSKIT_LIB_DIR` environment variable, with headers in
+//!   `TSKIT_INCLUDE_DIR`.
+//!   See [`c_api_version`] for the version of the C API in use.
 //!         
 //! To add features to your `Cargo.toml` file:
 //!
@@ -82,15 +106,25 @@ mod edge_table;
 pub mod error;
 mod flags;
 mod individual_table;
+mod integrity;
 pub mod metadata;
+#[cfg(feature = "serde_json")]
+mod metadata_schema;
 mod migration_table;
 mod mutation_table;
 mod node_table;
 mod population_table;
 pub mod prelude;
 mod site_table;
+#[cfg(feature = "serde")]
+mod struct_codec;
 mod table_collection;
+mod table_collection_builder;
+#[cfg(feature = "serde")]
+mod table_collection_serde;
+mod table_diff;
 mod table_iterator;
+mod text_format;
 mod traits;
 mod tree_interface;
 mod trees;
@@ -167,6 +201,11 @@ use bindings::tsk_size_t;
 ///
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, std::hash::Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct NodeId(tsk_id_t);
 
 /// An individual ID
@@ -176,6 +215,11 @@ pub struct NodeId(tsk_id_t);
 /// The features for this type follow the same pattern as for [``NodeId``]
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, std::hash::Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct IndividualId(tsk_id_t);
 
 /// A population ID
@@ -185,6 +229,11 @@ pub struct IndividualId(tsk_id_t);
 /// The features for this type follow the same pattern as for [``NodeId``]
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, std::hash::Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct PopulationId(tsk_id_t);
 
 /// A site ID
@@ -194,6 +243,11 @@ pub struct PopulationId(tsk_id_t);
 /// The features for this type follow the same pattern as for [``NodeId``]
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, std::hash::Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct SiteId(tsk_id_t);
 
 /// A mutation ID
@@ -203,6 +257,11 @@ pub struct SiteId(tsk_id_t);
 /// The features for this type follow the same pattern as for [``NodeId``]
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, std::hash::Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct MutationId(tsk_id_t);
 
 /// A migration ID
@@ -212,6 +271,11 @@ pub struct MutationId(tsk_id_t);
 /// The features for this type follow the same pattern as for [``NodeId``]
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, std::hash::Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct MigrationId(tsk_id_t);
 
 /// An edge ID
@@ -221,6 +285,11 @@ pub struct MigrationId(tsk_id_t);
 /// The features for this type follow the same pattern as for [``NodeId``]
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, std::hash::Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct EdgeId(tsk_id_t);
 
 impl_id_traits!(NodeId);
@@ -256,6 +325,17 @@ impl_size_type_comparisons_for_row_ids!(MigrationId);
 /// assert!(s < t);
 /// ```
 ///
+/// Arithmetic works with other `SizeType` values
+/// and with integer literals:
+///
+/// ```
+/// let s = tskit::SizeType::from(10 as tskit::bindings::tsk_size_t);
+/// assert_eq!(s + 1, 11);
+/// assert_eq!(s - s, tskit::SizeType::ZERO);
+/// assert_eq!(s * 2 / 4, 5);
+/// assert!(tskit::SizeType::ZERO.checked_sub(s).is_none());
+/// ```
+///
 /// #[repr(transparent)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, std::hash::Hash)]
 pub struct SizeType(tsk_size_t);
@@ -348,6 +428,86 @@ impl PartialOrd<SizeType> for tsk_size_t {
     }
 }
 
+macro_rules! impl_size_type_arithmetic {
+    ($($trait: ident, $fn: ident, $assign_trait: ident, $assign_fn: ident, $op: tt);+) => {
+        $(
+            impl std::ops::$trait for SizeType {
+                type Output = Self;
+                fn $fn(self, rhs: Self) -> Self {
+                    Self(self.0 $op rhs.0)
+                }
+            }
+
+            impl std::ops::$trait<tsk_size_t> for SizeType {
+                type Output = Self;
+                fn $fn(self, rhs: tsk_size_t) -> Self {
+                    Self(self.0 $op rhs)
+                }
+            }
+
+            impl std::ops::$assign_trait for SizeType {
+                fn $assign_fn(&mut self, rhs: Self) {
+                    self.0 = self.0 $op rhs.0;
+                }
+            }
+
+            impl std::ops::$assign_trait<tsk_size_t> for SizeType {
+                fn $assign_fn(&mut self, rhs: tsk_size_t) {
+                    self.0 = self.0 $op rhs;
+                }
+            }
+        )+
+    };
+}
+
+impl_size_type_arithmetic!(
+    Add, add, AddAssign, add_assign, +;
+    Sub, sub, SubAssign, sub_assign, -;
+    Mul, mul, MulAssign, mul_assign, *;
+    Div, div, DivAssign, div_assign, /;
+    Rem, rem, RemAssign, rem_assign, %
+);
+
+impl std::iter::Sum for SizeType {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|s| s.0).sum())
+    }
+}
+
+impl SizeType {
+    /// Zero, such as the number of rows of an empty table.
+    pub const ZERO: Self = Self(0);
+
+    /// Add `rhs`, returning `None` on overflow.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(Self)
+    }
+
+    /// Subtract `rhs`, returning `None` if the result would be negative.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(Self)
+    }
+
+    /// Convert to `usize`.
+    ///
+    /// This conversion is lossless on 64-bit platforms, where it is
+    /// provided in addition to `TryFrom<SizeType> for usize`.
+    /// (A `From` implementation would conflict with that `TryFrom`.)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// tables.add_node(0, 0.0, -1, -1).unwrap();
+    /// let mut times = vec![0.0; tables.nodes().num_rows().as_usize()];
+    /// assert_eq!(times.len(), 1);
+    /// ```
+    #[cfg(target_pointer_width = "64")]
+    pub const fn as_usize(self) -> usize {
+        self.0 as usize
+    }
+}
+
 /// A newtype for the concept of time.
 /// A `Time` value can represent either a point in time
 /// or the output of arithmetic involving time.
@@ -382,8 +542,33 @@ impl PartialOrd<SizeType> for tsk_size_t {
 ///
 /// A `Time` can be multiplied and divided by a [`Position`]
 ///
+/// Values can be shifted, scaled, and compared using [`f64`]
+/// directly:
+///
+/// ```
+/// use tskit::Time;
+///
+/// let t = Time::from(2.0);
+/// assert_eq!(t + 1.0, 3.0);
+/// assert_eq!(t - 1.0, 1.0);
+/// assert_eq!(t * 3.0, 6.0);
+/// assert_eq!(3.0 * t, 6.0);
+/// assert_eq!(t / 2.0, 1.0);
+/// assert_eq!(-t, -2.0);
+/// assert!(t > 1.0);
+/// assert!(Time::ZERO < t);
+///
+/// let total: Time = [t, t, t].into_iter().sum();
+/// assert_eq!(total, 6.0);
+/// ```
+///
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct Time(f64);
 
 /// A newtype for the concept of "genomic position".
@@ -397,6 +582,11 @@ pub struct Time(f64);
 /// This type can be multiplied and divided by [`Time`].
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct Position(f64);
 
 /// A newtype for the concept of location.
@@ -409,6 +599,11 @@ pub struct Position(f64);
 ///
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct Location(f64);
 
 impl_f64_newtypes!(Time);
@@ -425,23 +620,38 @@ impl_time_position_arithmetic!(Position, Time);
 /// "Null" identifier value.
 pub(crate) const TSK_NULL: tsk_id_t = -1;
 
-pub use edge_table::{EdgeTable, EdgeTableRow, OwnedEdgeTable};
+pub use edge_table::{EdgeRowBuilder, EdgeTable, EdgeTableRow, EdgeTableRowView, OwnedEdgeTable};
 pub use error::TskitError;
 pub use flags::*;
-pub use individual_table::{IndividualTable, IndividualTableRow, OwnedIndividualTable};
-pub use migration_table::{MigrationTable, MigrationTableRow, OwnedMigrationTable};
-pub use mutation_table::{MutationTable, MutationTableRow, OwnedMutationTable};
-pub use node_table::{NodeTable, NodeTableRow, OwnedNodeTable};
-pub use population_table::{OwnedPopulationTable, PopulationTable, PopulationTableRow};
-pub use site_table::{OwnedSiteTable, SiteTable, SiteTableRow};
-pub use table_collection::TableCollection;
+pub use individual_table::{
+    IndividualRowBuilder, IndividualTable, IndividualTableRow, IndividualTableRowView,
+    OwnedIndividualTable,
+};
+pub use integrity::IntegrityReport;
+pub use migration_table::{
+    MigrationTable, MigrationTableRow, MigrationTableRowView, OwnedMigrationTable,
+};
+pub use mutation_table::{
+    MutationRowBuilder, MutationTable, MutationTableRow, MutationTableRowView, OwnedMutationTable,
+};
+pub use node_table::{NodeRowBuilder, NodeTable, NodeTableRow, NodeTableRowView, OwnedNodeTable};
+pub use population_table::{
+    OwnedPopulationTable, PopulationTable, PopulationTableRow, PopulationTableRowView,
+};
+pub use site_table::{OwnedSiteTable, SiteTable, SiteTableRow, SiteTableRowView};
+pub use table_collection::{SimplificationIdMaps, TableCollection};
+pub use table_collection_builder::TableCollectionBuilder;
+pub use table_diff::TableDifference;
+pub use text_format::TextTables;
 pub use traits::IndividualLocation;
 pub use traits::IndividualParents;
 pub use traits::NodeListGenerator;
 pub use traits::TableAccess;
+pub use traits::TableColumns;
 pub use traits::TskitTypeAccess;
 pub use tree_interface::{NodeTraversalOrder, TreeInterface};
-pub use trees::{Tree, TreeSequence};
+pub use trees::{Tree, TreeSequence, TreeSequenceSummary};
+pub use types::{GenomicInterval, TimeWithUnits};
 
 // Optional features
 #[cfg(any(feature = "provenance", doc))]
@@ -455,6 +665,11 @@ pub mod provenance;
 /// The features for this type follow the same pattern as for [``NodeId``]
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, std::hash::Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct ProvenanceId(tsk_id_t);
 
 #[cfg(feature = "provenance")]
@@ -534,6 +749,44 @@ mod tests {
             .expect("Error occurred while trying to write in String");
         assert_eq!(output, "Location(1)".to_string());
     }
+
+    #[test]
+    fn test_f64_newtype_scalar_arithmetic() {
+        let mut x = Position::from(10.0);
+        x += 2.0;
+        x -= 1.0;
+        x *= 2.0;
+        x /= 11.0;
+        assert_eq!(x, 2.0);
+        assert_eq!(Position::ZERO, 0.0);
+        assert_eq!(Position::ZERO + x, x);
+        assert!(Position::from(1.0) < 1.5);
+        let total: Location = [1.0, 2.0, 3.0].into_iter().map(Location::from).sum();
+        assert_eq!(total, 6.0);
+        let empty: Time = std::iter::empty().sum();
+        assert_eq!(empty, Time::ZERO);
+    }
+
+    #[test]
+    fn test_size_type_arithmetic() {
+        let mut tables = crate::TableCollection::new(10.).unwrap();
+        for _ in 0..3 {
+            tables.add_node(0, 0.0, -1, -1).unwrap();
+        }
+        let n = tables.nodes().num_rows();
+        assert_eq!(n - 1, 2);
+        assert_eq!(n % 2, 1);
+        let mut m = n;
+        m += n;
+        m -= 1;
+        assert_eq!(m, 5);
+        assert_eq!([n, n].into_iter().sum::<crate::SizeType>(), 6);
+        assert_eq!(n.checked_add(n), Some(crate::SizeType::from(6_usize)));
+        assert!(crate::SizeType::from(crate::tsk_size_t::MAX)
+            .checked_add(n)
+            .is_none());
+        assert_eq!(n.as_usize(), 3);
+    }
 }
 
 // Testing modules
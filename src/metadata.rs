@@ -149,14 +149,92 @@
 //! assert_eq!(decoded.data, "Bananas".to_string());
 //! ```
 //!
+//! # Reading metadata
+//!
+//! All metadata getters follow the same convention.
+//! The table getters (for example [`NodeTable::metadata`](crate::NodeTable::metadata)),
+//! [`TableCollection::metadata`](crate::TableCollection::metadata),
+//! and [`TreeSequence::metadata`](crate::TreeSequence::metadata) return
+//! `Result<Option<T>, TskitError>`:
+//!
+//! * `Err(_)` if the row is out of range or decoding fails,
+//! * `Ok(None)` if there is no metadata,
+//! * `Ok(Some(T))` otherwise.
+//!
+//! Each of these has a `metadata_or_err` counterpart returning
+//! `Result<T, TskitError>`, for callers that treat missing metadata
+//! as an error.
+//! Missing metadata are then reported as
+//! [`TskitError::UnexpectedEmptyMetadata`](crate::TskitError::UnexpectedEmptyMetadata).
+//!
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! #[derive(serde::Serialize, serde::Deserialize, tskit::metadata::IndividualMetadata)]
+//! #[serializer("serde_json")]
+//! struct IndividualMetadata {
+//!     x: i32,
+//! }
+//!
+//! let mut tables = tskit::TableCollection::new(100.).unwrap();
+//! tables
+//!     .add_individual_with_metadata(0, None, None, &IndividualMetadata { x: 1 })
+//!     .unwrap();
+//! tables.add_individual(0, None, None).unwrap();
+//!
+//! // Handle the error and the absence of metadata separately
+//! match tables.individuals().metadata::<IndividualMetadata>(0) {
+//!     Ok(Some(metadata)) => assert_eq!(metadata.x, 1),
+//!     Ok(None) => panic!("we expected Some(metadata)"),
+//!     Err(e) => panic!("error: {:?}", e),
+//! }
+//! assert!(matches!(
+//!     tables.individuals().metadata::<IndividualMetadata>(1),
+//!     Ok(None)
+//! ));
+//!
+//! // Treat the absence of metadata as an error
+//! let decoded = tables
+//!     .individuals()
+//!     .metadata_or_err::<IndividualMetadata, _>(0)
+//!     .unwrap();
+//! assert_eq!(decoded.x, 1);
+//! assert!(matches!(
+//!     tables.individuals().metadata_or_err::<IndividualMetadata, _>(1),
+//!     Err(tskit::TskitError::UnexpectedEmptyMetadata { row: Some(1) })
+//! ));
+//! # }
+//! ```
+//!
 //! # Technial details and notes
 //!
-//! * The derive macros currently support two `serde` methods:
-//!   `serde_json` and `bincode`.
+//! * The derive macros currently support three `serde` methods:
+//!   `serde_json`, `bincode`, and `struct`.
+//! * The `struct` serializer implements the fixed binary layout of
+//!   the tskit `struct` codec, which is also readable by the `tskit`
+//!   `Python API`. See [`struct_encode`] for the supported types.
+//!   The fields of the schema must be ordered (via their `index`)
+//!   as the fields of the Rust type are declared.
 //! * A concept like "mutation metadata" is the combination of two traits:
 //!   [`MetadataRoundtrip`] plus [`MutationMetadata`].
 //!   The latter is a marker trait.
 //!   The derive macros handle all of this "boiler plate" for you.
+//! * There is a derive macro for each table with a metadata column.
+//!   Provenance tables have no metadata, and deriving `ProvenanceMetadata`
+//!   fails to compile:
+// Without the derive feature, the path below does not exist
+// and the example would fail to compile for the wrong reason.
+#![cfg_attr(
+    feature = "derive",
+    doc = r#"
+```compile_fail
+#[derive(serde::Serialize, serde::Deserialize, tskit::metadata::ProvenanceMetadata)]
+#[serializer("serde_json")]
+struct Record {
+    command: String,
+}
+```
+"#
+)]
 //!
 //! ## Limitations/unknowns
 //!
@@ -175,9 +253,16 @@ pub extern crate tskit_derive;
 #[doc(hidden)]
 pub use tskit_derive::{
     EdgeMetadata, IndividualMetadata, MigrationMetadata, MutationMetadata, NodeMetadata,
-    PopulationMetadata, SiteMetadata,
+    PopulationMetadata, ProvenanceMetadata, SiteMetadata, TreeSequenceMetadata,
 };
 
+pub mod slim;
+
+#[cfg(feature = "serde_json")]
+pub use crate::metadata_schema::{MetadataCodec, MetadataSchema};
+#[cfg(feature = "serde")]
+pub use crate::struct_codec::{struct_decode, struct_encode};
+
 /// Trait marking a type as table metadata
 pub trait MetadataRoundtrip {
     /// Encode `self` as bytes
@@ -199,7 +284,7 @@ pub trait NodeMetadata: MetadataRoundtrip {}
 /// Marker trait indicating [`MetadataRoundtrip`]
 /// for the edge table of a [`TableCollection`](crate::TableCollection).
 pub trait EdgeMetadata: MetadataRoundtrip {}
-///
+
 /// Marker trait indicating [`MetadataRoundtrip`]
 /// for the migration table of a [`TableCollection`](crate::TableCollection).
 pub trait MigrationMetadata: MetadataRoundtrip {}
@@ -216,6 +301,145 @@ pub trait IndividualMetadata: MetadataRoundtrip {}
 /// for the population table of a [`TableCollection`](crate::TableCollection).
 pub trait PopulationMetadata: MetadataRoundtrip {}
 
+/// Marker trait indicating [`MetadataRoundtrip`]
+/// for the top-level metadata of a [`TableCollection`](crate::TableCollection)
+/// or [`TreeSequence`](crate::TreeSequence).
+pub trait TreeSequenceMetadata: MetadataRoundtrip {}
+
+/// Opaque binary metadata.
+///
+/// The bytes are stored as they are, which corresponds to a
+/// metadata schema using the "null" codec (or no schema at all).
+/// This type can be used as metadata for any table.
+/// Note that an empty `Bytes` is stored as "no metadata",
+/// so reading it back gives `None`.
+///
+/// # Examples
+///
+/// ```
+/// use tskit::TableAccess;
+/// use tskit::metadata::Bytes;
+///
+/// let mut tables = tskit::TableCollection::new(1.).unwrap();
+/// let blob = Bytes::from(vec![0, 1, 2, 3]);
+/// let id = tables
+///     .add_node_with_metadata(0, 0.0, -1, -1, &blob)
+///     .unwrap();
+/// let decoded = tables.nodes().metadata::<Bytes>(id).unwrap().unwrap();
+/// assert_eq!(decoded, blob);
+/// assert_eq!(decoded.as_ref(), &[0, 1, 2, 3]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bytes(pub Vec<u8>);
+
+impl From<Vec<u8>> for Bytes {
+    fn from(value: Vec<u8>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&[u8]> for Bytes {
+    fn from(value: &[u8]) -> Self {
+        Self(value.to_vec())
+    }
+}
+
+impl From<Bytes> for Vec<u8> {
+    fn from(value: Bytes) -> Self {
+        value.0
+    }
+}
+
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl MetadataRoundtrip for Bytes {
+    fn encode(&self) -> Result<Vec<u8>, MetadataError> {
+        Ok(self.0.clone())
+    }
+
+    fn decode(md: &[u8]) -> Result<Self, MetadataError> {
+        Ok(Self(md.to_vec()))
+    }
+}
+
+impl MutationMetadata for Bytes {}
+impl NodeMetadata for Bytes {}
+impl EdgeMetadata for Bytes {}
+impl MigrationMetadata for Bytes {}
+impl SiteMetadata for Bytes {}
+impl IndividualMetadata for Bytes {}
+impl PopulationMetadata for Bytes {}
+impl TreeSequenceMetadata for Bytes {}
+
+/// Metadata using the "permissive_json" convention.
+///
+/// Any valid JSON value is accepted, so tables written by tools
+/// with loose schemas (or no schema at all) can be read without
+/// declaring a Rust type for their metadata.
+/// Empty metadata decode to an empty JSON object.
+///
+/// See [`MetadataSchema::permissive_json`] for the matching schema.
+///
+/// # Examples
+///
+/// ```
+/// use tskit::TableAccess;
+///
+/// let mut tables = tskit::TableCollection::new(1.).unwrap();
+/// tables
+///     .set_node_metadata_schema(tskit::metadata::MetadataSchema::permissive_json())
+///     .unwrap();
+/// let md = serde_json::json!({"name": "n0", "tags": [1, 2]});
+/// let id = tables.add_node_with_metadata(0, 0.0, -1, -1, &md).unwrap();
+/// let decoded = tables
+///     .nodes()
+///     .metadata::<serde_json::Value>(id)
+///     .unwrap()
+///     .unwrap();
+/// assert_eq!(decoded["name"], "n0");
+/// assert_eq!(decoded, md);
+/// ```
+#[cfg(feature = "serde_json")]
+impl MetadataRoundtrip for serde_json::Value {
+    fn encode(&self) -> Result<Vec<u8>, MetadataError> {
+        match serde_json::to_vec(self) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(MetadataError::RoundtripError { value: Box::new(e) }),
+        }
+    }
+
+    fn decode(md: &[u8]) -> Result<Self, MetadataError> {
+        if md.is_empty() {
+            return Ok(serde_json::Value::Object(serde_json::Map::new()));
+        }
+        match serde_json::from_slice(md) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(MetadataError::RoundtripError { value: Box::new(e) }),
+        }
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl MutationMetadata for serde_json::Value {}
+#[cfg(feature = "serde_json")]
+impl NodeMetadata for serde_json::Value {}
+#[cfg(feature = "serde_json")]
+impl EdgeMetadata for serde_json::Value {}
+#[cfg(feature = "serde_json")]
+impl MigrationMetadata for serde_json::Value {}
+#[cfg(feature = "serde_json")]
+impl SiteMetadata for serde_json::Value {}
+#[cfg(feature = "serde_json")]
+impl IndividualMetadata for serde_json::Value {}
+#[cfg(feature = "serde_json")]
+impl PopulationMetadata for serde_json::Value {}
+#[cfg(feature = "serde_json")]
+impl TreeSequenceMetadata for serde_json::Value {}
+
 pub(crate) struct EncodedMetadata {
     encoded: Vec<u8>,
 }
@@ -248,6 +472,12 @@ pub enum MetadataError {
         #[from]
         value: Box<dyn std::error::Error>,
     },
+    /// A metadata schema is invalid.
+    #[error("invalid metadata schema: {}", *message)]
+    SchemaError { message: String },
+    /// Encoded metadata do not match their schema.
+    #[error("metadata do not match the schema: {}", *message)]
+    ValidationError { message: String },
 }
 
 pub(crate) fn char_column_to_slice<T: Sized>(
@@ -312,6 +542,21 @@ pub(crate) fn char_column_to_slice<T: Sized>(
     }))
 }
 
+/// View a non-ragged char array, such as top-level metadata,
+/// as a byte slice whose lifetime is tied to `owner`.
+/// Returns `None` if the array is empty.
+///
+/// # Safety
+///
+/// See [`column_slice`](crate::util::column_slice).
+pub(crate) unsafe fn char_array_to_slice<T: Sized>(
+    owner: &T,
+    array: *const libc::c_char,
+    length: tsk_size_t,
+) -> Option<&[u8]> {
+    Some(crate::util::column_slice(owner, array.cast::<u8>(), length)).filter(|s| !s.is_empty())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
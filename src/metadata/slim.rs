@@ -0,0 +1,473 @@
+//! Metadata written by [SLiM](https://messerlab.org/slim/).
+//!
+//! SLiM stores binary metadata, using the `struct` codec, for nodes
+//! (genomes), individuals, and mutations.
+//! Population metadata are JSON objects in current versions of SLiM,
+//! and binary in versions before 3.7.
+//! All binary values are little-endian.
+//!
+//! The types in this module implement [`MetadataRoundtrip`]
+//! and the marker trait of their table, so they can be used with
+//! the `metadata` functions of tables.
+//! Decoding population metadata from JSON, and writing population
+//! metadata at all, requires the `serde` and `serde_json` features
+//! (both enabled by `derive`).
+//!
+//! # Examples
+//!
+//! ```
+//! use tskit::metadata::slim::{SlimGenome, SlimIndividual, SlimMutation, SlimMutationStack};
+//! use tskit::TableAccess;
+//!
+//! let mut tables = tskit::TableCollection::new(100.).unwrap();
+//! let genome = SlimGenome {
+//!     slim_id: 10,
+//!     is_null: false,
+//!     genome_type: SlimGenome::AUTOSOME,
+//! };
+//! let node = tables.add_node_with_metadata(0, 0.0, -1, -1, &genome).unwrap();
+//! assert_eq!(tables.nodes().metadata::<SlimGenome>(node).unwrap(), Some(genome));
+//!
+//! let stack = SlimMutationStack(vec![SlimMutation {
+//!     mutation_type: 1,
+//!     selection_coeff: 0.1,
+//!     subpopulation: 0,
+//!     origin_tick: 50,
+//!     nucleotide: -1,
+//! }]);
+//! let site = tables.add_site(1., None).unwrap();
+//! let mutation = tables
+//!     .add_mutation_with_metadata(site, node, -1, 0.0, None, &stack)
+//!     .unwrap();
+//! let decoded = tables
+//!     .mutations()
+//!     .metadata::<SlimMutationStack>(mutation)
+//!     .unwrap()
+//!     .unwrap();
+//! assert_eq!(decoded.0[0].origin_tick, 50);
+//! ```
+
+use crate::metadata::{
+    IndividualMetadata, MetadataError, MetadataRoundtrip, MutationMetadata, NodeMetadata,
+};
+
+fn invalid<T>(message: String) -> Result<T, MetadataError> {
+    Err(MetadataError::ValidationError { message })
+}
+
+/// Reads little-endian values from encoded metadata.
+struct Reader<'a> {
+    md: &'a [u8],
+}
+
+macro_rules! reader_fn {
+    ($name: ident, $type: ty) => {
+        fn $name(&mut self) -> Result<$type, MetadataError> {
+            const N: usize = std::mem::size_of::<$type>();
+            if self.md.len() < N {
+                return invalid(format!(
+                    "expected {} more bytes of SLiM metadata, found {}",
+                    N,
+                    self.md.len()
+                ));
+            }
+            let (value, rest) = self.md.split_at(N);
+            self.md = rest;
+            // value has exactly N bytes
+            Ok(<$type>::from_le_bytes(value.try_into().unwrap()))
+        }
+    };
+}
+
+impl<'a> Reader<'a> {
+    reader_fn!(i8, i8);
+    reader_fn!(u8, u8);
+    reader_fn!(i32, i32);
+    reader_fn!(u32, u32);
+    reader_fn!(i64, i64);
+    reader_fn!(f32, f32);
+    reader_fn!(f64, f64);
+
+    fn finish(&self) -> Result<(), MetadataError> {
+        if self.md.is_empty() {
+            Ok(())
+        } else {
+            invalid(format!(
+                "{} unexpected trailing bytes of SLiM metadata",
+                self.md.len()
+            ))
+        }
+    }
+}
+
+/// Metadata of a node, which SLiM calls a genome.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SlimGenome {
+    /// The SLiM id of the genome.
+    pub slim_id: i64,
+    /// `true` if the genome is a placeholder, such as the
+    /// Y chromosome of a female.
+    pub is_null: bool,
+    /// One of [`SlimGenome::AUTOSOME`], [`SlimGenome::X`], or [`SlimGenome::Y`].
+    pub genome_type: u8,
+}
+
+impl SlimGenome {
+    pub const AUTOSOME: u8 = 0;
+    pub const X: u8 = 1;
+    pub const Y: u8 = 2;
+}
+
+impl MetadataRoundtrip for SlimGenome {
+    fn encode(&self) -> Result<Vec<u8>, MetadataError> {
+        let mut rv = self.slim_id.to_le_bytes().to_vec();
+        rv.push(u8::from(self.is_null));
+        rv.push(self.genome_type);
+        Ok(rv)
+    }
+
+    fn decode(md: &[u8]) -> Result<Self, MetadataError> {
+        let mut reader = Reader { md };
+        let slim_id = reader.i64()?;
+        let is_null = match reader.u8()? {
+            0 => false,
+            1 => true,
+            x => return invalid(format!("invalid is_null value {}", x)),
+        };
+        let genome_type = reader.u8()?;
+        reader.finish()?;
+        Ok(Self {
+            slim_id,
+            is_null,
+            genome_type,
+        })
+    }
+}
+
+impl NodeMetadata for SlimGenome {}
+
+/// Metadata of an individual.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SlimIndividual {
+    /// The SLiM pedigree id of the individual.
+    pub pedigree_id: i64,
+    /// The pedigree id of the first parent, or -1.
+    pub pedigree_p1: i64,
+    /// The pedigree id of the second parent, or -1.
+    pub pedigree_p2: i64,
+    pub age: i32,
+    /// The SLiM id of the individual's subpopulation.
+    pub subpopulation: i32,
+    /// One of [`SlimIndividual::HERMAPHRODITE`], [`SlimIndividual::FEMALE`],
+    /// or [`SlimIndividual::MALE`].
+    pub sex: i32,
+    /// Bitwise flags, such as [`SlimIndividual::MIGRATED`].
+    pub flags: u32,
+}
+
+impl SlimIndividual {
+    pub const HERMAPHRODITE: i32 = -1;
+    pub const FEMALE: i32 = 0;
+    pub const MALE: i32 = 1;
+    /// The individual migrated during the current tick.
+    pub const MIGRATED: u32 = 0x01;
+
+    /// Decode the metadata written by SLiM versions before 3.7,
+    /// which lack the parental pedigree ids.
+    /// These are set to -1.
+    pub fn decode_legacy(md: &[u8]) -> Result<Self, MetadataError> {
+        let mut reader = Reader { md };
+        let rv = Self {
+            pedigree_id: reader.i64()?,
+            pedigree_p1: -1,
+            pedigree_p2: -1,
+            age: reader.i32()?,
+            subpopulation: reader.i32()?,
+            sex: reader.i32()?,
+            flags: reader.u32()?,
+        };
+        reader.finish()?;
+        Ok(rv)
+    }
+}
+
+impl MetadataRoundtrip for SlimIndividual {
+    fn encode(&self) -> Result<Vec<u8>, MetadataError> {
+        let mut rv = Vec::with_capacity(40);
+        rv.extend_from_slice(&self.pedigree_id.to_le_bytes());
+        rv.extend_from_slice(&self.pedigree_p1.to_le_bytes());
+        rv.extend_from_slice(&self.pedigree_p2.to_le_bytes());
+        rv.extend_from_slice(&self.age.to_le_bytes());
+        rv.extend_from_slice(&self.subpopulation.to_le_bytes());
+        rv.extend_from_slice(&self.sex.to_le_bytes());
+        rv.extend_from_slice(&self.flags.to_le_bytes());
+        Ok(rv)
+    }
+
+    /// Decode the metadata, accepting both the current form
+    /// and the form written by SLiM versions before 3.7.
+    fn decode(md: &[u8]) -> Result<Self, MetadataError> {
+        if md.len() == 24 {
+            return Self::decode_legacy(md);
+        }
+        let mut reader = Reader { md };
+        let rv = Self {
+            pedigree_id: reader.i64()?,
+            pedigree_p1: reader.i64()?,
+            pedigree_p2: reader.i64()?,
+            age: reader.i32()?,
+            subpopulation: reader.i32()?,
+            sex: reader.i32()?,
+            flags: reader.u32()?,
+        };
+        reader.finish()?;
+        Ok(rv)
+    }
+}
+
+impl IndividualMetadata for SlimIndividual {}
+
+/// One SLiM mutation.
+///
+/// A tskit mutation may represent several "stacked" SLiM
+/// mutations; see [`SlimMutationStack`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SlimMutation {
+    /// The SLiM id of the mutation type.
+    pub mutation_type: i32,
+    pub selection_coeff: f32,
+    /// The SLiM id of the subpopulation where the mutation arose.
+    pub subpopulation: i32,
+    /// The tick when the mutation arose.
+    pub origin_tick: i32,
+    /// The nucleotide (0 to 3 for A, C, G, T), or -1 in models
+    /// that are not nucleotide-based.
+    pub nucleotide: i8,
+}
+
+/// The metadata of a mutation: the SLiM mutations stacked
+/// at the mutation's site.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SlimMutationStack(pub Vec<SlimMutation>);
+
+impl MetadataRoundtrip for SlimMutationStack {
+    fn encode(&self) -> Result<Vec<u8>, MetadataError> {
+        let mut rv = Vec::with_capacity(17 * self.0.len());
+        for m in &self.0 {
+            rv.extend_from_slice(&m.mutation_type.to_le_bytes());
+            rv.extend_from_slice(&m.selection_coeff.to_le_bytes());
+            rv.extend_from_slice(&m.subpopulation.to_le_bytes());
+            rv.extend_from_slice(&m.origin_tick.to_le_bytes());
+            rv.extend_from_slice(&m.nucleotide.to_le_bytes());
+        }
+        Ok(rv)
+    }
+
+    fn decode(md: &[u8]) -> Result<Self, MetadataError> {
+        let mut reader = Reader { md };
+        let mut rv = vec![];
+        while !reader.md.is_empty() {
+            rv.push(SlimMutation {
+                mutation_type: reader.i32()?,
+                selection_coeff: reader.f32()?,
+                subpopulation: reader.i32()?,
+                origin_tick: reader.i32()?,
+                nucleotide: reader.i8()?,
+            });
+        }
+        Ok(Self(rv))
+    }
+}
+
+impl MutationMetadata for SlimMutationStack {}
+
+/// Migration rate into a population from a source population.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(
+    all(feature = "serde", feature = "serde_json"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct SlimMigrationRecord {
+    /// The SLiM id of the source subpopulation.
+    pub source_subpop: u32,
+    pub migration_rate: f64,
+}
+
+/// Metadata of a population.
+///
+/// Fields not present in JSON metadata take their default values.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    all(feature = "serde", feature = "serde_json"),
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct SlimPopulation {
+    /// The SLiM id of the subpopulation.
+    pub slim_id: u32,
+    /// The name of the subpopulation.
+    /// Not present in legacy binary metadata.
+    pub name: String,
+    /// Not present in legacy binary metadata.
+    pub description: String,
+    pub selfing_fraction: f64,
+    pub female_cloning_fraction: f64,
+    pub male_cloning_fraction: f64,
+    pub sex_ratio: f64,
+    pub bounds_x0: f64,
+    pub bounds_x1: f64,
+    pub bounds_y0: f64,
+    pub bounds_y1: f64,
+    pub bounds_z0: f64,
+    pub bounds_z1: f64,
+    pub migration_records: Vec<SlimMigrationRecord>,
+}
+
+impl SlimPopulation {
+    /// Decode the binary metadata written by SLiM versions before 3.7.
+    pub fn decode_legacy(md: &[u8]) -> Result<Self, MetadataError> {
+        let mut reader = Reader { md };
+        let mut rv = Self {
+            slim_id: reader.u32()?,
+            selfing_fraction: reader.f64()?,
+            female_cloning_fraction: reader.f64()?,
+            male_cloning_fraction: reader.f64()?,
+            sex_ratio: reader.f64()?,
+            bounds_x0: reader.f64()?,
+            bounds_x1: reader.f64()?,
+            bounds_y0: reader.f64()?,
+            bounds_y1: reader.f64()?,
+            bounds_z0: reader.f64()?,
+            bounds_z1: reader.f64()?,
+            ..Default::default()
+        };
+        let num_records = reader.u32()?;
+        for _ in 0..num_records {
+            rv.migration_records.push(SlimMigrationRecord {
+                source_subpop: reader.u32()?,
+                migration_rate: reader.f64()?,
+            });
+        }
+        reader.finish()?;
+        Ok(rv)
+    }
+
+    /// Decode JSON metadata, as written by SLiM 3.7 and later.
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    pub fn decode_json(md: &[u8]) -> Result<Self, MetadataError> {
+        serde_json::from_slice(md).map_err(|e| MetadataError::RoundtripError { value: Box::new(e) })
+    }
+}
+
+/// Population metadata are written as JSON, and decoded
+/// from either JSON or the legacy binary form.
+#[cfg(all(feature = "serde", feature = "serde_json"))]
+impl MetadataRoundtrip for SlimPopulation {
+    fn encode(&self) -> Result<Vec<u8>, MetadataError> {
+        serde_json::to_vec(self).map_err(|e| MetadataError::RoundtripError { value: Box::new(e) })
+    }
+
+    fn decode(md: &[u8]) -> Result<Self, MetadataError> {
+        if md.first() == Some(&b'{') {
+            if let Ok(rv) = Self::decode_json(md) {
+                return Ok(rv);
+            }
+        }
+        Self::decode_legacy(md)
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "serde_json"))]
+impl crate::metadata::PopulationMetadata for SlimPopulation {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_genome_layout() {
+        let genome = SlimGenome {
+            slim_id: 3,
+            is_null: true,
+            genome_type: SlimGenome::Y,
+        };
+        let encoded = genome.encode().unwrap();
+        assert_eq!(encoded, vec![3, 0, 0, 0, 0, 0, 0, 0, 1, 2]);
+        assert_eq!(SlimGenome::decode(&encoded).unwrap(), genome);
+        assert!(SlimGenome::decode(&encoded[..9]).is_err());
+    }
+
+    #[test]
+    fn test_individual_forms() {
+        let individual = SlimIndividual {
+            pedigree_id: 7,
+            pedigree_p1: 1,
+            pedigree_p2: 2,
+            age: 3,
+            subpopulation: 1,
+            sex: SlimIndividual::FEMALE,
+            flags: SlimIndividual::MIGRATED,
+        };
+        let encoded = individual.encode().unwrap();
+        assert_eq!(encoded.len(), 40);
+        assert_eq!(SlimIndividual::decode(&encoded).unwrap(), individual);
+
+        let mut legacy = encoded[..8].to_vec();
+        legacy.extend_from_slice(&encoded[24..]);
+        let decoded = SlimIndividual::decode(&legacy).unwrap();
+        assert_eq!(decoded.pedigree_id, 7);
+        assert_eq!(decoded.pedigree_p1, -1);
+        assert_eq!(decoded.flags, SlimIndividual::MIGRATED);
+    }
+
+    #[test]
+    fn test_mutation_stack() {
+        let m = SlimMutation {
+            mutation_type: 2,
+            selection_coeff: -0.5,
+            subpopulation: 1,
+            origin_tick: 10,
+            nucleotide: 3,
+        };
+        let stack = SlimMutationStack(vec![m, m]);
+        let encoded = stack.encode().unwrap();
+        assert_eq!(encoded.len(), 34);
+        assert_eq!(SlimMutationStack::decode(&encoded).unwrap(), stack);
+        assert!(SlimMutationStack::decode(&encoded[..20]).is_err());
+    }
+
+    #[test]
+    fn test_legacy_population() {
+        let mut md = 2_u32.to_le_bytes().to_vec();
+        for x in 0..10 {
+            md.extend_from_slice(&f64::from(x).to_le_bytes());
+        }
+        md.extend_from_slice(&1_u32.to_le_bytes());
+        md.extend_from_slice(&1_u32.to_le_bytes());
+        md.extend_from_slice(&0.25_f64.to_le_bytes());
+        let population = SlimPopulation::decode_legacy(&md).unwrap();
+        assert_eq!(population.slim_id, 2);
+        assert_eq!(population.sex_ratio, 3.);
+        assert_eq!(population.bounds_z1, 9.);
+        assert_eq!(
+            population.migration_records,
+            vec![SlimMigrationRecord {
+                source_subpop: 1,
+                migration_rate: 0.25
+            }]
+        );
+    }
+
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    #[test]
+    fn test_json_population() {
+        let md = br#"{"slim_id": 1, "name": "p1", "migration_records":
+                      [{"source_subpop": 0, "migration_rate": 0.1}]}"#;
+        let population = SlimPopulation::decode(md).unwrap();
+        assert_eq!(population.slim_id, 1);
+        assert_eq!(population.name, "p1");
+        assert_eq!(population.migration_records.len(), 1);
+        let encoded = population.encode().unwrap();
+        assert_eq!(SlimPopulation::decode(&encoded).unwrap(), population);
+    }
+}
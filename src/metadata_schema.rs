@@ -0,0 +1,682 @@
+//! Parsing of metadata schemas and validation of encoded metadata.
+
+use crate::metadata::MetadataError;
+use serde_json::Value;
+
+/// The codec named by a [`MetadataSchema`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MetadataCodec {
+    /// Metadata are JSON objects.
+    Json,
+    /// Metadata use the fixed binary layout of the `struct` codec.
+    /// See [`struct_encode`](crate::metadata::struct_encode).
+    Struct,
+    /// A codec not known to `tskit`.
+    /// Metadata using it are not validated.
+    Other(String),
+}
+
+/// Length encoding of an array in the struct codec.
+#[derive(Clone, Debug, PartialEq)]
+enum ArrayLength {
+    Fixed(usize),
+    Prefixed(usize),
+    ExhaustBuffer,
+}
+
+/// The binary layout described by a struct codec schema.
+#[derive(Clone, Debug, PartialEq)]
+enum StructLayout {
+    /// A value occupying a fixed number of bytes.
+    Fixed(usize),
+    /// Bytes preceded by their length.
+    VariableBytes(usize),
+    Object(Vec<StructLayout>),
+    Array {
+        item: Box<StructLayout>,
+        length: ArrayLength,
+    },
+}
+
+/// A parsed metadata schema.
+///
+/// Table metadata schemas are JSON documents naming a codec
+/// and describing the contents of each row's metadata.
+/// A `MetadataSchema` checks that a schema string is well-formed
+/// and can [`validate`](MetadataSchema::validate) encoded metadata
+/// against it.
+///
+/// For the `json` codec, validation supports the `type`, `enum`,
+/// `const`, `properties`, `required`, `additionalProperties`,
+/// `items`, `minItems`, `maxItems`, `minimum`, `maximum`, `anyOf`,
+/// and `oneOf` keywords.
+/// Other keywords are ignored.
+///
+/// For the `struct` codec, the schema must describe a binary layout:
+/// properties are ordered by their `index` (and then by name),
+/// leaf values must have a `binaryFormat`, and arrays either have
+/// a fixed `length`, are preceded by a length with format
+/// `arrayLengthFormat` (defaulting to `L`), or set
+/// `noLengthEncodingExhaustBuffer`.
+/// Strings with a `binaryFormat` of `s` (no length) are preceded by
+/// a length, as written by
+/// [`struct_encode`](crate::metadata::struct_encode).
+///
+/// A schema is attached to a table by passing it to one of the
+/// `set_metadata_schema` functions, which accept any `AsRef<str>`.
+///
+/// # Examples
+///
+/// ```
+/// use tskit::metadata::{MetadataCodec, MetadataSchema};
+///
+/// let schema: MetadataSchema = r#"{
+///     "codec": "json",
+///     "type": "object",
+///     "properties": {"x": {"type": "integer"}},
+///     "required": ["x"]
+/// }"#
+/// .parse()
+/// .unwrap();
+/// assert_eq!(schema.codec(), &MetadataCodec::Json);
+/// assert!(schema.validate(br#"{"x": 1}"#).is_ok());
+/// assert!(schema.validate(br#"{"x": "one"}"#).is_err());
+/// assert!(schema.validate(br#"{"y": 1}"#).is_err());
+///
+/// let mut nodes = tskit::OwnedNodeTable::default();
+/// nodes.set_metadata_schema(&schema).unwrap();
+/// assert_eq!(nodes.metadata_schema(), Some(schema.as_str()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct MetadataSchema {
+    schema: String,
+    value: Value,
+    codec: MetadataCodec,
+    layout: Option<StructLayout>,
+}
+
+fn schema_error<T>(message: impl Into<String>) -> Result<T, MetadataError> {
+    Err(MetadataError::SchemaError {
+        message: message.into(),
+    })
+}
+
+fn validation_error<T>(message: impl Into<String>) -> Result<T, MetadataError> {
+    Err(MetadataError::ValidationError {
+        message: message.into(),
+    })
+}
+
+/// Number of bytes occupied by a `binaryFormat`,
+/// or `None` for a length-prefixed string.
+fn binary_format_size(format: &str) -> Result<Option<usize>, MetadataError> {
+    let split = format
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(format.len());
+    let (count, code) = format.split_at(split);
+    let count = if count.is_empty() {
+        None
+    } else {
+        match count.parse::<usize>() {
+            Ok(c) => Some(c),
+            Err(_) => return schema_error(format!("invalid binaryFormat {:?}", format)),
+        }
+    };
+    let size = match code {
+        "x" | "c" | "b" | "B" | "?" | "s" | "p" => 1,
+        "h" | "H" | "e" => 2,
+        "i" | "I" | "l" | "L" | "f" => 4,
+        "q" | "Q" | "d" => 8,
+        _ => return schema_error(format!("invalid binaryFormat {:?}", format)),
+    };
+    match (count, code) {
+        (None, "s") | (None, "p") => Ok(None),
+        (count, _) => Ok(Some(size * count.unwrap_or(1))),
+    }
+}
+
+fn array_length_format_size(format: &str) -> Result<usize, MetadataError> {
+    match format {
+        "B" => Ok(1),
+        "H" => Ok(2),
+        "I" | "L" => Ok(4),
+        "Q" => Ok(8),
+        _ => schema_error(format!("invalid arrayLengthFormat {:?}", format)),
+    }
+}
+
+fn struct_layout(schema: &Value) -> Result<StructLayout, MetadataError> {
+    match schema.get("type").and_then(Value::as_str) {
+        Some("object") => {
+            let mut properties = match schema.get("properties") {
+                None => vec![],
+                Some(Value::Object(p)) => p.iter().collect::<Vec<_>>(),
+                Some(_) => return schema_error("properties must be an object"),
+            };
+            properties.sort_by(|(lname, l), (rname, r)| {
+                let lindex = l.get("index").and_then(Value::as_f64).unwrap_or(0.);
+                let rindex = r.get("index").and_then(Value::as_f64).unwrap_or(0.);
+                lindex
+                    .partial_cmp(&rindex)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| lname.cmp(rname))
+            });
+            let fields = properties
+                .into_iter()
+                .map(|(_, p)| struct_layout(p))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(StructLayout::Object(fields))
+        }
+        Some("array") => {
+            let item = match schema.get("items") {
+                Some(items) => struct_layout(items)?,
+                None => return schema_error("arrays must define items"),
+            };
+            let length = if let Some(length) = schema.get("length") {
+                match length.as_u64().and_then(|l| usize::try_from(l).ok()) {
+                    Some(l) => ArrayLength::Fixed(l),
+                    None => return schema_error("array length must be a non-negative integer"),
+                }
+            } else if schema
+                .get("noLengthEncodingExhaustBuffer")
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+            {
+                ArrayLength::ExhaustBuffer
+            } else {
+                let format = schema
+                    .get("arrayLengthFormat")
+                    .and_then(Value::as_str)
+                    .unwrap_or("L");
+                ArrayLength::Prefixed(array_length_format_size(format)?)
+            };
+            Ok(StructLayout::Array {
+                item: Box::new(item),
+                length,
+            })
+        }
+        _ => match schema.get("binaryFormat").and_then(Value::as_str) {
+            Some(format) => match binary_format_size(format)? {
+                Some(size) => Ok(StructLayout::Fixed(size)),
+                None => Ok(StructLayout::VariableBytes(4)),
+            },
+            None => schema_error("struct codec values must have a binaryFormat"),
+        },
+    }
+}
+
+fn read_length(md: &[u8], pos: &mut usize, nbytes: usize) -> Result<usize, MetadataError> {
+    let bytes = take(md, pos, nbytes)?;
+    let mut length: u64 = 0;
+    for (i, b) in bytes.iter().enumerate() {
+        length |= u64::from(*b) << (8 * i);
+    }
+    match usize::try_from(length) {
+        Ok(l) => Ok(l),
+        Err(_) => validation_error(format!("array length {} is too large", length)),
+    }
+}
+
+fn take<'a>(md: &'a [u8], pos: &mut usize, n: usize) -> Result<&'a [u8], MetadataError> {
+    match pos.checked_add(n) {
+        Some(end) if end <= md.len() => {
+            let rv = &md[*pos..end];
+            *pos = end;
+            Ok(rv)
+        }
+        _ => validation_error(format!(
+            "expected at least {} bytes at offset {}, found {}",
+            n,
+            pos,
+            md.len()
+        )),
+    }
+}
+
+fn validate_struct(layout: &StructLayout, md: &[u8], pos: &mut usize) -> Result<(), MetadataError> {
+    match layout {
+        StructLayout::Fixed(size) => take(md, pos, *size).map(|_| ()),
+        StructLayout::VariableBytes(prefix) => {
+            let length = read_length(md, pos, *prefix)?;
+            take(md, pos, length).map(|_| ())
+        }
+        StructLayout::Object(fields) => fields
+            .iter()
+            .try_for_each(|field| validate_struct(field, md, pos)),
+        StructLayout::Array { item, length } => match length {
+            ArrayLength::Fixed(n) => (0..*n).try_for_each(|_| validate_struct(item, md, pos)),
+            ArrayLength::Prefixed(prefix) => {
+                let n = read_length(md, pos, *prefix)?;
+                (0..n).try_for_each(|_| validate_struct(item, md, pos))
+            }
+            ArrayLength::ExhaustBuffer => {
+                while *pos < md.len() {
+                    validate_struct(item, md, pos)?;
+                }
+                Ok(())
+            }
+        },
+    }
+}
+
+fn json_type_matches(name: &str, value: &Value) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|x| x.fract() == 0.)
+        }
+        _ => false,
+    }
+}
+
+fn validate_json(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    let location = if path.is_empty() { "/" } else { path };
+    match schema.get("type") {
+        Some(Value::String(t)) if !json_type_matches(t, value) => {
+            return Err(format!("{}: expected type {}, got {}", location, t, value));
+        }
+        Some(Value::Array(types))
+            if !types
+                .iter()
+                .filter_map(Value::as_str)
+                .any(|t| json_type_matches(t, value)) =>
+        {
+            return Err(format!(
+                "{}: expected one of the types {}, got {}",
+                location,
+                Value::Array(types.clone()),
+                value
+            ));
+        }
+        _ => (),
+    }
+    if let Some(Value::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(value) {
+            return Err(format!(
+                "{}: {} is not one of {:?}",
+                location, value, allowed
+            ));
+        }
+    }
+    if let Some(constant) = schema.get("const") {
+        if constant != value {
+            return Err(format!(
+                "{}: expected {}, got {}",
+                location, constant, value
+            ));
+        }
+    }
+    if let Some(x) = value.as_f64() {
+        if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+            if x < minimum {
+                return Err(format!("{}: {} is less than {}", location, x, minimum));
+            }
+        }
+        if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+            if x > maximum {
+                return Err(format!("{}: {} is greater than {}", location, x, maximum));
+            }
+        }
+    }
+    if let Value::Object(map) = value {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !map.contains_key(key) {
+                    return Err(format!("{}: missing required property {:?}", location, key));
+                }
+            }
+        }
+        for (key, v) in map {
+            let child = format!("{}/{}", path, key);
+            match properties.and_then(|p| p.get(key)) {
+                Some(property) => validate_json(property, v, &child)?,
+                None => match schema.get("additionalProperties") {
+                    Some(Value::Bool(false)) => {
+                        return Err(format!("{}: unexpected property {:?}", location, key));
+                    }
+                    Some(additional @ Value::Object(_)) => validate_json(additional, v, &child)?,
+                    _ => (),
+                },
+            }
+        }
+    }
+    if let Value::Array(values) = value {
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+            if (values.len() as u64) < min {
+                return Err(format!("{}: expected at least {} items", location, min));
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+            if (values.len() as u64) > max {
+                return Err(format!("{}: expected at most {} items", location, max));
+            }
+        }
+        if let Some(items) = schema.get("items") {
+            for (i, v) in values.iter().enumerate() {
+                validate_json(items, v, &format!("{}/{}", path, i))?;
+            }
+        }
+    }
+    if let Some(Value::Array(options)) = schema.get("anyOf") {
+        if !options
+            .iter()
+            .any(|s| validate_json(s, value, path).is_ok())
+        {
+            return Err(format!("{}: {} matches none of anyOf", location, value));
+        }
+    }
+    if let Some(Value::Array(options)) = schema.get("oneOf") {
+        let matches = options
+            .iter()
+            .filter(|s| validate_json(s, value, path).is_ok())
+            .count();
+        if matches != 1 {
+            return Err(format!(
+                "{}: {} matches {} of oneOf, expected exactly 1",
+                location, value, matches
+            ));
+        }
+    }
+    Ok(())
+}
+
+impl MetadataSchema {
+    /// Parse a metadata schema.
+    ///
+    /// # Errors
+    ///
+    /// [`MetadataError::SchemaError`] if `schema` is not a JSON object,
+    /// does not name a codec, or does not describe a valid layout
+    /// for the `struct` codec.
+    pub fn parse(schema: &str) -> Result<Self, MetadataError> {
+        let value: Value = match serde_json::from_str(schema) {
+            Ok(v) => v,
+            Err(e) => return schema_error(e.to_string()),
+        };
+        if !value.is_object() {
+            return schema_error("the schema must be a JSON object");
+        }
+        let codec = match value.get("codec") {
+            Some(Value::String(codec)) => match codec.as_str() {
+                "json" => MetadataCodec::Json,
+                "struct" => MetadataCodec::Struct,
+                _ => MetadataCodec::Other(codec.clone()),
+            },
+            _ => return schema_error("the schema must name a codec"),
+        };
+        let layout = match codec {
+            MetadataCodec::Struct => Some(struct_layout(&value)?),
+            _ => None,
+        };
+        Ok(Self {
+            schema: schema.to_string(),
+            value,
+            codec,
+            layout,
+        })
+    }
+
+    /// The "permissive_json" schema.
+    ///
+    /// Metadata are JSON and any valid JSON value is accepted.
+    /// Use [`serde_json::Value`] as the metadata type to read
+    /// and write rows of tables with this schema.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::metadata::{MetadataCodec, MetadataSchema};
+    ///
+    /// let schema = MetadataSchema::permissive_json();
+    /// assert_eq!(schema.codec(), &MetadataCodec::Json);
+    /// assert!(schema.validate(br#"{"anything": [1, "two"]}"#).is_ok());
+    /// assert!(schema.validate(b"not json").is_err());
+    /// ```
+    pub fn permissive_json() -> Self {
+        // A valid schema naming the json codec
+        Self::parse(r#"{"codec":"json"}"#).unwrap()
+    }
+
+    /// The codec used by the schema.
+    pub fn codec(&self) -> &MetadataCodec {
+        &self.codec
+    }
+
+    /// The schema as a string, exactly as it was parsed.
+    pub fn as_str(&self) -> &str {
+        &self.schema
+    }
+
+    /// The schema as a JSON value.
+    pub fn as_json(&self) -> &Value {
+        &self.value
+    }
+
+    /// Validate encoded metadata against the schema.
+    ///
+    /// Empty metadata are valid for the `json` codec, as they
+    /// decode to an empty object.
+    /// Metadata using an [`Other`](MetadataCodec::Other) codec are not checked.
+    ///
+    /// # Errors
+    ///
+    /// [`MetadataError::ValidationError`] if `md` cannot be decoded
+    /// or does not match the schema.
+    pub fn validate(&self, md: &[u8]) -> Result<(), MetadataError> {
+        match &self.codec {
+            MetadataCodec::Json => {
+                if md.is_empty() {
+                    return Ok(());
+                }
+                let value: Value = match serde_json::from_slice(md) {
+                    Ok(v) => v,
+                    Err(e) => return validation_error(e.to_string()),
+                };
+                validate_json(&self.value, &value, "").or_else(validation_error)
+            }
+            MetadataCodec::Struct => {
+                // Set when parsing a struct codec schema.
+                let layout = self.layout.as_ref().unwrap();
+                let mut pos = 0;
+                validate_struct(layout, md, &mut pos)?;
+                if pos != md.len() {
+                    return validation_error(format!(
+                        "{} trailing bytes after position {}",
+                        md.len() - pos,
+                        pos
+                    ));
+                }
+                Ok(())
+            }
+            MetadataCodec::Other(_) => Ok(()),
+        }
+    }
+}
+
+impl std::str::FromStr for MetadataSchema {
+    type Err = MetadataError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&str> for MetadataSchema {
+    type Error = MetadataError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+impl AsRef<str> for MetadataSchema {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl std::fmt::Display for MetadataSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.schema)
+    }
+}
+
+impl PartialEq for MetadataSchema {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+/// Compare two metadata schemas.
+///
+/// Schemas that parse are compared as JSON values,
+/// so formatting and key order are ignored.
+pub(crate) fn schemas_equal(a: Option<&str>, b: Option<&str>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => match (MetadataSchema::parse(a), MetadataSchema::parse(b)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => a == b,
+        },
+        (a, b) => a == b,
+    }
+}
+
+/// Return `true` if the metadata of a table with this schema
+/// should be compared as JSON values.
+pub(crate) fn uses_json_codec(schema: Option<&str>) -> bool {
+    match schema.map(MetadataSchema::parse) {
+        Some(Ok(schema)) => schema.codec() == &MetadataCodec::Json,
+        _ => false,
+    }
+}
+
+/// Compare the encoded metadata of two rows.
+///
+/// If `json` is `true`, metadata that decode are compared as JSON
+/// values and empty metadata equal an empty object.
+/// Otherwise, the bytes are compared.
+pub(crate) fn encoded_metadata_equal(json: bool, a: Option<&[u8]>, b: Option<&[u8]>) -> bool {
+    if a == b {
+        return true;
+    }
+    if !json {
+        return false;
+    }
+    let decode = |md: Option<&[u8]>| -> Option<Value> {
+        match md {
+            None => Some(Value::Object(serde_json::Map::new())),
+            Some(md) => serde_json::from_slice(md).ok(),
+        }
+    };
+    match (decode(a), decode(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            MetadataSchema::parse("not json"),
+            Err(MetadataError::SchemaError { message: _ })
+        ));
+        assert!(MetadataSchema::parse("[]").is_err());
+        assert!(MetadataSchema::parse(r#"{"type": "object"}"#).is_err());
+        assert!(MetadataSchema::parse(
+            r#"{"codec": "struct", "type": "object", "properties": {"x": {"type": "number"}}}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_other_codec() {
+        let schema = MetadataSchema::parse(r#"{"codec": "pickle"}"#).unwrap();
+        assert_eq!(schema.codec(), &MetadataCodec::Other("pickle".to_string()));
+        assert!(schema.validate(b"anything").is_ok());
+    }
+
+    #[test]
+    fn test_json_validation() {
+        let schema = MetadataSchema::parse(
+            r#"{"codec": "json", "type": "object",
+                "properties": {
+                    "values": {"type": "array", "items": {"type": "number", "minimum": 0}},
+                    "kind": {"enum": ["a", "b"]}
+                },
+                "additionalProperties": false}"#,
+        )
+        .unwrap();
+        assert!(schema.validate(b"").is_ok());
+        assert!(schema
+            .validate(br#"{"values": [0, 1.5], "kind": "a"}"#)
+            .is_ok());
+        assert!(schema.validate(br#"{"values": [-1]}"#).is_err());
+        assert!(schema.validate(br#"{"kind": "c"}"#).is_err());
+        assert!(schema.validate(br#"{"other": 1}"#).is_err());
+        assert!(matches!(
+            schema.validate(b"{"),
+            Err(MetadataError::ValidationError { message: _ })
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_struct_validation() {
+        #[derive(serde::Serialize)]
+        struct Md {
+            x: i32,
+            name: String,
+            values: Vec<f64>,
+        }
+        let schema = MetadataSchema::parse(
+            r#"{"codec": "struct", "type": "object",
+                "properties": {
+                    "values": {"index": 2, "type": "array", "items": {"type": "number", "binaryFormat": "d"}},
+                    "name": {"index": 1, "type": "string", "binaryFormat": "s"},
+                    "x": {"index": 0, "type": "integer", "binaryFormat": "i"}
+                }}"#,
+        )
+        .unwrap();
+        let md = Md {
+            x: 1,
+            name: "abc".to_string(),
+            values: vec![1., 2.],
+        };
+        let encoded = crate::metadata::struct_encode(&md).unwrap();
+        assert!(schema.validate(&encoded).is_ok());
+        assert!(schema.validate(&encoded[..encoded.len() - 1]).is_err());
+        let mut longer = encoded.clone();
+        longer.push(0);
+        assert!(schema.validate(&longer).is_err());
+    }
+
+    #[test]
+    fn test_struct_fixed_layouts() {
+        let schema = MetadataSchema::parse(
+            r#"{"codec": "struct", "type": "object",
+                "properties": {
+                    "a": {"type": "string", "binaryFormat": "3s"},
+                    "b": {"type": "array", "length": 2, "items": {"type": "integer", "binaryFormat": "H"}},
+                    "c": {"type": "array", "arrayLengthFormat": "B", "items": {"type": "boolean", "binaryFormat": "?"}}
+                }}"#,
+        )
+        .unwrap();
+        assert!(schema
+            .validate(&[b'x', b'y', b'z', 0, 0, 1, 0, 2, 1, 0])
+            .is_ok());
+        assert!(schema
+            .validate(&[b'x', b'y', b'z', 0, 0, 1, 0, 2, 1])
+            .is_err());
+    }
+}
@@ -1,5 +1,4 @@
 use crate::bindings as ll_bindings;
-use crate::metadata;
 use crate::Position;
 use crate::SizeType;
 use crate::Time;
@@ -8,6 +7,7 @@ use crate::{MigrationId, NodeId, PopulationId};
 use ll_bindings::{tsk_migration_table_free, tsk_migration_table_init};
 
 /// Row of a [`MigrationTable`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MigrationTableRow {
     pub id: MigrationId,
     pub left: Position,
@@ -32,11 +32,68 @@ impl PartialEq for MigrationTableRow {
     }
 }
 
+impl std::fmt::Display for MigrationTableRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "id: {}, left: {}, right: {}, node: {}, source: {}, dest: {}, time: {}, metadata: {}",
+            self.id,
+            self.left,
+            self.right,
+            self.node,
+            self.source,
+            self.dest,
+            self.time,
+            crate::util::DisplayMetadata(&self.metadata),
+        )
+    }
+}
+
+/// A view of a row of a [`MigrationTable`].
+///
+/// Unlike [`MigrationTableRow`], the view borrows its data from the table.
+/// Views are returned by [`MigrationTable::get`] and [`MigrationTable::iter_views`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MigrationTableRowView<'a> {
+    pub id: crate::MigrationId,
+    pub left: crate::Position,
+    pub right: crate::Position,
+    pub node: crate::NodeId,
+    pub source: crate::PopulationId,
+    pub dest: crate::PopulationId,
+    pub time: crate::Time,
+    pub metadata: Option<&'a [u8]>,
+}
+
+impl_row_view_metadata!(MigrationTableRowView);
+impl_row_view_into_row!(
+    MigrationTableRowView,
+    MigrationTableRow,
+    [id, left, right, node, source, dest, time],
+    [metadata]
+);
+
+fn make_migration_table_row_view<'a>(
+    table: &'a MigrationTable<'_>,
+    pos: tsk_id_t,
+) -> MigrationTableRowView<'a> {
+    // pos is in range, so neither the indexing nor the unwraps can fail
+    let index = usize::try_from(pos).unwrap();
+    MigrationTableRowView {
+        id: pos.into(),
+        left: table.left_slice()[index],
+        right: table.right_slice()[index],
+        node: table.node_slice()[index],
+        source: table.source_slice()[index],
+        dest: table.dest_slice()[index],
+        time: table.time_slice()[index],
+        metadata: table.metadata_bytes(pos).unwrap(),
+    }
+}
+
 fn make_migration_table_row(table: &MigrationTable, pos: tsk_id_t) -> Option<MigrationTableRow> {
-    // panic is okay here, as we are handling a bad
-    // input value before we first call this to
-    // set up the iterator
-    let p = crate::SizeType::try_from(pos).unwrap();
+    // A negative pos is not a row, so there is nothing to return.
+    let p = crate::SizeType::try_from(pos).ok()?;
     if p < table.num_rows() {
         let table_ref = table.table_;
         Some(MigrationTableRow {
@@ -79,6 +136,13 @@ impl<'a> Iterator for MigrationTableIterator<'a> {
     }
 }
 
+impl_table_into_iterator!(
+    MigrationTable,
+    OwnedMigrationTable,
+    MigrationTableRow,
+    make_migration_table_row
+);
+
 /// An immutable view of a migration table.
 ///
 /// These are not created directly.
@@ -88,22 +152,67 @@ pub struct MigrationTable<'a> {
     table_: &'a ll_bindings::tsk_migration_table_t,
 }
 
+impl_send_sync_for_table_view!(MigrationTable);
+impl_table_columns!(
+    MigrationTable,
+    Migrations,
+    [
+        (left, Float64),
+        (right, Float64),
+        (node, Int32),
+        (source, Int32),
+        (dest, Int32),
+        (time, Float64)
+    ],
+    [(metadata, UInt8, metadata_length)]
+);
+impl_table_equality!(MigrationTable, tsk_migration_table_equals);
+
 impl<'a> MigrationTable<'a> {
     pub(crate) fn new_from_table(migrations: &'a ll_bindings::tsk_migration_table_t) -> Self {
         MigrationTable { table_: migrations }
     }
 
+    /// Return a pointer to the underlying C table, for use
+    /// with the functions in [`bindings`](crate::bindings).
+    pub fn as_ptr(&self) -> *const ll_bindings::tsk_migration_table_t {
+        self.table_
+    }
+
     /// Return the number of rows
-    pub fn num_rows(&'a self) -> SizeType {
+    pub fn num_rows(&self) -> SizeType {
         self.table_.num_rows.into()
     }
 
+    /// Return the number of bytes used by the data in this table.
+    pub fn nbytes(&self) -> usize {
+        let t = self.table_;
+        crate::util::column_nbytes::<crate::tsk_id_t>(t.num_rows)
+            + crate::util::column_nbytes::<crate::tsk_id_t>(t.num_rows)
+            + crate::util::column_nbytes::<crate::tsk_id_t>(t.num_rows)
+            + crate::util::column_nbytes::<f64>(t.num_rows)
+            + crate::util::column_nbytes::<f64>(t.num_rows)
+            + crate::util::column_nbytes::<f64>(t.num_rows)
+            + crate::util::ragged_column_nbytes::<u8>(t.metadata_length, t.num_rows)
+            + crate::util::column_nbytes::<u8>(t.metadata_schema_length)
+    }
+
+    build_table_metadata_schema_getter!();
+
+    build_table_metadata_bytes_access!(MigrationId);
+
+    build_table_row_views!(
+        MigrationId,
+        MigrationTableRowView,
+        make_migration_table_row_view
+    );
+
     /// Return the left coordinate for a given row.
     ///
     /// # Errors
     ///
     /// * [`TskitError::IndexError`] if `row` is out of range.
-    pub fn left<M: Into<MigrationId> + Copy>(&'a self, row: M) -> Result<Position, TskitError> {
+    pub fn left<M: Into<MigrationId> + Copy>(&self, row: M) -> Result<Position, TskitError> {
         match unsafe_tsk_column_access!(row.into().0, 0, self.num_rows(), self.table_.left) {
             Ok(p) => Ok(p.into()),
             Err(e) => Err(e),
@@ -115,7 +224,7 @@ impl<'a> MigrationTable<'a> {
     /// # Errors
     ///
     /// * [`TskitError::IndexError`] if `row` is out of range.
-    pub fn right<M: Into<MigrationId> + Copy>(&'a self, row: M) -> Result<Position, TskitError> {
+    pub fn right<M: Into<MigrationId> + Copy>(&self, row: M) -> Result<Position, TskitError> {
         match unsafe_tsk_column_access!(row.into().0, 0, self.num_rows(), self.table_.right) {
             Ok(p) => Ok(p.into()),
             Err(e) => Err(e),
@@ -127,7 +236,7 @@ impl<'a> MigrationTable<'a> {
     /// # Errors
     ///
     /// * [`TskitError::IndexError`] if `row` is out of range.
-    pub fn node<M: Into<MigrationId> + Copy>(&'a self, row: M) -> Result<NodeId, TskitError> {
+    pub fn node<M: Into<MigrationId> + Copy>(&self, row: M) -> Result<NodeId, TskitError> {
         unsafe_tsk_column_access!(row.into().0, 0, self.num_rows(), self.table_.node, NodeId)
     }
 
@@ -136,10 +245,7 @@ impl<'a> MigrationTable<'a> {
     /// # Errors
     ///
     /// * [`TskitError::IndexError`] if `row` is out of range.
-    pub fn source<M: Into<MigrationId> + Copy>(
-        &'a self,
-        row: M,
-    ) -> Result<PopulationId, TskitError> {
+    pub fn source<M: Into<MigrationId> + Copy>(&self, row: M) -> Result<PopulationId, TskitError> {
         unsafe_tsk_column_access!(
             row.into().0,
             0,
@@ -154,7 +260,7 @@ impl<'a> MigrationTable<'a> {
     /// # Errors
     ///
     /// * [`TskitError::IndexError`] if `row` is out of range.
-    pub fn dest<M: Into<MigrationId> + Copy>(&'a self, row: M) -> Result<PopulationId, TskitError> {
+    pub fn dest<M: Into<MigrationId> + Copy>(&self, row: M) -> Result<PopulationId, TskitError> {
         unsafe_tsk_column_access!(
             row.into().0,
             0,
@@ -169,26 +275,31 @@ impl<'a> MigrationTable<'a> {
     /// # Errors
     ///
     /// * [`TskitError::IndexError`] if `row` is out of range.
-    pub fn time<M: Into<MigrationId> + Copy>(&'a self, row: M) -> Result<Time, TskitError> {
+    pub fn time<M: Into<MigrationId> + Copy>(&self, row: M) -> Result<Time, TskitError> {
         match unsafe_tsk_column_access!(row.into().0, 0, self.num_rows(), self.table_.time) {
             Ok(t) => Ok(t.into()),
             Err(e) => Err(e),
         }
     }
 
-    /// Return the metadata for a given row.
-    ///
-    /// # Errors
-    ///
-    /// * [`TskitError::IndexError`] if `row` is out of range.
-    pub fn metadata<T: metadata::MetadataRoundtrip>(
-        &'a self,
-        row: MigrationId,
-    ) -> Result<Option<T>, TskitError> {
-        let table_ref = self.table_;
-        let buffer = metadata_to_vector!(self, table_ref, row.0)?;
-        decode_metadata_row!(T, buffer)
-    }
+    build_table_column_slice_getter!(
+        /// Get the left column as a slice
+        => left, left_slice, crate::Position);
+    build_table_column_slice_getter!(
+        /// Get the right column as a slice
+        => right, right_slice, crate::Position);
+    build_table_column_slice_getter!(
+        /// Get the node column as a slice
+        => node, node_slice, crate::NodeId);
+    build_table_column_slice_getter!(
+        /// Get the source column as a slice
+        => source, source_slice, crate::PopulationId);
+    build_table_column_slice_getter!(
+        /// Get the dest column as a slice
+        => dest, dest_slice, crate::PopulationId);
+    build_table_column_slice_getter!(
+        /// Get the time column as a slice
+        => time, time_slice, crate::Time);
 
     /// Return an iterator over rows of the table.
     /// The value of the iterator is [`MigrationTableRow`].
@@ -263,10 +374,186 @@ build_owned_table_type!(
     tsk_migration_table_t,
     tsk_migration_table_init,
     tsk_migration_table_free,
-    ll_bindings::tsk_migration_table_clear
+    ll_bindings::tsk_migration_table_clear,
+    ll_bindings::tsk_migration_table_truncate
 );
 
 impl OwnedMigrationTable {
     migration_table_add_row!(=> add_row, self, *self.table);
     migration_table_add_row_with_metadata!(=> add_row_with_metadata, self, *self.table);
+    table_set_metadata_schema!(
+    /// Set the metadata schema of the table.
+    => set_metadata_schema, self, *self.table, ll_bindings::tsk_migration_table_set_metadata_schema);
+    migration_table_update_row!(
+    /// Replace the contents of row `row`.
+    ///
+    /// The row's metadata are cleared.
+    => update_row, self, *self.table);
+    migration_table_update_row_with_metadata!(
+    /// Replace the contents of row `row`, including its metadata.
+    => update_row_with_metadata, self, *self.table);
+
+    /// Replace the metadata of row `row`, keeping its other values.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`](crate::TskitError::IndexError) if `row` is out of range.
+    /// * [`TskitError::MetadataError`](crate::TskitError::MetadataError) if encoding fails.
+    pub fn set_metadata<
+        R: Into<crate::MigrationId> + Copy,
+        M: crate::metadata::MigrationMetadata,
+    >(
+        &mut self,
+        row: R,
+        metadata: &M,
+    ) -> crate::TskReturnValue {
+        let r = self.get(row).ok_or(crate::TskitError::IndexError)?;
+        let (id, span, node) = (r.id, (r.left, r.right), r.node);
+        let (source_dest, time) = ((r.source, r.dest), r.time);
+        self.update_row_with_metadata(id, span, node, source_dest, time, metadata)
+    }
+    migration_table_set_columns!(
+    /// Replace the contents of the table with the
+    /// span, node, source/destination, and time columns.
+    ///
+    /// All rows are loaded with a single call to the C API.
+    /// Metadata are cleared.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`](crate::TskitError::ValueError) if the columns
+    /// do not all have the same length.
+    => set_columns, self, *self.table);
+
+    /// Create a table from columns.
+    ///
+    /// See [`OwnedMigrationTable::set_columns`] for details.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`](crate::TskitError::ValueError) if the columns
+    /// do not all have the same length.
+    pub fn from_columns(
+        span: (&[crate::Position], &[crate::Position]),
+        node: &[crate::NodeId],
+        source_dest: (&[crate::PopulationId], &[crate::PopulationId]),
+        time: &[crate::Time],
+    ) -> Result<Self, crate::TskitError> {
+        let mut table = Self::default();
+        table.set_columns(span, node, source_dest, time)?;
+        Ok(table)
+    }
+
+    table_packset_metadata!(
+    /// Replace the metadata column of the table.
+    ///
+    /// See [`OwnedNodeTable::packset_metadata`](crate::OwnedNodeTable::packset_metadata).
+    => packset_metadata, self, *self.table);
+
+    table_migrate_metadata!(
+    /// Re-encode the metadata of every row with a new schema.
+    ///
+    /// See [`OwnedNodeTable::migrate_metadata`](crate::OwnedNodeTable::migrate_metadata).
+    => migrate_metadata, self, &*self, packset_metadata, set_metadata_schema, MigrationMetadata);
+
+    table_column_slice_mut!(
+    /// Mutable access to the left column.
+    => left_array_mut, self, *self.table, left, crate::Position);
+
+    table_column_slice_mut!(
+    /// Mutable access to the right column.
+    => right_array_mut, self, *self.table, right, crate::Position);
+
+    table_column_slice_mut!(
+    /// Mutable access to the time column.
+    => time_array_mut, self, *self.table, time, crate::Time);
+
+    table_set_max_increment!(
+    /// Set the number of rows by which the table grows
+    /// when it runs out of space.
+    ///
+    /// See [`OwnedNodeTable::set_max_rows_increment`](crate::OwnedNodeTable::set_max_rows_increment).
+    => set_max_rows_increment, self, *self.table, ll_bindings::tsk_migration_table_set_max_rows_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the metadata
+    /// column grows when it runs out of space.
+    => set_max_metadata_length_increment, self, *self.table, ll_bindings::tsk_migration_table_set_max_metadata_length_increment);
+}
+
+#[cfg(test)]
+mod test_owned_migration_table {
+    use super::*;
+    use crate::metadata;
+
+    #[derive(Debug, PartialEq)]
+    struct GenericMetadata(i32);
+
+    impl metadata::MetadataRoundtrip for GenericMetadata {
+        fn encode(&self) -> Result<Vec<u8>, metadata::MetadataError> {
+            Ok(self.0.to_le_bytes().to_vec())
+        }
+
+        fn decode(md: &[u8]) -> Result<Self, metadata::MetadataError> {
+            Ok(Self(i32::from_le_bytes(md.try_into().unwrap())))
+        }
+    }
+
+    impl metadata::MigrationMetadata for GenericMetadata {}
+
+    #[test]
+    fn test_add_row() {
+        let mut migrations = OwnedMigrationTable::default();
+        let rowid = migrations.add_row((0., 1.), 1, (0, 1), 10.3).unwrap();
+        assert_eq!(rowid, 0);
+        assert_eq!(migrations.num_rows(), 1);
+        let row = migrations.row(rowid).unwrap();
+        assert_eq!(row.left, 0.);
+        assert_eq!(row.right, 1.);
+        assert_eq!(row.node, 1);
+        assert_eq!(row.source, 0);
+        assert_eq!(row.dest, 1);
+        assert_eq!(row.time, 10.3);
+        assert!(row.metadata.is_none());
+    }
+
+    #[test]
+    fn test_metadata_and_iteration() {
+        let mut migrations = OwnedMigrationTable::default();
+        migrations.add_row((0., 1.), 1, (0, 1), 1.0).unwrap();
+        migrations
+            .add_row_with_metadata((1., 2.), 2, (1, 0), 2.0, &GenericMetadata(-3))
+            .unwrap();
+        assert!(migrations.metadata::<GenericMetadata>(0).unwrap().is_none());
+        assert_eq!(
+            migrations.metadata::<GenericMetadata>(1).unwrap(),
+            Some(GenericMetadata(-3))
+        );
+        let rows = migrations.iter().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 2);
+        assert!(rows[1] == migrations.row(1).unwrap());
+
+        let mut tables = crate::TableCollection::new(10.).unwrap();
+        tables.set_migrations(&migrations).unwrap();
+        assert!(tables.migrations().iter().eq(migrations.iter()));
+        assert!(tables.migrations_iter().eq(migrations.iter()));
+    }
+
+    #[test]
+    fn test_update_row_metadata() {
+        let mut migrations = OwnedMigrationTable::default();
+        migrations
+            .add_row_with_metadata((0., 1.), 1, (0, 1), 1.0, &GenericMetadata(1))
+            .unwrap();
+        migrations
+            .update_row_with_metadata(0, (0., 2.), 1, (0, 1), 1.0, &GenericMetadata(2))
+            .unwrap();
+        assert_eq!(migrations.right(0).unwrap(), 2.);
+        assert_eq!(
+            migrations.metadata::<GenericMetadata>(0).unwrap(),
+            Some(GenericMetadata(2))
+        );
+        migrations.update_row(0, (0., 2.), 1, (0, 1), 1.0).unwrap();
+        assert!(migrations.row(0).unwrap().metadata.is_none());
+    }
 }
@@ -7,6 +7,7 @@ use crate::{MutationId, NodeId, SiteId};
 use ll_bindings::{tsk_mutation_table_free, tsk_mutation_table_init};
 
 /// Row of a [`MutationTable`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MutationTableRow {
     pub id: MutationId,
     pub site: SiteId,
@@ -29,11 +30,82 @@ impl PartialEq for MutationTableRow {
     }
 }
 
+impl std::fmt::Display for MutationTableRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "id: {}, site: {}, node: {}, parent: {}, time: {}, derived_state: {}, metadata: {}",
+            self.id,
+            self.site,
+            self.node,
+            self.parent,
+            self.time,
+            crate::util::DisplayState(&self.derived_state),
+            crate::util::DisplayMetadata(&self.metadata),
+        )
+    }
+}
+
+/// A view of a row of a [`MutationTable`].
+///
+/// Unlike [`MutationTableRow`], the view borrows its data from the table.
+/// Views are returned by [`MutationTable::get`] and [`MutationTable::iter_views`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MutationTableRowView<'a> {
+    pub id: crate::MutationId,
+    pub site: crate::SiteId,
+    pub node: crate::NodeId,
+    pub parent: crate::MutationId,
+    pub time: crate::Time,
+    pub derived_state: Option<&'a [u8]>,
+    pub metadata: Option<&'a [u8]>,
+}
+
+impl_row_view_metadata!(MutationTableRowView);
+impl_row_view_into_row!(
+    MutationTableRowView,
+    MutationTableRow,
+    [id, site, node, parent, time],
+    [derived_state, metadata]
+);
+
+fn make_mutation_table_row_view<'a>(
+    table: &'a MutationTable<'_>,
+    pos: tsk_id_t,
+) -> MutationTableRowView<'a> {
+    mutation_table_row_view(table.table_, pos)
+}
+
+// Borrows from the C table so that tree sequences can
+// return views that live as long as they do.
+pub(crate) fn mutation_table_row_view(
+    table: &ll_bindings::tsk_mutation_table_t,
+    pos: tsk_id_t,
+) -> MutationTableRowView<'_> {
+    // pos is in range, so neither the indexing nor the unwraps can fail
+    let index = usize::try_from(pos).unwrap();
+    MutationTableRowView {
+        id: pos.into(),
+        site: table_column!(table, site)[index].into(),
+        node: table_column!(table, node)[index].into(),
+        parent: table_column!(table, parent)[index].into(),
+        time: table_column!(table, time)[index].into(),
+        derived_state: metadata::char_column_to_slice(
+            table,
+            table.derived_state,
+            table.derived_state_offset,
+            pos,
+            table.num_rows,
+            table.derived_state_length,
+        )
+        .unwrap(),
+        metadata: metadata_to_vector!(table, table, pos).unwrap(),
+    }
+}
+
 fn make_mutation_table_row(table: &MutationTable, pos: tsk_id_t) -> Option<MutationTableRow> {
-    // panic is okay here, as we are handling a bad
-    // input value before we first call this to
-    // set up the iterator
-    let p = crate::SizeType::try_from(pos).unwrap();
+    // A negative pos is not a row, so there is nothing to return.
+    let p = crate::SizeType::try_from(pos).ok()?;
     if p < table.num_rows() {
         let table_ref = table.table_;
         let rv = MutationTableRow {
@@ -74,6 +146,13 @@ impl<'a> Iterator for MutationTableIterator<'a> {
     }
 }
 
+impl_table_into_iterator!(
+    MutationTable,
+    OwnedMutationTable,
+    MutationTableRow,
+    make_mutation_table_row
+);
+
 /// An immutable view of site table.
 ///
 /// These are not created directly.
@@ -83,23 +162,68 @@ pub struct MutationTable<'a> {
     table_: &'a ll_bindings::tsk_mutation_table_t,
 }
 
+impl_send_sync_for_table_view!(MutationTable);
+impl_table_columns!(
+    MutationTable,
+    Mutations,
+    [
+        (site, Int32),
+        (node, Int32),
+        (parent, Int32),
+        (time, Float64)
+    ],
+    [
+        (derived_state, UInt8, derived_state_length),
+        (metadata, UInt8, metadata_length)
+    ]
+);
+impl_table_equality!(MutationTable, tsk_mutation_table_equals);
+
 impl<'a> MutationTable<'a> {
     pub(crate) fn new_from_table(mutations: &'a ll_bindings::tsk_mutation_table_t) -> Self {
         MutationTable { table_: mutations }
     }
 
+    /// Return a pointer to the underlying C table, for use
+    /// with the functions in [`bindings`](crate::bindings).
+    pub fn as_ptr(&self) -> *const ll_bindings::tsk_mutation_table_t {
+        self.table_
+    }
+
     /// Return the number of rows.
-    pub fn num_rows(&'a self) -> SizeType {
+    pub fn num_rows(&self) -> SizeType {
         self.table_.num_rows.into()
     }
 
+    /// Return the number of bytes used by the data in this table.
+    pub fn nbytes(&self) -> usize {
+        let t = self.table_;
+        crate::util::column_nbytes::<crate::tsk_id_t>(t.num_rows)
+            + crate::util::column_nbytes::<crate::tsk_id_t>(t.num_rows)
+            + crate::util::column_nbytes::<crate::tsk_id_t>(t.num_rows)
+            + crate::util::column_nbytes::<f64>(t.num_rows)
+            + crate::util::ragged_column_nbytes::<u8>(t.derived_state_length, t.num_rows)
+            + crate::util::ragged_column_nbytes::<u8>(t.metadata_length, t.num_rows)
+            + crate::util::column_nbytes::<u8>(t.metadata_schema_length)
+    }
+
+    build_table_metadata_schema_getter!();
+
+    build_table_metadata_bytes_access!(MutationId);
+
+    build_table_row_views!(
+        MutationId,
+        MutationTableRowView,
+        make_mutation_table_row_view
+    );
+
     /// Return the ``site`` value from row ``row`` of the table.
     ///
     /// # Errors
     ///
     /// Will return [``IndexError``](crate::TskitError::IndexError)
     /// if ``row`` is out of range.
-    pub fn site<M: Into<MutationId> + Copy>(&'a self, row: M) -> Result<SiteId, TskitError> {
+    pub fn site<M: Into<MutationId> + Copy>(&self, row: M) -> Result<SiteId, TskitError> {
         unsafe_tsk_column_access!(row.into().0, 0, self.num_rows(), self.table_.site, SiteId)
     }
 
@@ -109,7 +233,7 @@ impl<'a> MutationTable<'a> {
     ///
     /// Will return [``IndexError``](crate::TskitError::IndexError)
     /// if ``row`` is out of range.
-    pub fn node<M: Into<MutationId> + Copy>(&'a self, row: M) -> Result<NodeId, TskitError> {
+    pub fn node<M: Into<MutationId> + Copy>(&self, row: M) -> Result<NodeId, TskitError> {
         unsafe_tsk_column_access!(row.into().0, 0, self.num_rows(), self.table_.node, NodeId)
     }
 
@@ -119,7 +243,7 @@ impl<'a> MutationTable<'a> {
     ///
     /// Will return [``IndexError``](crate::TskitError::IndexError)
     /// if ``row`` is out of range.
-    pub fn parent<M: Into<MutationId> + Copy>(&'a self, row: M) -> Result<MutationId, TskitError> {
+    pub fn parent<M: Into<MutationId> + Copy>(&self, row: M) -> Result<MutationId, TskitError> {
         unsafe_tsk_column_access!(
             row.into().0,
             0,
@@ -129,13 +253,27 @@ impl<'a> MutationTable<'a> {
         )
     }
 
+    /// Return the ``parent`` value from row ``row`` of the table,
+    /// or `None` if the mutation has no parent.
+    ///
+    /// # Errors
+    ///
+    /// Will return [``IndexError``](crate::TskitError::IndexError)
+    /// if ``row`` is out of range.
+    pub fn parent_opt<M: Into<MutationId> + Copy>(
+        &self,
+        row: M,
+    ) -> Result<Option<MutationId>, TskitError> {
+        Ok(self.parent(row)?.to_option())
+    }
+
     /// Return the ``time`` value from row ``row`` of the table.
     ///
     /// # Errors
     ///
     /// Will return [``IndexError``](crate::TskitError::IndexError)
     /// if ``row`` is out of range.
-    pub fn time<M: Into<MutationId> + Copy>(&'a self, row: M) -> Result<Time, TskitError> {
+    pub fn time<M: Into<MutationId> + Copy>(&self, row: M) -> Result<Time, TskitError> {
         match unsafe_tsk_column_access!(row.into().0, 0, self.num_rows(), self.table_.time) {
             Ok(t) => Ok(t.into()),
             Err(e) => Err(e),
@@ -152,10 +290,7 @@ impl<'a> MutationTable<'a> {
     ///
     /// Will return [``IndexError``](crate::TskitError::IndexError)
     /// if ``row`` is out of range.
-    pub fn derived_state<M: Into<MutationId>>(
-        &'a self,
-        row: M,
-    ) -> Result<Option<&[u8]>, TskitError> {
+    pub fn derived_state<M: Into<MutationId>>(&self, row: M) -> Result<Option<&[u8]>, TskitError> {
         metadata::char_column_to_slice(
             self,
             self.table_.derived_state,
@@ -166,14 +301,18 @@ impl<'a> MutationTable<'a> {
         )
     }
 
-    pub fn metadata<T: metadata::MetadataRoundtrip>(
-        &'a self,
-        row: MutationId,
-    ) -> Result<Option<T>, TskitError> {
-        let table_ref = self.table_;
-        let buffer = metadata_to_vector!(self, table_ref, row.0)?;
-        decode_metadata_row!(T, buffer)
-    }
+    build_table_column_slice_getter!(
+        /// Get the site column as a slice
+        => site, site_slice, crate::SiteId);
+    build_table_column_slice_getter!(
+        /// Get the node column as a slice
+        => node, node_slice, crate::NodeId);
+    build_table_column_slice_getter!(
+        /// Get the parent column as a slice
+        => parent, parent_slice, crate::MutationId);
+    build_table_column_slice_getter!(
+        /// Get the time column as a slice
+        => time, time_slice, crate::Time);
 
     /// Return an iterator over rows of the table.
     /// The value of the iterator is [`MutationTableRow`].
@@ -199,6 +338,222 @@ impl<'a> MutationTable<'a> {
     }
 }
 
+/// Builder for adding a row to a mutation table.
+///
+/// The site and node must be set.
+/// By default, the mutation has no parent, no derived state,
+/// no metadata, and an unknown time.
+///
+/// The row is checked before it is passed to the C library,
+/// so that common mistakes give descriptive errors:
+///
+/// * neither the site nor the node may be NULL,
+/// * the time must be finite or unknown, and a `NaN` other than
+///   the special "unknown time" value is rejected,
+/// * a parent mutation must already be in the table and be at the same site,
+/// * a mutation and its parent must either both have known times,
+///   with the parent no younger than the child, or both have unknown times.
+///
+/// Obtained from [`OwnedMutationTable::add_row_defaults`]
+/// or [`TableCollection::add_mutation_defaults`](crate::TableCollection::add_mutation_defaults).
+///
+/// # Examples
+///
+/// ```
+/// let mut mutations = tskit::OwnedMutationTable::default();
+/// let parent = mutations
+///     .add_row_defaults()
+///     .site(0)
+///     .node(1)
+///     .time(2.0)
+///     .derived_state(b"G")
+///     .build()
+///     .unwrap();
+/// let child = mutations
+///     .add_row_defaults()
+///     .site(0)
+///     .node(2)
+///     .parent(parent)
+///     .time(1.0)
+///     .build()
+///     .unwrap();
+/// assert_eq!(mutations.parent(child).unwrap(), parent);
+///
+/// // A mutation with an unknown time cannot have a parent with a known time
+/// assert!(mutations
+///     .add_row_defaults()
+///     .site(0)
+///     .node(3)
+///     .parent(parent)
+///     .build()
+///     .is_err());
+/// ```
+#[must_use = "the row is not added until build is called"]
+pub struct MutationRowBuilder<'a> {
+    table: &'a mut ll_bindings::tsk_mutation_table_t,
+    site: SiteId,
+    node: NodeId,
+    parent: MutationId,
+    time: Time,
+    derived_state: Option<Vec<u8>>,
+    metadata: Result<Option<metadata::EncodedMetadata>, TskitError>,
+}
+
+impl<'a> MutationRowBuilder<'a> {
+    pub(crate) fn new(table: &'a mut ll_bindings::tsk_mutation_table_t) -> Self {
+        Self {
+            table,
+            site: SiteId::NULL,
+            node: NodeId::NULL,
+            parent: MutationId::NULL,
+            time: Time::from(f64::from_bits(ll_bindings::TSK_UNKNOWN_TIME_HEX)),
+            derived_state: None,
+            metadata: Ok(None),
+        }
+    }
+
+    /// Set the site of the mutation.
+    pub fn site<S: Into<SiteId>>(mut self, site: S) -> Self {
+        self.site = site.into();
+        self
+    }
+
+    /// Set the node of the mutation.
+    pub fn node<N: Into<NodeId>>(mut self, node: N) -> Self {
+        self.node = node.into();
+        self
+    }
+
+    /// Set the parent mutation.
+    pub fn parent<M: Into<MutationId>>(mut self, parent: M) -> Self {
+        self.parent = parent.into();
+        self
+    }
+
+    /// Set the time of the mutation.
+    pub fn time<T: Into<Time>>(mut self, time: T) -> Self {
+        self.time = time.into();
+        self
+    }
+
+    /// Mark the time of the mutation as unknown.
+    ///
+    /// This is the default.
+    pub fn unknown_time(mut self) -> Self {
+        self.time = Time::from(f64::from_bits(ll_bindings::TSK_UNKNOWN_TIME_HEX));
+        self
+    }
+
+    /// Set the derived state of the mutation.
+    pub fn derived_state<D: AsRef<[u8]> + ?Sized>(mut self, derived_state: &D) -> Self {
+        self.derived_state = Some(derived_state.as_ref().to_vec());
+        self
+    }
+
+    /// Set the metadata of the mutation.
+    ///
+    /// Encoding errors are reported by [`MutationRowBuilder::build`].
+    pub fn metadata<M: metadata::MutationMetadata>(mut self, metadata: &M) -> Self {
+        self.metadata = metadata::EncodedMetadata::new(metadata)
+            .map(Some)
+            .map_err(TskitError::from);
+        self
+    }
+
+    fn validate(&self) -> Result<(), TskitError> {
+        if self.site.is_null() {
+            return Err(TskitError::ValueError {
+                got: "a NULL site".to_string(),
+                expected: "a valid site".to_string(),
+            });
+        }
+        if self.node.is_null() {
+            return Err(TskitError::ValueError {
+                got: "a NULL node".to_string(),
+                expected: "a valid node".to_string(),
+            });
+        }
+        let time = f64::from(self.time);
+        let unknown = unsafe { ll_bindings::tsk_is_unknown_time(time) };
+        if !unknown && !time.is_finite() {
+            return Err(TskitError::ValueError {
+                got: format!("mutation time {}", time),
+                expected: "a finite time or an unknown time".to_string(),
+            });
+        }
+        if self.parent.is_null() {
+            return Ok(());
+        }
+        let num_rows = self.table.num_rows;
+        let parent = match crate::SizeType::try_from(self.parent) {
+            Ok(p) if u64::from(p) < num_rows => p.as_usize(),
+            _ => {
+                return Err(TskitError::ValueError {
+                    got: format!("parent mutation {}", self.parent),
+                    expected: format!("a mutation already in the table (< {})", num_rows),
+                })
+            }
+        };
+        let (parent_site, parent_time) =
+            unsafe { (*self.table.site.add(parent), *self.table.time.add(parent)) };
+        if parent_site != self.site.0 {
+            return Err(TskitError::ValueError {
+                got: format!("parent mutation at site {}", parent_site),
+                expected: format!("a parent mutation at site {}", self.site),
+            });
+        }
+        let parent_unknown = unsafe { ll_bindings::tsk_is_unknown_time(parent_time) };
+        if unknown != parent_unknown {
+            return Err(TskitError::ValueError {
+                got: "a mix of known and unknown times for a mutation and its parent".to_string(),
+                expected: "times that are either both known or both unknown".to_string(),
+            });
+        }
+        if !unknown && time > parent_time {
+            return Err(TskitError::ValueError {
+                got: format!("mutation time {} and parent time {}", time, parent_time),
+                expected: "a parent no younger than its child".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Check the row and add it to the table.
+    ///
+    /// # Returns
+    ///
+    /// The id of the new mutation.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if the row is not valid.
+    /// * [`TskitError::MetadataError`] if encoding the metadata failed.
+    /// * [`TskitError`] if the C library returns an error code.
+    pub fn build(self) -> Result<MutationId, TskitError> {
+        self.validate()?;
+        let md = self.metadata?;
+        let (md_ptr, md_len) = match &md {
+            Some(md) => (md.as_ptr(), md.len().into()),
+            None => (std::ptr::null(), 0),
+        };
+        let dstate = process_state_input!(self.derived_state.as_deref());
+        let rv = unsafe {
+            ll_bindings::tsk_mutation_table_add_row(
+                self.table,
+                self.site.0,
+                self.node.0,
+                self.parent.0,
+                self.time.0,
+                dstate.0,
+                dstate.1,
+                md_ptr,
+                md_len,
+            )
+        };
+        handle_tsk_return_value!(rv, rv.into())
+    }
+}
+
 build_owned_table_type!(
 /// A standalone mutation table that owns its data.
 ///
@@ -248,10 +603,119 @@ build_owned_table_type!(
     tsk_mutation_table_t,
     tsk_mutation_table_init,
     tsk_mutation_table_free,
-    ll_bindings::tsk_mutation_table_clear
+    ll_bindings::tsk_mutation_table_clear,
+    ll_bindings::tsk_mutation_table_truncate
 );
 
 impl OwnedMutationTable {
     mutation_table_add_row!(=> add_row, self, *self.table);
     mutation_table_add_row_with_metadata!(=> add_row_with_metadata, self, *self.table);
+
+    /// Start adding a row that is checked before insertion.
+    ///
+    /// See [`MutationRowBuilder`] for details.
+    pub fn add_row_defaults(&mut self) -> MutationRowBuilder<'_> {
+        MutationRowBuilder::new(&mut self.table)
+    }
+    table_set_metadata_schema!(
+    /// Set the metadata schema of the table.
+    => set_metadata_schema, self, *self.table, ll_bindings::tsk_mutation_table_set_metadata_schema);
+    mutation_table_update_row!(
+    /// Replace the contents of row `row`.
+    ///
+    /// The row's metadata are cleared.
+    => update_row, self, *self.table);
+    mutation_table_update_row_with_metadata!(
+    /// Replace the contents of row `row`, including its metadata.
+    => update_row_with_metadata, self, *self.table);
+
+    /// Replace the metadata of row `row`, keeping its other values.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`](crate::TskitError::IndexError) if `row` is out of range.
+    /// * [`TskitError::MetadataError`](crate::TskitError::MetadataError) if encoding fails.
+    pub fn set_metadata<R: Into<crate::MutationId> + Copy, M: crate::metadata::MutationMetadata>(
+        &mut self,
+        row: R,
+        metadata: &M,
+    ) -> crate::TskReturnValue {
+        let r = self.get(row).ok_or(crate::TskitError::IndexError)?;
+        let (id, site, node, parent, time) = (r.id, r.site, r.node, r.parent, r.time);
+        let derived_state = r.derived_state.map(<[_]>::to_vec);
+        self.update_row_with_metadata(
+            id,
+            site,
+            node,
+            parent,
+            time,
+            derived_state.as_deref(),
+            metadata,
+        )
+    }
+    mutation_table_set_columns!(
+    /// Replace the contents of the table with the
+    /// site, node, parent, time, and derived state columns.
+    ///
+    /// All rows are loaded with a single call to the C API.
+    /// Metadata are cleared.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`](crate::TskitError::ValueError) if the columns
+    /// do not all have the same length.
+    => set_columns, self, *self.table);
+
+    /// Create a table from columns.
+    ///
+    /// See [`OwnedMutationTable::set_columns`] for details.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`](crate::TskitError::ValueError) if the columns
+    /// do not all have the same length.
+    pub fn from_columns(
+        site: &[crate::SiteId],
+        node: &[crate::NodeId],
+        parent: &[crate::MutationId],
+        time: &[crate::Time],
+        derived_state: &[Option<&[u8]>],
+    ) -> Result<Self, crate::TskitError> {
+        let mut table = Self::default();
+        table.set_columns(site, node, parent, time, derived_state)?;
+        Ok(table)
+    }
+
+    table_packset_metadata!(
+    /// Replace the metadata column of the table.
+    ///
+    /// See [`OwnedNodeTable::packset_metadata`](crate::OwnedNodeTable::packset_metadata).
+    => packset_metadata, self, *self.table);
+
+    table_migrate_metadata!(
+    /// Re-encode the metadata of every row with a new schema.
+    ///
+    /// See [`OwnedNodeTable::migrate_metadata`](crate::OwnedNodeTable::migrate_metadata).
+    => migrate_metadata, self, &*self, packset_metadata, set_metadata_schema, MutationMetadata);
+
+    table_column_slice_mut!(
+    /// Mutable access to the time column.
+    => time_array_mut, self, *self.table, time, crate::Time);
+
+    table_set_max_increment!(
+    /// Set the number of rows by which the table grows
+    /// when it runs out of space.
+    ///
+    /// See [`OwnedNodeTable::set_max_rows_increment`](crate::OwnedNodeTable::set_max_rows_increment).
+    => set_max_rows_increment, self, *self.table, ll_bindings::tsk_mutation_table_set_max_rows_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the metadata
+    /// column grows when it runs out of space.
+    => set_max_metadata_length_increment, self, *self.table, ll_bindings::tsk_mutation_table_set_max_metadata_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the derived_state
+    /// column grows when it runs out of space.
+    => set_max_derived_state_length_increment, self, *self.table, ll_bindings::tsk_mutation_table_set_max_derived_state_length_increment);
 }
@@ -8,6 +8,7 @@ use crate::{IndividualId, NodeId, PopulationId};
 use ll_bindings::{tsk_node_table_free, tsk_node_table_init};
 
 /// Row of a [`NodeTable`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NodeTableRow {
     pub id: NodeId,
     pub time: Time,
@@ -28,11 +29,82 @@ impl PartialEq for NodeTableRow {
     }
 }
 
+impl std::fmt::Display for NodeTableRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "id: {}, time: {}, flags: {}, population: {}, individual: {}, metadata: {}",
+            self.id,
+            self.time,
+            self.flags,
+            self.population,
+            self.individual,
+            crate::util::DisplayMetadata(&self.metadata),
+        )
+    }
+}
+
+/// A view of a row of a [`NodeTable`].
+///
+/// Unlike [`NodeTableRow`], the view borrows its data from the table.
+/// Views are returned by [`NodeTable::get`] and [`NodeTable::iter_views`].
+///
+/// # Examples
+///
+/// ```
+/// use tskit::prelude::*;
+/// use tskit::metadata::Bytes;
+///
+/// let mut tables = tskit::TableCollection::new(1.).unwrap();
+/// tables.add_node(0, 1.0, -1, -1).unwrap();
+/// tables.add_node_with_metadata(0, 0.0, -1, -1, &Bytes::from(vec![1, 2])).unwrap();
+///
+/// let nodes = tables.nodes();
+/// for view in nodes.iter_views() {
+///     match view.metadata {
+///         Some(md) => assert_eq!(md, &[1, 2]),
+///         None => assert_eq!(view.time, 1.0),
+///     }
+/// }
+///
+/// let view = nodes.get(1).unwrap();
+/// assert_eq!(view.id, 1);
+/// assert_eq!(view.decode_metadata::<Bytes>().unwrap(), Some(Bytes::from(vec![1, 2])));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NodeTableRowView<'a> {
+    pub id: crate::NodeId,
+    pub time: crate::Time,
+    pub flags: crate::NodeFlags,
+    pub population: crate::PopulationId,
+    pub individual: crate::IndividualId,
+    pub metadata: Option<&'a [u8]>,
+}
+
+impl_row_view_metadata!(NodeTableRowView);
+impl_row_view_into_row!(
+    NodeTableRowView,
+    NodeTableRow,
+    [id, time, flags, population, individual],
+    [metadata]
+);
+
+fn make_node_table_row_view<'a>(table: &'a NodeTable<'_>, pos: tsk_id_t) -> NodeTableRowView<'a> {
+    // pos is in range, so neither the indexing nor the unwraps can fail
+    let index = usize::try_from(pos).unwrap();
+    NodeTableRowView {
+        id: pos.into(),
+        time: table.time_slice()[index],
+        flags: table.flags_slice()[index],
+        population: table.population_slice()[index],
+        individual: table.individual_slice()[index],
+        metadata: table.metadata_bytes(pos).unwrap(),
+    }
+}
+
 fn make_node_table_row(table: &NodeTable, pos: tsk_id_t) -> Option<NodeTableRow> {
-    // panic is okay here, as we are handling a bad
-    // input value before we first call this to
-    // set up the iterator
-    let p = crate::SizeType::try_from(pos).unwrap();
+    // A negative pos is not a row, so there is nothing to return.
+    let p = crate::SizeType::try_from(pos).ok()?;
     if p < table.num_rows() {
         let table_ref = table.table_;
         Some(NodeTableRow {
@@ -71,32 +143,112 @@ impl<'a> Iterator for NodeTableIterator<'a> {
     }
 }
 
+impl_table_into_iterator!(NodeTable, OwnedNodeTable, NodeTableRow, make_node_table_row);
+
 /// An immtable view of a node table.
 ///
 /// These are not created directly.
 /// Instead, use [`TableAccess::nodes`](crate::TableAccess::nodes)
 /// to get a reference to an existing node table;
+///
+/// A view borrows the tables it was obtained from.
+/// Its methods only borrow the view itself, so views
+/// can be stored in other types:
+///
+/// ```
+/// use tskit::TableAccess;
+///
+/// struct Tables<'a> {
+///     nodes: tskit::NodeTable<'a>,
+///     edges: tskit::EdgeTable<'a>,
+/// }
+///
+/// impl Tables<'_> {
+///     fn num_rows(&self) -> u64 {
+///         u64::from(self.nodes.num_rows()) + u64::from(self.edges.num_rows())
+///     }
+/// }
+///
+/// let mut tables = tskit::TableCollection::new(1.).unwrap();
+/// tables.add_node(0, 1.0, -1, -1).unwrap();
+/// let views = Tables {
+///     nodes: tables.nodes(),
+///     edges: tables.edges(),
+/// };
+/// assert_eq!(views.num_rows(), 1);
+/// assert_eq!(views.nodes.time(0).unwrap(), 1.0);
+/// ```
+///
+/// Views are read-only.
+/// To modify columns in place, use the `*_array_mut` functions of
+/// [`TableCollection`](crate::TableCollection) or [`OwnedNodeTable`]:
+///
+/// ```compile_fail
+/// use tskit::TableAccess;
+///
+/// let mut tables = tskit::TableCollection::new(1.).unwrap();
+/// tables.add_node(0, 1.0, -1, -1).unwrap();
+/// let mut nodes = tables.nodes();
+/// nodes.time_array_mut()[0] = 2.0.into();
+/// ```
 pub struct NodeTable<'a> {
     table_: &'a ll_bindings::tsk_node_table_t,
 }
 
+impl_send_sync_for_table_view!(NodeTable);
+impl_table_columns!(
+    NodeTable,
+    Nodes,
+    [
+        (flags, UInt32),
+        (time, Float64),
+        (population, Int32),
+        (individual, Int32)
+    ],
+    [(metadata, UInt8, metadata_length)]
+);
+impl_table_equality!(NodeTable, tsk_node_table_equals);
+
 impl<'a> NodeTable<'a> {
     pub(crate) fn new_from_table(nodes: &'a ll_bindings::tsk_node_table_t) -> Self {
         NodeTable { table_: nodes }
     }
 
+    /// Return a pointer to the underlying C table, for use
+    /// with the functions in [`bindings`](crate::bindings).
+    pub fn as_ptr(&self) -> *const ll_bindings::tsk_node_table_t {
+        self.table_
+    }
+
     /// Return the number of rows
-    pub fn num_rows(&'a self) -> SizeType {
+    pub fn num_rows(&self) -> SizeType {
         self.table_.num_rows.into()
     }
 
+    /// Return the number of bytes used by the data in this table.
+    pub fn nbytes(&self) -> usize {
+        let t = self.table_;
+        crate::util::column_nbytes::<crate::tsk_flags_t>(t.num_rows)
+            + crate::util::column_nbytes::<f64>(t.num_rows)
+            + crate::util::column_nbytes::<crate::tsk_id_t>(t.num_rows)
+            + crate::util::column_nbytes::<crate::tsk_id_t>(t.num_rows)
+            + crate::util::ragged_column_nbytes::<u8>(t.metadata_length, t.num_rows)
+            + crate::util::column_nbytes::<u8>(t.metadata_schema_length)
+    }
+
+    build_table_metadata_schema_getter!();
+
+    build_table_metadata_bytes_access!(NodeId);
+
+    build_table_row_views!(NodeId, NodeTableRowView, make_node_table_row_view);
+
     /// Return the ``time`` value from row ``row`` of the table.
     ///
     /// # Errors
     ///
     /// Will return [``IndexError``](crate::TskitError::IndexError)
     /// if ``row`` is out of range.
-    pub fn time<N: Into<NodeId> + Copy>(&'a self, row: N) -> Result<Time, TskitError> {
+    pub fn time<N: Into<NodeId> + Copy>(&self, row: N) -> Result<Time, TskitError> {
         match unsafe_tsk_column_access!(row.into().0, 0, self.num_rows(), self.table_.time) {
             Ok(t) => Ok(t.into()),
             Err(e) => Err(e),
@@ -109,43 +261,20 @@ impl<'a> NodeTable<'a> {
     ///
     /// Will return [``IndexError``](crate::TskitError::IndexError)
     /// if ``row`` is out of range.
-    pub fn flags<N: Into<NodeId> + Copy>(&'a self, row: N) -> Result<NodeFlags, TskitError> {
+    pub fn flags<N: Into<NodeId> + Copy>(&self, row: N) -> Result<NodeFlags, TskitError> {
         match unsafe_tsk_column_access!(row.into().0, 0, self.num_rows(), self.table_.flags) {
             Ok(f) => Ok(NodeFlags::from(f)),
             Err(e) => Err(e),
         }
     }
 
-    /// Mutable access to node flags.
-    pub fn flags_array_mut(&mut self) -> &mut [NodeFlags] {
-        unsafe {
-            std::slice::from_raw_parts_mut(
-                self.table_.flags.cast::<NodeFlags>(),
-                usize::try_from(self.table_.num_rows).unwrap(),
-            )
-        }
-    }
-
-    /// Mutable access to node times.
-    pub fn time_array_mut(&mut self) -> &mut [Time] {
-        unsafe {
-            std::slice::from_raw_parts_mut(
-                self.table_.time.cast::<Time>(),
-                usize::try_from(self.table_.num_rows).unwrap(),
-            )
-        }
-    }
-
     /// Return the ``population`` value from row ``row`` of the table.
     ///
     /// # Errors
     ///
     /// Will return [``IndexError``](crate::TskitError::IndexError)
     /// if ``row`` is out of range.
-    pub fn population<N: Into<NodeId> + Copy>(
-        &'a self,
-        row: N,
-    ) -> Result<PopulationId, TskitError> {
+    pub fn population<N: Into<NodeId> + Copy>(&self, row: N) -> Result<PopulationId, TskitError> {
         unsafe_tsk_column_access!(
             row.into().0,
             0,
@@ -161,20 +290,31 @@ impl<'a> NodeTable<'a> {
     ///
     /// Will return [``IndexError``](crate::TskitError::IndexError)
     /// if ``row`` is out of range.
-    pub fn deme<N: Into<NodeId> + Copy>(&'a self, row: N) -> Result<PopulationId, TskitError> {
+    pub fn deme<N: Into<NodeId> + Copy>(&self, row: N) -> Result<PopulationId, TskitError> {
         self.population(row)
     }
 
-    /// Return the ``individual`` value from row ``row`` of the table.
+    /// Return the ``population`` value from row ``row`` of the table,
+    /// or `None` if it is `NULL`.
     ///
     /// # Errors
     ///
     /// Will return [``IndexError``](crate::TskitError::IndexError)
     /// if ``row`` is out of range.
-    pub fn individual<N: Into<NodeId> + Copy>(
-        &'a self,
+    pub fn population_opt<N: Into<NodeId> + Copy>(
+        &self,
         row: N,
-    ) -> Result<IndividualId, TskitError> {
+    ) -> Result<Option<PopulationId>, TskitError> {
+        Ok(self.population(row)?.to_option())
+    }
+
+    /// Return the ``individual`` value from row ``row`` of the table.
+    ///
+    /// # Errors
+    ///
+    /// Will return [``IndexError``](crate::TskitError::IndexError)
+    /// if ``row`` is out of range.
+    pub fn individual<N: Into<NodeId> + Copy>(&self, row: N) -> Result<IndividualId, TskitError> {
         unsafe_tsk_column_access!(
             row.into().0,
             0,
@@ -184,15 +324,44 @@ impl<'a> NodeTable<'a> {
         )
     }
 
-    pub fn metadata<T: metadata::MetadataRoundtrip>(
-        &'a self,
-        row: NodeId,
-    ) -> Result<Option<T>, TskitError> {
-        let table_ref = self.table_;
-        let buffer = metadata_to_vector!(self, table_ref, row.0)?;
-        decode_metadata_row!(T, buffer)
+    /// Return the ``individual`` value from row ``row`` of the table,
+    /// or `None` if it is `NULL`.
+    ///
+    /// # Errors
+    ///
+    /// Will return [``IndexError``](crate::TskitError::IndexError)
+    /// if ``row`` is out of range.
+    pub fn individual_opt<N: Into<NodeId> + Copy>(
+        &self,
+        row: N,
+    ) -> Result<Option<IndividualId>, TskitError> {
+        Ok(self.individual(row)?.to_option())
     }
 
+    build_table_column_slice_getter!(
+        /// Get the flags column as a slice
+        => flags, flags_slice, crate::NodeFlags);
+    build_table_column_slice_getter!(
+        /// Get the time column as a slice
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use tskit::TableAccess;
+        /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+        /// tables.add_node(0, 1.0, -1, -1).unwrap();
+        /// tables.add_node(0, 2.0, -1, -1).unwrap();
+        /// let total: f64 = tables.nodes().time_slice().iter().map(|&t| f64::from(t)).sum();
+        /// assert_eq!(total, 3.0);
+        /// ```
+        => time, time_slice, crate::Time);
+    build_table_column_slice_getter!(
+        /// Get the population column as a slice
+        => population, population_slice, crate::PopulationId);
+    build_table_column_slice_getter!(
+        /// Get the individual column as a slice
+        => individual, individual_slice, crate::IndividualId);
+
     /// Return an iterator over rows of the table.
     /// The value of the iterator is [`NodeTableRow`].
     pub fn iter(&self) -> impl Iterator<Item = NodeTableRow> + '_ {
@@ -245,6 +414,113 @@ impl<'a> NodeTable<'a> {
     }
 }
 
+/// Builder for adding a row to a node table.
+///
+/// Every column starts out with a default value:
+/// no flags, a time of `0.0`, no population,
+/// no individual, and no metadata.
+/// Only the columns that differ from the defaults need to be set.
+///
+/// Obtained from [`OwnedNodeTable::add_row_defaults`]
+/// or [`TableCollection::add_node_defaults`](crate::TableCollection::add_node_defaults).
+///
+/// # Examples
+///
+/// ```
+/// let mut nodes = tskit::OwnedNodeTable::default();
+/// let id = nodes.add_row_defaults().time(1.0).population(0).build().unwrap();
+/// assert_eq!(id, 0);
+/// assert_eq!(nodes.time(id).unwrap(), 1.0);
+/// assert_eq!(nodes.population(id).unwrap(), 0);
+/// assert_eq!(nodes.individual(id).unwrap(), tskit::IndividualId::NULL);
+/// ```
+#[must_use = "the row is not added until build is called"]
+pub struct NodeRowBuilder<'a> {
+    table: &'a mut ll_bindings::tsk_node_table_t,
+    flags: NodeFlags,
+    time: Time,
+    population: PopulationId,
+    individual: IndividualId,
+    metadata: Result<Option<metadata::EncodedMetadata>, TskitError>,
+}
+
+impl<'a> NodeRowBuilder<'a> {
+    pub(crate) fn new(table: &'a mut ll_bindings::tsk_node_table_t) -> Self {
+        Self {
+            table,
+            flags: NodeFlags::default(),
+            time: Time::from(0.0),
+            population: PopulationId::NULL,
+            individual: IndividualId::NULL,
+            metadata: Ok(None),
+        }
+    }
+
+    /// Set the node flags.
+    pub fn flags<F: Into<NodeFlags>>(mut self, flags: F) -> Self {
+        self.flags = flags.into();
+        self
+    }
+
+    /// Set the node time.
+    pub fn time<T: Into<Time>>(mut self, time: T) -> Self {
+        self.time = time.into();
+        self
+    }
+
+    /// Set the population of the node.
+    pub fn population<P: Into<PopulationId>>(mut self, population: P) -> Self {
+        self.population = population.into();
+        self
+    }
+
+    /// Set the individual of the node.
+    pub fn individual<I: Into<IndividualId>>(mut self, individual: I) -> Self {
+        self.individual = individual.into();
+        self
+    }
+
+    /// Set the metadata of the node.
+    ///
+    /// Encoding errors are reported by [`NodeRowBuilder::build`].
+    pub fn metadata<M: metadata::NodeMetadata>(mut self, metadata: &M) -> Self {
+        self.metadata = metadata::EncodedMetadata::new(metadata)
+            .map(Some)
+            .map_err(TskitError::from);
+        self
+    }
+
+    /// Add the row to the table.
+    ///
+    /// # Returns
+    ///
+    /// The id of the new node.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::MetadataError`] if encoding the metadata failed.
+    /// * [`TskitError`] if the C library returns an error code.
+    pub fn build(self) -> Result<NodeId, TskitError> {
+        let md = self.metadata?;
+        let (md_ptr, md_len) = match &md {
+            Some(md) => (md.as_ptr(), md.len().into()),
+            None => (std::ptr::null(), 0),
+        };
+        let rv = unsafe {
+            ll_bindings::tsk_node_table_add_row(
+                self.table,
+                self.flags.bits(),
+                self.time.0,
+                self.population.0,
+                self.individual.0,
+                md_ptr,
+                md_len,
+            )
+        };
+        handle_tsk_return_value!(rv, rv.into())
+    }
+}
+
 build_owned_table_type!(
     /// A standalone node table that owns its data.
     ///
@@ -294,12 +570,213 @@ build_owned_table_type!(
     tsk_node_table_t,
     tsk_node_table_init,
     tsk_node_table_free,
-    ll_bindings::tsk_node_table_clear
+    ll_bindings::tsk_node_table_clear,
+    ll_bindings::tsk_node_table_truncate
 );
 
 impl OwnedNodeTable {
     node_table_add_row!(=> add_row, self, (*self.table));
     node_table_add_row_with_metadata!(=> add_row_with_metadata, self, (*self.table));
+
+    /// Start adding a row whose columns take default values.
+    ///
+    /// See [`NodeRowBuilder`] for details.
+    pub fn add_row_defaults(&mut self) -> NodeRowBuilder<'_> {
+        NodeRowBuilder::new(&mut self.table)
+    }
+    table_set_metadata_schema!(
+    /// Set the metadata schema of the table.
+    => set_metadata_schema, self, (*self.table), ll_bindings::tsk_node_table_set_metadata_schema);
+    node_table_update_row!(
+    /// Replace the contents of row `row`.
+    ///
+    /// The row's metadata are cleared.
+    => update_row, self, (*self.table));
+    node_table_update_row_with_metadata!(
+    /// Replace the contents of row `row`, including its metadata.
+    => update_row_with_metadata, self, (*self.table));
+
+    /// Replace the metadata of row `row`, keeping its other values.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`](crate::TskitError::IndexError) if `row` is out of range.
+    /// * [`TskitError::MetadataError`](crate::TskitError::MetadataError) if encoding fails.
+    pub fn set_metadata<R: Into<crate::NodeId> + Copy, M: crate::metadata::NodeMetadata>(
+        &mut self,
+        row: R,
+        metadata: &M,
+    ) -> crate::TskReturnValue {
+        let r = self.get(row).ok_or(crate::TskitError::IndexError)?;
+        let (id, flags, time) = (r.id, r.flags, r.time);
+        let (population, individual) = (r.population, r.individual);
+        self.update_row_with_metadata(id, flags, time, population, individual, metadata)
+    }
+    node_table_set_columns!(
+    /// Replace the contents of the table with the
+    /// flags, time, population, and individual columns.
+    ///
+    /// All rows are loaded with a single call to the C API.
+    /// Metadata are cleared.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`](crate::TskitError::ValueError) if the columns
+    /// do not all have the same length.
+    => set_columns, self, (*self.table));
+
+    /// Create a table from columns.
+    ///
+    /// See [`OwnedNodeTable::set_columns`] for details.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`](crate::TskitError::ValueError) if the columns
+    /// do not all have the same length.
+    pub fn from_columns(
+        flags: &[crate::NodeFlags],
+        time: &[crate::Time],
+        population: &[crate::PopulationId],
+        individual: &[crate::IndividualId],
+    ) -> Result<Self, crate::TskitError> {
+        let mut table = Self::default();
+        table.set_columns(flags, time, population, individual)?;
+        Ok(table)
+    }
+
+    table_packset_metadata!(
+    /// Replace the metadata column of the table.
+    ///
+    /// `metadata` must contain one encoded value per row.
+    /// An empty value means that a row has no metadata.
+    /// The encoded values are copied into the table in a single pass,
+    /// which is much faster than updating each row.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`](crate::TskitError::ValueError) if the length of
+    /// `metadata` differs from the number of rows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut nodes = tskit::OwnedNodeTable::default();
+    /// nodes.add_row(0, 1.0, -1, -1).unwrap();
+    /// nodes.add_row(0, 0.0, -1, -1).unwrap();
+    /// nodes.packset_metadata(&[b"first".to_vec(), vec![]]).unwrap();
+    /// assert_eq!(nodes.row(0).unwrap().metadata, Some(b"first".to_vec()));
+    /// assert!(nodes.row(1).unwrap().metadata.is_none());
+    ///
+    /// // One value is required for each row
+    /// assert!(nodes.packset_metadata(&[b"first"]).is_err());
+    /// ```
+    => packset_metadata, self, (*self.table));
+
+    table_migrate_metadata!(
+    /// Re-encode the metadata of every row with a new schema.
+    ///
+    /// The metadata of each row is decoded as `Old`, passed to `convert`,
+    /// and the result is encoded with `New`.
+    /// Rows without metadata are left empty.
+    /// All rows are converted before the table is modified,
+    /// so the table is unchanged if any row fails to convert.
+    /// The new metadata column is then written in a single pass
+    /// and `new_schema` becomes the metadata schema of the table.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`](crate::TskitError::ValueError) if the current
+    ///   metadata schema differs from `old_schema`.
+    ///   A table without a schema has the schema `""`.
+    /// * [`TskitError::MetadataError`](crate::TskitError::MetadataError) if the metadata
+    ///   of a row cannot be decoded or encoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "derive")] {
+    /// use tskit::metadata::MetadataRoundtrip;
+    ///
+    /// #[derive(serde::Serialize, serde::Deserialize, tskit::metadata::NodeMetadata)]
+    /// #[serializer("serde_json")]
+    /// struct V1 {
+    ///     x: i32,
+    /// }
+    ///
+    /// #[derive(serde::Serialize, serde::Deserialize, tskit::metadata::NodeMetadata)]
+    /// #[serializer("serde_json")]
+    /// struct V2 {
+    ///     x: i64,
+    ///     label: String,
+    /// }
+    ///
+    /// let mut nodes = tskit::OwnedNodeTable::default();
+    /// nodes.add_row_with_metadata(0, 1.0, -1, -1, &V1 { x: 1 }).unwrap();
+    /// nodes.add_row(0, 0.0, -1, -1).unwrap();
+    /// nodes
+    ///     .migrate_metadata("", "v2", |old: V1| V2 {
+    ///         x: old.x.into(),
+    ///         label: format!("node {}", old.x),
+    ///     })
+    ///     .unwrap();
+    /// let new = nodes.metadata::<V2>(0).unwrap().unwrap();
+    /// assert_eq!(new.x, 1);
+    /// assert_eq!(new.label, "node 1");
+    /// assert!(nodes.row(1).unwrap().metadata.is_none());
+    /// assert_eq!(nodes.metadata_schema(), Some("v2"));
+    ///
+    /// // The schema must match
+    /// assert!(nodes
+    ///     .migrate_metadata("v1", "v3", |old: V2| old)
+    ///     .is_err());
+    /// # }
+    /// ```
+    => migrate_metadata, self, &*self, packset_metadata, set_metadata_schema, NodeMetadata);
+
+    table_column_slice_mut!(
+    /// Mutable access to the flags column.
+    => flags_array_mut, self, (*self.table), flags, crate::NodeFlags);
+
+    table_column_slice_mut!(
+    /// Mutable access to the time column.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut nodes = tskit::OwnedNodeTable::default();
+    /// nodes.add_row(0, 10.0, -1, -1).unwrap();
+    /// nodes.add_row(0, 0.0, -1, -1).unwrap();
+    /// // Convert from generations to years
+    /// for t in nodes.time_array_mut() {
+    ///     *t *= tskit::Time::from(25.0);
+    /// }
+    /// assert_eq!(nodes.time(0).unwrap(), 250.0);
+    /// ```
+    => time_array_mut, self, (*self.table), time, crate::Time);
+
+    table_set_max_increment!(
+    /// Set the number of rows by which the table grows
+    /// when it runs out of space.
+    ///
+    /// By default, the table doubles in size each time that it
+    /// grows, so setting an increment close to the final number of rows
+    /// avoids repeated reallocation.
+    /// An increment of zero restores the default behavior.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut nodes = tskit::OwnedNodeTable::default();
+    /// nodes.set_max_rows_increment(10000_usize).unwrap();
+    /// nodes.set_max_metadata_length_increment(10000_usize).unwrap();
+    /// nodes.add_row(0, 0.0, -1, -1).unwrap();
+    /// ```
+    => set_max_rows_increment, self, (*self.table), ll_bindings::tsk_node_table_set_max_rows_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the metadata
+    /// column grows when it runs out of space.
+    => set_max_metadata_length_increment, self, (*self.table), ll_bindings::tsk_node_table_set_max_metadata_length_increment);
 }
 
 #[cfg(test)]
@@ -313,4 +790,266 @@ mod test_owned_node_table {
         assert_eq!(rowid, 0);
         assert_eq!(nodes.num_rows(), 1);
     }
+
+    #[test]
+    fn test_add_row_defaults() {
+        let mut nodes = OwnedNodeTable::default();
+        let a = nodes.add_row_defaults().build().unwrap();
+        let b = nodes
+            .add_row_defaults()
+            .flags(NodeFlags::IS_SAMPLE)
+            .time(2.0)
+            .individual(3)
+            .metadata(&crate::metadata::Bytes::from(vec![7]))
+            .build()
+            .unwrap();
+        let row = nodes.row(a).unwrap();
+        assert_eq!(row.flags, NodeFlags::default());
+        assert_eq!(row.time, 0.0);
+        assert_eq!(row.population, PopulationId::NULL);
+        assert_eq!(row.individual, IndividualId::NULL);
+        assert!(row.metadata.is_none());
+        let row = nodes.row(b).unwrap();
+        assert!(row.flags.is_sample());
+        assert_eq!(row.time, 2.0);
+        assert_eq!(row.population, PopulationId::NULL);
+        assert_eq!(row.individual, 3);
+        assert_eq!(row.metadata, Some(vec![7]));
+    }
+
+    #[test]
+    fn test_update_row() {
+        let mut nodes = OwnedNodeTable::default();
+        nodes.add_row(0, 1.1, -1, -1).unwrap();
+        nodes.add_row(0, 2.2, -1, -1).unwrap();
+        nodes
+            .update_row(1, NodeFlags::IS_SAMPLE, 0.0, 3, -1)
+            .unwrap();
+        assert_eq!(nodes.time(0).unwrap(), 1.1);
+        let row = nodes.row(1).unwrap();
+        assert!(row.flags.is_sample());
+        assert_eq!(row.time, 0.0);
+        assert_eq!(row.population, 3);
+        assert!(nodes.update_row(2, 0, 0.0, -1, -1).is_err());
+    }
+
+    #[test]
+    fn test_row_display() {
+        let mut nodes = OwnedNodeTable::default();
+        nodes.add_row(NodeFlags::IS_SAMPLE, 0.5, 1, -1).unwrap();
+        let row = nodes.row(0).unwrap();
+        assert_eq!(
+            row.to_string(),
+            "id: 0, time: Time(0.5), flags: IS_SAMPLE, population: 1, individual: NULL, metadata: None"
+        );
+    }
+
+    #[test]
+    fn test_metadata_bytes() {
+        let mut nodes = OwnedNodeTable::default();
+        nodes.add_row(0, 1.1, -1, -1).unwrap();
+        nodes
+            .add_row_with_metadata(0, 1.1, -1, -1, &crate::metadata::Bytes::from(vec![3]))
+            .unwrap();
+        assert!(nodes.metadata_bytes(0).unwrap().is_none());
+        assert_eq!(nodes.metadata_bytes(1).unwrap(), Some(&[3_u8][..]));
+        assert!(nodes.metadata_bytes(2).is_err());
+    }
+
+    #[test]
+    fn test_set_metadata() {
+        use crate::metadata::Bytes;
+        let mut nodes = OwnedNodeTable::default();
+        nodes.add_row(NodeFlags::IS_SAMPLE, 1.1, 2, -1).unwrap();
+        nodes.add_row(0, 2.2, -1, -1).unwrap();
+        nodes.set_metadata(0, &Bytes::from(vec![4, 5])).unwrap();
+        let row = nodes.row(0).unwrap();
+        assert!(row.flags.is_sample());
+        assert_eq!(row.time, 1.1);
+        assert_eq!(row.population, 2);
+        assert_eq!(row.metadata, Some(vec![4, 5]));
+        assert!(nodes.row(1).unwrap().metadata.is_none());
+        assert!(matches!(
+            nodes.set_metadata(2, &Bytes::from(vec![1])),
+            Err(TskitError::IndexError)
+        ));
+
+        // Flags with bits that tskit does not name are kept.
+        let raw = NodeFlags::IS_SAMPLE.bits() | 1 << 4 | 1 << 17;
+        let rv = unsafe {
+            ll_bindings::tsk_node_table_add_row(
+                &mut *nodes.table,
+                raw,
+                0.0,
+                -1,
+                -1,
+                std::ptr::null(),
+                0,
+            )
+        };
+        assert_eq!(rv, 2);
+        nodes.set_metadata(2, &Bytes::from(vec![1])).unwrap();
+        assert_eq!(nodes.flags_slice()[2].bits(), raw);
+    }
+
+    #[test]
+    fn test_opt_accessors() {
+        let mut nodes = OwnedNodeTable::default();
+        nodes.add_row(0, 1.1, 2, -1).unwrap();
+        assert_eq!(nodes.population_opt(0).unwrap(), Some(2.into()));
+        assert!(nodes.individual_opt(0).unwrap().is_none());
+        assert!(nodes.population_opt(1).is_err());
+        assert_eq!(PopulationId::from(None), PopulationId::NULL);
+    }
+
+    #[test]
+    fn test_into_iterator() {
+        let mut tables = crate::TableCollection::new(1.).unwrap();
+        tables.add_node(0, 0.0, -1, -1).unwrap();
+        tables.add_node(0, 1.0, -1, -1).unwrap();
+        let mut times = vec![];
+        for row in tables.nodes() {
+            times.push(row.time);
+        }
+        let nodes = tables.nodes();
+        for (row, time) in (&nodes).into_iter().zip(times.iter()) {
+            assert_eq!(row.time, *time);
+        }
+        assert_eq!(times, vec![0.0, 1.0]);
+
+        let mut owned = OwnedNodeTable::default();
+        owned.add_row(0, 2.0, -1, -1).unwrap();
+        for row in &owned {
+            assert_eq!(row.time, 2.0);
+        }
+        assert_eq!(owned.into_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_get() {
+        let mut nodes = OwnedNodeTable::default();
+        nodes.add_row(0, 1.1, 2, -1).unwrap();
+        let view = nodes.get(0).unwrap();
+        assert_eq!(view.id, 0);
+        assert_eq!(view.time, 1.1);
+        assert_eq!(view.population, 2);
+        assert!(view.metadata.is_none());
+        assert!(nodes.get(1).is_none());
+        assert!(nodes.get(NodeId::NULL).is_none());
+    }
+
+    #[test]
+    fn test_metadata_iter() {
+        use crate::metadata::Bytes;
+        let mut nodes = OwnedNodeTable::default();
+        nodes
+            .add_row_with_metadata(0, 1.1, -1, -1, &Bytes::from(vec![1]))
+            .unwrap();
+        nodes.add_row(0, 1.1, -1, -1).unwrap();
+        nodes
+            .add_row_with_metadata(0, 1.1, -1, -1, &Bytes::from(vec![2, 3]))
+            .unwrap();
+        let decoded = nodes
+            .metadata_iter::<Bytes>()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            decoded,
+            vec![
+                Some(Bytes::from(vec![1])),
+                None,
+                Some(Bytes::from(vec![2, 3]))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_metadata_errors() {
+        use crate::metadata::{Bytes, MetadataError, MetadataRoundtrip};
+
+        struct Fails;
+
+        impl MetadataRoundtrip for Fails {
+            fn encode(&self) -> Result<Vec<u8>, MetadataError> {
+                Ok(vec![])
+            }
+
+            fn decode(_: &[u8]) -> Result<Self, MetadataError> {
+                Err(MetadataError::ValidationError {
+                    message: "always fails".to_string(),
+                })
+            }
+        }
+
+        let mut nodes = OwnedNodeTable::default();
+        nodes
+            .add_row_with_metadata(0, 1.1, -1, -1, &Bytes::from(vec![1]))
+            .unwrap();
+        nodes.add_row(0, 1.1, -1, -1).unwrap();
+        assert!(matches!(
+            nodes.metadata::<Fails>(0),
+            Err(TskitError::MetadataDecodeError { row: Some(0), .. })
+        ));
+        assert!(matches!(nodes.metadata::<Fails>(1), Ok(None)));
+        assert!(matches!(
+            nodes.metadata_or_err::<Fails, _>(1),
+            Err(TskitError::UnexpectedEmptyMetadata { row: Some(1) })
+        ));
+        assert_eq!(
+            nodes.metadata_or_err::<Bytes, _>(0).unwrap(),
+            Bytes::from(vec![1])
+        );
+        match nodes.metadata_bytes(2) {
+            Err(TskitError::MetadataRowOutOfRange { row, num_rows }) => {
+                assert_eq!(row, 2);
+                assert_eq!(num_rows, 2);
+            }
+            _ => panic!("expected MetadataRowOutOfRange"),
+        }
+        #[cfg(feature = "serde_json")]
+        {
+            assert!(matches!(
+                nodes.parse_metadata_schema(),
+                Err(TskitError::MissingMetadataSchema)
+            ));
+            nodes.set_metadata_schema(r#"{"codec": "json"}"#).unwrap();
+            assert!(nodes.parse_metadata_schema().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_metadata_schema() {
+        let mut nodes = OwnedNodeTable::default();
+        assert!(nodes.metadata_schema().is_none());
+        let schema = r#"{"codec": "json"}"#;
+        nodes.set_metadata_schema(schema).unwrap();
+        assert_eq!(nodes.metadata_schema(), Some(schema));
+        nodes.set_metadata_schema("").unwrap();
+        assert!(nodes.metadata_schema().is_none());
+    }
+
+    #[test]
+    fn test_max_rows_increment() {
+        let mut nodes = OwnedNodeTable::default();
+        nodes.set_max_rows_increment(5000_usize).unwrap();
+        // The initial allocation holds a single row.
+        nodes.add_row(0, 1.1, -1, -1).unwrap();
+        nodes.add_row(0, 1.1, -1, -1).unwrap();
+        assert_eq!(unsafe { (*nodes.as_ptr()).max_rows }, 5001);
+    }
+
+    #[test]
+    fn test_from_columns() {
+        let nodes = OwnedNodeTable::from_columns(
+            &[NodeFlags::default(), NodeFlags::IS_SAMPLE],
+            &[Time::from(1.), Time::from(0.)],
+            &[PopulationId::NULL; 2],
+            &[IndividualId::NULL; 2],
+        )
+        .unwrap();
+        assert_eq!(nodes.num_rows(), 2);
+        assert_eq!(nodes.time(1).unwrap(), 0.0);
+        assert!(nodes.flags(1).unwrap().is_sample());
+        assert!(OwnedNodeTable::from_columns(&[], &[Time::from(1.)], &[], &[]).is_err());
+    }
 }
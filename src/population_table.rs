@@ -1,5 +1,4 @@
 use crate::bindings as ll_bindings;
-use crate::metadata;
 use crate::tsk_id_t;
 use crate::PopulationId;
 use crate::SizeType;
@@ -8,6 +7,7 @@ use ll_bindings::{tsk_population_table_free, tsk_population_table_init};
 
 /// Row of a [`PopulationTable`]
 #[derive(Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PopulationTableRow {
     pub id: PopulationId,
     pub metadata: Option<Vec<u8>>,
@@ -19,11 +19,44 @@ impl PartialEq for PopulationTableRow {
     }
 }
 
+impl std::fmt::Display for PopulationTableRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "id: {}, metadata: {}",
+            self.id,
+            crate::util::DisplayMetadata(&self.metadata),
+        )
+    }
+}
+
+/// A view of a row of a [`PopulationTable`].
+///
+/// Unlike [`PopulationTableRow`], the view borrows its data from the table.
+/// Views are returned by [`PopulationTable::get`] and [`PopulationTable::iter_views`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PopulationTableRowView<'a> {
+    pub id: crate::PopulationId,
+    pub metadata: Option<&'a [u8]>,
+}
+
+impl_row_view_metadata!(PopulationTableRowView);
+impl_row_view_into_row!(PopulationTableRowView, PopulationTableRow, [id], [metadata]);
+
+fn make_population_table_row_view<'a>(
+    table: &'a PopulationTable<'_>,
+    pos: tsk_id_t,
+) -> PopulationTableRowView<'a> {
+    // pos is in range, so neither the indexing nor the unwraps can fail
+    PopulationTableRowView {
+        id: pos.into(),
+        metadata: table.metadata_bytes(pos).unwrap(),
+    }
+}
+
 fn make_population_table_row(table: &PopulationTable, pos: tsk_id_t) -> Option<PopulationTableRow> {
-    // panic is okay here, as we are handling a bad
-    // input value before we first call this to
-    // set up the iterator
-    let p = crate::SizeType::try_from(pos).unwrap();
+    // A negative pos is not a row, so there is nothing to return.
+    let p = crate::SizeType::try_from(pos).ok()?;
     if p < table.num_rows() {
         let table_ref = table.table_;
         let rv = PopulationTableRow {
@@ -61,6 +94,13 @@ impl<'a> Iterator for PopulationTableIterator<'a> {
     }
 }
 
+impl_table_into_iterator!(
+    PopulationTable,
+    OwnedPopulationTable,
+    PopulationTableRow,
+    make_population_table_row
+);
+
 /// An immutable view of site table.
 ///
 /// These are not created directly.
@@ -71,25 +111,48 @@ pub struct PopulationTable<'a> {
     table_: &'a ll_bindings::tsk_population_table_t,
 }
 
+impl_send_sync_for_table_view!(PopulationTable);
+impl_table_columns!(
+    PopulationTable,
+    Populations,
+    [],
+    [(metadata, UInt8, metadata_length)]
+);
+impl_table_equality!(PopulationTable, tsk_population_table_equals);
+
 impl<'a> PopulationTable<'a> {
     pub(crate) fn new_from_table(mutations: &'a ll_bindings::tsk_population_table_t) -> Self {
         PopulationTable { table_: mutations }
     }
 
+    /// Return a pointer to the underlying C table, for use
+    /// with the functions in [`bindings`](crate::bindings).
+    pub fn as_ptr(&self) -> *const ll_bindings::tsk_population_table_t {
+        self.table_
+    }
+
     /// Return the number of rows.
-    pub fn num_rows(&'a self) -> SizeType {
+    pub fn num_rows(&self) -> SizeType {
         self.table_.num_rows.into()
     }
 
-    pub fn metadata<T: metadata::MetadataRoundtrip>(
-        &'a self,
-        row: PopulationId,
-    ) -> Result<Option<T>, TskitError> {
-        let table_ref = self.table_;
-        let buffer = metadata_to_vector!(self, table_ref, row.0)?;
-        decode_metadata_row!(T, buffer)
+    /// Return the number of bytes used by the data in this table.
+    pub fn nbytes(&self) -> usize {
+        let t = self.table_;
+        crate::util::ragged_column_nbytes::<u8>(t.metadata_length, t.num_rows)
+            + crate::util::column_nbytes::<u8>(t.metadata_schema_length)
     }
 
+    build_table_metadata_schema_getter!();
+
+    build_table_metadata_bytes_access!(PopulationId);
+
+    build_table_row_views!(
+        PopulationId,
+        PopulationTableRowView,
+        make_population_table_row_view
+    );
+
     /// Return an iterator over rows of the table.
     /// The value of the iterator is [`PopulationTableRow`].
     pub fn iter(&self) -> impl Iterator<Item = PopulationTableRow> + '_ {
@@ -166,10 +229,66 @@ build_owned_table_type!(
     tsk_population_table_t,
     tsk_population_table_init,
     tsk_population_table_free,
-    ll_bindings::tsk_population_table_clear
+    ll_bindings::tsk_population_table_clear,
+    ll_bindings::tsk_population_table_truncate
 );
 
 impl OwnedPopulationTable {
     population_table_add_row!(=> add_row, self, *self.table);
     population_table_add_row_with_metadata!(=> add_row_with_metadata, self, *self.table);
+    table_set_metadata_schema!(
+    /// Set the metadata schema of the table.
+    => set_metadata_schema, self, *self.table, ll_bindings::tsk_population_table_set_metadata_schema);
+
+    table_packset_metadata!(
+    /// Replace the metadata column of the table.
+    ///
+    /// See [`OwnedNodeTable::packset_metadata`](crate::OwnedNodeTable::packset_metadata).
+    => packset_metadata, self, *self.table);
+
+    table_migrate_metadata!(
+    /// Re-encode the metadata of every row with a new schema.
+    ///
+    /// See [`OwnedNodeTable::migrate_metadata`](crate::OwnedNodeTable::migrate_metadata).
+    => migrate_metadata, self, &*self, packset_metadata, set_metadata_schema, PopulationMetadata);
+
+    table_set_max_increment!(
+    /// Set the number of rows by which the table grows
+    /// when it runs out of space.
+    ///
+    /// See [`OwnedNodeTable::set_max_rows_increment`](crate::OwnedNodeTable::set_max_rows_increment).
+    => set_max_rows_increment, self, *self.table, ll_bindings::tsk_population_table_set_max_rows_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the metadata
+    /// column grows when it runs out of space.
+    => set_max_metadata_length_increment, self, *self.table, ll_bindings::tsk_population_table_set_max_metadata_length_increment);
+    population_table_update_row!(
+    /// Replace the contents of row `row`.
+    ///
+    /// The row's metadata are cleared.
+    => update_row, self, *self.table);
+    population_table_update_row_with_metadata!(
+    /// Replace the contents of row `row`, including its metadata.
+    => update_row_with_metadata, self, *self.table);
+
+    /// Replace the metadata of row `row`.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`](crate::TskitError::IndexError) if `row` is out of range.
+    /// * [`TskitError::MetadataError`](crate::TskitError::MetadataError) if encoding fails.
+    pub fn set_metadata<
+        R: Into<crate::PopulationId> + Copy,
+        M: crate::metadata::PopulationMetadata,
+    >(
+        &mut self,
+        row: R,
+        metadata: &M,
+    ) -> crate::TskReturnValue {
+        if self.get(row).is_none() {
+            return Err(crate::TskitError::IndexError);
+        }
+        self.update_row_with_metadata(row, metadata)
+    }
 }
@@ -2,13 +2,32 @@
 
 pub use crate::tsk_flags_t;
 pub use crate::NodeListGenerator;
+#[cfg(any(feature = "provenance", doc))]
+pub use crate::ProvenanceId;
 pub use crate::TableAccess;
+pub use crate::TableColumns;
 pub use crate::TskitTypeAccess;
 pub use crate::TSK_NODE_IS_SAMPLE;
 pub use streaming_iterator::DoubleEndedStreamingIterator;
 pub use streaming_iterator::StreamingIterator;
 pub use {
-    crate::EdgeId, crate::IndividualId, crate::Location, crate::MigrationId, crate::MutationId,
-    crate::NodeId, crate::PopulationId, crate::Position, crate::RawFlags, crate::SiteId,
-    crate::SizeType, crate::Time,
+    crate::EdgeId, crate::GenomicInterval, crate::IndividualId, crate::Location,
+    crate::MigrationId, crate::MutationId, crate::NodeId, crate::PopulationId, crate::Position,
+    crate::RawFlags, crate::SiteId, crate::SizeType, crate::Time,
+};
+pub use {
+    crate::EdgeTable, crate::IndividualTable, crate::MigrationTable, crate::MutationTable,
+    crate::NodeTable, crate::OwnedEdgeTable, crate::OwnedIndividualTable,
+    crate::OwnedMigrationTable, crate::OwnedMutationTable, crate::OwnedNodeTable,
+    crate::OwnedPopulationTable, crate::OwnedSiteTable, crate::PopulationTable, crate::SiteTable,
+};
+pub use {
+    crate::IndividualFlags, crate::IndividualTableSortOptions, crate::NodeFlags,
+    crate::SimplificationOptions, crate::TableClearOptions, crate::TableEqualityOptions,
+    crate::TableIntegrityCheckFlags, crate::TableOutputOptions, crate::TableSortOptions,
+    crate::TreeFlags, crate::TreeSequenceFlags,
+};
+pub use {
+    crate::NodeTraversalOrder, crate::TableCollection, crate::Tree, crate::TreeSequence,
+    crate::TskitError,
 };
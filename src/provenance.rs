@@ -4,10 +4,13 @@
 //! the following:
 //!
 //! * [`crate::TableCollection::add_provenance`]
+//! * [`crate::TableCollection::add_provenance_with_timestamp`]
 //! * [`crate::TableAccess::provenances`]
 //! * [`crate::TableAccess::provenances_iter`]
 //! * [`crate::TreeSequence::add_provenance`]
 //! * [`ProvenanceTable`].
+//! * [`OwnedProvenanceTable`], which can be added to a table collection via
+//!   [`crate::TableCollection::set_provenances`].
 //! * [`ProvenanceTableRow`], which is the value type returned by
 //!   [`ProvenanceTable::iter`].
 //!
@@ -17,8 +20,9 @@ use crate::SizeType;
 use crate::{tsk_id_t, tsk_size_t, ProvenanceId, TskitError};
 use ll_bindings::{tsk_provenance_table_free, tsk_provenance_table_init};
 
-#[derive(Eq)]
+#[derive(Clone, Debug, Eq)]
 /// Row of a [`ProvenanceTable`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProvenanceTableRow {
     /// The row id
     pub id: ProvenanceId,
@@ -45,10 +49,8 @@ impl std::fmt::Display for ProvenanceTableRow {
 }
 
 fn make_provenance_row(table: &ProvenanceTable, pos: tsk_id_t) -> Option<ProvenanceTableRow> {
-    // panic is okay here, as we are handling a bad
-    // input value before we first call this to
-    // set up the iterator
-    let p = crate::SizeType::try_from(pos).unwrap();
+    // A negative pos is not a row, so there is nothing to return.
+    let p = crate::SizeType::try_from(pos).ok()?;
     if p < table.num_rows() {
         Some(ProvenanceTableRow {
             id: pos.into(),
@@ -83,6 +85,13 @@ impl<'a> Iterator for ProvenanceTableIterator<'a> {
     }
 }
 
+impl_table_into_iterator!(
+    ProvenanceTable,
+    OwnedProvenanceTable,
+    ProvenanceTableRow,
+    make_provenance_row
+);
+
 /// An immutable view of a provenance table.
 ///
 /// These are not created directly.
@@ -97,6 +106,18 @@ pub struct ProvenanceTable<'a> {
     table_: &'a ll_bindings::tsk_provenance_table_t,
 }
 
+impl_send_sync_for_table_view!(ProvenanceTable);
+impl_table_columns!(
+    ProvenanceTable,
+    Provenances,
+    [],
+    [
+        (timestamp, UInt8, timestamp_length),
+        (record, UInt8, record_length)
+    ]
+);
+impl_table_equality!(ProvenanceTable, tsk_provenance_table_equals);
+
 impl<'a> ProvenanceTable<'a> {
     pub(crate) fn new_from_table(provenances: &'a ll_bindings::tsk_provenance_table_t) -> Self {
         ProvenanceTable {
@@ -104,17 +125,28 @@ impl<'a> ProvenanceTable<'a> {
         }
     }
 
+    /// Return a pointer to the underlying C table, for use
+    /// with the functions in [`bindings`](crate::bindings).
+    pub fn as_ptr(&self) -> *const ll_bindings::tsk_provenance_table_t {
+        self.table_
+    }
+
     /// Return the number of rows
-    pub fn num_rows(&'a self) -> SizeType {
+    pub fn num_rows(&self) -> SizeType {
         self.table_.num_rows.into()
     }
 
+    /// Return the number of bytes used by the data in this table.
+    pub fn nbytes(&self) -> usize {
+        crate::util::provenance_table_nbytes(self.table_)
+    }
+
     /// Get the ISO-formatted time stamp for row `row`.
     ///
     /// # Errors
     ///
     /// [`TskitError::IndexError`] if `r` is out of range.
-    pub fn timestamp<P: Into<ProvenanceId> + Copy>(&'a self, row: P) -> Result<String, TskitError> {
+    pub fn timestamp<P: Into<ProvenanceId> + Copy>(&self, row: P) -> Result<String, TskitError> {
         match unsafe_tsk_ragged_char_column_access!(
             row.into().0,
             0,
@@ -137,7 +169,7 @@ impl<'a> ProvenanceTable<'a> {
     /// # Errors
     ///
     /// [`TskitError::IndexError`] if `r` is out of range.
-    pub fn record<P: Into<ProvenanceId> + Copy>(&'a self, row: P) -> Result<String, TskitError> {
+    pub fn record<P: Into<ProvenanceId> + Copy>(&self, row: P) -> Result<String, TskitError> {
         match unsafe_tsk_ragged_char_column_access!(
             row.into().0,
             0,
@@ -158,7 +190,7 @@ impl<'a> ProvenanceTable<'a> {
     ///
     /// [`TskitError::IndexError`] if `r` is out of range.
     pub fn row<P: Into<ProvenanceId> + Copy>(
-        &'a self,
+        &self,
         row: P,
     ) -> Result<ProvenanceTableRow, TskitError> {
         if row.into() < 0 {
@@ -197,18 +229,54 @@ build_owned_table_type!(
     tsk_provenance_table_t,
     tsk_provenance_table_init,
     tsk_provenance_table_free,
-    ll_bindings::tsk_provenance_table_clear
+    ll_bindings::tsk_provenance_table_clear,
+    ll_bindings::tsk_provenance_table_truncate
 );
 
 impl OwnedProvenanceTable {
-    provenance_table_add_row!(=> add_row, self, *self.table);
+    provenance_table_add_row!(
+    /// Add a row with the current time as its time stamp.
+    => add_row, self, *self.table);
+    provenance_table_add_row_with_timestamp!(
+    /// Add a row with a given time stamp.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # #[cfg(any(doc, feature = "provenance"))] {
+    /// let mut provenances = tskit::provenance::OwnedProvenanceTable::default();
+    /// provenances
+    ///     .add_row_with_timestamp("2018-02-14T00:28:07.123456Z", "message")
+    ///     .unwrap();
+    /// let row = provenances.iter().next().unwrap();
+    /// assert_eq!(row.timestamp, "2018-02-14T00:28:07.123456Z");
+    /// assert_eq!(row.record, "message");
+    /// # }
+    /// ```
+    => add_row_with_timestamp, self, *self.table);
+
+    table_set_max_increment!(
+    /// Set the number of rows by which the table grows
+    /// when it runs out of space.
+    ///
+    /// See [`OwnedNodeTable::set_max_rows_increment`](crate::OwnedNodeTable::set_max_rows_increment).
+    => set_max_rows_increment, self, *self.table, ll_bindings::tsk_provenance_table_set_max_rows_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the timestamp
+    /// column grows when it runs out of space.
+    => set_max_timestamp_length_increment, self, *self.table, ll_bindings::tsk_provenance_table_set_max_timestamp_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the record
+    /// column grows when it runs out of space.
+    => set_max_record_length_increment, self, *self.table, ll_bindings::tsk_provenance_table_set_max_record_length_increment);
 }
 
 #[cfg(test)]
 mod test_provenances {
     use super::*;
     use crate::test_fixtures::make_empty_table_collection;
-    use crate::TableAccess;
 
     #[test]
     fn test_empty_record_string() {
@@ -250,4 +318,45 @@ mod test_provenances {
         assert!(tables.provenances().row(0).unwrap() == tables.provenances().row(0).unwrap());
         assert!(tables.provenances().row(0).unwrap() != tables.provenances().row(1).unwrap());
     }
+
+    #[test]
+    fn test_timestamps_and_tree_sequence_iteration() {
+        use core::str::FromStr;
+
+        let mut tables = make_empty_table_collection(1.);
+        tables.add_provenance("tables").unwrap();
+        tables.build_index().unwrap();
+        let mut ts = tables
+            .tree_sequence(crate::TreeSequenceFlags::default())
+            .unwrap();
+        ts.add_provenance("treeseq").unwrap();
+
+        let rows = ts.provenances_iter().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].record, "tables");
+        assert_eq!(rows[1].record, "treeseq");
+        for row in rows {
+            // e.g., 2018-02-14T00:28:07.123456Z
+            assert!(humantime::Timestamp::from_str(&row.timestamp).is_ok());
+            let fraction = row.timestamp.split('.').nth(1).unwrap();
+            assert_eq!(fraction.len(), "123456Z".len());
+        }
+    }
+
+    #[test]
+    fn test_owned_table_with_timestamps() {
+        let mut provenances = OwnedProvenanceTable::default();
+        provenances
+            .add_row_with_timestamp("2022-01-01T00:00:00.000000Z", "first")
+            .unwrap();
+        provenances.add_row("second").unwrap();
+        let rows = provenances.iter().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].timestamp, "2022-01-01T00:00:00.000000Z");
+        assert_eq!(rows[1].record, "second");
+
+        let mut tables = make_empty_table_collection(1.);
+        tables.set_provenances(&provenances).unwrap();
+        assert!(tables.provenances().iter().eq(provenances.iter()));
+    }
 }
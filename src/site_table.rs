@@ -1,5 +1,4 @@
 use crate::bindings as ll_bindings;
-use crate::metadata;
 use crate::tsk_id_t;
 use crate::Position;
 use crate::SiteId;
@@ -8,6 +7,7 @@ use crate::TskitError;
 use ll_bindings::{tsk_site_table_free, tsk_site_table_init};
 
 /// Row of a [`SiteTable`]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SiteTableRow {
     pub id: SiteId,
     pub position: Position,
@@ -24,11 +24,70 @@ impl PartialEq for SiteTableRow {
     }
 }
 
+impl std::fmt::Display for SiteTableRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "id: {}, position: {}, ancestral_state: {}, metadata: {}",
+            self.id,
+            self.position,
+            crate::util::DisplayState(&self.ancestral_state),
+            crate::util::DisplayMetadata(&self.metadata),
+        )
+    }
+}
+
+/// A view of a row of a [`SiteTable`].
+///
+/// Unlike [`SiteTableRow`], the view borrows its data from the table.
+/// Views are returned by [`SiteTable::get`] and [`SiteTable::iter_views`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SiteTableRowView<'a> {
+    pub id: crate::SiteId,
+    pub position: crate::Position,
+    pub ancestral_state: Option<&'a [u8]>,
+    pub metadata: Option<&'a [u8]>,
+}
+
+impl_row_view_metadata!(SiteTableRowView);
+impl_row_view_into_row!(
+    SiteTableRowView,
+    SiteTableRow,
+    [id, position],
+    [ancestral_state, metadata]
+);
+
+fn make_site_table_row_view<'a>(table: &'a SiteTable<'_>, pos: tsk_id_t) -> SiteTableRowView<'a> {
+    site_table_row_view(table.table_, pos)
+}
+
+// Borrows from the C table so that tree sequences can
+// return views that live as long as they do.
+pub(crate) fn site_table_row_view(
+    table: &ll_bindings::tsk_site_table_t,
+    pos: tsk_id_t,
+) -> SiteTableRowView<'_> {
+    // pos is in range, so neither the indexing nor the unwraps can fail
+    let index = usize::try_from(pos).unwrap();
+    SiteTableRowView {
+        id: pos.into(),
+        position: table_column!(table, position)[index].into(),
+        ancestral_state: crate::metadata::char_column_to_slice(
+            table,
+            table.ancestral_state,
+            table.ancestral_state_offset,
+            pos,
+            table.num_rows,
+            table.ancestral_state_length,
+        )
+        .unwrap(),
+        metadata: metadata_to_vector!(table, table, pos).unwrap(),
+    }
+}
+
 fn make_site_table_row(table: &SiteTable, pos: tsk_id_t) -> Option<SiteTableRow> {
-    // panic is okay here, as we are handling a bad
-    // input value before we first call this to
-    // set up the iterator
-    let p = crate::SizeType::try_from(pos).unwrap();
+    // A negative pos is not a row, so there is nothing to return.
+    let p = crate::SizeType::try_from(pos).ok()?;
     if p < table.num_rows() {
         let table_ref = table.table_;
         let rv = SiteTableRow {
@@ -66,6 +125,8 @@ impl<'a> Iterator for SiteTableIterator<'a> {
     }
 }
 
+impl_table_into_iterator!(SiteTable, OwnedSiteTable, SiteTableRow, make_site_table_row);
+
 /// An immutable view of site table.
 ///
 /// These are not created directly.
@@ -75,23 +136,56 @@ pub struct SiteTable<'a> {
     table_: &'a ll_bindings::tsk_site_table_t,
 }
 
+impl_send_sync_for_table_view!(SiteTable);
+impl_table_columns!(
+    SiteTable,
+    Sites,
+    [(position, Float64)],
+    [
+        (ancestral_state, UInt8, ancestral_state_length),
+        (metadata, UInt8, metadata_length)
+    ]
+);
+impl_table_equality!(SiteTable, tsk_site_table_equals);
+
 impl<'a> SiteTable<'a> {
     pub(crate) fn new_from_table(sites: &'a ll_bindings::tsk_site_table_t) -> Self {
         SiteTable { table_: sites }
     }
 
+    /// Return a pointer to the underlying C table, for use
+    /// with the functions in [`bindings`](crate::bindings).
+    pub fn as_ptr(&self) -> *const ll_bindings::tsk_site_table_t {
+        self.table_
+    }
+
     /// Return the number of rows
-    pub fn num_rows(&'a self) -> SizeType {
+    pub fn num_rows(&self) -> SizeType {
         self.table_.num_rows.into()
     }
 
+    /// Return the number of bytes used by the data in this table.
+    pub fn nbytes(&self) -> usize {
+        let t = self.table_;
+        crate::util::column_nbytes::<f64>(t.num_rows)
+            + crate::util::ragged_column_nbytes::<u8>(t.ancestral_state_length, t.num_rows)
+            + crate::util::ragged_column_nbytes::<u8>(t.metadata_length, t.num_rows)
+            + crate::util::column_nbytes::<u8>(t.metadata_schema_length)
+    }
+
+    build_table_metadata_schema_getter!();
+
+    build_table_metadata_bytes_access!(SiteId);
+
+    build_table_row_views!(SiteId, SiteTableRowView, make_site_table_row_view);
+
     /// Return the ``position`` value from row ``row`` of the table.
     ///
     /// # Errors
     ///
     /// Will return [``IndexError``](crate::TskitError::IndexError)
     /// if ``row`` is out of range.
-    pub fn position<S: Into<SiteId> + Copy>(&'a self, row: S) -> Result<Position, TskitError> {
+    pub fn position<S: Into<SiteId> + Copy>(&self, row: S) -> Result<Position, TskitError> {
         match unsafe_tsk_column_access!(row.into().0, 0, self.num_rows(), self.table_.position) {
             Ok(p) => Ok(p.into()),
             Err(e) => Err(e),
@@ -108,7 +202,7 @@ impl<'a> SiteTable<'a> {
     ///
     /// Will return [``IndexError``](crate::TskitError::IndexError)
     /// if ``row`` is out of range.
-    pub fn ancestral_state<S: Into<SiteId>>(&'a self, row: S) -> Result<Option<&[u8]>, TskitError> {
+    pub fn ancestral_state<S: Into<SiteId>>(&self, row: S) -> Result<Option<&[u8]>, TskitError> {
         crate::metadata::char_column_to_slice(
             self,
             self.table_.ancestral_state,
@@ -119,14 +213,9 @@ impl<'a> SiteTable<'a> {
         )
     }
 
-    pub fn metadata<T: metadata::MetadataRoundtrip>(
-        &'a self,
-        row: SiteId,
-    ) -> Result<Option<T>, TskitError> {
-        let table_ref = self.table_;
-        let buffer = metadata_to_vector!(self, table_ref, row.0)?;
-        decode_metadata_row!(T, buffer)
-    }
+    build_table_column_slice_getter!(
+        /// Get the position column as a slice
+        => position, position_slice, crate::Position);
 
     /// Return an iterator over rows of the table.
     /// The value of the iterator is [`SiteTableRow`].
@@ -201,10 +290,101 @@ build_owned_table_type!(
     tsk_site_table_t,
     tsk_site_table_init,
     tsk_site_table_free,
-    ll_bindings::tsk_site_table_clear
+    ll_bindings::tsk_site_table_clear,
+    ll_bindings::tsk_site_table_truncate
 );
 
 impl OwnedSiteTable {
     site_table_add_row!(=> add_row, self, *self.table);
     site_table_add_row_with_metadata!(=> add_row_with_metadata, self, *self.table);
+    table_set_metadata_schema!(
+    /// Set the metadata schema of the table.
+    => set_metadata_schema, self, *self.table, ll_bindings::tsk_site_table_set_metadata_schema);
+    site_table_update_row!(
+    /// Replace the contents of row `row`.
+    ///
+    /// The row's metadata are cleared.
+    => update_row, self, *self.table);
+    site_table_update_row_with_metadata!(
+    /// Replace the contents of row `row`, including its metadata.
+    => update_row_with_metadata, self, *self.table);
+
+    /// Replace the metadata of row `row`, keeping its other values.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`](crate::TskitError::IndexError) if `row` is out of range.
+    /// * [`TskitError::MetadataError`](crate::TskitError::MetadataError) if encoding fails.
+    pub fn set_metadata<R: Into<crate::SiteId> + Copy, M: crate::metadata::SiteMetadata>(
+        &mut self,
+        row: R,
+        metadata: &M,
+    ) -> crate::TskReturnValue {
+        let r = self.get(row).ok_or(crate::TskitError::IndexError)?;
+        let (id, position) = (r.id, r.position);
+        let ancestral_state = r.ancestral_state.map(<[_]>::to_vec);
+        self.update_row_with_metadata(id, position, ancestral_state.as_deref(), metadata)
+    }
+    site_table_set_columns!(
+    /// Replace the contents of the table with the
+    /// position and ancestral state columns.
+    ///
+    /// All rows are loaded with a single call to the C API.
+    /// Metadata are cleared.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`](crate::TskitError::ValueError) if the columns
+    /// do not all have the same length.
+    => set_columns, self, *self.table);
+
+    /// Create a table from columns.
+    ///
+    /// See [`OwnedSiteTable::set_columns`] for details.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`](crate::TskitError::ValueError) if the columns
+    /// do not all have the same length.
+    pub fn from_columns(
+        position: &[crate::Position],
+        ancestral_state: &[Option<&[u8]>],
+    ) -> Result<Self, crate::TskitError> {
+        let mut table = Self::default();
+        table.set_columns(position, ancestral_state)?;
+        Ok(table)
+    }
+
+    table_packset_metadata!(
+    /// Replace the metadata column of the table.
+    ///
+    /// See [`OwnedNodeTable::packset_metadata`](crate::OwnedNodeTable::packset_metadata).
+    => packset_metadata, self, *self.table);
+
+    table_migrate_metadata!(
+    /// Re-encode the metadata of every row with a new schema.
+    ///
+    /// See [`OwnedNodeTable::migrate_metadata`](crate::OwnedNodeTable::migrate_metadata).
+    => migrate_metadata, self, &*self, packset_metadata, set_metadata_schema, SiteMetadata);
+
+    table_column_slice_mut!(
+    /// Mutable access to the position column.
+    => position_array_mut, self, *self.table, position, crate::Position);
+
+    table_set_max_increment!(
+    /// Set the number of rows by which the table grows
+    /// when it runs out of space.
+    ///
+    /// See [`OwnedNodeTable::set_max_rows_increment`](crate::OwnedNodeTable::set_max_rows_increment).
+    => set_max_rows_increment, self, *self.table, ll_bindings::tsk_site_table_set_max_rows_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the metadata
+    /// column grows when it runs out of space.
+    => set_max_metadata_length_increment, self, *self.table, ll_bindings::tsk_site_table_set_max_metadata_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the ancestral_state
+    /// column grows when it runs out of space.
+    => set_max_ancestral_state_length_increment, self, *self.table, ll_bindings::tsk_site_table_set_max_ancestral_state_length_increment);
 }
@@ -0,0 +1,621 @@
+//! The tskit "struct" metadata codec.
+//!
+//! Values are encoded with the fixed binary layout used by the
+//! `struct` codec of the `tskit` Python API:
+//!
+//! * Fields are written in declaration order, without padding.
+//! * Numbers are little-endian.
+//!   Rust integer and float types map to the `binaryFormat`s
+//!   `b`, `B`, `h`, `H`, `i`, `I`, `q`, `Q`, `f`, and `d`,
+//!   and `bool` maps to `?`.
+//! * Strings, byte buffers, and sequences such as `Vec` are
+//!   variable-length and are preceded by their length as
+//!   a `u32` (`arrayLengthFormat` `L`, the default).
+//! * Fixed-size arrays and tuples are written without a length.
+//!
+//! `Option`s, enums, maps, and `char`s have no struct representation
+//! and result in errors.
+
+use crate::metadata::MetadataError;
+use serde::de::{self, DeserializeSeed, SeqAccess, Visitor};
+use serde::ser::{self, Serialize};
+
+#[derive(Debug)]
+struct StructCodecError(String);
+
+impl std::fmt::Display for StructCodecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "struct codec error: {}", self.0)
+    }
+}
+
+impl std::error::Error for StructCodecError {}
+
+impl ser::Error for StructCodecError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl de::Error for StructCodecError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl From<StructCodecError> for MetadataError {
+    fn from(e: StructCodecError) -> Self {
+        MetadataError::RoundtripError { value: Box::new(e) }
+    }
+}
+
+fn unsupported<T>(what: &str) -> Result<T, StructCodecError> {
+    Err(StructCodecError(format!(
+        "{} cannot be represented by the struct codec",
+        what
+    )))
+}
+
+/// Encode `value` using the tskit struct codec.
+///
+/// # Examples
+///
+/// ```
+/// #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+/// struct Metadata {
+///     x: i32,
+///     values: Vec<f64>,
+/// }
+///
+/// let md = Metadata { x: -1, values: vec![0.5] };
+/// let encoded = tskit::metadata::struct_encode(&md).unwrap();
+/// // i32, u32 length prefix, one f64
+/// assert_eq!(encoded.len(), 4 + 4 + 8);
+/// let decoded: Metadata = tskit::metadata::struct_decode(&encoded).unwrap();
+/// assert_eq!(decoded, md);
+/// ```
+///
+/// # Errors
+///
+/// [`MetadataError::RoundtripError`] if `value` contains types
+/// that the struct codec cannot represent.
+pub fn struct_encode<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, MetadataError> {
+    let mut serializer = StructSerializer { output: vec![] };
+    value.serialize(&mut serializer)?;
+    Ok(serializer.output)
+}
+
+/// Decode a value encoded using the tskit struct codec.
+///
+/// See [`struct_encode`] for an example.
+///
+/// # Errors
+///
+/// [`MetadataError::RoundtripError`] if the data are too short,
+/// have trailing bytes, or are otherwise invalid for `T`.
+pub fn struct_decode<'de, T: serde::Deserialize<'de>>(md: &'de [u8]) -> Result<T, MetadataError> {
+    let mut deserializer = StructDeserializer { input: md };
+    let value = T::deserialize(&mut deserializer)?;
+    if !deserializer.input.is_empty() {
+        return Err(StructCodecError(format!(
+            "{} trailing bytes after decoding",
+            deserializer.input.len()
+        ))
+        .into());
+    }
+    Ok(value)
+}
+
+struct StructSerializer {
+    output: Vec<u8>,
+}
+
+impl StructSerializer {
+    fn write_length(&mut self, len: usize) -> Result<(), StructCodecError> {
+        let len = u32::try_from(len)
+            .map_err(|_| StructCodecError(format!("length {} does not fit in a u32", len)))?;
+        self.output.extend_from_slice(&len.to_le_bytes());
+        Ok(())
+    }
+}
+
+impl ser::Serializer for &mut StructSerializer {
+    type Ok = ();
+    type Error = StructCodecError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = ser::Impossible<(), StructCodecError>;
+    type SerializeMap = ser::Impossible<(), StructCodecError>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = ser::Impossible<(), StructCodecError>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), StructCodecError> {
+        self.output.push(u8::from(v));
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), StructCodecError> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), StructCodecError> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), StructCodecError> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), StructCodecError> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), StructCodecError> {
+        self.output.push(v);
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), StructCodecError> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), StructCodecError> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), StructCodecError> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), StructCodecError> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), StructCodecError> {
+        self.output.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_char(self, _: char) -> Result<(), StructCodecError> {
+        unsupported("char")
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), StructCodecError> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), StructCodecError> {
+        self.write_length(v.len())?;
+        self.output.extend_from_slice(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), StructCodecError> {
+        unsupported("Option")
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _: &T) -> Result<(), StructCodecError> {
+        unsupported("Option")
+    }
+
+    fn serialize_unit(self) -> Result<(), StructCodecError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), StructCodecError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<(), StructCodecError> {
+        unsupported(name)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), StructCodecError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), StructCodecError> {
+        unsupported(name)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, StructCodecError> {
+        match len {
+            Some(len) => {
+                self.write_length(len)?;
+                Ok(self)
+            }
+            None => unsupported("a sequence of unknown length"),
+        }
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self, StructCodecError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self, StructCodecError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, StructCodecError> {
+        unsupported(name)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, StructCodecError> {
+        unsupported("a map")
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self, StructCodecError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, StructCodecError> {
+        unsupported(name)
+    }
+}
+
+impl ser::SerializeSeq for &mut StructSerializer {
+    type Ok = ();
+    type Error = StructCodecError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTuple for &mut StructSerializer {
+    type Ok = ();
+    type Error = StructCodecError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for &mut StructSerializer {
+    type Ok = ();
+    type Error = StructCodecError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut StructSerializer {
+    type Ok = ();
+    type Error = StructCodecError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+struct StructDeserializer<'de> {
+    input: &'de [u8],
+}
+
+impl<'de> StructDeserializer<'de> {
+    fn take(&mut self, n: usize) -> Result<&'de [u8], StructCodecError> {
+        if self.input.len() < n {
+            return Err(StructCodecError(format!(
+                "expected {} more bytes, found {}",
+                n,
+                self.input.len()
+            )));
+        }
+        let (taken, rest) = self.input.split_at(n);
+        self.input = rest;
+        Ok(taken)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], StructCodecError> {
+        // take returns exactly N bytes
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn read_length(&mut self) -> Result<usize, StructCodecError> {
+        let len = u32::from_le_bytes(self.take_array()?);
+        usize::try_from(len)
+            .map_err(|_| StructCodecError(format!("length {} does not fit in a usize", len)))
+    }
+
+    fn read_bytes(&mut self) -> Result<&'de [u8], StructCodecError> {
+        let len = self.read_length()?;
+        self.take(len)
+    }
+
+    fn read_str(&mut self) -> Result<&'de str, StructCodecError> {
+        std::str::from_utf8(self.read_bytes()?).map_err(|e| StructCodecError(e.to_string()))
+    }
+}
+
+struct Elements<'a, 'de> {
+    deserializer: &'a mut StructDeserializer<'de>,
+    remaining: usize,
+}
+
+impl<'de> SeqAccess<'de> for Elements<'_, 'de> {
+    type Error = StructCodecError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.deserializer).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+macro_rules! deserialize_number {
+    ($method: ident, $visit: ident, $type: ty) => {
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, StructCodecError> {
+            visitor.$visit(<$type>::from_le_bytes(self.take_array()?))
+        }
+    };
+}
+
+impl<'de> de::Deserializer<'de> for &mut StructDeserializer<'de> {
+    type Error = StructCodecError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, StructCodecError> {
+        unsupported("a self-describing type")
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, StructCodecError> {
+        match self.take(1)?[0] {
+            0 => visitor.visit_bool(false),
+            1 => visitor.visit_bool(true),
+            x => Err(StructCodecError(format!("invalid bool value {}", x))),
+        }
+    }
+
+    deserialize_number!(deserialize_i8, visit_i8, i8);
+    deserialize_number!(deserialize_i16, visit_i16, i16);
+    deserialize_number!(deserialize_i32, visit_i32, i32);
+    deserialize_number!(deserialize_i64, visit_i64, i64);
+    deserialize_number!(deserialize_u8, visit_u8, u8);
+    deserialize_number!(deserialize_u16, visit_u16, u16);
+    deserialize_number!(deserialize_u32, visit_u32, u32);
+    deserialize_number!(deserialize_u64, visit_u64, u64);
+    deserialize_number!(deserialize_f32, visit_f32, f32);
+    deserialize_number!(deserialize_f64, visit_f64, f64);
+
+    fn deserialize_char<V: Visitor<'de>>(self, _: V) -> Result<V::Value, StructCodecError> {
+        unsupported("char")
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, StructCodecError> {
+        visitor.visit_borrowed_str(self.read_str()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, StructCodecError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, StructCodecError> {
+        visitor.visit_borrowed_bytes(self.read_bytes()?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, StructCodecError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, _: V) -> Result<V::Value, StructCodecError> {
+        unsupported("Option")
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, StructCodecError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, StructCodecError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, StructCodecError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, StructCodecError> {
+        let remaining = self.read_length()?;
+        visitor.visit_seq(Elements {
+            deserializer: self,
+            remaining,
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, StructCodecError> {
+        visitor.visit_seq(Elements {
+            deserializer: self,
+            remaining: len,
+        })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, StructCodecError> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _: V) -> Result<V::Value, StructCodecError> {
+        unsupported("a map")
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, StructCodecError> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        _: &'static [&'static str],
+        _: V,
+    ) -> Result<V::Value, StructCodecError> {
+        unsupported(name)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, _: V) -> Result<V::Value, StructCodecError> {
+        unsupported("an identifier")
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, StructCodecError> {
+        unsupported("an ignored value")
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Inner {
+        flag: bool,
+        pair: (u8, i16),
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Outer {
+        id: u64,
+        name: String,
+        fixed: [f32; 2],
+        inner: Inner,
+        values: Vec<i32>,
+    }
+
+    #[test]
+    fn test_layout() {
+        let value = Inner {
+            flag: true,
+            pair: (7, -2),
+        };
+        let encoded = struct_encode(&value).unwrap();
+        assert_eq!(encoded, vec![1, 7, 0xfe, 0xff]);
+    }
+
+    #[test]
+    fn test_length_prefix() {
+        let encoded = struct_encode("abc").unwrap();
+        assert_eq!(encoded, vec![3, 0, 0, 0, b'a', b'b', b'c']);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let value = Outer {
+            id: 11,
+            name: "x".to_string(),
+            fixed: [0.5, -1.5],
+            inner: Inner {
+                flag: false,
+                pair: (0, 3),
+            },
+            values: vec![1, -1, 3],
+        };
+        let encoded = struct_encode(&value).unwrap();
+        assert_eq!(encoded.len(), 8 + (4 + 1) + 8 + 4 + (4 + 12));
+        let decoded: Outer = struct_decode(&encoded).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn test_unsupported() {
+        assert!(struct_encode(&Some(1)).is_err());
+        assert!(struct_encode(&'c').is_err());
+    }
+
+    #[test]
+    fn test_bad_input() {
+        let encoded = struct_encode(&(1_u32, 2_u32)).unwrap();
+        assert!(struct_decode::<(u32, u32, u32)>(&encoded).is_err());
+        assert!(struct_decode::<u32>(&encoded).is_err());
+        assert!(struct_decode::<bool>(&[2]).is_err());
+    }
+}
@@ -1,16 +1,20 @@
 use crate::bindings as ll_bindings;
 use crate::error::TskitError;
 use crate::types::Bookmark;
+use crate::types::TableKind;
 use crate::EdgeTable;
 use crate::IndividualTable;
 use crate::IndividualTableSortOptions;
 use crate::MigrationTable;
+use crate::MutationParentsFlags;
 use crate::MutationTable;
 use crate::NodeTable;
 use crate::PopulationTable;
 use crate::Position;
 use crate::SimplificationOptions;
 use crate::SiteTable;
+use crate::SizeType;
+use crate::SubsetFlags;
 use crate::TableAccess;
 use crate::TableClearOptions;
 use crate::TableEqualityOptions;
@@ -20,8 +24,9 @@ use crate::TableSortOptions;
 use crate::TreeSequenceFlags;
 use crate::TskReturnValue;
 use crate::TskitTypeAccess;
+use crate::UnionFlags;
 use crate::{tsk_id_t, tsk_size_t};
-use crate::{EdgeId, NodeId};
+use crate::{EdgeId, IndividualId, MutationId, NodeId, PopulationId, SiteId};
 use ll_bindings::tsk_table_collection_free;
 use mbox::MBox;
 
@@ -58,10 +63,46 @@ use mbox::MBox;
 /// assert_eq!(nodes.num_rows(), 1);
 /// ```
 ///
+/// # `serde` representation
+///
+/// With the `serde` feature, a table collection is serialized as a map
+/// with the following keys:
+///
+/// * `sequence_length`
+/// * `time_units`, `metadata`, and `metadata_schema`, which are optional
+///   when deserializing.
+/// * `individuals`, `nodes`, `edges`, `migrations`, `sites`, `mutations`,
+///   and `populations`, which are sequences of the corresponding row types,
+///   such as [`NodeTableRow`](crate::NodeTableRow).
+///   The `id` of each row must equal its position in the sequence.
+///   Missing tables are empty.
+/// * `provenances`, a sequence of maps with `timestamp` and `record` keys.
+///
+/// Table metadata schemas, the reference sequence, and edge indexes
+/// are not included.
+///
+/// ```
+/// # #[cfg(feature = "derive")] {
+/// let json = r#"{
+///     "sequence_length": 10.0,
+///     "nodes": [{"id": 0, "time": 0.0, "flags": 1, "population": -1,
+///                "individual": -1, "metadata": null}]
+/// }"#;
+/// let tables: tskit::TableCollection = serde_json::from_str(json).unwrap();
+/// assert_eq!(tables.sequence_length(), 10.0);
+/// # }
+/// ```
 pub struct TableCollection {
     inner: MBox<ll_bindings::tsk_table_collection_t>,
 }
 
+// Safety: the C tables are owned exclusively by this type,
+// the tskit C library keeps no global or thread-local state,
+// and only methods taking `&mut self` modify the tables.
+// Table views, which only need `&self`, are read-only.
+unsafe impl Send for TableCollection {}
+unsafe impl Sync for TableCollection {}
+
 impl TskitTypeAccess<ll_bindings::tsk_table_collection_t> for TableCollection {
     fn as_ptr(&self) -> *const ll_bindings::tsk_table_collection_t {
         &*self.inner
@@ -79,6 +120,68 @@ impl Drop for TableCollection {
     }
 }
 
+/// Prints a summary of the tables rather than their contents.
+///
+/// # Examples
+///
+/// ```
+/// let mut tables = tskit::TableCollection::new(10.).unwrap();
+/// tables.add_node(0, 1.0, -1, -1).unwrap();
+/// assert_eq!(
+///     format!("{:?}", tables),
+///     "TableCollection { sequence_length: 10.0, indexed: false, num_nodes: 1, \
+///      num_edges: 0, num_sites: 0, num_mutations: 0, num_individuals: 0, \
+///      num_populations: 0, num_migrations: 0, num_provenances: 0 }"
+/// );
+/// ```
+impl std::fmt::Debug for TableCollection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_struct("TableCollection");
+        d.field("sequence_length", &self.inner.sequence_length)
+            .field("indexed", &self.is_indexed());
+        debug_num_rows(&mut d, &self.inner);
+        d.finish()
+    }
+}
+
+pub(crate) fn debug_num_rows(
+    d: &mut std::fmt::DebugStruct<'_, '_>,
+    tables: &ll_bindings::tsk_table_collection_t,
+) {
+    d.field("num_nodes", &tables.nodes.num_rows)
+        .field("num_edges", &tables.edges.num_rows)
+        .field("num_sites", &tables.sites.num_rows)
+        .field("num_mutations", &tables.mutations.num_rows)
+        .field("num_individuals", &tables.individuals.num_rows)
+        .field("num_populations", &tables.populations.num_rows)
+        .field("num_migrations", &tables.migrations.num_rows)
+        .field("num_provenances", &tables.provenances.num_rows);
+}
+
+/// Cloning performs a "deep" copy of the tables.
+///
+/// # Panics
+///
+/// Will panic if [`TableCollection::deepcopy`] returns an error,
+/// which only happens if the system runs out of memory.
+///
+/// # Examples
+///
+/// ```
+/// use tskit::TableAccess;
+/// let mut tables = tskit::TableCollection::new(10.).unwrap();
+/// tables.add_node(0, 0.0, -1, -1).unwrap();
+/// let mut branch = tables.clone();
+/// branch.add_node(0, 1.0, -1, -1).unwrap();
+/// assert_eq!(tables.nodes().num_rows(), 1);
+/// assert_eq!(branch.nodes().num_rows(), 2);
+/// ```
+impl Clone for TableCollection {
+    fn clone(&self) -> Self {
+        self.deepcopy().unwrap()
+    }
+}
+
 /// Returns a pointer to an uninitialized tsk_table_collection_t
 pub(crate) fn uninit_table_collection() -> MBox<ll_bindings::tsk_table_collection_t> {
     let temp = unsafe {
@@ -92,6 +195,104 @@ pub(crate) fn uninit_table_collection() -> MBox<ll_bindings::tsk_table_collectio
     unsafe { MBox::from_non_null_raw(nonnull) }
 }
 
+/// Id maps returned by [`TableCollection::simplify_with_id_maps`]
+/// and [`TreeSequence::simplify_with_id_maps`](crate::TreeSequence::simplify_with_id_maps).
+///
+/// Each vector is equal in length to the corresponding input table.
+/// For each input row, the vector contains either that row's new
+/// index or `NULL` if the row was removed by simplification.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SimplificationIdMaps {
+    pub nodes: Vec<NodeId>,
+    pub individuals: Vec<IndividualId>,
+    pub populations: Vec<PopulationId>,
+    pub sites: Vec<SiteId>,
+    pub mutations: Vec<MutationId>,
+}
+
+// Metadata column in which each row holds its own id
+// as little-endian bytes.
+struct IdTags {
+    tags: Vec<u8>,
+    offsets: Vec<tsk_size_t>,
+}
+
+impl IdTags {
+    fn new(num_rows: SizeType) -> Result<Self, TskitError> {
+        let width = std::mem::size_of::<tsk_id_t>() as tsk_size_t;
+        let tags = (0..tsk_id_t::try_from(num_rows)?)
+            .flat_map(|i| i.to_le_bytes())
+            .collect();
+        let offsets = (0..=tsk_size_t::from(num_rows))
+            .map(|i| i * width)
+            .collect();
+        Ok(Self { tags, offsets })
+    }
+
+    fn tags(&self) -> *const libc::c_char {
+        self.tags.as_ptr().cast::<libc::c_char>()
+    }
+
+    fn offsets(&self) -> *const tsk_size_t {
+        self.offsets.as_ptr()
+    }
+}
+
+// Invert the tags written by IdTags into a map from input row
+// to output row.
+fn id_map_from_tags<I: From<tsk_id_t> + Copy>(
+    tags: &[u8],
+    num_input_rows: SizeType,
+) -> Result<Vec<I>, TskitError> {
+    let width = std::mem::size_of::<tsk_id_t>();
+    let mut map = vec![I::from(crate::TSK_NULL); usize::try_from(num_input_rows)?];
+    for (output, tag) in tags.chunks_exact(width).enumerate() {
+        let input = tsk_id_t::from_le_bytes(tag.try_into().unwrap());
+        map[usize::try_from(SizeType::try_from(input)?)?] =
+            I::from(tsk_id_t::try_from(SizeType::from(output))?);
+    }
+    Ok(map)
+}
+
+// Offset the non-NULL ids in a column.
+fn offset_ids(ids: &[tsk_id_t], offset: tsk_size_t) -> Result<Vec<tsk_id_t>, TskitError> {
+    let offset = tsk_id_t::try_from(offset).map_err(|_| TskitError::ErrorCode {
+        code: ll_bindings::TSK_ERR_TABLE_OVERFLOW,
+    })?;
+    ids.iter()
+        .map(|&id| {
+            if id < 0 {
+                Ok(id)
+            } else {
+                id.checked_add(offset).ok_or(TskitError::ErrorCode {
+                    code: ll_bindings::TSK_ERR_TABLE_OVERFLOW,
+                })
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn table_collection_nbytes(tables: &ll_bindings::tsk_table_collection_t) -> usize {
+    use crate::util::column_nbytes;
+    let reference_sequence = &tables.reference_sequence;
+    EdgeTable::new_from_table(&tables.edges).nbytes()
+        + NodeTable::new_from_table(&tables.nodes).nbytes()
+        + SiteTable::new_from_table(&tables.sites).nbytes()
+        + MutationTable::new_from_table(&tables.mutations).nbytes()
+        + IndividualTable::new_from_table(&tables.individuals).nbytes()
+        + PopulationTable::new_from_table(&tables.populations).nbytes()
+        + MigrationTable::new_from_table(&tables.migrations).nbytes()
+        + crate::util::provenance_table_nbytes(&tables.provenances)
+        + column_nbytes::<u8>(tables.metadata_length)
+        + column_nbytes::<u8>(tables.metadata_schema_length)
+        + column_nbytes::<u8>(tables.time_units_length)
+        + column_nbytes::<u8>(reference_sequence.data_length)
+        + column_nbytes::<u8>(reference_sequence.url_length)
+        + column_nbytes::<u8>(reference_sequence.metadata_length)
+        + column_nbytes::<u8>(reference_sequence.metadata_schema_length)
+        + 2 * column_nbytes::<tsk_id_t>(tables.indexes.num_edges)
+}
+
 impl TableCollection {
     /// Create a new table collection with a sequence length.
     ///
@@ -126,6 +327,13 @@ impl TableCollection {
         Ok(tables)
     }
 
+    /// Start building a tree sequence row by row.
+    ///
+    /// See [`TableCollectionBuilder`](crate::TableCollectionBuilder).
+    pub fn builder() -> crate::TableCollectionBuilder {
+        crate::TableCollectionBuilder::default()
+    }
+
     /// # Safety
     ///
     /// It is possible that the mbox's inner pointer has not be run through
@@ -199,6 +407,81 @@ impl TableCollection {
         handle_tsk_return_value!(rv, tables)
     }
 
+    /// Load a table collection from an in-memory buffer.
+    ///
+    /// The buffer must contain a complete table collection,
+    /// in the same format written by [`TableCollection::dump`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let empty_tables = tskit::TableCollection::new(100.).unwrap();
+    /// # empty_tables.dump("trees.bytes", tskit::TableOutputOptions::default()).unwrap();
+    /// let bytes = std::fs::read("trees.bytes").unwrap();
+    /// let tables = tskit::TableCollection::new_from_bytes(&bytes).unwrap();
+    /// assert_eq!(tables.sequence_length(), 100.);
+    /// # std::fs::remove_file("trees.bytes").unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IOError`] if the buffer cannot be opened as a stream.
+    /// * [`TskitError::ErrorCode`] if the data are not a valid table collection.
+    pub fn new_from_bytes(bytes: &[u8]) -> Result<Self, TskitError> {
+        // Arbitrary sequence_length.
+        let mut tables = TableCollection::new(1.0)?;
+
+        let mode = std::ffi::CString::new("r").unwrap();
+        // SAFETY: the stream is opened read-only, so the C side
+        // never writes through the (cast-away) const pointer.
+        let file = unsafe {
+            libc::fmemopen(
+                bytes.as_ptr() as *mut libc::c_void,
+                bytes.len(),
+                mode.as_ptr(),
+            )
+        };
+        if file.is_null() {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_loadf(
+                tables.as_mut_ptr(),
+                file.cast::<ll_bindings::FILE>(),
+                ll_bindings::TSK_NO_INIT,
+            )
+        };
+        unsafe { libc::fclose(file) };
+
+        handle_tsk_return_value!(rv, tables)
+    }
+
+    /// Load a table collection from any type implementing [`std::io::Read`].
+    ///
+    /// The entire contents of `reader` are read into memory
+    /// and then passed to [`TableCollection::new_from_bytes`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # let empty_tables = tskit::TableCollection::new(100.).unwrap();
+    /// # empty_tables.dump("trees.reader", tskit::TableOutputOptions::default()).unwrap();
+    /// let file = std::fs::File::open("trees.reader").unwrap();
+    /// let tables = tskit::TableCollection::new_from_reader(file).unwrap();
+    /// assert_eq!(tables.sequence_length(), 100.);
+    /// # std::fs::remove_file("trees.reader").unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IOError`] if reading fails.
+    /// * [`TskitError::ErrorCode`] if the data are not a valid table collection.
+    pub fn new_from_reader<R: std::io::Read>(mut reader: R) -> Result<Self, TskitError> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        Self::new_from_bytes(&bytes)
+    }
+
     /// Length of the sequence/"genome".
     /// # Examples
     ///
@@ -210,6 +493,28 @@ impl TableCollection {
         unsafe { (*self.as_ptr()).sequence_length }.into()
     }
 
+    /// Return the number of bytes used by the data in all tables.
+    ///
+    /// The total includes top-level metadata, the reference sequence,
+    /// and the edge indexes.
+    /// The contribution of each table can be obtained from the
+    /// `nbytes` function of that table (e.g., [`EdgeTable::nbytes`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let total = tables.nbytes();
+    /// let nodes = tables.nodes().nbytes();
+    /// tables.add_node(0, 0.0, -1, -1).unwrap();
+    /// assert!(tables.nodes().nbytes() > nodes);
+    /// assert_eq!(tables.nbytes() - total, tables.nodes().nbytes() - nodes);
+    /// ```
+    pub fn nbytes(&self) -> usize {
+        table_collection_nbytes(unsafe { &*self.as_ptr() })
+    }
+
     edge_table_add_row!(
     /// Add a row to the edge table
     ///
@@ -255,6 +560,14 @@ impl TableCollection {
     /// violations.
     => add_edge, self, (*self.inner).edges);
 
+    /// Start adding a row to the edge table that is
+    /// checked before insertion.
+    ///
+    /// See [`EdgeRowBuilder`](crate::EdgeRowBuilder) for details.
+    pub fn add_edge_defaults(&mut self) -> crate::EdgeRowBuilder<'_> {
+        crate::EdgeRowBuilder::new(&mut self.inner.edges)
+    }
+
     edge_table_add_row_with_metadata!(
     /// Add a row with optional metadata to the edge table
     ///
@@ -278,6 +591,29 @@ impl TableCollection {
     /// ```
     => add_edge_with_metadata, self, (*self.inner).edges);
 
+    edge_table_set_columns!(
+    /// Replace the contents of the edge table with the given columns.
+    ///
+    /// See [`OwnedEdgeTable::set_columns`](crate::OwnedEdgeTable::set_columns).
+    ///
+    /// As when adding rows, the edge table index is not updated.
+    /// Call [`TableCollection::build_index`] before creating a tree sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// # use tskit::{NodeId, Position};
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let left = vec![Position::from(0.); 2];
+    /// let right = vec![Position::from(100.); 2];
+    /// let parent = vec![NodeId::from(0); 2];
+    /// let child = vec![NodeId::from(1), NodeId::from(2)];
+    /// tables.set_edge_columns(&left, &right, &parent, &child).unwrap();
+    /// assert_eq!(tables.edges().num_rows(), 2);
+    /// ```
+    => set_edge_columns, self, self.inner.edges);
+
     individual_table_add_row!(
     /// Add a row to the individual table
     ///
@@ -317,6 +653,14 @@ impl TableCollection {
     /// ```
     => add_individual, self, (*self.inner).individuals);
 
+    /// Start adding a row to the individual table whose
+    /// columns take default values.
+    ///
+    /// See [`IndividualRowBuilder`](crate::IndividualRowBuilder) for details.
+    pub fn add_individual_defaults(&mut self) -> crate::IndividualRowBuilder<'_> {
+        crate::IndividualRowBuilder::new(&mut self.inner.individuals)
+    }
+
     individual_table_add_row_with_metadata!(
     /// Add a row with metadata to the individual table
     ///
@@ -338,11 +682,17 @@ impl TableCollection {
     /// let metadata = IndividualMetadata{x: 1};
     /// assert!(tables.add_individual_with_metadata(0, None, None,
     ///                                             &metadata).is_ok());
-    /// # let decoded = tables.individuals().metadata::<IndividualMetadata>(0.into()).unwrap().unwrap();
+    /// # let decoded = tables.individuals().metadata::<IndividualMetadata>(0).unwrap().unwrap();
     /// # assert_eq!(decoded.x, 1);
     /// # }
     => add_individual_with_metadata, self, (*self.inner).individuals);
 
+    individual_table_set_columns!(
+    /// Replace the contents of the individual table with the given columns.
+    ///
+    /// See [`OwnedIndividualTable::set_columns`](crate::OwnedIndividualTable::set_columns).
+    => set_individual_columns, self, self.inner.individuals);
+
     migration_table_add_row!(
     /// Add a row to the migration table
     ///
@@ -393,11 +743,33 @@ impl TableCollection {
     /// by tree sequence simplification.
     => add_migration_with_metadata, self, (*self.inner).migrations);
 
+    migration_table_set_columns!(
+    /// Replace the contents of the migration table with the given columns.
+    ///
+    /// See [`OwnedMigrationTable::set_columns`](crate::OwnedMigrationTable::set_columns).
+    => set_migration_columns, self, self.inner.migrations);
+
     node_table_add_row!(
     /// Add a row to the node table
     => add_node, self, (*self.inner).nodes
     );
 
+    /// Start adding a row to the node table whose
+    /// columns take default values.
+    ///
+    /// See [`NodeRowBuilder`](crate::NodeRowBuilder) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let id = tables.add_node_defaults().time(1.0).build().unwrap();
+    /// assert_eq!(tables.nodes().time(id).unwrap(), 1.0);
+    /// ```
+    pub fn add_node_defaults(&mut self) -> crate::NodeRowBuilder<'_> {
+        crate::NodeRowBuilder::new(&mut self.inner.nodes)
+    }
+
     node_table_add_row_with_metadata!(
 
     /// Add a row with optional metadata to the node table
@@ -422,6 +794,12 @@ impl TableCollection {
     /// ```
     => add_node_with_metadata, self, (*self.inner).nodes);
 
+    node_table_set_columns!(
+    /// Replace the contents of the node table with the given columns.
+    ///
+    /// See [`OwnedNodeTable::set_columns`](crate::OwnedNodeTable::set_columns).
+    => set_node_columns, self, self.inner.nodes);
+
     site_table_add_row!(
     /// Add a row to the site table
     => add_site, self, (*self.inner).sites);
@@ -451,10 +829,24 @@ impl TableCollection {
     /// ```
     => add_site_with_metadata, self, (*self.inner).sites);
 
+    site_table_set_columns!(
+    /// Replace the contents of the site table with the given columns.
+    ///
+    /// See [`OwnedSiteTable::set_columns`](crate::OwnedSiteTable::set_columns).
+    => set_site_columns, self, self.inner.sites);
+
     mutation_table_add_row!(
     /// Add a row to the mutation table.
     => add_mutation, self, (*self.inner).mutations);
 
+    /// Start adding a row to the mutation table that is
+    /// checked before insertion.
+    ///
+    /// See [`MutationRowBuilder`](crate::MutationRowBuilder) for details.
+    pub fn add_mutation_defaults(&mut self) -> crate::MutationRowBuilder<'_> {
+        crate::MutationRowBuilder::new(&mut self.inner.mutations)
+    }
+
     mutation_table_add_row_with_metadata!(
     /// Add a row with optional metadata to the mutation table.
     ///
@@ -479,6 +871,12 @@ impl TableCollection {
     /// ```
     => add_mutation_with_metadata, self, (*self.inner).mutations);
 
+    mutation_table_set_columns!(
+    /// Replace the contents of the mutation table with the given columns.
+    ///
+    /// See [`OwnedMutationTable::set_columns`](crate::OwnedMutationTable::set_columns).
+    => set_mutation_columns, self, self.inner.mutations);
+
     population_table_add_row!(
     /// Add a row to the population_table
     ///
@@ -512,1278 +910,4254 @@ impl TableCollection {
     /// # }
     => add_population_with_metadata, self, (*self.inner).populations);
 
-    /// Build the "input" and "output"
-    /// indexes for the edge table.
+    node_table_update_row!(
+    /// Replace the contents of row `row` of the node table.
     ///
-    /// # Note
+    /// The row's metadata are cleared.
+    /// If edges are updated, any existing edge indexes are no longer valid.
     ///
-    /// The `C API` call behind this takes a `flags` argument
-    /// that is currently unused.  A future release may break `API`
-    /// here if the `C` library is updated to use flags.
-    pub fn build_index(&mut self) -> TskReturnValue {
-        let rv = unsafe { ll_bindings::tsk_table_collection_build_index(self.as_mut_ptr(), 0) };
-        handle_tsk_return_value!(rv)
-    }
-
-    /// Return `true` if tables are indexed.
-    pub fn is_indexed(&self) -> bool {
-        unsafe { ll_bindings::tsk_table_collection_has_index(self.as_ptr(), 0) }
-    }
-
-    /// If `self.is_indexed()` is `true`, return a non-owning
-    /// slice containing the edge insertion order.
-    /// Otherwise, return `None`.
-    pub fn edge_insertion_order(&self) -> Option<&[EdgeId]> {
-        if self.is_indexed() {
-            Some(unsafe {
-                std::slice::from_raw_parts(
-                    (*self.as_ptr()).indexes.edge_insertion_order as *const EdgeId,
-                    usize::try_from((*self.as_ptr()).indexes.num_edges).unwrap(),
-                )
-            })
-        } else {
-            None
-        }
-    }
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_node(0, 0.0, -1, -1).unwrap();
+    /// tables.update_node(0, tskit::TSK_NODE_IS_SAMPLE, 1.0, -1, -1).unwrap();
+    /// assert!(tables.nodes().flags(0).unwrap().is_sample());
+    /// assert_eq!(tables.nodes().time(0).unwrap(), 1.0);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] if `row` is out of range.
+    => update_node, self, self.inner.nodes);
 
-    /// If `self.is_indexed()` is `true`, return a non-owning
-    /// slice containing the edge removal order.
-    /// Otherwise, return `None`.
-    pub fn edge_removal_order(&self) -> Option<&[EdgeId]> {
-        if self.is_indexed() {
-            Some(unsafe {
-                std::slice::from_raw_parts(
-                    (*self.as_ptr()).indexes.edge_removal_order as *const EdgeId,
-                    usize::try_from((*self.as_ptr()).indexes.num_edges).unwrap(),
-                )
-            })
-        } else {
-            None
-        }
-    }
+    node_table_update_row_with_metadata!(
+    /// Replace the contents of row `row` of the node table,
+    /// including its metadata.
+    ///
+    /// See [`TableCollection::update_node`].
+    => update_node_with_metadata, self, self.inner.nodes);
 
-    /// Sort the tables.  
-    /// The [``bookmark``](crate::types::Bookmark) can
-    /// be used to affect where sorting starts from for each table.
+    edge_table_update_row!(
+    /// Replace the contents of row `row` of the edge table.
     ///
-    /// # Details
+    /// The row's metadata are cleared.
+    /// If edges are updated, any existing edge indexes are no longer valid.
     ///
-    /// See [`full_sort`](crate::TableCollection::full_sort)
-    /// for more details about which tables are sorted.
-    pub fn sort<O: Into<TableSortOptions>>(
-        &mut self,
-        start: &Bookmark,
-        options: O,
-    ) -> TskReturnValue {
-        let rv = unsafe {
-            ll_bindings::tsk_table_collection_sort(
-                self.as_mut_ptr(),
-                &start.offsets,
-                options.into().bits(),
-            )
-        };
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_edge(0., 53., 1, 11).unwrap();
+    /// tables.update_edge(0, 0., 100., 1, 12).unwrap();
+    /// assert_eq!(tables.edges().right(0).unwrap(), 100.);
+    /// assert_eq!(tables.edges().child(0).unwrap(), 12);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] if `row` is out of range.
+    => update_edge, self, self.inner.edges);
 
-        handle_tsk_return_value!(rv)
-    }
+    edge_table_update_row_with_metadata!(
+    /// Replace the contents of row `row` of the edge table,
+    /// including its metadata.
+    ///
+    /// See [`TableCollection::update_edge`].
+    => update_edge_with_metadata, self, self.inner.edges);
 
-    /// Fully sort all tables.
-    /// Implemented via a call to [``sort``](crate::TableCollection::sort).
+    individual_table_update_row!(
+    /// Replace the contents of row `row` of the individual table.
     ///
-    /// # Details
+    /// The row's metadata are cleared.
+    /// If edges are updated, any existing edge indexes are no longer valid.
     ///
-    /// This function only sorts the tables that have a strict sortedness
-    /// requirement according to the `tskit` [data
-    /// model](https://tskit.dev/tskit/docs/stable/data-model.html).
+    /// # Examples
     ///
-    /// These tables are:
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_individual(0, None, None).unwrap();
+    /// tables.update_individual(0, 0, [1.0, 2.0], None).unwrap();
+    /// assert_eq!(tables.individuals().location(0).unwrap().unwrap(), &[1.0, 2.0]);
+    /// ```
     ///
-    /// * edges
-    /// * mutations
-    /// * sites
+    /// # Errors
     ///
-    /// For some use cases it is desirable to have the individual table
-    /// sorted so that parents appear before offspring. See
-    /// [``topological_sort_individuals``](crate::TableCollection::topological_sort_individuals).
-    pub fn full_sort<O: Into<TableSortOptions>>(&mut self, options: O) -> TskReturnValue {
-        let b = Bookmark::new();
-        self.sort(&b, options)
-    }
+    /// [`TskitError`] if `row` is out of range.
+    => update_individual, self, self.inner.individuals);
 
-    /// Sorts the individual table in place, so that parents come before children,
-    /// and the parent column is remapped as required. Node references to individuals
-    /// are also updated.
+    individual_table_update_row_with_metadata!(
+    /// Replace the contents of row `row` of the individual table,
+    /// including its metadata.
     ///
-    /// This function is needed because neither [``sort``](crate::TableCollection::sort) nor
-    /// [``full_sort``](crate::TableCollection::full_sort) sorts
-    /// the individual table!
+    /// See [`TableCollection::update_individual`].
+    => update_individual_with_metadata, self, self.inner.individuals);
+
+    site_table_update_row!(
+    /// Replace the contents of row `row` of the site table.
+    ///
+    /// The row's metadata are cleared.
+    /// If edges are updated, any existing edge indexes are no longer valid.
     ///
     /// # Examples
     ///
     /// ```
-    /// // Parent comes AFTER the child
-    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
-    /// let i0 = tables.add_individual(0, None, &[1]).unwrap();
-    /// assert_eq!(i0, 0);
-    /// let i1 = tables.add_individual(0, None, None).unwrap();
-    /// assert_eq!(i1, 1);
-    /// let n0 = tables.add_node(0, 0.0, -1, i1).unwrap();
-    /// assert_eq!(n0, 0);
-    /// let n1 = tables.add_node(0, 1.0, -1, i0).unwrap();
-    /// assert_eq!(n1, 1);
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_site(3., Some(b"A")).unwrap();
+    /// tables.update_site(0, 4., Some(b"GC")).unwrap();
+    /// assert_eq!(tables.sites().position(0).unwrap(), 4.);
+    /// assert_eq!(tables.sites().ancestral_state(0).unwrap(), Some(&b"GC"[..]));
+    /// ```
     ///
-    /// // Testing for valid individual order will Err:
-    /// match tables.check_integrity(tskit::TableIntegrityCheckFlags::CHECK_INDIVIDUAL_ORDERING) {
-    ///     Ok(_) => panic!("expected Err"),
-    ///     Err(_) => (),
-    /// };
+    /// # Errors
     ///
-    /// // The standard sort doesn't fix the Err...:
-    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
-    /// match tables.check_integrity(tskit::TableIntegrityCheckFlags::CHECK_INDIVIDUAL_ORDERING) {
-    ///     Ok(_) => panic!("expected Err"),
-    ///     Err(_) => (),
-    /// };
+    /// [`TskitError`] if `row` is out of range.
+    => update_site, self, self.inner.sites);
+
+    site_table_update_row_with_metadata!(
+    /// Replace the contents of row `row` of the site table,
+    /// including its metadata.
     ///
-    /// // ... so we need to intentionally sort the individuals.
-    /// let _ = tables.topological_sort_individuals(tskit::IndividualTableSortOptions::default()).unwrap();
-    /// tables.check_integrity(tskit::TableIntegrityCheckFlags::CHECK_INDIVIDUAL_ORDERING).unwrap();
+    /// See [`TableCollection::update_site`].
+    => update_site_with_metadata, self, self.inner.sites);
+
+    mutation_table_update_row!(
+    /// Replace the contents of row `row` of the mutation table.
+    ///
+    /// The row's metadata are cleared.
+    /// If edges are updated, any existing edge indexes are no longer valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_mutation(0, 0, -1, 0.0, Some(b"A")).unwrap();
+    /// tables.update_mutation(0, 0, 5, -1, 1.0, Some(b"T")).unwrap();
+    /// assert_eq!(tables.mutations().node(0).unwrap(), 5);
+    /// assert_eq!(tables.mutations().time(0).unwrap(), 1.0);
     /// ```
     ///
     /// # Errors
     ///
-    /// Will return an error code if the underlying `C` function returns an error.
-    pub fn topological_sort_individuals<O: Into<IndividualTableSortOptions>>(
-        &mut self,
-        options: O,
-    ) -> TskReturnValue {
-        let rv = unsafe {
-            ll_bindings::tsk_table_collection_individual_topological_sort(
-                self.as_mut_ptr(),
-                options.into().bits(),
-            )
-        };
-        handle_tsk_return_value!(rv)
-    }
+    /// [`TskitError`] if `row` is out of range.
+    => update_mutation, self, self.inner.mutations);
 
-    /// Dump the table collection to file.
-    ///
-    /// # Panics
+    mutation_table_update_row_with_metadata!(
+    /// Replace the contents of row `row` of the mutation table,
+    /// including its metadata.
     ///
-    /// This function allocates a `CString` to pass the file name to the C API.
-    /// A panic will occur if the system runs out of memory.
-    pub fn dump<O: Into<TableOutputOptions>>(&self, filename: &str, options: O) -> TskReturnValue {
-        let c_str = std::ffi::CString::new(filename).unwrap();
-        let rv = unsafe {
-            ll_bindings::tsk_table_collection_dump(
-                self.as_ptr(),
-                c_str.as_ptr(),
-                options.into().bits(),
-            )
-        };
+    /// See [`TableCollection::update_mutation`].
+    => update_mutation_with_metadata, self, self.inner.mutations);
 
-        handle_tsk_return_value!(rv)
-    }
+    migration_table_update_row!(
+    /// Replace the contents of row `row` of the migration table.
+    ///
+    /// The row's metadata are cleared.
+    /// If edges are updated, any existing edge indexes are no longer valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_migration((0., 1.), 0, (0, 1), 1.0).unwrap();
+    /// tables.update_migration(0, (0., 5.), 0, (1, 0), 1.0).unwrap();
+    /// assert_eq!(tables.migrations().right(0).unwrap(), 5.);
+    /// assert_eq!(tables.migrations().source(0).unwrap(), 1);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] if `row` is out of range.
+    => update_migration, self, self.inner.migrations);
 
-    /// Clear the contents of all tables.
-    /// Does not release memory.
-    /// Memory will be released when the object goes out
-    /// of scope.
-    pub fn clear<O: Into<TableClearOptions>>(&mut self, options: O) -> TskReturnValue {
-        let rv = unsafe {
-            ll_bindings::tsk_table_collection_clear(self.as_mut_ptr(), options.into().bits())
-        };
+    migration_table_update_row_with_metadata!(
+    /// Replace the contents of row `row` of the migration table,
+    /// including its metadata.
+    ///
+    /// See [`TableCollection::update_migration`].
+    => update_migration_with_metadata, self, self.inner.migrations);
 
-        handle_tsk_return_value!(rv)
-    }
+    population_table_update_row!(
+    /// Replace the contents of row `row` of the population table.
+    ///
+    /// The row's metadata are cleared.
+    /// If edges are updated, any existing edge indexes are no longer valid.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] if `row` is out of range.
+    => update_population, self, self.inner.populations);
 
-    /// Free all memory allocated on the C side.
-    /// Not public b/c not very safe.
-    #[allow(dead_code)]
-    fn free(&mut self) -> TskReturnValue {
-        let rv = unsafe { ll_bindings::tsk_table_collection_free(self.as_mut_ptr()) };
+    population_table_update_row_with_metadata!(
+    /// Replace the contents of row `row` of the population table,
+    /// including its metadata.
+    ///
+    /// See [`TableCollection::update_population`].
+    => update_population_with_metadata, self, self.inner.populations);
 
-        handle_tsk_return_value!(rv)
-    }
+    table_set_metadata_schema!(
+    /// Set the metadata schema of the node table.
+    ///
+    /// Schemas are `JSON` strings, as described in the
+    /// [`tskit` documentation](https://tskit.dev/tskit/docs/stable/metadata.html).
+    /// `tskit` does not validate the schema.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(1.).unwrap();
+    /// assert!(tables.nodes().metadata_schema().is_none());
+    /// let schema = r#"{"codec": "json"}"#;
+    /// tables.set_node_metadata_schema(schema).unwrap();
+    /// assert_eq!(tables.nodes().metadata_schema(), Some(schema));
+    /// ```
+    => set_node_metadata_schema, self, self.inner.nodes, ll_bindings::tsk_node_table_set_metadata_schema);
 
-    /// Return ``true`` if ``self`` contains the same
-    /// data as ``other``, and ``false`` otherwise.
-    pub fn equals<O: Into<TableEqualityOptions>>(
-        &self,
-        other: &TableCollection,
-        options: O,
-    ) -> bool {
-        unsafe {
-            ll_bindings::tsk_table_collection_equals(
-                self.as_ptr(),
-                other.as_ptr(),
-                options.into().bits(),
-            )
-        }
-    }
+    table_set_metadata_schema!(
+    /// Set the metadata schema of the edge table.
+    ///
+    /// See [`TableCollection::set_node_metadata_schema`].
+    => set_edge_metadata_schema, self, self.inner.edges, ll_bindings::tsk_edge_table_set_metadata_schema);
 
-    /// Return a "deep" copy of the tables.
-    pub fn deepcopy(&self) -> Result<TableCollection, TskitError> {
-        // The output is UNINITIALIZED tables,
-        // else we leak memory
-        let mut inner = uninit_table_collection();
+    table_set_metadata_schema!(
+    /// Set the metadata schema of the individual table.
+    ///
+    /// See [`TableCollection::set_node_metadata_schema`].
+    => set_individual_metadata_schema, self, self.inner.individuals, ll_bindings::tsk_individual_table_set_metadata_schema);
 
-        let rv = unsafe { ll_bindings::tsk_table_collection_copy(self.as_ptr(), &mut *inner, 0) };
+    table_set_metadata_schema!(
+    /// Set the metadata schema of the site table.
+    ///
+    /// See [`TableCollection::set_node_metadata_schema`].
+    => set_site_metadata_schema, self, self.inner.sites, ll_bindings::tsk_site_table_set_metadata_schema);
 
-        // SAFETY: we just initialized it.
-        // The C API doesn't free NULL pointers.
-        handle_tsk_return_value!(rv, unsafe { Self::new_from_mbox(inner) })
-    }
+    table_set_metadata_schema!(
+    /// Set the metadata schema of the mutation table.
+    ///
+    /// See [`TableCollection::set_node_metadata_schema`].
+    => set_mutation_metadata_schema, self, self.inner.mutations, ll_bindings::tsk_mutation_table_set_metadata_schema);
 
-    /// Return a [`crate::TreeSequence`] based on the tables.
-    /// This function will raise errors if tables are not sorted,
-    /// not indexed, or invalid in any way.
-    pub fn tree_sequence(
-        self,
-        flags: TreeSequenceFlags,
-    ) -> Result<crate::TreeSequence, TskitError> {
-        crate::TreeSequence::new(self, flags)
-    }
+    table_set_metadata_schema!(
+    /// Set the metadata schema of the migration table.
+    ///
+    /// See [`TableCollection::set_node_metadata_schema`].
+    => set_migration_metadata_schema, self, self.inner.migrations, ll_bindings::tsk_migration_table_set_metadata_schema);
 
-    /// Simplify tables in place.
+    table_set_metadata_schema!(
+    /// Set the metadata schema of the population table.
     ///
-    /// # Parameters
+    /// See [`TableCollection::set_node_metadata_schema`].
+    => set_population_metadata_schema, self, self.inner.populations, ll_bindings::tsk_population_table_set_metadata_schema);
+
+    table_packset_metadata!(
+    /// Replace the metadata column of the node table.
     ///
-    /// * `samples`: a slice containing non-null node ids.
-    ///   The tables are simplified with respect to the ancestry
-    ///   of these nodes.
-    /// * `options`: A [`SimplificationOptions`] bit field controlling
-    ///   the behavior of simplification.
-    /// * `idmap`: if `true`, the return value contains a vector equal
-    ///   in length to the input node table.  For each input node,
-    ///   this vector either contains the node's new index or [`NodeId::NULL`]
-    ///   if the input node is not part of the simplified history.
-    pub fn simplify<N: Into<NodeId>, O: Into<SimplificationOptions>>(
-        &mut self,
-        samples: &[N],
-        options: O,
-        idmap: bool,
-    ) -> Result<Option<Vec<NodeId>>, TskitError> {
-        let mut output_node_map: Vec<NodeId> = vec![];
-        if idmap {
-            output_node_map.resize(usize::try_from(self.nodes().num_rows())?, NodeId::NULL);
-        }
-        let rv = unsafe {
-            ll_bindings::tsk_table_collection_simplify(
-                self.as_mut_ptr(),
-                samples.as_ptr().cast::<tsk_id_t>(),
-                samples.len() as tsk_size_t,
-                options.into().bits(),
-                match idmap {
-                    true => output_node_map.as_mut_ptr().cast::<tsk_id_t>(),
-                    false => std::ptr::null_mut(),
-                },
-            )
-        };
-        handle_tsk_return_value!(
-            rv,
-            match idmap {
-                true => Some(output_node_map),
-                false => None,
-            }
-        )
-    }
+    /// See [`OwnedNodeTable::packset_metadata`](crate::OwnedNodeTable::packset_metadata).
+    => packset_node_metadata, self, self.inner.nodes);
 
-    /// Validate the contents of the table collection
+    table_packset_metadata!(
+    /// Replace the metadata column of the edge table.
     ///
-    /// # Parameters
+    /// See [`OwnedNodeTable::packset_metadata`](crate::OwnedNodeTable::packset_metadata).
+    => packset_edge_metadata, self, self.inner.edges);
+
+    table_packset_metadata!(
+    /// Replace the metadata column of the individual table.
     ///
-    /// `flags` is an instance of [`TableIntegrityCheckFlags`]
+    /// See [`OwnedNodeTable::packset_metadata`](crate::OwnedNodeTable::packset_metadata).
+    => packset_individual_metadata, self, self.inner.individuals);
+
+    table_packset_metadata!(
+    /// Replace the metadata column of the site table.
     ///
-    /// # Return value
+    /// See [`OwnedNodeTable::packset_metadata`](crate::OwnedNodeTable::packset_metadata).
+    => packset_site_metadata, self, self.inner.sites);
+
+    table_packset_metadata!(
+    /// Replace the metadata column of the mutation table.
     ///
-    /// `0` upon success, or an error code.
-    /// However, if `flags` contains [`TableIntegrityCheckFlags::CHECK_TREES`],
-    /// and no error is returned, then the return value is the number
-    /// of trees.
+    /// See [`OwnedNodeTable::packset_metadata`](crate::OwnedNodeTable::packset_metadata).
+    => packset_mutation_metadata, self, self.inner.mutations);
+
+    table_packset_metadata!(
+    /// Replace the metadata column of the migration table.
     ///
-    /// # Note
+    /// See [`OwnedNodeTable::packset_metadata`](crate::OwnedNodeTable::packset_metadata).
+    => packset_migration_metadata, self, self.inner.migrations);
+
+    table_packset_metadata!(
+    /// Replace the metadata column of the population table.
     ///
-    /// Creating a [`crate::TreeSequence`] from a table collection will automatically
-    /// run an integrity check.
-    /// See [`TableCollection::tree_sequence`].
+    /// See [`OwnedNodeTable::packset_metadata`](crate::OwnedNodeTable::packset_metadata).
+    => packset_population_metadata, self, self.inner.populations);
+
+    table_migrate_metadata!(
+    /// Re-encode the metadata of every row of the edge table with a new schema.
     ///
-    /// # Examples
+    /// See [`OwnedNodeTable::migrate_metadata`](crate::OwnedNodeTable::migrate_metadata).
+    => migrate_edge_metadata, self, self.edges(), packset_edge_metadata, set_edge_metadata_schema, EdgeMetadata);
+
+    table_migrate_metadata!(
+    /// Re-encode the metadata of every row of the individual table with a new schema.
     ///
-    /// There are many ways for a table colletion to be invalid.
-    /// These examples are just the tip of the iceberg.
+    /// See [`OwnedNodeTable::migrate_metadata`](crate::OwnedNodeTable::migrate_metadata).
+    => migrate_individual_metadata, self, self.individuals(), packset_individual_metadata, set_individual_metadata_schema, IndividualMetadata);
+
+    table_migrate_metadata!(
+    /// Re-encode the metadata of every row of the migration table with a new schema.
     ///
-    /// ```should_panic
-    /// let mut tables = tskit::TableCollection::new(10.0).unwrap();
-    /// // Right position is > sequence_length
-    /// tables.add_edge(0.0, 11.0, 0, 0);
-    /// tables.check_integrity(tskit::TableIntegrityCheckFlags::default()).unwrap();
-    /// ```
+    /// See [`OwnedNodeTable::migrate_metadata`](crate::OwnedNodeTable::migrate_metadata).
+    => migrate_migration_metadata, self, self.migrations(), packset_migration_metadata, set_migration_metadata_schema, MigrationMetadata);
+
+    table_migrate_metadata!(
+    /// Re-encode the metadata of every row of the mutation table with a new schema.
     ///
-    /// ```should_panic
-    /// # let mut tables = tskit::TableCollection::new(10.0).unwrap();
-    /// // Left position is < 0.0
-    /// tables.add_edge(-1., 10.0, 0, 0);
-    /// tables.check_integrity(tskit::TableIntegrityCheckFlags::default()).unwrap();
-    /// ```
+    /// See [`OwnedNodeTable::migrate_metadata`](crate::OwnedNodeTable::migrate_metadata).
+    => migrate_mutation_metadata, self, self.mutations(), packset_mutation_metadata, set_mutation_metadata_schema, MutationMetadata);
+
+    table_migrate_metadata!(
+    /// Re-encode the metadata of every row of the node table with a new schema.
     ///
-    /// ```should_panic
-    /// # let mut tables = tskit::TableCollection::new(10.0).unwrap();
-    /// // Edges cannot have null node ids
-    /// tables.add_edge(0., 10.0, tskit::NodeId::NULL, 0);
-    /// tables.check_integrity(tskit::TableIntegrityCheckFlags::default()).unwrap();
-    /// ```
-    pub fn check_integrity(&self, flags: TableIntegrityCheckFlags) -> TskReturnValue {
-        let rv = unsafe {
-            ll_bindings::tsk_table_collection_check_integrity(self.as_ptr(), flags.bits())
-        };
-        handle_tsk_return_value!(rv)
-    }
+    /// See [`OwnedNodeTable::migrate_metadata`](crate::OwnedNodeTable::migrate_metadata).
+    => migrate_node_metadata, self, self.nodes(), packset_node_metadata, set_node_metadata_schema, NodeMetadata);
 
-    #[cfg(any(feature = "provenance", doc))]
-    provenance_table_add_row!(
-    /// Add provenance record with a time stamp.
+    table_migrate_metadata!(
+    /// Re-encode the metadata of every row of the population table with a new schema.
     ///
-    /// All implementation of this trait provided by `tskit` use
-    /// an `ISO 8601` format time stamp
-    /// written using the [RFC 3339](https://tools.ietf.org/html/rfc3339)
-    /// specification.
-    /// This formatting approach has been the most straightforward method
-    /// for supporting round trips to/from a [`crate::provenance::ProvenanceTable`].
-    /// The implementations used here use the [`humantime`](https://docs.rs/humantime/latest/humantime/) crate.
+    /// See [`OwnedNodeTable::migrate_metadata`](crate::OwnedNodeTable::migrate_metadata).
+    => migrate_population_metadata, self, self.populations(), packset_population_metadata, set_population_metadata_schema, PopulationMetadata);
+
+    table_migrate_metadata!(
+    /// Re-encode the metadata of every row of the site table with a new schema.
     ///
-    /// # Parameters
+    /// See [`OwnedNodeTable::migrate_metadata`](crate::OwnedNodeTable::migrate_metadata).
+    => migrate_site_metadata, self, self.sites(), packset_site_metadata, set_site_metadata_schema, SiteMetadata);
+
+    table_set_max_increment!(
+    /// Set the number of rows by which the node table grows
+    /// when it runs out of space.
     ///
-    /// * `record`: the provenance record
+    /// See [`OwnedNodeTable::set_max_rows_increment`](crate::OwnedNodeTable::set_max_rows_increment).
+    => set_node_max_rows_increment, self, self.inner.nodes, ll_bindings::tsk_node_table_set_max_rows_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the metadata
+    /// column of the node table grows when it runs out of space.
+    => set_node_max_metadata_length_increment, self, self.inner.nodes, ll_bindings::tsk_node_table_set_max_metadata_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of rows by which the edge table grows
+    /// when it runs out of space.
     ///
-    /// # Examples
-    /// ```
-    /// use tskit::TableAccess;
-    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
-    /// # #[cfg(feature = "provenance")] {
-    /// tables.add_provenance(&String::from("Some provenance")).unwrap();
+    /// See [`OwnedNodeTable::set_max_rows_increment`](crate::OwnedNodeTable::set_max_rows_increment).
+    => set_edge_max_rows_increment, self, self.inner.edges, ll_bindings::tsk_edge_table_set_max_rows_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the metadata
+    /// column of the edge table grows when it runs out of space.
+    => set_edge_max_metadata_length_increment, self, self.inner.edges, ll_bindings::tsk_edge_table_set_max_metadata_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of rows by which the individual table grows
+    /// when it runs out of space.
     ///
-    /// // Get reference to the table
-    /// let prov_ref = tables.provenances();
+    /// See [`OwnedNodeTable::set_max_rows_increment`](crate::OwnedNodeTable::set_max_rows_increment).
+    => set_individual_max_rows_increment, self, self.inner.individuals, ll_bindings::tsk_individual_table_set_max_rows_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the metadata
+    /// column of the individual table grows when it runs out of space.
+    => set_individual_max_metadata_length_increment, self, self.inner.individuals, ll_bindings::tsk_individual_table_set_max_metadata_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the location
+    /// column of the individual table grows when it runs out of space.
+    => set_individual_max_location_length_increment, self, self.inner.individuals, ll_bindings::tsk_individual_table_set_max_location_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the parents
+    /// column of the individual table grows when it runs out of space.
+    => set_individual_max_parents_length_increment, self, self.inner.individuals, ll_bindings::tsk_individual_table_set_max_parents_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of rows by which the site table grows
+    /// when it runs out of space.
     ///
-    /// // Get the first row
-    /// let row_0 = prov_ref.row(0).unwrap();
-    ///
-    /// assert_eq!(row_0.record, "Some provenance");
+    /// See [`OwnedNodeTable::set_max_rows_increment`](crate::OwnedNodeTable::set_max_rows_increment).
+    => set_site_max_rows_increment, self, self.inner.sites, ll_bindings::tsk_site_table_set_max_rows_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the metadata
+    /// column of the site table grows when it runs out of space.
+    => set_site_max_metadata_length_increment, self, self.inner.sites, ll_bindings::tsk_site_table_set_max_metadata_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the ancestral_state
+    /// column of the site table grows when it runs out of space.
+    => set_site_max_ancestral_state_length_increment, self, self.inner.sites, ll_bindings::tsk_site_table_set_max_ancestral_state_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of rows by which the mutation table grows
+    /// when it runs out of space.
     ///
-    /// // Get the first record
-    /// let record_0 = prov_ref.record(0).unwrap();
-    /// assert_eq!(record_0, row_0.record);
+    /// See [`OwnedNodeTable::set_max_rows_increment`](crate::OwnedNodeTable::set_max_rows_increment).
+    => set_mutation_max_rows_increment, self, self.inner.mutations, ll_bindings::tsk_mutation_table_set_max_rows_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the metadata
+    /// column of the mutation table grows when it runs out of space.
+    => set_mutation_max_metadata_length_increment, self, self.inner.mutations, ll_bindings::tsk_mutation_table_set_max_metadata_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the derived_state
+    /// column of the mutation table grows when it runs out of space.
+    => set_mutation_max_derived_state_length_increment, self, self.inner.mutations, ll_bindings::tsk_mutation_table_set_max_derived_state_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of rows by which the migration table grows
+    /// when it runs out of space.
     ///
-    /// // Get the first time stamp
-    /// let timestamp = prov_ref.timestamp(0).unwrap();
-    /// assert_eq!(timestamp, row_0.timestamp);
+    /// See [`OwnedNodeTable::set_max_rows_increment`](crate::OwnedNodeTable::set_max_rows_increment).
+    => set_migration_max_rows_increment, self, self.inner.migrations, ll_bindings::tsk_migration_table_set_max_rows_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the metadata
+    /// column of the migration table grows when it runs out of space.
+    => set_migration_max_metadata_length_increment, self, self.inner.migrations, ll_bindings::tsk_migration_table_set_max_metadata_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of rows by which the population table grows
+    /// when it runs out of space.
+    ///
+    /// See [`OwnedNodeTable::set_max_rows_increment`](crate::OwnedNodeTable::set_max_rows_increment).
+    => set_population_max_rows_increment, self, self.inner.populations, ll_bindings::tsk_population_table_set_max_rows_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the metadata
+    /// column of the population table grows when it runs out of space.
+    => set_population_max_metadata_length_increment, self, self.inner.populations, ll_bindings::tsk_population_table_set_max_metadata_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of rows by which the provenance table grows
+    /// when it runs out of space.
+    ///
+    /// See [`OwnedNodeTable::set_max_rows_increment`](crate::OwnedNodeTable::set_max_rows_increment).
+    => set_provenance_max_rows_increment, self, self.inner.provenances, ll_bindings::tsk_provenance_table_set_max_rows_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the timestamp
+    /// column of the provenance table grows when it runs out of space.
+    => set_provenance_max_timestamp_length_increment, self, self.inner.provenances, ll_bindings::tsk_provenance_table_set_max_timestamp_length_increment);
+
+    table_set_max_increment!(
+    /// Set the number of elements by which the record
+    /// column of the provenance table grows when it runs out of space.
+    => set_provenance_max_record_length_increment, self, self.inner.provenances, ll_bindings::tsk_provenance_table_set_max_record_length_increment);
+
+    table_column_slice_mut!(
+    /// Mutable access to the flags column of the node table.
+    ///
+    /// The values are changed in place, which is useful for
+    /// converting units.
+    /// Changes are not validated: call [`TableCollection::check_integrity`]
+    /// afterwards if needed.
+    /// Changing node times or edge coordinates invalidates the edge
+    /// indexes, which must be rebuilt via [`TableCollection::build_index`].
     ///
-    /// // You can get the `humantime::Timestamp` object back from the `String`:
-    /// use core::str::FromStr;
-    /// let timestamp_string = humantime::Timestamp::from_str(&timestamp).unwrap();
+    /// # Examples
     ///
-    /// // Provenance transfers to the tree sequences
-    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
-    /// assert_eq!(treeseq.provenances().record(0).unwrap(), "Some provenance");
-    /// // We can still compare to row_0 because it is a copy of the row data:
-    /// assert_eq!(treeseq.provenances().record(0).unwrap(), row_0.record);
-    /// # }
     /// ```
-    => add_provenance, self, (*self.inner).provenances);
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// tables.add_node(0, 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 10., 0, 1).unwrap();
+    /// tables.node_time_array_mut()[0] = 2.0.into();
+    /// tables.build_index().unwrap();
+    /// ```
+    => node_flags_array_mut, self, self.inner.nodes, flags, crate::NodeFlags);
 
-    /// Set the edge table from an [`OwnedEdgeTable`](`crate::OwnedEdgeTable`)
+    table_column_slice_mut!(
+    /// Mutable access to the time column of the node table.
     ///
-    /// # Errors
+    /// See [`TableCollection::node_flags_array_mut`].
+    => node_time_array_mut, self, self.inner.nodes, time, crate::Time);
+
+    table_column_slice_mut!(
+    /// Mutable access to the left column of the edge table.
     ///
-    /// Any errors from the C API propagate.
+    /// See [`TableCollection::node_flags_array_mut`].
+    => edge_left_array_mut, self, self.inner.edges, left, crate::Position);
+
+    table_column_slice_mut!(
+    /// Mutable access to the right column of the edge table.
     ///
-    /// # Example
+    /// See [`TableCollection::node_flags_array_mut`].
+    => edge_right_array_mut, self, self.inner.edges, right, crate::Position);
+
+    table_column_slice_mut!(
+    /// Mutable access to the flags column of the individual table.
     ///
-    /// ```rust
-    /// # use tskit::TableAccess;
-    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
-    /// let mut edges = tskit::OwnedEdgeTable::default();
-    /// edges.add_row(0., 1., 0, 12).unwrap();
-    /// tables.set_edges(&edges).unwrap();
-    /// assert_eq!(tables.edges().num_rows(), 1);
-    /// assert_eq!(tables.edges().child(0).unwrap(), 12);
-    /// # edges.clear().unwrap();
-    /// # assert_eq!(edges.num_rows(), 0);
-    /// ```
-    pub fn set_edges(&mut self, edges: &crate::OwnedEdgeTable) -> TskReturnValue {
-        // SAFETY: neither self nor edges are possible
-        // to create with null pointers.
-        let rv = unsafe {
-            ll_bindings::tsk_edge_table_set_columns(
-                &mut (*self.inner).edges,
-                (*edges.as_ptr()).num_rows,
-                (*edges.as_ptr()).left,
-                (*edges.as_ptr()).right,
-                (*edges.as_ptr()).parent,
-                (*edges.as_ptr()).child,
-                (*edges.as_ptr()).metadata,
-                (*edges.as_ptr()).metadata_offset,
-            )
-        };
-        handle_tsk_return_value!(rv)
-    }
+    /// See [`TableCollection::node_flags_array_mut`].
+    => individual_flags_array_mut, self, self.inner.individuals, flags, crate::IndividualFlags);
 
-    /// Set the node table from an [`OwnedNodeTable`](`crate::OwnedNodeTable`)
+    table_column_slice_mut!(
+    /// Mutable access to the position column of the site table.
     ///
-    /// # Errors
+    /// See [`TableCollection::node_flags_array_mut`].
+    => site_position_array_mut, self, self.inner.sites, position, crate::Position);
+
+    table_column_slice_mut!(
+    /// Mutable access to the time column of the mutation table.
     ///
-    /// Any errors from the C API propagate.
+    /// See [`TableCollection::node_flags_array_mut`].
+    => mutation_time_array_mut, self, self.inner.mutations, time, crate::Time);
+
+    table_column_slice_mut!(
+    /// Mutable access to the left column of the migration table.
     ///
-    /// # Example
+    /// See [`TableCollection::node_flags_array_mut`].
+    => migration_left_array_mut, self, self.inner.migrations, left, crate::Position);
+
+    table_column_slice_mut!(
+    /// Mutable access to the right column of the migration table.
     ///
-    /// ```rust
-    /// # use tskit::TableAccess;
-    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
-    /// let mut nodes = tskit::OwnedNodeTable::default();
-    /// nodes.add_row(0, 10.0, -1, -1).unwrap();
-    /// tables.set_nodes(&nodes).unwrap();
-    /// assert_eq!(tables.nodes().num_rows(), 1);
-    /// assert_eq!(tables.nodes().time(0).unwrap(), 10.0);
-    /// # nodes.clear().unwrap();
-    /// # assert_eq!(nodes.num_rows(), 0);
-    /// ```
-    pub fn set_nodes(&mut self, nodes: &crate::OwnedNodeTable) -> TskReturnValue {
-        // SAFETY: neither self nor nodes are possible
-        // to create with null pointers.
-        let rv = unsafe {
-            ll_bindings::tsk_node_table_set_columns(
-                &mut (*self.inner).nodes,
-                (*nodes.as_ptr()).num_rows,
-                (*nodes.as_ptr()).flags,
-                (*nodes.as_ptr()).time,
-                (*nodes.as_ptr()).population,
-                (*nodes.as_ptr()).individual,
-                (*nodes.as_ptr()).metadata,
-                (*nodes.as_ptr()).metadata_offset,
-            )
-        };
-        handle_tsk_return_value!(rv)
-    }
+    /// See [`TableCollection::node_flags_array_mut`].
+    => migration_right_array_mut, self, self.inner.migrations, right, crate::Position);
 
-    /// Set the site table from an [`OwnedSiteTable`](`crate::OwnedSiteTable`)
+    table_column_slice_mut!(
+    /// Mutable access to the time column of the migration table.
+    ///
+    /// See [`TableCollection::node_flags_array_mut`].
+    => migration_time_array_mut, self, self.inner.migrations, time, crate::Time);
+
+    /// Set the top-level metadata of the table collection.
+    ///
+    /// See [`metadata`](crate::metadata) for more details about required
+    /// trait implementations.
     ///
     /// # Errors
     ///
-    /// Any errors from the C API propagate.
+    /// * [`TskitError::MetadataError`] if encoding fails.
+    /// * [`TskitError`] if the underlying C library returns an error code.
     ///
-    /// # Example
+    /// # Examples
     ///
-    /// ```rust
-    /// # use tskit::TableAccess;
-    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
-    /// let mut sites = tskit::OwnedSiteTable::default();
-    /// sites.add_row(11.0, None).unwrap();
-    /// tables.set_sites(&sites).unwrap();
-    /// assert_eq!(tables.sites().num_rows(), 1);
-    /// assert_eq!(tables.sites().position(0).unwrap(), 11.0);
-    /// # sites.clear().unwrap();
-    /// # assert_eq!(sites.num_rows(), 0);
     /// ```
-    pub fn set_sites(&mut self, sites: &crate::OwnedSiteTable) -> TskReturnValue {
-        // SAFETY: neither self nor nodes are possible
-        // to create with null pointers.
+    /// struct RunConfig {
+    ///     seed: u64,
+    /// }
+    ///
+    /// impl tskit::metadata::MetadataRoundtrip for RunConfig {
+    ///     fn encode(&self) -> Result<Vec<u8>, tskit::metadata::MetadataError> {
+    ///         Ok(self.seed.to_le_bytes().to_vec())
+    ///     }
+    ///
+    ///     fn decode(md: &[u8]) -> Result<Self, tskit::metadata::MetadataError> {
+    ///         let bytes = md.try_into().map_err(|e| {
+    ///             tskit::metadata::MetadataError::RoundtripError { value: Box::new(e) }
+    ///         })?;
+    ///         Ok(Self { seed: u64::from_le_bytes(bytes) })
+    ///     }
+    /// }
+    ///
+    /// impl tskit::metadata::TreeSequenceMetadata for RunConfig {}
+    ///
+    /// let mut tables = tskit::TableCollection::new(1.).unwrap();
+    /// assert!(tables.metadata::<RunConfig>().unwrap().is_none());
+    /// tables.set_metadata(&RunConfig { seed: 101 }).unwrap();
+    /// let decoded = tables.metadata::<RunConfig>().unwrap().unwrap();
+    /// assert_eq!(decoded.seed, 101);
+    /// ```
+    pub fn set_metadata<T: crate::metadata::TreeSequenceMetadata + ?Sized>(
+        &mut self,
+        metadata: &T,
+    ) -> TskReturnValue {
+        let md = crate::metadata::EncodedMetadata::new(metadata)?;
         let rv = unsafe {
-            ll_bindings::tsk_site_table_set_columns(
-                &mut (*self.inner).sites,
-                (*sites.as_ptr()).num_rows,
-                (*sites.as_ptr()).position,
-                (*sites.as_ptr()).ancestral_state,
-                (*sites.as_ptr()).ancestral_state_offset,
-                (*sites.as_ptr()).metadata,
-                (*sites.as_ptr()).metadata_offset,
+            ll_bindings::tsk_table_collection_set_metadata(
+                self.as_mut_ptr(),
+                md.as_ptr(),
+                md.len().into(),
             )
         };
         handle_tsk_return_value!(rv)
     }
 
-    /// Set the mutation table from an [`OwnedMutationTable`](`crate::OwnedSiteTable`)
+    /// Decode the top-level metadata of the table collection.
     ///
-    /// # Errors
+    /// # Returns
     ///
-    /// Any errors from the C API propagate.
+    /// * `Ok(Some(T))` if there is metadata that decodes successfully.
+    /// * `Ok(None)` if there is no metadata.
     ///
-    /// # Example
+    /// # Errors
     ///
-    /// ```rust
-    /// # use tskit::TableAccess;
-    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
-    /// let mut mutations = tskit::OwnedMutationTable::default();
-    /// mutations.add_row(14, 12, -1, 11.3, None).unwrap();
-    /// tables.set_mutations(&mutations).unwrap();
-    /// assert_eq!(tables.mutations().num_rows(), 1);
-    /// assert_eq!(tables.mutations().site(0).unwrap(), 14);
-    /// # mutations.clear().unwrap();
-    /// # assert_eq!(mutations.num_rows(), 0);
-    /// ```
-    pub fn set_mutations(&mut self, mutations: &crate::OwnedMutationTable) -> TskReturnValue {
-        // SAFETY: neither self nor nodes are possible
-        // to create with null pointers.
-        let rv = unsafe {
-            ll_bindings::tsk_mutation_table_set_columns(
-                &mut (*self.inner).mutations,
-                (*mutations.as_ptr()).num_rows,
-                (*mutations.as_ptr()).site,
-                (*mutations.as_ptr()).node,
-                (*mutations.as_ptr()).parent,
-                (*mutations.as_ptr()).time,
-                (*mutations.as_ptr()).derived_state,
-                (*mutations.as_ptr()).derived_state_offset,
-                (*mutations.as_ptr()).metadata,
-                (*mutations.as_ptr()).metadata_offset,
+    /// [`TskitError::MetadataDecodeError`] if decoding fails.
+    pub fn metadata<T: crate::metadata::TreeSequenceMetadata>(
+        &self,
+    ) -> Result<Option<T>, TskitError> {
+        let buffer = unsafe {
+            crate::metadata::char_array_to_slice(
+                self,
+                self.inner.metadata,
+                self.inner.metadata_length,
             )
         };
-        handle_tsk_return_value!(rv)
+        decode_metadata_row!(T, buffer)
     }
 
-    /// Set the individual table from an [`OwnedIndividualTable`](`crate::OwnedSiteTable`)
+    /// Decode the top-level metadata of the table collection,
+    /// treating the absence of metadata as an error.
     ///
     /// # Errors
     ///
-    /// Any errors from the C API propagate.
+    /// * [`TskitError::UnexpectedEmptyMetadata`] if there is no metadata.
+    /// * [`TskitError::MetadataDecodeError`] if decoding fails.
+    pub fn metadata_or_err<T: crate::metadata::TreeSequenceMetadata>(
+        &self,
+    ) -> Result<T, TskitError> {
+        self.metadata()?
+            .ok_or(TskitError::UnexpectedEmptyMetadata { row: None })
+    }
+
+    /// Set the top-level metadata schema of the table collection.
     ///
-    /// # Example
+    /// See [`TableCollection::set_node_metadata_schema`].
+    ///
+    /// # Examples
     ///
-    /// ```rust
-    /// # use tskit::TableAccess;
-    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
-    /// let mut individuals = tskit::OwnedIndividualTable::default();
-    /// individuals.add_row(0, [0.1, 10.0], None).unwrap();
-    /// tables.set_individuals(&individuals).unwrap();
-    /// assert_eq!(tables.individuals().num_rows(), 1);
-    /// let expected = vec![tskit::Location::from(0.1), tskit::Location::from(10.0)];
-    /// assert_eq!(tables.individuals().location(0).unwrap(), Some(expected.as_slice()));
-    /// # individuals.clear().unwrap();
-    /// # assert_eq!(individuals.num_rows(), 0);
     /// ```
-    pub fn set_individuals(&mut self, individuals: &crate::OwnedIndividualTable) -> TskReturnValue {
-        // SAFETY: neither self nor nodes are possible
-        // to create with null pointers.
+    /// let mut tables = tskit::TableCollection::new(1.).unwrap();
+    /// assert!(tables.metadata_schema().is_none());
+    /// let schema = r#"{"codec": "json"}"#;
+    /// tables.set_metadata_schema(schema).unwrap();
+    /// assert_eq!(tables.metadata_schema(), Some(schema));
+    /// ```
+    pub fn set_metadata_schema<S: AsRef<str>>(&mut self, schema: S) -> TskReturnValue {
+        let schema = schema.as_ref();
         let rv = unsafe {
-            ll_bindings::tsk_individual_table_set_columns(
-                &mut (*self.inner).individuals,
-                (*individuals.as_ptr()).num_rows,
-                (*individuals.as_ptr()).flags,
-                (*individuals.as_ptr()).location,
-                (*individuals.as_ptr()).location_offset,
-                (*individuals.as_ptr()).parents,
-                (*individuals.as_ptr()).parents_offset,
-                (*individuals.as_ptr()).metadata,
-                (*individuals.as_ptr()).metadata_offset,
+            ll_bindings::tsk_table_collection_set_metadata_schema(
+                self.as_mut_ptr(),
+                schema.as_ptr().cast::<libc::c_char>(),
+                schema.len() as ll_bindings::tsk_size_t,
             )
         };
         handle_tsk_return_value!(rv)
     }
 
-    /// Set the migration table from an [`OwnedMigrationTable`](`crate::OwnedSiteTable`)
+    /// Return the top-level metadata schema of the table collection.
     ///
-    /// # Errors
+    /// Returns `None` if there is no schema or if the schema
+    /// is not valid UTF-8.
+    pub fn metadata_schema(&self) -> Option<&str> {
+        unsafe {
+            crate::metadata::char_array_to_slice(
+                self,
+                self.inner.metadata_schema,
+                self.inner.metadata_schema_length,
+            )
+        }
+        .and_then(|s| std::str::from_utf8(s).ok())
+    }
+
+    /// Set the time units of the table collection.
     ///
-    /// Any errors from the C API propagate.
+    /// By convention, the units are a short name such as
+    /// `"generations"` or `"years"`.
     ///
-    /// # Example
+    /// # Errors
     ///
-    /// ```rust
-    /// # use tskit::TableAccess;
-    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
-    /// let mut migrations = tskit::OwnedMigrationTable::default();
-    /// migrations.add_row((0.25, 0.37), 1, (0, 1), 111.0).unwrap();
-    /// tables.set_migrations(&migrations).unwrap();
-    /// assert_eq!(tables.migrations().num_rows(), 1);
-    /// assert_eq!(tables.migrations().time(0).unwrap(), 111.0);
-    /// # migrations.clear().unwrap();
-    /// # assert_eq!(migrations.num_rows(), 0);
-    /// ```
-    pub fn set_migrations(&mut self, migrations: &crate::OwnedMigrationTable) -> TskReturnValue {
-        // SAFETY: neither self nor edges are possible
-        // to create with null pointers.
+    /// [`TskitError`] if the underlying C function returns an error.
+    pub fn set_time_units<S: AsRef<str>>(&mut self, units: S) -> TskReturnValue {
+        let units = units.as_ref();
         let rv = unsafe {
-            ll_bindings::tsk_migration_table_set_columns(
-                &mut (*self.inner).migrations,
-                (*migrations.as_ptr()).num_rows,
-                (*migrations.as_ptr()).left,
-                (*migrations.as_ptr()).right,
-                (*migrations.as_ptr()).node,
-                (*migrations.as_ptr()).source,
-                (*migrations.as_ptr()).dest,
-                (*migrations.as_ptr()).time,
-                (*migrations.as_ptr()).metadata,
-                (*migrations.as_ptr()).metadata_offset,
+            ll_bindings::tsk_table_collection_set_time_units(
+                self.as_mut_ptr(),
+                units.as_ptr().cast::<libc::c_char>(),
+                units.len() as ll_bindings::tsk_size_t,
             )
         };
         handle_tsk_return_value!(rv)
     }
 
-    /// Set the population table from an [`OwnedPopulationTable`](`crate::OwnedSiteTable`)
+    /// Return the time units of the table collection.
     ///
-    /// # Errors
+    /// Returns `None` if the time units are not set or
+    /// are not valid UTF-8.
+    pub fn time_units(&self) -> Option<&str> {
+        unsafe {
+            crate::metadata::char_array_to_slice(
+                self,
+                self.inner.time_units,
+                self.inner.time_units_length,
+            )
+        }
+        .and_then(|s| std::str::from_utf8(s).ok())
+    }
+
+    /// Return `time` with the time units of the table collection.
     ///
-    /// Any errors from the C API propagate.
+    /// See [`TimeWithUnits`](crate::TimeWithUnits).
+    pub fn time_with_units<T: Into<crate::Time>>(&self, time: T) -> crate::TimeWithUnits<'_> {
+        crate::TimeWithUnits::new(time, self.time_units())
+    }
+
+    /// Build the "input" and "output"
+    /// indexes for the edge table.
     ///
-    /// # Example
+    /// # Note
+    ///
+    /// The `C API` call behind this takes a `flags` argument
+    /// that is currently unused.  A future release may break `API`
+    /// here if the `C` library is updated to use flags.
+    pub fn build_index(&mut self) -> TskReturnValue {
+        let rv = unsafe { ll_bindings::tsk_table_collection_build_index(self.as_mut_ptr(), 0) };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Return `true` if tables are indexed.
+    pub fn is_indexed(&self) -> bool {
+        unsafe { ll_bindings::tsk_table_collection_has_index(self.as_ptr(), 0) }
+    }
+
+    /// Return `true` if tables are indexed.
+    ///
+    /// This is an alias for [`TableCollection::is_indexed`],
+    /// named after the `C API` function.
+    pub fn has_index(&self) -> bool {
+        self.is_indexed()
+    }
+
+    /// Drop the "input" and "output" indexes for the edge table.
+    ///
+    /// Adding or changing edges does not update the indexes,
+    /// leaving them out of date.
+    /// Dropping them records that
+    /// [`TableCollection::build_index`] must be called
+    /// before the tables are used as a tree sequence.
+    ///
+    /// # Examples
     ///
-    /// ```rust
-    /// # use tskit::TableAccess;
-    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
-    /// let mut populations = tskit::OwnedPopulationTable::default();
-    /// populations.add_row().unwrap();
-    /// tables.set_populations(&populations).unwrap();
-    /// assert_eq!(tables.populations().num_rows(), 1);
-    /// # populations.clear().unwrap();
-    /// # assert_eq!(populations.num_rows(), 0);
     /// ```
-    pub fn set_populations(&mut self, populations: &crate::OwnedPopulationTable) -> TskReturnValue {
-        // SAFETY: neither self nor edges are possible
-        // to create with null pointers.
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// tables.build_index().unwrap();
+    /// assert!(tables.has_index());
+    /// tables.drop_index().unwrap();
+    /// assert!(!tables.has_index());
+    /// assert!(tables.edge_insertion_order().is_none());
+    /// ```
+    ///
+    /// # Note
+    ///
+    /// The `C API` call behind this takes a `flags` argument
+    /// that is currently unused.
+    pub fn drop_index(&mut self) -> TskReturnValue {
+        let rv = unsafe { ll_bindings::tsk_table_collection_drop_index(self.as_mut_ptr(), 0) };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// If `self.is_indexed()` is `true`, return a non-owning
+    /// slice containing the edge insertion order.
+    /// Otherwise, return `None`.
+    pub fn edge_insertion_order(&self) -> Option<&[EdgeId]> {
+        if self.is_indexed() {
+            Some(unsafe {
+                std::slice::from_raw_parts(
+                    (*self.as_ptr()).indexes.edge_insertion_order as *const EdgeId,
+                    usize::try_from((*self.as_ptr()).indexes.num_edges).unwrap(),
+                )
+            })
+        } else {
+            None
+        }
+    }
+
+    /// If `self.is_indexed()` is `true`, return a non-owning
+    /// slice containing the edge removal order.
+    /// Otherwise, return `None`.
+    pub fn edge_removal_order(&self) -> Option<&[EdgeId]> {
+        if self.is_indexed() {
+            Some(unsafe {
+                std::slice::from_raw_parts(
+                    (*self.as_ptr()).indexes.edge_removal_order as *const EdgeId,
+                    usize::try_from((*self.as_ptr()).indexes.num_edges).unwrap(),
+                )
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Record the number of rows in each table.
+    ///
+    /// The result can be passed to [`TableCollection::truncate`]
+    /// to roll the tables back to their current state.
+    pub fn record_num_rows(&self) -> Bookmark {
+        let mut bookmark = Bookmark::new();
         let rv = unsafe {
-            ll_bindings::tsk_population_table_set_columns(
-                &mut (*self.inner).populations,
-                (*populations.as_ptr()).num_rows,
-                (*populations.as_ptr()).metadata,
-                (*populations.as_ptr()).metadata_offset,
-            )
+            ll_bindings::tsk_table_collection_record_num_rows(self.as_ptr(), &mut bookmark.offsets)
         };
-        handle_tsk_return_value!(rv)
+        assert_eq!(rv, 0);
+        bookmark
     }
 
-    #[cfg(any(doc, feature = "provenance"))]
-    /// Set the provenance table from an
-    /// [`OwnedProvenanceTable`](`crate::provenance::OwnedProvenanceTable`)
+    /// Truncate each table to the number of rows given
+    /// by the corresponding field of `bookmark`.
+    ///
+    /// Any table indexes are dropped.
     ///
     /// # Errors
     ///
-    /// Any errors from the C API propagate.
+    /// [`TskitError`] if any table has fewer rows than
+    /// requested by the `bookmark`.
     ///
-    /// # Example
+    /// # Examples
     ///
-    /// ```rust
-    /// # #[cfg(feature="provenance")] {
-    /// # use tskit::TableAccess;
-    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
-    /// let mut provenances = tskit::provenance::OwnedProvenanceTable::default();
-    /// provenances.add_row("I like pancakes").unwrap();
-    /// tables.set_provenances(&provenances).unwrap();
-    /// assert_eq!(tables.provenances().num_rows(), 1);
-    /// assert_eq!(tables.provenances().record(0).unwrap(), "I like pancakes");
-    /// # provenances.clear().unwrap();
-    /// # assert_eq!(provenances.num_rows(), 0);
-    /// # }
     /// ```
-    pub fn set_provenances(
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let bookmark = tables.record_num_rows();
+    /// tables.add_node(0, 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., 0, 1).unwrap();
+    /// tables.truncate(&bookmark).unwrap();
+    /// assert_eq!(tables.nodes().num_rows(), 1);
+    /// assert_eq!(tables.edges().num_rows(), 0);
+    /// ```
+    pub fn truncate(&mut self, bookmark: &Bookmark) -> TskReturnValue {
+        let mut offsets = bookmark.offsets;
+        let rv =
+            unsafe { ll_bindings::tsk_table_collection_truncate(self.as_mut_ptr(), &mut offsets) };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Sort the tables.  
+    /// The [``bookmark``](crate::types::Bookmark) can
+    /// be used to affect where sorting starts from for each table.
+    ///
+    /// # Details
+    ///
+    /// See [`full_sort`](crate::TableCollection::full_sort)
+    /// for more details about which tables are sorted.
+    ///
+    /// Only the `edges` and `migrations` offsets may start
+    /// sorting part way through a table.
+    /// The `sites` and `mutations` offsets must either both be zero,
+    /// or both equal the number of rows in their tables, in which case
+    /// those tables are not sorted.
+    /// The indexes are dropped.
+    ///
+    /// # Examples
+    ///
+    /// Sort only the edges added since the last sort,
+    /// as when simulating in epochs:
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// let parent = tables.add_node(0, 2.0, -1, -1).unwrap();
+    /// let child = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 10., parent, child).unwrap();
+    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
+    ///
+    /// let bookmark = tables.record_num_rows();
+    /// let grandchild = tables.add_node(0, 0.0, -1, -1).unwrap();
+    /// tables.add_edge(5., 10., child, grandchild).unwrap();
+    /// tables.add_edge(0., 5., child, grandchild).unwrap();
+    /// tables.sort(&bookmark, tskit::TableSortOptions::default()).unwrap();
+    /// assert_eq!(tables.edges().left(1).unwrap(), 0.);
+    /// assert_eq!(tables.edges().left(2).unwrap(), 5.);
+    /// ```
+    pub fn sort<O: Into<TableSortOptions>>(
         &mut self,
-        provenances: &crate::provenance::OwnedProvenanceTable,
+        start: &Bookmark,
+        options: O,
     ) -> TskReturnValue {
-        // SAFETY: neither self nor edges are possible
-        // to create with null pointers.
         let rv = unsafe {
-            ll_bindings::tsk_provenance_table_set_columns(
-                &mut (*self.inner).provenances,
-                (*provenances.as_ptr()).num_rows,
-                (*provenances.as_ptr()).timestamp,
-                (*provenances.as_ptr()).timestamp_offset,
-                (*provenances.as_ptr()).record,
-                (*provenances.as_ptr()).record_offset,
+            ll_bindings::tsk_table_collection_sort(
+                self.as_mut_ptr(),
+                &start.offsets,
+                options.into().bits(),
             )
         };
+
         handle_tsk_return_value!(rv)
     }
-}
 
-impl TableAccess for TableCollection {
-    fn edges(&self) -> EdgeTable {
-        EdgeTable::new_from_table(&(*self.inner).edges)
+    /// Fully sort all tables.
+    /// Implemented via a call to [``sort``](crate::TableCollection::sort).
+    ///
+    /// # Details
+    ///
+    /// This function only sorts the tables that have a strict sortedness
+    /// requirement according to the `tskit` [data
+    /// model](https://tskit.dev/tskit/docs/stable/data-model.html).
+    ///
+    /// These tables are:
+    ///
+    /// * edges
+    /// * mutations
+    /// * sites
+    ///
+    /// For some use cases it is desirable to have the individual table
+    /// sorted so that parents appear before offspring. See
+    /// [``topological_sort_individuals``](crate::TableCollection::topological_sort_individuals).
+    pub fn full_sort<O: Into<TableSortOptions>>(&mut self, options: O) -> TskReturnValue {
+        let b = Bookmark::new();
+        self.sort(&b, options)
     }
 
-    fn individuals(&self) -> IndividualTable {
-        IndividualTable::new_from_table(&(*self.inner).individuals)
+    /// Sort only the edge table.
+    ///
+    /// All other tables are left as they are.
+    /// Implemented via a call to [``sort``](crate::TableCollection::sort)
+    /// with a bookmark that skips all tables except the edges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let child = tables.add_node(0, 0.0, -1, -1).unwrap();
+    /// tables.add_site(7., None).unwrap();
+    /// tables.add_site(3., None).unwrap();
+    /// tables.add_edge(5., 10., parent, child).unwrap();
+    /// tables.add_edge(0., 5., parent, child).unwrap();
+    /// tables.sort_edges(tskit::TableSortOptions::default()).unwrap();
+    /// assert_eq!(tables.edges().left(0).unwrap(), 0.);
+    /// // The sites are still unsorted
+    /// assert_eq!(tables.sites().position(0).unwrap(), 7.);
+    /// ```
+    pub fn sort_edges<O: Into<TableSortOptions>>(&mut self, options: O) -> TskReturnValue {
+        let mut b = self.record_num_rows();
+        b.offsets.edges = 0;
+        self.sort(&b, options)
     }
 
-    fn migrations(&self) -> MigrationTable {
-        MigrationTable::new_from_table(&(*self.inner).migrations)
+    /// Sorts the individual table in place, so that parents come before children,
+    /// and the parent column is remapped as required. Node references to individuals
+    /// are also updated.
+    ///
+    /// This function is needed because neither [``sort``](crate::TableCollection::sort) nor
+    /// [``full_sort``](crate::TableCollection::full_sort) sorts
+    /// the individual table!
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Parent comes AFTER the child
+    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
+    /// let i0 = tables.add_individual(0, None, &[1]).unwrap();
+    /// assert_eq!(i0, 0);
+    /// let i1 = tables.add_individual(0, None, None).unwrap();
+    /// assert_eq!(i1, 1);
+    /// let n0 = tables.add_node(0, 0.0, -1, i1).unwrap();
+    /// assert_eq!(n0, 0);
+    /// let n1 = tables.add_node(0, 1.0, -1, i0).unwrap();
+    /// assert_eq!(n1, 1);
+    ///
+    /// // Testing for valid individual order will Err:
+    /// match tables.check_integrity(tskit::TableIntegrityCheckFlags::CHECK_INDIVIDUAL_ORDERING) {
+    ///     Ok(_) => panic!("expected Err"),
+    ///     Err(_) => (),
+    /// };
+    ///
+    /// // The standard sort doesn't fix the Err...:
+    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
+    /// match tables.check_integrity(tskit::TableIntegrityCheckFlags::CHECK_INDIVIDUAL_ORDERING) {
+    ///     Ok(_) => panic!("expected Err"),
+    ///     Err(_) => (),
+    /// };
+    ///
+    /// // ... so we need to intentionally sort the individuals.
+    /// let _ = tables.topological_sort_individuals(tskit::IndividualTableSortOptions::default()).unwrap();
+    /// tables.check_integrity(tskit::TableIntegrityCheckFlags::CHECK_INDIVIDUAL_ORDERING).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error code if the underlying `C` function returns an error.
+    pub fn topological_sort_individuals<O: Into<IndividualTableSortOptions>>(
+        &mut self,
+        options: O,
+    ) -> TskReturnValue {
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_individual_topological_sort(
+                self.as_mut_ptr(),
+                options.into().bits(),
+            )
+        };
+        handle_tsk_return_value!(rv)
     }
 
-    fn nodes(&self) -> NodeTable {
-        NodeTable::new_from_table(&(*self.inner).nodes)
-    }
+    /// Dump the table collection to file.
+    ///
+    /// # Panics
+    ///
+    /// This function allocates a `CString` to pass the file name to the C API.
+    /// A panic will occur if the system runs out of memory.
+    pub fn dump<O: Into<TableOutputOptions>>(&self, filename: &str, options: O) -> TskReturnValue {
+        let c_str = std::ffi::CString::new(filename).unwrap();
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_dump(
+                self.as_ptr(),
+                c_str.as_ptr(),
+                options.into().bits(),
+            )
+        };
 
-    fn sites(&self) -> SiteTable {
-        SiteTable::new_from_table(&(*self.inner).sites)
+        handle_tsk_return_value!(rv)
     }
 
-    fn mutations(&self) -> MutationTable {
-        MutationTable::new_from_table(&(*self.inner).mutations)
+    /// Write tables in the `tskit` text format.
+    ///
+    /// Each table for which `writers` holds a value is written
+    /// as tab-separated text with a header line.
+    /// Metadata are base64-encoded.
+    /// Bytes of states that cannot be written as is, such as tabs
+    /// or invalid `UTF-8`, are escaped as `\xHH`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, -1, -1).unwrap();
+    /// let mut writers = tskit::TextTables::<Vec<u8>> {
+    ///     nodes: Some(vec![]),
+    ///     ..Default::default()
+    /// };
+    /// tables.dump_text(&mut writers).unwrap();
+    /// let text = String::from_utf8(writers.nodes.unwrap()).unwrap();
+    /// assert_eq!(
+    ///     text,
+    ///     "id\tis_sample\ttime\tpopulation\tindividual\tmetadata\n0\t1\t0\t-1\t-1\t\n"
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IOError`] if writing fails.
+    pub fn dump_text<W: std::io::Write>(
+        &self,
+        writers: &mut crate::TextTables<W>,
+    ) -> Result<(), TskitError> {
+        crate::text_format::dump_text(self, writers)
+    }
+
+    /// Create a table collection from tables in the `tskit` text format.
+    ///
+    /// Columns are identified by the header line of each table,
+    /// and unknown columns are ignored.
+    /// If `sequence_length` is `0.0`, it is set to the largest
+    /// right coordinate of the edges and migrations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let nodes = "is_sample\ttime\n0\t1.0\n1\t0.0\n1\t0.0\n";
+    /// let edges = "left\tright\tparent\tchild\n0\t10\t0\t1\n0\t10\t0\t2\n";
+    /// let readers = tskit::TextTables {
+    ///     nodes: Some(nodes.as_bytes()),
+    ///     edges: Some(edges.as_bytes()),
+    ///     ..Default::default()
+    /// };
+    /// let tables = tskit::TableCollection::load_text(0., readers).unwrap();
+    /// assert_eq!(tables.sequence_length(), 10.);
+    /// assert_eq!(tables.edges().num_rows(), 2);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if a required column is missing,
+    ///   a value cannot be parsed, or no sequence length can be inferred.
+    /// * [`TskitError::IOError`] if reading fails.
+    /// * [`TskitError::ErrorCode`] if adding a row fails.
+    pub fn load_text<P: Into<Position>, R: std::io::BufRead>(
+        sequence_length: P,
+        readers: crate::TextTables<R>,
+    ) -> Result<Self, TskitError> {
+        crate::text_format::load_text(sequence_length.into(), readers)
+    }
+
+    /// Clear the contents of all tables.
+    /// Does not release memory.
+    /// Memory will be released when the object goes out
+    /// of scope.
+    pub fn clear<O: Into<TableClearOptions>>(&mut self, options: O) -> TskReturnValue {
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_clear(self.as_mut_ptr(), options.into().bits())
+        };
+
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Clear the contents of a single table.
+    /// Does not release memory, and the table's
+    /// metadata schema is kept.
+    ///
+    /// Other tables are not modified, so rows of other
+    /// tables referring to the cleared rows are left
+    /// dangling until they are cleared or updated as well.
+    /// Clearing the edge table drops the edge indexes.
+    ///
+    /// # Examples
+    ///
+    /// Removing all sites and mutations while keeping the topology:
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// use tskit::types::TableKind;
+    ///
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// let node = tables.add_node(0, 0.0, -1, -1).unwrap();
+    /// let site = tables.add_site(1., None).unwrap();
+    /// tables.add_mutation(site, node, -1, 0.0, None).unwrap();
+    ///
+    /// tables.clear_table(TableKind::Mutations).unwrap();
+    /// tables.clear_table(TableKind::Sites).unwrap();
+    /// assert_eq!(tables.sites().num_rows(), 0);
+    /// assert_eq!(tables.mutations().num_rows(), 0);
+    /// assert_eq!(tables.nodes().num_rows(), 1);
+    /// ```
+    pub fn clear_table(&mut self, table: TableKind) -> TskReturnValue {
+        let rv = unsafe {
+            match table {
+                TableKind::Nodes => ll_bindings::tsk_node_table_clear(&mut self.inner.nodes),
+                TableKind::Edges => ll_bindings::tsk_edge_table_clear(&mut self.inner.edges),
+                TableKind::Sites => ll_bindings::tsk_site_table_clear(&mut self.inner.sites),
+                TableKind::Mutations => {
+                    ll_bindings::tsk_mutation_table_clear(&mut self.inner.mutations)
+                }
+                TableKind::Migrations => {
+                    ll_bindings::tsk_migration_table_clear(&mut self.inner.migrations)
+                }
+                TableKind::Individuals => {
+                    ll_bindings::tsk_individual_table_clear(&mut self.inner.individuals)
+                }
+                TableKind::Populations => {
+                    ll_bindings::tsk_population_table_clear(&mut self.inner.populations)
+                }
+                TableKind::Provenances => {
+                    ll_bindings::tsk_provenance_table_clear(&mut self.inner.provenances)
+                }
+            }
+        };
+        if rv < 0 {
+            return Err(TskitError::ErrorCode { code: rv });
+        }
+        if table == TableKind::Edges {
+            let rv = unsafe { ll_bindings::tsk_table_collection_drop_index(self.as_mut_ptr(), 0) };
+            handle_tsk_return_value!(rv)
+        } else {
+            Ok(rv)
+        }
+    }
+
+    /// Free all memory allocated on the C side.
+    /// Not public b/c not very safe.
+    #[allow(dead_code)]
+    fn free(&mut self) -> TskReturnValue {
+        let rv = unsafe { ll_bindings::tsk_table_collection_free(self.as_mut_ptr()) };
+
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Return ``true`` if ``self`` contains the same
+    /// data as ``other``, and ``false`` otherwise.
+    pub fn equals<O: Into<TableEqualityOptions>>(
+        &self,
+        other: &TableCollection,
+        options: O,
+    ) -> bool {
+        unsafe {
+            ll_bindings::tsk_table_collection_equals(
+                self.as_ptr(),
+                other.as_ptr(),
+                options.into().bits(),
+            )
+        }
+    }
+
+    /// Return ``true`` if ``self`` contains the same
+    /// data as ``other``, comparing metadata through their schemas.
+    ///
+    /// [`equals`](TableCollection::equals) compares metadata byte by byte,
+    /// so metadata written by different tools may differ even if they
+    /// decode to the same values.
+    /// This function instead compares metadata schemas as JSON values
+    /// and, for tables whose schema uses the `json` codec, compares the
+    /// metadata of each row as JSON values.
+    /// Key order and formatting are therefore ignored, and empty metadata
+    /// equal an empty JSON object.
+    /// Metadata using any other codec, or of tables without a schema,
+    /// are compared byte by byte.
+    ///
+    /// The remaining data are compared by [`equals`](TableCollection::equals)
+    /// using `options`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let schema = r#"{"codec": "json"}"#;
+    /// let mut tables = tskit::TableCollection::new(1.).unwrap();
+    /// tables.set_population_metadata_schema(schema).unwrap();
+    /// tables
+    ///     .add_population_with_metadata(&tskit::metadata::Bytes::from(
+    ///         br#"{"a": 1, "b": 2}"#.to_vec(),
+    ///     ))
+    ///     .unwrap();
+    ///
+    /// let mut other = tskit::TableCollection::new(1.).unwrap();
+    /// other.set_population_metadata_schema(r#"{ "codec":"json" }"#).unwrap();
+    /// other
+    ///     .add_population_with_metadata(&tskit::metadata::Bytes::from(
+    ///         br#"{"b":2,"a":1}"#.to_vec(),
+    ///     ))
+    ///     .unwrap();
+    ///
+    /// assert!(!tables.equals(&other, tskit::TableEqualityOptions::default()));
+    /// assert!(tables.equals_normalized_metadata(&other, tskit::TableEqualityOptions::default()));
+    /// ```
+    #[cfg(feature = "serde_json")]
+    pub fn equals_normalized_metadata<O: Into<TableEqualityOptions>>(
+        &self,
+        other: &TableCollection,
+        options: O,
+    ) -> bool {
+        use crate::metadata_schema::{encoded_metadata_equal, schemas_equal, uses_json_codec};
+
+        let options = options.into();
+        if !self.equals(other, options | TableEqualityOptions::IGNORE_METADATA) {
+            return false;
+        }
+        if options.contains(TableEqualityOptions::IGNORE_METADATA) {
+            return true;
+        }
+
+        macro_rules! metadata_equal {
+            ($schema: expr, $other_schema: expr, $metadata: expr, $other_metadata: expr) => {{
+                let schema = $schema;
+                schemas_equal(schema, $other_schema)
+                    && encoded_metadata_equal(uses_json_codec(schema), $metadata, $other_metadata)
+            }};
+        }
+
+        if !options.contains(TableEqualityOptions::IGNORE_TS_METADATA)
+            && !metadata_equal!(
+                self.metadata_schema(),
+                other.metadata_schema(),
+                unsafe {
+                    crate::metadata::char_array_to_slice(
+                        self,
+                        self.inner.metadata,
+                        self.inner.metadata_length,
+                    )
+                },
+                unsafe {
+                    crate::metadata::char_array_to_slice(
+                        other,
+                        other.inner.metadata,
+                        other.inner.metadata_length,
+                    )
+                }
+            )
+        {
+            return false;
+        }
+
+        let (reference, other_reference) = (
+            &self.inner.reference_sequence,
+            &other.inner.reference_sequence,
+        );
+        fn as_str(s: Option<&[u8]>) -> Option<&str> {
+            s.and_then(|s| std::str::from_utf8(s).ok())
+        }
+        if !metadata_equal!(
+            as_str(unsafe {
+                crate::metadata::char_array_to_slice(
+                    self,
+                    reference.metadata_schema,
+                    reference.metadata_schema_length,
+                )
+            }),
+            as_str(unsafe {
+                crate::metadata::char_array_to_slice(
+                    other,
+                    other_reference.metadata_schema,
+                    other_reference.metadata_schema_length,
+                )
+            }),
+            unsafe {
+                crate::metadata::char_array_to_slice(
+                    self,
+                    reference.metadata,
+                    reference.metadata_length,
+                )
+            },
+            unsafe {
+                crate::metadata::char_array_to_slice(
+                    other,
+                    other_reference.metadata,
+                    other_reference.metadata_length,
+                )
+            }
+        ) {
+            return false;
+        }
+
+        macro_rules! table_metadata_equal {
+            ($table: ident) => {{
+                let (table, other_table) = (self.$table(), other.$table());
+                let schema = table.metadata_schema();
+                let json = uses_json_codec(schema);
+                // The tables have equal row counts, which fit in tsk_id_t.
+                let num_rows = tsk_id_t::try_from(table.num_rows()).unwrap();
+                schemas_equal(schema, other_table.metadata_schema())
+                    && (0..num_rows).all(|row| {
+                        // row is in range for both tables
+                        encoded_metadata_equal(
+                            json,
+                            table.metadata_bytes(row).unwrap(),
+                            other_table.metadata_bytes(row).unwrap(),
+                        )
+                    })
+            }};
+        }
+
+        table_metadata_equal!(nodes)
+            && table_metadata_equal!(edges)
+            && table_metadata_equal!(individuals)
+            && table_metadata_equal!(sites)
+            && table_metadata_equal!(mutations)
+            && table_metadata_equal!(migrations)
+            && table_metadata_equal!(populations)
+    }
+
+    /// Describe how ``self`` differs from ``other``.
+    ///
+    /// The comparison follows the same rules as
+    /// [`TableCollection::equals`], so the result is empty
+    /// if and only if `equals` returns ``true``.
+    /// Differences are reported table by table, in the order
+    /// that the `C` API compares them, and then row by row.
+    ///
+    /// See [`TableDifference`](crate::TableDifference) for an example.
+    pub fn diff<O: Into<TableEqualityOptions>>(
+        &self,
+        other: &TableCollection,
+        options: O,
+    ) -> Vec<crate::TableDifference> {
+        crate::table_diff::diff(
+            unsafe { &*self.as_ptr() },
+            unsafe { &*other.as_ptr() },
+            options.into().bits(),
+        )
+    }
+
+    /// Return a "deep" copy of the tables.
+    ///
+    /// Unlike [`Clone::clone`], errors are returned rather
+    /// than causing a panic.
+    pub fn deepcopy(&self) -> Result<TableCollection, TskitError> {
+        // The output is UNINITIALIZED tables,
+        // else we leak memory
+        let mut inner = uninit_table_collection();
+
+        let rv = unsafe { ll_bindings::tsk_table_collection_copy(self.as_ptr(), &mut *inner, 0) };
+
+        // SAFETY: we just initialized it.
+        // The C API doesn't free NULL pointers.
+        handle_tsk_return_value!(rv, unsafe { Self::new_from_mbox(inner) })
+    }
+
+    /// Return a [`crate::TreeSequence`] based on the tables.
+    /// This function will raise errors if tables are not sorted,
+    /// not indexed, or invalid in any way.
+    pub fn tree_sequence(
+        self,
+        flags: TreeSequenceFlags,
+    ) -> Result<crate::TreeSequence, TskitError> {
+        crate::TreeSequence::new(self, flags)
+    }
+
+    /// Simplify tables in place.
+    ///
+    /// # Parameters
+    ///
+    /// * `samples`: a slice containing non-null node ids.
+    ///   The tables are simplified with respect to the ancestry
+    ///   of these nodes.
+    /// * `options`: A [`SimplificationOptions`] bit field controlling
+    ///   the behavior of simplification.
+    /// * `idmap`: if `true`, the return value contains a vector equal
+    ///   in length to the input node table.  For each input node,
+    ///   this vector either contains the node's new index or [`NodeId::NULL`]
+    ///   if the input node is not part of the simplified history.
+    pub fn simplify<N: Into<NodeId>, O: Into<SimplificationOptions>>(
+        &mut self,
+        samples: &[N],
+        options: O,
+        idmap: bool,
+    ) -> Result<Option<Vec<NodeId>>, TskitError> {
+        let mut output_node_map: Vec<NodeId> = vec![];
+        if idmap {
+            output_node_map.resize(usize::try_from(self.nodes().num_rows())?, NodeId::NULL);
+        }
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_simplify(
+                self.as_mut_ptr(),
+                samples.as_ptr().cast::<tsk_id_t>(),
+                samples.len() as tsk_size_t,
+                options.into().bits(),
+                match idmap {
+                    true => output_node_map.as_mut_ptr().cast::<tsk_id_t>(),
+                    false => std::ptr::null_mut(),
+                },
+            )
+        };
+        handle_tsk_return_value!(
+            rv,
+            match idmap {
+                true => Some(output_node_map),
+                false => None,
+            }
+        )
+    }
+
+    /// Simplify tables in place, returning the id maps of all tables
+    /// that simplification may modify.
+    ///
+    /// The parameters have the same meaning as for
+    /// [`TableCollection::simplify`].
+    ///
+    /// # Note
+    ///
+    /// The `C API` only reports the node id map.
+    /// The remaining maps are obtained by first simplifying a copy of
+    /// the tables in which the metadata of sites, mutations,
+    /// individuals, and populations is replaced by each row's id.
+    /// This function therefore takes about twice as long and
+    /// requires about twice as much memory as [`TableCollection::simplify`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// let pop = tables.add_population().unwrap();
+    /// let unused_pop = tables.add_population().unwrap();
+    /// let parent = tables.add_node(0, 1.0, unused_pop, -1).unwrap();
+    /// let child = tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, pop, -1).unwrap();
+    /// tables.add_edge(0., 10., parent, child).unwrap();
+    /// let site = tables.add_site(5., None).unwrap();
+    /// tables.add_mutation(site, child, tskit::MutationId::NULL, 0.5, None).unwrap();
+    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
+    ///
+    /// let maps = tables
+    ///     .simplify_with_id_maps(&[child], tskit::SimplificationOptions::FILTER_POPULATIONS)
+    ///     .unwrap();
+    /// assert_eq!(maps.nodes, [tskit::NodeId::NULL, tskit::NodeId::from(0)]);
+    /// assert_eq!(maps.populations, [tskit::PopulationId::from(0), tskit::PopulationId::NULL]);
+    /// assert_eq!(maps.sites, [tskit::SiteId::from(0)]);
+    /// assert_eq!(maps.mutations, [tskit::MutationId::from(0)]);
+    /// ```
+    pub fn simplify_with_id_maps<N: Into<NodeId>, O: Into<SimplificationOptions>>(
+        &mut self,
+        samples: &[N],
+        options: O,
+    ) -> Result<SimplificationIdMaps, TskitError> {
+        let options = options.into();
+        let num_individuals = self.individuals().num_rows();
+        let num_populations = self.populations().num_rows();
+        let num_sites = self.sites().num_rows();
+        let num_mutations = self.mutations().num_rows();
+
+        let mut tagged = self.tagged_with_row_ids()?;
+        tagged.simplify(samples, options, false)?;
+        let nodes = self.simplify(samples, options, true)?.unwrap_or_default();
+
+        let output = unsafe { &*tagged.as_ptr() };
+        Ok(SimplificationIdMaps {
+            nodes,
+            individuals: id_map_from_tags(
+                table_column!(@bytes &output.individuals, metadata, output.individuals.metadata_length),
+                num_individuals,
+            )?,
+            populations: id_map_from_tags(
+                table_column!(@bytes &output.populations, metadata, output.populations.metadata_length),
+                num_populations,
+            )?,
+            sites: id_map_from_tags(
+                table_column!(@bytes &output.sites, metadata, output.sites.metadata_length),
+                num_sites,
+            )?,
+            mutations: id_map_from_tags(
+                table_column!(@bytes &output.mutations, metadata, output.mutations.metadata_length),
+                num_mutations,
+            )?,
+        })
+    }
+
+    // Return a copy of self in which the metadata of each row of the
+    // site, mutation, individual, and population tables is the row's id.
+    fn tagged_with_row_ids(&self) -> Result<TableCollection, TskitError> {
+        let mut tagged = self.deepcopy()?;
+        let input = unsafe { &*self.as_ptr() };
+        let output = unsafe { &mut *tagged.as_mut_ptr() };
+
+        // NOTE: the columns are copied from self into tagged,
+        // so the C API never reads from the table that it is writing to.
+        let tags = IdTags::new(input.sites.num_rows.into())?;
+        let rv = unsafe {
+            ll_bindings::tsk_site_table_set_columns(
+                &mut output.sites,
+                input.sites.num_rows,
+                input.sites.position,
+                input.sites.ancestral_state,
+                input.sites.ancestral_state_offset,
+                tags.tags(),
+                tags.offsets(),
+            )
+        };
+        if rv < 0 {
+            return Err(TskitError::ErrorCode { code: rv });
+        }
+
+        let tags = IdTags::new(input.mutations.num_rows.into())?;
+        let rv = unsafe {
+            ll_bindings::tsk_mutation_table_set_columns(
+                &mut output.mutations,
+                input.mutations.num_rows,
+                input.mutations.site,
+                input.mutations.node,
+                input.mutations.parent,
+                input.mutations.time,
+                input.mutations.derived_state,
+                input.mutations.derived_state_offset,
+                tags.tags(),
+                tags.offsets(),
+            )
+        };
+        if rv < 0 {
+            return Err(TskitError::ErrorCode { code: rv });
+        }
+
+        let tags = IdTags::new(input.individuals.num_rows.into())?;
+        let rv = unsafe {
+            ll_bindings::tsk_individual_table_set_columns(
+                &mut output.individuals,
+                input.individuals.num_rows,
+                input.individuals.flags,
+                input.individuals.location,
+                input.individuals.location_offset,
+                input.individuals.parents,
+                input.individuals.parents_offset,
+                tags.tags(),
+                tags.offsets(),
+            )
+        };
+        if rv < 0 {
+            return Err(TskitError::ErrorCode { code: rv });
+        }
+
+        let tags = IdTags::new(input.populations.num_rows.into())?;
+        let rv = unsafe {
+            ll_bindings::tsk_population_table_set_columns(
+                &mut output.populations,
+                input.populations.num_rows,
+                tags.tags(),
+                tags.offsets(),
+            )
+        };
+        if rv < 0 {
+            return Err(TskitError::ErrorCode { code: rv });
+        }
+
+        Ok(tagged)
+    }
+
+    /// Validate the contents of the table collection
+    ///
+    /// # Parameters
+    ///
+    /// `flags` is an instance of [`TableIntegrityCheckFlags`]
+    ///
+    /// # Return value
+    ///
+    /// `0` upon success, or an error code.
+    /// However, if `flags` contains [`TableIntegrityCheckFlags::CHECK_TREES`],
+    /// and no error is returned, then the return value is the number
+    /// of trees.
+    ///
+    /// # Note
+    ///
+    /// Creating a [`crate::TreeSequence`] from a table collection will automatically
+    /// run an integrity check.
+    /// See [`TableCollection::tree_sequence`].
+    ///
+    /// # Examples
+    ///
+    /// There are many ways for a table colletion to be invalid.
+    /// These examples are just the tip of the iceberg.
+    ///
+    /// ```should_panic
+    /// let mut tables = tskit::TableCollection::new(10.0).unwrap();
+    /// // Right position is > sequence_length
+    /// tables.add_edge(0.0, 11.0, 0, 0);
+    /// tables.check_integrity(tskit::TableIntegrityCheckFlags::default()).unwrap();
+    /// ```
+    ///
+    /// ```should_panic
+    /// # let mut tables = tskit::TableCollection::new(10.0).unwrap();
+    /// // Left position is < 0.0
+    /// tables.add_edge(-1., 10.0, 0, 0);
+    /// tables.check_integrity(tskit::TableIntegrityCheckFlags::default()).unwrap();
+    /// ```
+    ///
+    /// ```should_panic
+    /// # let mut tables = tskit::TableCollection::new(10.0).unwrap();
+    /// // Edges cannot have null node ids
+    /// tables.add_edge(0., 10.0, tskit::NodeId::NULL, 0);
+    /// tables.check_integrity(tskit::TableIntegrityCheckFlags::default()).unwrap();
+    /// ```
+    pub fn check_integrity(&self, flags: TableIntegrityCheckFlags) -> TskReturnValue {
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_check_integrity(self.as_ptr(), flags.bits())
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Run the same checks as [`TableCollection::check_integrity`],
+    /// reporting which table and row failed.
+    ///
+    /// # Return value
+    ///
+    /// The same value as [`TableCollection::check_integrity`] upon success,
+    /// or an [`IntegrityReport`](crate::IntegrityReport).
+    /// The table and row are `None` for failures that are not
+    /// caused by a single row, such as a bad sequence length or
+    /// [`TableIntegrityCheckFlags::CHECK_TREES`] failing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(10.0).unwrap();
+    /// tables.add_site(3.0, None).unwrap();
+    /// tables.add_site(11.0, None).unwrap();
+    /// let report = tables
+    ///     .check_integrity_report(tskit::TableIntegrityCheckFlags::default())
+    ///     .unwrap_err();
+    /// assert_eq!(report.table, Some(tskit::types::TableKind::Sites));
+    /// assert_eq!(report.row.map(u64::from), Some(1));
+    /// assert!(report.to_string().starts_with("site table, row 1:"));
+    /// ```
+    pub fn check_integrity_report(
+        &self,
+        flags: TableIntegrityCheckFlags,
+    ) -> Result<i32, crate::IntegrityReport> {
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_check_integrity(self.as_ptr(), flags.bits())
+        };
+        if rv < 0 {
+            Err(crate::integrity::integrity_report(
+                unsafe { &*self.as_ptr() },
+                flags.bits(),
+                rv,
+            ))
+        } else {
+            Ok(rv)
+        }
+    }
+
+    /// Compute the times of all mutations.
+    ///
+    /// Mutations are placed at evenly-spaced times along the edge
+    /// on which they occur, preserving the order of mutations at
+    /// each site.
+    /// Any existing mutation times are overwritten.
+    ///
+    /// # Note
+    ///
+    /// The tables must be sorted and indexed.
+    ///
+    /// The `C API` call behind this takes a `random` argument
+    /// intended to jitter the computed times.
+    /// The `C` library currently rejects any value other than `NULL`,
+    /// so random placement is not yet available.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// let parent = tables.add_node(0, 2.0, -1, -1).unwrap();
+    /// let child = tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 10., parent, child).unwrap();
+    /// let site = tables.add_site(5., None).unwrap();
+    /// let unknown = f64::from_bits(tskit::bindings::TSK_UNKNOWN_TIME_HEX);
+    /// let mutation = tables
+    ///     .add_mutation(site, child, tskit::MutationId::NULL, unknown, None)
+    ///     .unwrap();
+    /// tables.build_index().unwrap();
+    /// tables.compute_mutation_times().unwrap();
+    /// assert_eq!(tables.mutations().time(mutation).unwrap(), 1.0);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error code if the underlying `C` function returns an error.
+    pub fn compute_mutation_times(&mut self) -> TskReturnValue {
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_compute_mutation_times(
+                self.as_mut_ptr(),
+                std::ptr::null_mut(),
+                0,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Compute the `parent` column of the mutation table.
+    ///
+    /// Any existing values in the column are overwritten.
+    ///
+    /// # Note
+    ///
+    /// The tables must be sorted and indexed.
+    ///
+    /// The `options` argument is currently unused by the `C API`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// let parent = tables.add_node(0, 2.0, -1, -1).unwrap();
+    /// let child = tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 10., parent, child).unwrap();
+    /// let site = tables.add_site(5., None).unwrap();
+    /// let first = tables.add_mutation(site, parent, tskit::MutationId::NULL, 3.0, None).unwrap();
+    /// let second = tables.add_mutation(site, child, tskit::MutationId::NULL, 1.0, None).unwrap();
+    /// tables.build_index().unwrap();
+    /// tables.compute_mutation_parents(tskit::MutationParentsFlags::default()).unwrap();
+    /// assert_eq!(tables.mutations().parent(second).unwrap(), first);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error code if the underlying `C` function returns an error.
+    pub fn compute_mutation_parents<O: Into<MutationParentsFlags>>(
+        &mut self,
+        options: O,
+    ) -> TskReturnValue {
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_compute_mutation_parents(
+                self.as_mut_ptr(),
+                options.into().bits(),
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Modify the tables in place so that they contain only
+    /// the given nodes, in the given order.
+    ///
+    /// Edges, mutations, and migrations are retained if all of the
+    /// nodes they refer to are retained.
+    /// Unless [`SubsetFlags::KEEP_UNREFERENCED`] is given,
+    /// individuals, populations, and sites that are no longer
+    /// referenced are removed.
+    ///
+    /// Unlike [`TableCollection::simplify`], the tables need not be
+    /// sorted and no topology is simplified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let child = tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, -1, -1).unwrap();
+    /// let other = tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 10., parent, child).unwrap();
+    /// tables.add_edge(0., 10., parent, other).unwrap();
+    /// tables.subset(&[other, parent], tskit::SubsetFlags::default()).unwrap();
+    /// assert_eq!(tables.nodes().num_rows(), 2);
+    /// assert_eq!(tables.edges().num_rows(), 1);
+    /// assert_eq!(tables.edges().parent(0).unwrap(), 1);
+    /// assert_eq!(tables.edges().child(0).unwrap(), 0);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error code if the underlying `C` function returns an error,
+    /// such as when a node id is out of range.
+    pub fn subset<N: Into<NodeId> + Copy, O: Into<SubsetFlags>>(
+        &mut self,
+        nodes: &[N],
+        options: O,
+    ) -> TskReturnValue {
+        let nodes = nodes.iter().map(|&n| n.into()).collect::<Vec<NodeId>>();
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_subset(
+                self.as_mut_ptr(),
+                nodes.as_ptr().cast::<tsk_id_t>(),
+                SizeType::from(nodes.len()).into(),
+                options.into().bits(),
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Add the non-shared portions of `other` to `self`.
+    ///
+    /// # Parameters
+    ///
+    /// * `other`: the tables to merge into `self`.
+    /// * `other_node_mapping`: element `j` is the id of the node in `self`
+    ///   that is equivalent to node `j` of `other`, or [`NodeId::NULL`]
+    ///   if node `j` is new to `self`.
+    /// * `options`: see [`UnionFlags`].
+    ///
+    /// Nodes new to `self` are added along with their individuals,
+    /// the edges and mutations referring to them, and any new sites.
+    /// The result is sorted.
+    ///
+    /// # Note
+    ///
+    /// The `C` library does not support migrations here.
+    ///
+    /// # Examples
+    ///
+    /// Two replicates that share a burn-in:
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut burnin = tskit::TableCollection::new(10.).unwrap();
+    /// let pop = burnin.add_population().unwrap();
+    /// let root = burnin.add_node(0, 2.0, pop, -1).unwrap();
+    ///
+    /// let mut a = burnin.deepcopy().unwrap();
+    /// let child = a.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, pop, -1).unwrap();
+    /// a.add_edge(0., 10., root, child).unwrap();
+    ///
+    /// let mut b = burnin.deepcopy().unwrap();
+    /// let child = b.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, pop, -1).unwrap();
+    /// b.add_edge(0., 10., root, child).unwrap();
+    ///
+    /// let mapping = [root, tskit::NodeId::NULL];
+    /// a.union(&b, &mapping, tskit::UnionFlags::NO_ADD_POP).unwrap();
+    /// assert_eq!(a.nodes().num_rows(), 3);
+    /// assert_eq!(a.edges().num_rows(), 2);
+    /// assert_eq!(a.populations().num_rows(), 1);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if the length of `other_node_mapping`
+    ///   differs from the number of nodes in `other`.
+    /// * [`TskitError::ErrorCode`] if the underlying `C` function returns an error.
+    pub fn union<O: Into<UnionFlags>>(
+        &mut self,
+        other: &TableCollection,
+        other_node_mapping: &[NodeId],
+        options: O,
+    ) -> TskReturnValue {
+        if SizeType::from(other_node_mapping.len()) != other.nodes().num_rows() {
+            return Err(TskitError::ValueError {
+                got: format!("a node mapping of length {}", other_node_mapping.len()),
+                expected: format!("length {}", u64::from(other.nodes().num_rows())),
+            });
+        }
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_union(
+                self.as_mut_ptr(),
+                other.as_ptr(),
+                other_node_mapping.as_ptr().cast::<tsk_id_t>(),
+                options.into().bits(),
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Append the rows of all tables of `other` onto the tables of `self`.
+    ///
+    /// The node, individual, population, site, and mutation ids stored
+    /// in rows of `other` are offset by the number of rows of the
+    /// corresponding table of `self`, so that appended rows keep
+    /// referring to the same (appended) rows.
+    /// `NULL` ids are unchanged.
+    /// Provenance records are appended as they are.
+    ///
+    /// The tables are neither sorted nor indexed afterwards, and any
+    /// existing edge indexes are dropped.
+    ///
+    /// # Examples
+    ///
+    /// Combining two independent replicates:
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut a = tskit::TableCollection::new(10.).unwrap();
+    /// let pop = a.add_population().unwrap();
+    /// let parent = a.add_node(0, 1.0, pop, -1).unwrap();
+    /// let child = a.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, pop, -1).unwrap();
+    /// a.add_edge(0., 10., parent, child).unwrap();
+    /// let b = a.deepcopy().unwrap();
+    ///
+    /// a.append(&b).unwrap();
+    /// assert_eq!(a.nodes().num_rows(), 4);
+    /// assert_eq!(a.populations().num_rows(), 2);
+    /// assert_eq!(a.edges().parent(1).unwrap(), 2);
+    /// assert_eq!(a.edges().child(1).unwrap(), 3);
+    /// assert_eq!(a.nodes().population(3).unwrap(), 1);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if the sequence length of `other`
+    ///   is greater than that of `self`.
+    /// * [`TskitError::ErrorCode`] if the underlying `C` functions return an error,
+    ///   such as when offset ids overflow.
+    ///   In this case, `self` may have been partially modified.
+    pub fn append(&mut self, other: &TableCollection) -> TskReturnValue {
+        if other.sequence_length() > self.sequence_length() {
+            return Err(TskitError::ValueError {
+                got: format!("sequence length {}", other.sequence_length()),
+                expected: format!("at most {}", self.sequence_length()),
+            });
+        }
+        let o = unsafe { &*other.as_ptr() };
+        let offsets = self.record_num_rows().offsets;
+
+        macro_rules! check {
+            ($rv: expr) => {
+                let rv = $rv;
+                if rv < 0 {
+                    return Err(TskitError::ErrorCode { code: rv });
+                }
+            };
+        }
+
+        let parents = offset_ids(
+            table_column!(&o.individuals, parents, o.individuals.parents_length),
+            offsets.individuals,
+        )?;
+        check!(unsafe {
+            ll_bindings::tsk_individual_table_append_columns(
+                &mut self.inner.individuals,
+                o.individuals.num_rows,
+                o.individuals.flags,
+                o.individuals.location,
+                o.individuals.location_offset,
+                parents.as_ptr(),
+                o.individuals.parents_offset,
+                o.individuals.metadata,
+                o.individuals.metadata_offset,
+            )
+        });
+
+        let population = offset_ids(table_column!(&o.nodes, population), offsets.populations)?;
+        let individual = offset_ids(table_column!(&o.nodes, individual), offsets.individuals)?;
+        check!(unsafe {
+            ll_bindings::tsk_node_table_append_columns(
+                &mut self.inner.nodes,
+                o.nodes.num_rows,
+                o.nodes.flags,
+                o.nodes.time,
+                population.as_ptr(),
+                individual.as_ptr(),
+                o.nodes.metadata,
+                o.nodes.metadata_offset,
+            )
+        });
+
+        let parent = offset_ids(table_column!(&o.edges, parent), offsets.nodes)?;
+        let child = offset_ids(table_column!(&o.edges, child), offsets.nodes)?;
+        check!(unsafe {
+            ll_bindings::tsk_edge_table_append_columns(
+                &mut self.inner.edges,
+                o.edges.num_rows,
+                o.edges.left,
+                o.edges.right,
+                parent.as_ptr(),
+                child.as_ptr(),
+                o.edges.metadata,
+                o.edges.metadata_offset,
+            )
+        });
+
+        let node = offset_ids(table_column!(&o.migrations, node), offsets.nodes)?;
+        let source = offset_ids(table_column!(&o.migrations, source), offsets.populations)?;
+        let dest = offset_ids(table_column!(&o.migrations, dest), offsets.populations)?;
+        check!(unsafe {
+            ll_bindings::tsk_migration_table_append_columns(
+                &mut self.inner.migrations,
+                o.migrations.num_rows,
+                o.migrations.left,
+                o.migrations.right,
+                node.as_ptr(),
+                source.as_ptr(),
+                dest.as_ptr(),
+                o.migrations.time,
+                o.migrations.metadata,
+                o.migrations.metadata_offset,
+            )
+        });
+
+        check!(unsafe {
+            ll_bindings::tsk_site_table_append_columns(
+                &mut self.inner.sites,
+                o.sites.num_rows,
+                o.sites.position,
+                o.sites.ancestral_state,
+                o.sites.ancestral_state_offset,
+                o.sites.metadata,
+                o.sites.metadata_offset,
+            )
+        });
+
+        let site = offset_ids(table_column!(&o.mutations, site), offsets.sites)?;
+        let node = offset_ids(table_column!(&o.mutations, node), offsets.nodes)?;
+        let parent = offset_ids(table_column!(&o.mutations, parent), offsets.mutations)?;
+        check!(unsafe {
+            ll_bindings::tsk_mutation_table_append_columns(
+                &mut self.inner.mutations,
+                o.mutations.num_rows,
+                site.as_ptr(),
+                node.as_ptr(),
+                parent.as_ptr(),
+                o.mutations.time,
+                o.mutations.derived_state,
+                o.mutations.derived_state_offset,
+                o.mutations.metadata,
+                o.mutations.metadata_offset,
+            )
+        });
+
+        check!(unsafe {
+            ll_bindings::tsk_population_table_append_columns(
+                &mut self.inner.populations,
+                o.populations.num_rows,
+                o.populations.metadata,
+                o.populations.metadata_offset,
+            )
+        });
+
+        check!(unsafe {
+            ll_bindings::tsk_provenance_table_append_columns(
+                &mut self.inner.provenances,
+                o.provenances.num_rows,
+                o.provenances.timestamp,
+                o.provenances.timestamp_offset,
+                o.provenances.record,
+                o.provenances.record_offset,
+            )
+        });
+
+        let rv = unsafe { ll_bindings::tsk_table_collection_drop_index(self.as_mut_ptr(), 0) };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Obtain the edges that describe the ancestral paths
+    /// from `samples` to `ancestors`.
+    ///
+    /// In the output, each edge records that the "parent" ancestor
+    /// is the most recent node from `ancestors` on the path
+    /// from the "child" sample over the edge's interval.
+    /// Nodes in the path that are in neither set are skipped.
+    ///
+    /// # Note
+    ///
+    /// The tables must be sorted.
+    /// The edge table must not have metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// let grandparent = tables.add_node(0, 2.0, -1, -1).unwrap();
+    /// let parent = tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// let child = tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 10., grandparent, parent).unwrap();
+    /// tables.add_edge(0., 10., parent, child).unwrap();
+    /// tables.full_sort(tskit::TableSortOptions::default()).unwrap();
+    /// let edges = tables.link_ancestors(&[child], &[grandparent]).unwrap();
+    /// assert_eq!(edges.num_rows(), 1);
+    /// assert_eq!(edges.parent(0).unwrap(), grandparent);
+    /// assert_eq!(edges.child(0).unwrap(), child);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if any node id is out of range.
+    /// * [`TskitError::ErrorCode`] if the underlying `C` function returns an error.
+    pub fn link_ancestors<S: Into<NodeId> + Copy, A: Into<NodeId> + Copy>(
+        &self,
+        samples: &[S],
+        ancestors: &[A],
+    ) -> Result<crate::OwnedEdgeTable, TskitError> {
+        let num_nodes = self.nodes().num_rows();
+        let to_ids = |nodes: &[NodeId]| -> Result<Vec<tsk_id_t>, TskitError> {
+            nodes
+                .iter()
+                .map(|&n| {
+                    if n < 0 || n >= num_nodes {
+                        Err(TskitError::IndexError)
+                    } else {
+                        Ok(tsk_id_t::from(n))
+                    }
+                })
+                .collect()
+        };
+        let mut samples = to_ids(&samples.iter().map(|&n| n.into()).collect::<Vec<_>>())?;
+        let mut ancestors = to_ids(&ancestors.iter().map(|&n| n.into()).collect::<Vec<_>>())?;
+        let mut result = crate::OwnedEdgeTable::default();
+        // SAFETY: the C function does not modify the tables.
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_link_ancestors(
+                self.as_ptr() as *mut ll_bindings::tsk_table_collection_t,
+                samples.as_mut_ptr(),
+                SizeType::from(samples.len()).into(),
+                ancestors.as_mut_ptr(),
+                SizeType::from(ancestors.len()).into(),
+                0,
+                result.as_mut_ptr(),
+            )
+        };
+        handle_tsk_return_value!(rv, result)
+    }
+
+    /// Merge adjacent edges with the same parent and child.
+    ///
+    /// Two edges are adjacent if the right coordinate of one
+    /// equals the left coordinate of the other.
+    /// Each set of adjacent edges is replaced by a single edge.
+    ///
+    /// # Note
+    ///
+    /// The edge table is left sorted by parent, child, and left coordinate,
+    /// which is not the order required for a tree sequence.
+    /// The tables must be sorted before creating a tree sequence.
+    ///
+    /// Edges must not have metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// tables.add_edge(5., 10., 0, 1).unwrap();
+    /// tables.add_edge(0., 5., 0, 1).unwrap();
+    /// tables.squash_edges().unwrap();
+    /// assert_eq!(tables.edges().num_rows(), 1);
+    /// assert_eq!(tables.edges().left(0).unwrap(), 0.);
+    /// assert_eq!(tables.edges().right(0).unwrap(), 10.);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error code if the underlying `C` function returns an error.
+    pub fn squash_edges(&mut self) -> TskReturnValue {
+        let rv = unsafe { ll_bindings::tsk_edge_table_squash(&mut self.inner.edges) };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Put the tables into canonical form.
+    ///
+    /// Tables are sorted as by [`TableCollection::full_sort`],
+    /// and mutations and individuals are additionally sorted
+    /// into a canonical order.
+    /// Unless [`SubsetFlags::KEEP_UNREFERENCED`] is given,
+    /// unreferenced individuals, populations, and sites are removed.
+    ///
+    /// Table collections that are equal up to row ordering
+    /// compare equal after canonicalising.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut a = tskit::TableCollection::new(10.).unwrap();
+    /// a.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, -1, -1).unwrap();
+    /// a.add_site(7., None).unwrap();
+    /// a.add_site(3., None).unwrap();
+    /// let mut b = tskit::TableCollection::new(10.).unwrap();
+    /// b.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, -1, -1).unwrap();
+    /// b.add_site(3., None).unwrap();
+    /// b.add_site(7., None).unwrap();
+    /// assert!(!a.equals(&b, tskit::TableEqualityOptions::default()));
+    /// let flags = tskit::SubsetFlags::KEEP_UNREFERENCED;
+    /// a.canonicalise(flags).unwrap();
+    /// b.canonicalise(flags).unwrap();
+    /// assert!(a.equals(&b, tskit::TableEqualityOptions::default()));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error code if the underlying `C` function returns an error.
+    pub fn canonicalise<O: Into<SubsetFlags>>(&mut self, options: O) -> TskReturnValue {
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_canonicalise(self.as_mut_ptr(), options.into().bits())
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Remove sites with duplicate positions,
+    /// retaining only the first such site.
+    ///
+    /// Mutations at removed sites are reassigned to the
+    /// retained site at the same position.
+    ///
+    /// # Note
+    ///
+    /// The site table must be sorted by position.
+    /// Table collections assembled from multiple sources
+    /// should therefore be sorted first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(10.).unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, -1, -1).unwrap();
+    /// tables.add_site(5., None).unwrap();
+    /// tables.add_site(5., None).unwrap();
+    /// tables.add_mutation(1, 0, tskit::MutationId::NULL, 0.0, None).unwrap();
+    /// tables.deduplicate_sites().unwrap();
+    /// assert_eq!(tables.sites().num_rows(), 1);
+    /// assert_eq!(tables.mutations().site(0).unwrap(), 0);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an error code if the underlying `C` function returns an error.
+    pub fn deduplicate_sites(&mut self) -> TskReturnValue {
+        let rv =
+            unsafe { ll_bindings::tsk_table_collection_deduplicate_sites(self.as_mut_ptr(), 0) };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Keep only the parts of the genome within `intervals`.
+    ///
+    /// Edges and migrations are clipped to the intervals, and sites
+    /// outside of the intervals are removed along with their mutations.
+    /// The tables are then sorted.
+    ///
+    /// # Parameters
+    ///
+    /// * `intervals`: [`GenomicInterval`](crate::GenomicInterval)s or
+    ///   `(left, right)` pairs, sorted by position and not overlapping.
+    /// * `simplify`: if `true`, simplify the tables with respect to
+    ///   the current sample nodes.
+    /// * `record_provenance`: if `true`, add a provenance row recording
+    ///   the operation.
+    ///   Ignored unless the `provenance` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., 0, 1).unwrap();
+    /// tables.add_site(10., None).unwrap();
+    /// tables.add_site(60., None).unwrap();
+    /// tables.keep_intervals([(50., 75.)], false, false).unwrap();
+    /// assert_eq!(tables.edges().left(0).unwrap(), 50.);
+    /// assert_eq!(tables.edges().right(0).unwrap(), 75.);
+    /// assert_eq!(tables.sites().num_rows(), 1);
+    /// assert_eq!(tables.sites().position(0).unwrap(), 60.);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if the intervals are not sorted,
+    ///   overlap, or are not within the genome.
+    /// * [`TskitError`] if the tables fail
+    ///   [`TableCollection::check_integrity`] with default flags,
+    ///   or if sorting or simplifying the tables fails.
+    pub fn keep_intervals<I, T>(
+        &mut self,
+        intervals: I,
+        simplify: bool,
+        record_provenance: bool,
+    ) -> TskReturnValue
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<crate::GenomicInterval>,
+    {
+        let intervals = self.validate_intervals(intervals)?;
+        self.keep_validated_intervals(&intervals, simplify)?;
+        self.record_interval_provenance("keep_intervals", &intervals, simplify, record_provenance)
+    }
+
+    /// Remove the parts of the genome within `intervals`.
+    ///
+    /// This is the complement of [`TableCollection::keep_intervals`],
+    /// which documents the parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_node(0, 1.0, -1, -1).unwrap();
+    /// tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, -1, -1).unwrap();
+    /// tables.add_edge(0., 100., 0, 1).unwrap();
+    /// tables.delete_intervals([(0., 25.), (50., 100.)], false, false).unwrap();
+    /// assert_eq!(tables.edges().num_rows(), 1);
+    /// assert_eq!(tables.edges().left(0).unwrap(), 25.);
+    /// assert_eq!(tables.edges().right(0).unwrap(), 50.);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// See [`TableCollection::keep_intervals`].
+    pub fn delete_intervals<I, T>(
+        &mut self,
+        intervals: I,
+        simplify: bool,
+        record_provenance: bool,
+    ) -> TskReturnValue
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<crate::GenomicInterval>,
+    {
+        let intervals = self.validate_intervals(intervals)?;
+        let mut keep = vec![];
+        let mut last = 0.0;
+        for &(left, right) in &intervals {
+            if left > last {
+                keep.push((last, left));
+            }
+            last = right;
+        }
+        let sequence_length = f64::from(self.sequence_length());
+        if last < sequence_length {
+            keep.push((last, sequence_length));
+        }
+        self.keep_validated_intervals(&keep, simplify)?;
+        self.record_interval_provenance("delete_intervals", &intervals, simplify, record_provenance)
+    }
+
+    fn validate_intervals<I, T>(&self, intervals: I) -> Result<Vec<(f64, f64)>, TskitError>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<crate::GenomicInterval>,
+    {
+        let sequence_length = f64::from(self.sequence_length());
+        let mut last = 0.0;
+        let mut rv = vec![];
+        for interval in intervals {
+            let interval = interval.into();
+            let (left, right) = (f64::from(interval.left), f64::from(interval.right));
+            if !(left >= last && left < right && right <= sequence_length) {
+                return Err(TskitError::ValueError {
+                    got: format!("interval ({}, {})", left, right),
+                    expected: format!(
+                        "sorted, non-overlapping intervals within [0, {})",
+                        sequence_length
+                    ),
+                });
+            }
+            last = right;
+            rv.push((left, right));
+        }
+        Ok(rv)
+    }
+
+    fn keep_validated_intervals(
+        &mut self,
+        intervals: &[(f64, f64)],
+        simplify: bool,
+    ) -> TskReturnValue {
+        // The id maps below index with the site and parent columns.
+        self.check_integrity(TableIntegrityCheckFlags::default())?;
+        let mut edges = crate::OwnedEdgeTable::default();
+        let mut migrations = crate::OwnedMigrationTable::default();
+        let mut keep_sites = vec![false; usize::try_from(self.sites().num_rows())?];
+        for &(s, e) in intervals {
+            let overlaps = |left: &[Position], right: &[Position]| {
+                left.iter()
+                    .zip(right.iter())
+                    .enumerate()
+                    .filter(|(_, (&l, &r))| !(r <= s || l >= e))
+                    .map(|(i, _)| i as tsk_id_t)
+                    .collect::<Vec<_>>()
+            };
+            let rows = overlaps(self.edges().left_slice(), self.edges().right_slice());
+            let start = usize::try_from(edges.num_rows())?;
+            let rv = unsafe {
+                ll_bindings::tsk_edge_table_extend(
+                    edges.as_mut_ptr(),
+                    &self.inner.edges,
+                    rows.len() as tsk_size_t,
+                    rows.as_ptr(),
+                    0,
+                )
+            };
+            let _: TskReturnValue = handle_tsk_return_value!(rv);
+            let table = unsafe { &mut *edges.as_mut_ptr() };
+            for i in start..start + rows.len() {
+                unsafe {
+                    *table.left.add(i) = f64::max(s, *table.left.add(i));
+                    *table.right.add(i) = f64::min(e, *table.right.add(i));
+                }
+            }
+
+            let rows = overlaps(
+                self.migrations().left_slice(),
+                self.migrations().right_slice(),
+            );
+            let start = usize::try_from(migrations.num_rows())?;
+            let rv = unsafe {
+                ll_bindings::tsk_migration_table_extend(
+                    migrations.as_mut_ptr(),
+                    &self.inner.migrations,
+                    rows.len() as tsk_size_t,
+                    rows.as_ptr(),
+                    0,
+                )
+            };
+            let _: TskReturnValue = handle_tsk_return_value!(rv);
+            let table = unsafe { &mut *migrations.as_mut_ptr() };
+            for i in start..start + rows.len() {
+                unsafe {
+                    *table.left.add(i) = f64::max(s, *table.left.add(i));
+                    *table.right.add(i) = f64::min(e, *table.right.add(i));
+                }
+            }
+
+            for (keep, &p) in keep_sites.iter_mut().zip(self.sites().position_slice()) {
+                *keep |= p >= s && p < e;
+            }
+        }
+        self.set_edges(&edges)?;
+        self.set_migrations(&migrations)?;
+
+        let mut site_map = vec![SiteId::NULL; keep_sites.len()];
+        let mut kept_sites = vec![];
+        for (i, _) in keep_sites.iter().enumerate().filter(|(_, &k)| k) {
+            site_map[i] = SiteId::from(kept_sites.len() as tsk_id_t);
+            kept_sites.push(i as tsk_id_t);
+        }
+        let mutation_sites = self.mutations().site_slice().to_vec();
+        let mut mutation_map = vec![MutationId::NULL; mutation_sites.len()];
+        let mut kept_mutations = vec![];
+        for (i, site) in mutation_sites.iter().enumerate() {
+            if keep_sites[tsk_id_t::from(*site) as usize] {
+                mutation_map[i] = MutationId::from(kept_mutations.len() as tsk_id_t);
+                kept_mutations.push(i as tsk_id_t);
+            }
+        }
+
+        let mut sites = crate::OwnedSiteTable::default();
+        let rv = unsafe {
+            ll_bindings::tsk_site_table_extend(
+                sites.as_mut_ptr(),
+                &self.inner.sites,
+                kept_sites.len() as tsk_size_t,
+                kept_sites.as_ptr(),
+                0,
+            )
+        };
+        let _: TskReturnValue = handle_tsk_return_value!(rv);
+        let mut mutations = crate::OwnedMutationTable::default();
+        let rv = unsafe {
+            ll_bindings::tsk_mutation_table_extend(
+                mutations.as_mut_ptr(),
+                &self.inner.mutations,
+                kept_mutations.len() as tsk_size_t,
+                kept_mutations.as_ptr(),
+                0,
+            )
+        };
+        let _: TskReturnValue = handle_tsk_return_value!(rv);
+        let table = unsafe { &mut *mutations.as_mut_ptr() };
+        for i in 0..kept_mutations.len() {
+            unsafe {
+                let site = &mut *table.site.add(i);
+                *site = site_map[*site as usize].into();
+                let parent = &mut *table.parent.add(i);
+                if *parent != ll_bindings::TSK_NULL {
+                    *parent = mutation_map[*parent as usize].into();
+                }
+            }
+        }
+        self.set_sites(&sites)?;
+        self.set_mutations(&mutations)?;
+
+        self.full_sort(TableSortOptions::default())?;
+        if simplify {
+            let samples = self.nodes().samples_as_vector();
+            self.simplify(&samples, SimplificationOptions::default(), false)?;
+        }
+        Ok(0)
+    }
+
+    #[allow(unused_variables)]
+    fn record_interval_provenance(
+        &mut self,
+        command: &str,
+        intervals: &[(f64, f64)],
+        simplify: bool,
+        record_provenance: bool,
+    ) -> TskReturnValue {
+        #[cfg(feature = "provenance")]
+        if record_provenance {
+            let record = serde_json::json!({
+                "software": {"name": "tskit-rust", "version": env!("CARGO_PKG_VERSION")},
+                "parameters": {"command": command, "intervals": intervals, "simplify": simplify},
+            });
+            self.add_provenance(&record.to_string())?;
+        }
+        Ok(0)
+    }
+
+    #[cfg(any(feature = "provenance", doc))]
+    provenance_table_add_row!(
+    /// Add provenance record with a time stamp.
+    ///
+    /// All implementation of this trait provided by `tskit` use
+    /// an `ISO 8601` format time stamp
+    /// written using the [RFC 3339](https://tools.ietf.org/html/rfc3339)
+    /// specification, with microsecond precision as in the `Python` API.
+    /// This formatting approach has been the most straightforward method
+    /// for supporting round trips to/from a [`crate::provenance::ProvenanceTable`].
+    /// The implementations used here use the [`humantime`](https://docs.rs/humantime/latest/humantime/) crate.
+    ///
+    /// # Parameters
+    ///
+    /// * `record`: the provenance record
+    ///
+    /// # Examples
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    /// # #[cfg(feature = "provenance")] {
+    /// tables.add_provenance(&String::from("Some provenance")).unwrap();
+    ///
+    /// // Get reference to the table
+    /// let prov_ref = tables.provenances();
+    ///
+    /// // Get the first row
+    /// let row_0 = prov_ref.row(0).unwrap();
+    ///
+    /// assert_eq!(row_0.record, "Some provenance");
+    ///
+    /// // Get the first record
+    /// let record_0 = prov_ref.record(0).unwrap();
+    /// assert_eq!(record_0, row_0.record);
+    ///
+    /// // Get the first time stamp
+    /// let timestamp = prov_ref.timestamp(0).unwrap();
+    /// assert_eq!(timestamp, row_0.timestamp);
+    ///
+    /// // You can get the `humantime::Timestamp` object back from the `String`:
+    /// use core::str::FromStr;
+    /// let timestamp_string = humantime::Timestamp::from_str(&timestamp).unwrap();
+    ///
+    /// // Provenance transfers to the tree sequences
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::BUILD_INDEXES).unwrap();
+    /// assert_eq!(treeseq.provenances().record(0).unwrap(), "Some provenance");
+    /// // We can still compare to row_0 because it is a copy of the row data:
+    /// assert_eq!(treeseq.provenances().record(0).unwrap(), row_0.record);
+    /// # }
+    /// ```
+    => add_provenance, self, (*self.inner).provenances);
+
+    #[cfg(any(feature = "provenance", doc))]
+    provenance_table_add_row_with_timestamp!(
+    /// Add provenance record with a given time stamp.
+    ///
+    /// Unlike [`TableCollection::add_provenance`], the time stamp
+    /// is not generated.
+    /// It should be an `ISO 8601` time stamp, but this is not checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(1000.).unwrap();
+    /// # #[cfg(feature = "provenance")] {
+    /// tables
+    ///     .add_provenance_with_timestamp("2018-02-14T00:28:07.123456Z", "Some provenance")
+    ///     .unwrap();
+    /// let row = tables.provenances().row(0).unwrap();
+    /// assert_eq!(row.timestamp, "2018-02-14T00:28:07.123456Z");
+    /// assert_eq!(row.record, "Some provenance");
+    /// # }
+    /// ```
+    => add_provenance_with_timestamp, self, self.inner.provenances);
+
+    /// Set the edge table from an [`OwnedEdgeTable`](`crate::OwnedEdgeTable`)
+    ///
+    /// # Errors
+    ///
+    /// Any errors from the C API propagate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
+    /// let mut edges = tskit::OwnedEdgeTable::default();
+    /// edges.add_row(0., 1., 0, 12).unwrap();
+    /// tables.set_edges(&edges).unwrap();
+    /// assert_eq!(tables.edges().num_rows(), 1);
+    /// assert_eq!(tables.edges().child(0).unwrap(), 12);
+    /// # edges.clear().unwrap();
+    /// # assert_eq!(edges.num_rows(), 0);
+    /// ```
+    pub fn set_edges(&mut self, edges: &crate::OwnedEdgeTable) -> TskReturnValue {
+        // SAFETY: neither self nor edges are possible
+        // to create with null pointers.
+        let rv = unsafe {
+            ll_bindings::tsk_edge_table_set_columns(
+                &mut (*self.inner).edges,
+                (*edges.as_ptr()).num_rows,
+                (*edges.as_ptr()).left,
+                (*edges.as_ptr()).right,
+                (*edges.as_ptr()).parent,
+                (*edges.as_ptr()).child,
+                (*edges.as_ptr()).metadata,
+                (*edges.as_ptr()).metadata_offset,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Set the node table from an [`OwnedNodeTable`](`crate::OwnedNodeTable`)
+    ///
+    /// # Errors
+    ///
+    /// Any errors from the C API propagate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
+    /// let mut nodes = tskit::OwnedNodeTable::default();
+    /// nodes.add_row(0, 10.0, -1, -1).unwrap();
+    /// tables.set_nodes(&nodes).unwrap();
+    /// assert_eq!(tables.nodes().num_rows(), 1);
+    /// assert_eq!(tables.nodes().time(0).unwrap(), 10.0);
+    /// # nodes.clear().unwrap();
+    /// # assert_eq!(nodes.num_rows(), 0);
+    /// ```
+    pub fn set_nodes(&mut self, nodes: &crate::OwnedNodeTable) -> TskReturnValue {
+        // SAFETY: neither self nor nodes are possible
+        // to create with null pointers.
+        let rv = unsafe {
+            ll_bindings::tsk_node_table_set_columns(
+                &mut (*self.inner).nodes,
+                (*nodes.as_ptr()).num_rows,
+                (*nodes.as_ptr()).flags,
+                (*nodes.as_ptr()).time,
+                (*nodes.as_ptr()).population,
+                (*nodes.as_ptr()).individual,
+                (*nodes.as_ptr()).metadata,
+                (*nodes.as_ptr()).metadata_offset,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Set the site table from an [`OwnedSiteTable`](`crate::OwnedSiteTable`)
+    ///
+    /// # Errors
+    ///
+    /// Any errors from the C API propagate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
+    /// let mut sites = tskit::OwnedSiteTable::default();
+    /// sites.add_row(11.0, None).unwrap();
+    /// tables.set_sites(&sites).unwrap();
+    /// assert_eq!(tables.sites().num_rows(), 1);
+    /// assert_eq!(tables.sites().position(0).unwrap(), 11.0);
+    /// # sites.clear().unwrap();
+    /// # assert_eq!(sites.num_rows(), 0);
+    /// ```
+    pub fn set_sites(&mut self, sites: &crate::OwnedSiteTable) -> TskReturnValue {
+        // SAFETY: neither self nor nodes are possible
+        // to create with null pointers.
+        let rv = unsafe {
+            ll_bindings::tsk_site_table_set_columns(
+                &mut (*self.inner).sites,
+                (*sites.as_ptr()).num_rows,
+                (*sites.as_ptr()).position,
+                (*sites.as_ptr()).ancestral_state,
+                (*sites.as_ptr()).ancestral_state_offset,
+                (*sites.as_ptr()).metadata,
+                (*sites.as_ptr()).metadata_offset,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Set the mutation table from an [`OwnedMutationTable`](`crate::OwnedSiteTable`)
+    ///
+    /// # Errors
+    ///
+    /// Any errors from the C API propagate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
+    /// let mut mutations = tskit::OwnedMutationTable::default();
+    /// mutations.add_row(14, 12, -1, 11.3, None).unwrap();
+    /// tables.set_mutations(&mutations).unwrap();
+    /// assert_eq!(tables.mutations().num_rows(), 1);
+    /// assert_eq!(tables.mutations().site(0).unwrap(), 14);
+    /// # mutations.clear().unwrap();
+    /// # assert_eq!(mutations.num_rows(), 0);
+    /// ```
+    pub fn set_mutations(&mut self, mutations: &crate::OwnedMutationTable) -> TskReturnValue {
+        // SAFETY: neither self nor nodes are possible
+        // to create with null pointers.
+        let rv = unsafe {
+            ll_bindings::tsk_mutation_table_set_columns(
+                &mut (*self.inner).mutations,
+                (*mutations.as_ptr()).num_rows,
+                (*mutations.as_ptr()).site,
+                (*mutations.as_ptr()).node,
+                (*mutations.as_ptr()).parent,
+                (*mutations.as_ptr()).time,
+                (*mutations.as_ptr()).derived_state,
+                (*mutations.as_ptr()).derived_state_offset,
+                (*mutations.as_ptr()).metadata,
+                (*mutations.as_ptr()).metadata_offset,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Set the individual table from an [`OwnedIndividualTable`](`crate::OwnedSiteTable`)
+    ///
+    /// # Errors
+    ///
+    /// Any errors from the C API propagate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
+    /// let mut individuals = tskit::OwnedIndividualTable::default();
+    /// individuals.add_row(0, [0.1, 10.0], None).unwrap();
+    /// tables.set_individuals(&individuals).unwrap();
+    /// assert_eq!(tables.individuals().num_rows(), 1);
+    /// let expected = vec![tskit::Location::from(0.1), tskit::Location::from(10.0)];
+    /// assert_eq!(tables.individuals().location(0).unwrap(), Some(expected.as_slice()));
+    /// # individuals.clear().unwrap();
+    /// # assert_eq!(individuals.num_rows(), 0);
+    /// ```
+    pub fn set_individuals(&mut self, individuals: &crate::OwnedIndividualTable) -> TskReturnValue {
+        // SAFETY: neither self nor nodes are possible
+        // to create with null pointers.
+        let rv = unsafe {
+            ll_bindings::tsk_individual_table_set_columns(
+                &mut (*self.inner).individuals,
+                (*individuals.as_ptr()).num_rows,
+                (*individuals.as_ptr()).flags,
+                (*individuals.as_ptr()).location,
+                (*individuals.as_ptr()).location_offset,
+                (*individuals.as_ptr()).parents,
+                (*individuals.as_ptr()).parents_offset,
+                (*individuals.as_ptr()).metadata,
+                (*individuals.as_ptr()).metadata_offset,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Set the migration table from an [`OwnedMigrationTable`](`crate::OwnedSiteTable`)
+    ///
+    /// # Errors
+    ///
+    /// Any errors from the C API propagate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
+    /// let mut migrations = tskit::OwnedMigrationTable::default();
+    /// migrations.add_row((0.25, 0.37), 1, (0, 1), 111.0).unwrap();
+    /// tables.set_migrations(&migrations).unwrap();
+    /// assert_eq!(tables.migrations().num_rows(), 1);
+    /// assert_eq!(tables.migrations().time(0).unwrap(), 111.0);
+    /// # migrations.clear().unwrap();
+    /// # assert_eq!(migrations.num_rows(), 0);
+    /// ```
+    pub fn set_migrations(&mut self, migrations: &crate::OwnedMigrationTable) -> TskReturnValue {
+        // SAFETY: neither self nor edges are possible
+        // to create with null pointers.
+        let rv = unsafe {
+            ll_bindings::tsk_migration_table_set_columns(
+                &mut (*self.inner).migrations,
+                (*migrations.as_ptr()).num_rows,
+                (*migrations.as_ptr()).left,
+                (*migrations.as_ptr()).right,
+                (*migrations.as_ptr()).node,
+                (*migrations.as_ptr()).source,
+                (*migrations.as_ptr()).dest,
+                (*migrations.as_ptr()).time,
+                (*migrations.as_ptr()).metadata,
+                (*migrations.as_ptr()).metadata_offset,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    /// Set the population table from an [`OwnedPopulationTable`](`crate::OwnedSiteTable`)
+    ///
+    /// # Errors
+    ///
+    /// Any errors from the C API propagate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
+    /// let mut populations = tskit::OwnedPopulationTable::default();
+    /// populations.add_row().unwrap();
+    /// tables.set_populations(&populations).unwrap();
+    /// assert_eq!(tables.populations().num_rows(), 1);
+    /// # populations.clear().unwrap();
+    /// # assert_eq!(populations.num_rows(), 0);
+    /// ```
+    pub fn set_populations(&mut self, populations: &crate::OwnedPopulationTable) -> TskReturnValue {
+        // SAFETY: neither self nor edges are possible
+        // to create with null pointers.
+        let rv = unsafe {
+            ll_bindings::tsk_population_table_set_columns(
+                &mut (*self.inner).populations,
+                (*populations.as_ptr()).num_rows,
+                (*populations.as_ptr()).metadata,
+                (*populations.as_ptr()).metadata_offset,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+
+    table_collection_takeset_table!(
+    /// Replace the node table with an [`OwnedNodeTable`](`crate::OwnedNodeTable`),
+    /// taking ownership of its data.
+    ///
+    /// Unlike [`TableCollection::set_nodes`], no rows are copied,
+    /// including the metadata schema.
+    /// Tables can therefore be built in isolation and assembled into
+    /// a collection at no cost.
+    /// Any edge indexes are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Any errors from the C API propagate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
+    /// let mut nodes = tskit::OwnedNodeTable::default();
+    /// nodes.add_row(0, 10.0, -1, -1).unwrap();
+    /// tables.takeset_nodes(nodes).unwrap();
+    /// assert_eq!(tables.nodes().num_rows(), 1);
+    /// assert_eq!(tables.nodes().time(0).unwrap(), 10.0);
+    /// ```
+    => takeset_nodes, self, nodes, crate::OwnedNodeTable);
+
+    table_collection_takeset_table!(
+    /// Replace the edge table with an [`OwnedEdgeTable`](`crate::OwnedEdgeTable`),
+    /// taking ownership of its data.
+    ///
+    /// See [`TableCollection::takeset_nodes`].
+    => takeset_edges, self, edges, crate::OwnedEdgeTable);
+
+    table_collection_takeset_table!(
+    /// Replace the individual table with an [`OwnedIndividualTable`](`crate::OwnedIndividualTable`),
+    /// taking ownership of its data.
+    ///
+    /// See [`TableCollection::takeset_nodes`].
+    => takeset_individuals, self, individuals, crate::OwnedIndividualTable);
+
+    table_collection_takeset_table!(
+    /// Replace the site table with an [`OwnedSiteTable`](`crate::OwnedSiteTable`),
+    /// taking ownership of its data.
+    ///
+    /// See [`TableCollection::takeset_nodes`].
+    => takeset_sites, self, sites, crate::OwnedSiteTable);
+
+    table_collection_takeset_table!(
+    /// Replace the mutation table with an [`OwnedMutationTable`](`crate::OwnedMutationTable`),
+    /// taking ownership of its data.
+    ///
+    /// See [`TableCollection::takeset_nodes`].
+    => takeset_mutations, self, mutations, crate::OwnedMutationTable);
+
+    table_collection_takeset_table!(
+    /// Replace the migration table with an [`OwnedMigrationTable`](`crate::OwnedMigrationTable`),
+    /// taking ownership of its data.
+    ///
+    /// See [`TableCollection::takeset_nodes`].
+    => takeset_migrations, self, migrations, crate::OwnedMigrationTable);
+
+    table_collection_takeset_table!(
+    /// Replace the population table with an [`OwnedPopulationTable`](`crate::OwnedPopulationTable`),
+    /// taking ownership of its data.
+    ///
+    /// See [`TableCollection::takeset_nodes`].
+    => takeset_populations, self, populations, crate::OwnedPopulationTable);
+
+    #[cfg(any(doc, feature = "provenance"))]
+    table_collection_takeset_table!(
+    /// Replace the provenance table with an
+    /// [`OwnedProvenanceTable`](`crate::provenance::OwnedProvenanceTable`),
+    /// taking ownership of its data.
+    ///
+    /// See [`TableCollection::takeset_nodes`].
+    => takeset_provenances, self, provenances, crate::provenance::OwnedProvenanceTable);
+
+    #[cfg(any(doc, feature = "provenance"))]
+    /// Set the provenance table from an
+    /// [`OwnedProvenanceTable`](`crate::provenance::OwnedProvenanceTable`)
+    ///
+    /// # Errors
+    ///
+    /// Any errors from the C API propagate.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[cfg(feature="provenance")] {
+    /// # use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(1.0).unwrap();
+    /// let mut provenances = tskit::provenance::OwnedProvenanceTable::default();
+    /// provenances.add_row("I like pancakes").unwrap();
+    /// tables.set_provenances(&provenances).unwrap();
+    /// assert_eq!(tables.provenances().num_rows(), 1);
+    /// assert_eq!(tables.provenances().record(0).unwrap(), "I like pancakes");
+    /// # provenances.clear().unwrap();
+    /// # assert_eq!(provenances.num_rows(), 0);
+    /// # }
+    /// ```
+    pub fn set_provenances(
+        &mut self,
+        provenances: &crate::provenance::OwnedProvenanceTable,
+    ) -> TskReturnValue {
+        // SAFETY: neither self nor edges are possible
+        // to create with null pointers.
+        let rv = unsafe {
+            ll_bindings::tsk_provenance_table_set_columns(
+                &mut (*self.inner).provenances,
+                (*provenances.as_ptr()).num_rows,
+                (*provenances.as_ptr()).timestamp,
+                (*provenances.as_ptr()).timestamp_offset,
+                (*provenances.as_ptr()).record,
+                (*provenances.as_ptr()).record_offset,
+            )
+        };
+        handle_tsk_return_value!(rv)
+    }
+}
+
+impl TableAccess for TableCollection {
+    fn edges(&self) -> EdgeTable {
+        EdgeTable::new_from_table(&(*self.inner).edges)
+    }
+
+    fn individuals(&self) -> IndividualTable {
+        IndividualTable::new_from_table(&(*self.inner).individuals)
+    }
+
+    fn migrations(&self) -> MigrationTable {
+        MigrationTable::new_from_table(&(*self.inner).migrations)
+    }
+
+    fn nodes(&self) -> NodeTable {
+        NodeTable::new_from_table(&(*self.inner).nodes)
+    }
+
+    fn sites(&self) -> SiteTable {
+        SiteTable::new_from_table(&(*self.inner).sites)
+    }
+
+    fn mutations(&self) -> MutationTable {
+        MutationTable::new_from_table(&(*self.inner).mutations)
+    }
+
+    fn populations(&self) -> PopulationTable {
+        PopulationTable::new_from_table(&(*self.inner).populations)
+    }
+
+    #[cfg(any(feature = "provenance", doc))]
+    fn provenances(&self) -> crate::provenance::ProvenanceTable {
+        crate::provenance::ProvenanceTable::new_from_table(&(*self.inner).provenances)
+    }
+}
+
+impl_table_access_inherent!(TableCollection);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::metadata::*;
+    use crate::prelude::*;
+    use crate::NodeFlags;
+
+    fn make_small_table_collection() -> TableCollection {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables
+            .add_node(0, 1.0, PopulationId::NULL, IndividualId::NULL)
+            .unwrap();
+        tables
+            .add_node(0, 0.0, PopulationId::NULL, IndividualId::NULL)
+            .unwrap();
+        tables
+            .add_node(0, 0.0, PopulationId::NULL, IndividualId::NULL)
+            .unwrap();
+        tables.add_edge(0., 1000., 0, 1).unwrap();
+        tables.add_edge(0., 1000., 0, 2).unwrap();
+        tables.build_index().unwrap();
+        tables
+    }
+
+    #[test]
+    fn test_sequence_length() {
+        let tables = TableCollection::new(1000.).unwrap();
+        assert!(close_enough(tables.sequence_length(), 1000.));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_sequence_length() {
+        let _ = TableCollection::new(0.).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_negative_sequence_length() {
+        let _ = TableCollection::new(-1.).unwrap();
+    }
+
+    #[test]
+    fn test_add_edges() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        for i in 0..5 {
+            let _ = tables.add_edge(0., 1000., i, 2 * i).unwrap();
+        }
+        let edges = tables.edges();
+        for i in 0..5 {
+            assert_eq!(edges.parent(i).unwrap(), i);
+            assert_eq!(edges.child(i).unwrap(), 2 * i);
+        }
+    }
+
+    #[test]
+    fn test_mutable_node_access() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables.add_node(0, 1.0, -1, -1).unwrap();
+        for i in tables.node_flags_array_mut() {
+            *i = NodeFlags::from(1 << 16 | 1);
+        }
+
+        for t in tables.node_time_array_mut() {
+            *t = Time::from(-33.0);
+        }
+
+        for i in tables.nodes_iter() {
+            assert_eq!(i.flags.bits(), 1 << 16 | 1);
+            assert_eq!(f64::from(i.time) as i64, -33);
+        }
+    }
+
+    #[test]
+    fn test_undefined_reserved_flag_bits() {
+        let raw = NodeFlags::IS_SAMPLE.bits() | 1 << 3 | 1 << 20;
+        let mut tables = TableCollection::new(1000.).unwrap();
+        let rv = unsafe {
+            ll_bindings::tsk_node_table_add_row(
+                &mut (*tables.as_mut_ptr()).nodes,
+                raw,
+                0.0,
+                -1,
+                -1,
+                std::ptr::null(),
+                0,
+            )
+        };
+        assert_eq!(rv, 0);
+
+        // Conversions drop the bits that tskit does not define...
+        let flags = tables.nodes().flags(0).unwrap();
+        assert_eq!(flags, NodeFlags::IS_SAMPLE | NodeFlags::USER_BIT_20);
+        assert!(flags.is_valid());
+        assert_eq!(tables.nodes().row(0).unwrap().flags, flags);
+        assert_eq!(NodeFlags::from(raw), flags);
+        assert_eq!(IndividualFlags::from(1 << 5 | 1 << 16).bits(), 1 << 16);
+
+        // ... but the column slices show the stored values.
+        let stored = tables.nodes().flags_slice()[0];
+        assert_eq!(stored.bits(), raw);
+        assert!(!stored.is_valid());
+    }
+
+    #[test]
+    fn test_set_individual_metadata_keeps_columns() {
+        use crate::metadata::Bytes;
+        let mut individuals = OwnedIndividualTable::default();
+        let flags = IndividualFlags::from(1 << 16 | 1 << 20);
+        individuals
+            .add_row_with_metadata(flags, [0.5, 1.5], None, &Bytes::from(vec![1, 2, 3]))
+            .unwrap();
+        individuals.add_row(0, None, [0]).unwrap();
+        individuals.set_metadata(0, &Bytes::from(vec![4])).unwrap();
+        individuals.set_metadata(1, &Bytes::from(vec![5])).unwrap();
+        let row = individuals.row(0).unwrap();
+        assert_eq!(row.flags, flags);
+        assert_eq!(
+            row.location.unwrap(),
+            [Location::from(0.5), Location::from(1.5)]
+        );
+        assert_eq!(row.metadata, Some(vec![4]));
+        assert_eq!(
+            individuals.row(1).unwrap().parents.unwrap(),
+            [IndividualId::from(0)]
+        );
+        assert!(individuals.set_metadata(2, &Bytes::from(vec![6])).is_err());
+    }
+
+    #[test]
+    fn test_mutable_position_access() {
+        let mut tables = make_small_table_collection();
+        let before = tables.deepcopy().unwrap();
+        for x in tables.edge_left_array_mut() {
+            *x *= Position::from(2.);
+        }
+        for x in tables.edge_right_array_mut() {
+            *x *= Position::from(2.);
+        }
+        for (a, b) in tables.edges().iter().zip(before.edges().iter()) {
+            assert_eq!(a.left, b.left * Position::from(2.));
+            assert_eq!(a.right, b.right * Position::from(2.));
+        }
+    }
+
+    #[test]
+    fn test_mutable_access_to_empty_columns() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        assert!(tables.edge_left_array_mut().is_empty());
+        assert!(tables.node_flags_array_mut().is_empty());
+        assert!(tables.node_time_array_mut().is_empty());
+        let mut edges = OwnedEdgeTable::default();
+        assert!(edges.left_array_mut().is_empty());
+        assert!(edges.right_array_mut().is_empty());
+    }
+
+    #[test]
+    fn test_node_iteration() {
+        let tables = make_small_table_collection();
+        for (i, row) in tables.nodes().iter().enumerate() {
+            assert!(close_enough(
+                tables.nodes().time(i as tsk_id_t).unwrap(),
+                row.time
+            ));
+            assert_eq!(tables.nodes().flags(i as tsk_id_t).unwrap(), row.flags);
+            assert_eq!(
+                tables.nodes().population(i as tsk_id_t).unwrap(),
+                row.population
+            );
+            assert_eq!(
+                tables.nodes().individual(i as tsk_id_t).unwrap(),
+                row.individual
+            );
+            assert!(row.metadata.is_none());
+        }
+
+        for row in tables.nodes_iter() {
+            assert!(close_enough(tables.nodes().time(row.id).unwrap(), row.time));
+            assert_eq!(tables.nodes().flags(row.id).unwrap(), row.flags);
+            assert_eq!(tables.nodes().population(row.id).unwrap(), row.population);
+            assert_eq!(tables.nodes().individual(row.id).unwrap(), row.individual);
+            assert!(row.metadata.is_none());
+        }
+    }
+
+    #[test]
+    fn test_edge_iteration() {
+        let tables = make_small_table_collection();
+        for (i, row) in tables.edges().iter().enumerate() {
+            assert!(close_enough(
+                tables.edges().left(i as tsk_id_t).unwrap(),
+                row.left
+            ));
+            assert!(close_enough(
+                tables.edges().right(i as tsk_id_t).unwrap(),
+                row.right
+            ));
+            assert_eq!(tables.edges().parent(i as tsk_id_t).unwrap(), row.parent);
+            assert_eq!(tables.edges().child(i as tsk_id_t).unwrap(), row.child);
+            assert!(row.metadata.is_none());
+        }
+        for row in tables.edges_iter() {
+            assert!(close_enough(tables.edges().left(row.id).unwrap(), row.left));
+            assert!(close_enough(
+                tables.edges().right(row.id).unwrap(),
+                row.right
+            ));
+            assert_eq!(tables.edges().parent(row.id).unwrap(), row.parent);
+            assert_eq!(tables.edges().child(row.id).unwrap(), row.child);
+            assert!(row.metadata.is_none());
+        }
+    }
+
+    #[test]
+    fn test_edge_index_access() {
+        let tables = make_small_table_collection();
+        assert!(tables.is_indexed());
+        assert_eq!(
+            tables.edge_insertion_order().unwrap().len(),
+            usize::try_from(tables.edges().num_rows()).unwrap()
+        );
+        assert_eq!(
+            tables.edge_removal_order().unwrap().len(),
+            usize::try_from(tables.edges().num_rows()).unwrap()
+        );
+
+        for i in tables.edge_insertion_order().unwrap() {
+            assert!(*i >= 0);
+            assert!(*i < tables.edges().num_rows());
+        }
+
+        for i in tables.edge_removal_order().unwrap() {
+            assert!(*i >= 0);
+            assert!(*i < tables.edges().num_rows());
+        }
+
+        // The "transparent" casts are such black magic that we
+        // should probably test against what C thinks is going on :)
+        let input = unsafe {
+            std::slice::from_raw_parts(
+                (*tables.inner).indexes.edge_insertion_order,
+                (*tables.inner).indexes.num_edges as usize,
+            )
+        };
+
+        assert!(!input.is_empty());
+
+        let tables_input = tables.edge_insertion_order().unwrap();
+
+        assert_eq!(input.len(), tables_input.len());
+
+        for i in 0..input.len() {
+            assert_eq!(EdgeId::from(input[i]), tables_input[i]);
+        }
+
+        let output = unsafe {
+            std::slice::from_raw_parts(
+                (*tables.inner).indexes.edge_removal_order,
+                (*tables.inner).indexes.num_edges as usize,
+            )
+        };
+        assert!(!output.is_empty());
+
+        let tables_output = tables.edge_removal_order().unwrap();
+
+        assert_eq!(output.len(), tables_output.len());
+
+        for i in 0..output.len() {
+            assert_eq!(EdgeId::from(output[i]), tables_output[i]);
+        }
+    }
+
+    #[test]
+    fn test_add_site() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables.add_site(0.3, Some(b"Eggnog")).unwrap();
+        tables.add_site(0.5, None).unwrap(); // No ancestral_state specified!!!
+        let longer_metadata = "Hot Toddy";
+        tables
+            .add_site(0.9, Some(longer_metadata.as_bytes()))
+            .unwrap();
+
+        let sites = tables.sites();
+        assert!(close_enough(sites.position(0).unwrap(), 0.3));
+        assert!(close_enough(sites.position(1).unwrap(), 0.5));
+        assert!(close_enough(sites.position(2).unwrap(), 0.9));
+
+        match sites.ancestral_state(0).unwrap() {
+            Some(astate) => assert_eq!(astate, b"Eggnog"),
+            None => panic!(),
+        };
+
+        if sites.ancestral_state(1).unwrap().is_some() {
+            panic!()
+        }
+
+        match sites.ancestral_state(2).unwrap() {
+            Some(astate) => assert_eq!(astate, longer_metadata.as_bytes()),
+            None => panic!(),
+        };
+
+        // NOTE: this is a useful test as not all rows have ancestral_state
+        let mut no_anc_state = 0;
+        for (i, row) in sites.iter().enumerate() {
+            assert!(close_enough(
+                sites.position(i as tsk_id_t).unwrap(),
+                row.position
+            ));
+            if row.ancestral_state.is_some() {
+                if i == 0 {
+                    assert_eq!(row.ancestral_state.unwrap(), b"Eggnog");
+                } else if i == 2 {
+                    assert_eq!(row.ancestral_state.unwrap(), longer_metadata.as_bytes());
+                }
+            } else {
+                no_anc_state += 1;
+            }
+        }
+        assert_eq!(no_anc_state, 1);
+        no_anc_state = 0;
+        for row in tables.sites_iter() {
+            assert!(close_enough(sites.position(row.id).unwrap(), row.position));
+            if row.ancestral_state.is_some() {
+                if row.id == 0 {
+                    assert_eq!(row.ancestral_state.unwrap(), b"Eggnog");
+                } else if row.id == 2 {
+                    assert_eq!(row.ancestral_state.unwrap(), longer_metadata.as_bytes());
+                }
+            } else {
+                no_anc_state += 1;
+            }
+        }
+        assert_eq!(no_anc_state, 1);
     }
 
-    fn populations(&self) -> PopulationTable {
-        PopulationTable::new_from_table(&(*self.inner).populations)
+    fn close_enough<L: Into<f64>, R: Into<f64>>(a: L, b: R) -> bool {
+        (a.into() - b.into()).abs() < f64::EPSILON
     }
 
-    #[cfg(any(feature = "provenance", doc))]
-    fn provenances(&self) -> crate::provenance::ProvenanceTable {
-        crate::provenance::ProvenanceTable::new_from_table(&(*self.inner).provenances)
+    #[test]
+    fn test_add_mutation() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+
+        tables
+            .add_mutation(0, 0, MutationId::NULL, 1.123, Some(b"pajamas"))
+            .unwrap();
+        tables
+            .add_mutation(1, 1, MutationId::NULL, 2.123, None)
+            .unwrap();
+        tables
+            .add_mutation(2, 2, MutationId::NULL, 3.123, Some(b"more pajamas"))
+            .unwrap();
+        let mutations = tables.mutations();
+        assert!(close_enough(mutations.time(0).unwrap(), 1.123));
+        assert!(close_enough(mutations.time(1).unwrap(), 2.123));
+        assert!(close_enough(mutations.time(2).unwrap(), 3.123));
+        assert_eq!(mutations.node(0).unwrap(), 0);
+        assert_eq!(mutations.node(1).unwrap(), 1);
+        assert_eq!(mutations.node(2).unwrap(), 2);
+        assert_eq!(mutations.parent(0).unwrap(), MutationId::NULL);
+        assert_eq!(mutations.parent(1).unwrap(), MutationId::NULL);
+        assert_eq!(mutations.parent(2).unwrap(), MutationId::NULL);
+        assert_eq!(mutations.derived_state(0).unwrap().unwrap(), b"pajamas");
+
+        if mutations.derived_state(1).unwrap().is_some() {
+            panic!()
+        }
+
+        assert_eq!(
+            mutations.derived_state(2).unwrap().unwrap(),
+            b"more pajamas"
+        );
+
+        let mut nmuts = 0;
+        for (i, row) in tables.mutations().iter().enumerate() {
+            assert_eq!(row.site, tables.mutations().site(i as tsk_id_t).unwrap());
+            assert_eq!(row.node, tables.mutations().node(i as tsk_id_t).unwrap());
+            assert_eq!(
+                row.parent,
+                tables.mutations().parent(i as tsk_id_t).unwrap()
+            );
+            assert!(close_enough(
+                row.time,
+                tables.mutations().time(i as tsk_id_t).unwrap()
+            ));
+            assert!(row.metadata.is_none());
+            nmuts += 1;
+        }
+        assert_eq!(nmuts, tables.mutations().num_rows());
+        assert_eq!(nmuts, 3);
+
+        nmuts = 0;
+        for row in tables.mutations_iter() {
+            assert_eq!(row.site, tables.mutations().site(row.id).unwrap());
+            assert_eq!(row.node, tables.mutations().node(row.id).unwrap());
+            assert_eq!(row.parent, tables.mutations().parent(row.id).unwrap());
+            assert!(close_enough(
+                row.time,
+                tables.mutations().time(row.id).unwrap()
+            ));
+            assert!(row.metadata.is_none());
+            nmuts += 1;
+        }
+        assert_eq!(nmuts, tables.mutations().num_rows());
+        assert_eq!(nmuts, 3);
+        for row in tables.mutations().iter() {
+            assert!(row.metadata.is_none());
+        }
+
+        nmuts = 0;
+        for _ in tables.mutations().iter().skip(1) {
+            nmuts += 1;
+        }
+        assert_eq!(
+            crate::SizeType::try_from(nmuts + 1).unwrap(),
+            tables.mutations().num_rows()
+        );
     }
-}
 
-impl crate::traits::NodeListGenerator for TableCollection {}
+    struct F {
+        x: i32,
+        y: u32,
+    }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::metadata::*;
-    use crate::prelude::*;
-    use crate::NodeFlags;
+    impl MetadataRoundtrip for F {
+        fn encode(&self) -> Result<Vec<u8>, MetadataError> {
+            let mut rv = vec![];
+            rv.extend(self.x.to_le_bytes().iter().copied());
+            rv.extend(self.y.to_le_bytes().iter().copied());
+            Ok(rv)
+        }
+        fn decode(md: &[u8]) -> Result<Self, MetadataError> {
+            let (x_int_bytes, rest) = md.split_at(std::mem::size_of::<i32>());
+            let (y_int_bytes, _) = rest.split_at(std::mem::size_of::<u32>());
+            Ok(Self {
+                x: i32::from_le_bytes(x_int_bytes.try_into().unwrap()),
+                y: u32::from_le_bytes(y_int_bytes.try_into().unwrap()),
+            })
+        }
+    }
 
-    fn make_small_table_collection() -> TableCollection {
+    impl MutationMetadata for F {}
+
+    #[test]
+    fn test_add_mutation_with_metadata() {
         let mut tables = TableCollection::new(1000.).unwrap();
         tables
-            .add_node(0, 1.0, PopulationId::NULL, IndividualId::NULL)
+            .add_mutation_with_metadata(0, 0, MutationId::NULL, 1.123, None, &F { x: -3, y: 666 })
             .unwrap();
+        // The double unwrap is to first check for error
+        // and then to process the Option.
+        let md = tables.mutations().metadata::<F>(0).unwrap().unwrap();
+        assert_eq!(md.x, -3);
+        assert_eq!(md.y, 666);
+
+        for row in tables.mutations().iter() {
+            assert!(row.metadata.is_some());
+            let md = F::decode(&row.metadata.unwrap()).unwrap();
+            assert_eq!(md.x, -3);
+            assert_eq!(md.y, 666);
+        }
+    }
+
+    #[test]
+    fn test_add_mutation_with_metadata_for_some_columns() {
+        let mut tables = TableCollection::new(1000.).unwrap();
         tables
-            .add_node(0, 0.0, PopulationId::NULL, IndividualId::NULL)
+            .add_mutation_with_metadata(0, 0, MutationId::NULL, 1.123, None, &F { x: -3, y: 666 })
             .unwrap();
+
         tables
-            .add_node(0, 0.0, PopulationId::NULL, IndividualId::NULL)
+            .add_mutation(1, 2, MutationId::NULL, 2.0, None)
             .unwrap();
-        tables.add_edge(0., 1000., 0, 1).unwrap();
-        tables.add_edge(0., 1000., 0, 2).unwrap();
-        tables.build_index().unwrap();
+
+        let mut num_with_metadata = 0;
+        let mut num_without_metadata = 0;
+        for i in 0..usize::try_from(tables.mutations().num_rows()).unwrap() {
+            match tables.mutations().metadata::<F>(i as tsk_id_t).unwrap() {
+                Some(x) => {
+                    num_with_metadata += 1;
+                    assert_eq!(x.x, -3);
+                    assert_eq!(x.y, 666);
+                }
+                None => {
+                    num_without_metadata += 1;
+                }
+            }
+        }
+        assert_eq!(num_with_metadata, 1);
+        assert_eq!(num_without_metadata, 1);
+    }
+
+    #[test]
+    fn test_add_population() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        let pop_id = tables.add_population().unwrap();
+        assert_eq!(pop_id, 0);
+        assert_eq!(tables.populations().num_rows(), 1);
+
         tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, pop_id, IndividualId::NULL)
+            .unwrap();
+
+        match tables.nodes().row(NodeId::from(0)) {
+            Ok(x) => match x.population {
+                PopulationId(0) => (),
+                _ => panic!("expected PopulationId(0)"),
+            },
+            Err(_) => panic!("expected Ok(_)"),
+        };
+    }
+
+    #[test]
+    fn test_dump_tables() {
+        let treefile = "trees.trees";
+        let mut tables = TableCollection::new(1000.).unwrap();
+        let pop_id = tables.add_population().unwrap();
+        tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, pop_id, IndividualId::NULL)
+            .unwrap();
+        tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 1.0, pop_id, IndividualId::NULL)
+            .unwrap();
+        tables.add_edge(0., tables.sequence_length(), 1, 0).unwrap();
+        tables
+            .dump(treefile, TableOutputOptions::default())
+            .unwrap();
+
+        let tables2 = TableCollection::new_from_file(treefile).unwrap();
+        assert!(tables.equals(&tables2, TableEqualityOptions::default()));
+
+        std::fs::remove_file(&treefile).unwrap();
     }
 
     #[test]
-    fn test_sequence_length() {
-        let tables = TableCollection::new(1000.).unwrap();
-        assert!(close_enough(tables.sequence_length(), 1000.));
-    }
+    fn test_new_from_bytes() {
+        let treefile = "new_from_bytes.trees";
+        let tables = make_small_table_collection();
+        tables
+            .dump(treefile, TableOutputOptions::default())
+            .unwrap();
+        let bytes = std::fs::read(treefile).unwrap();
+        std::fs::remove_file(treefile).unwrap();
 
-    #[test]
-    #[should_panic]
-    fn test_zero_sequence_length() {
-        let _ = TableCollection::new(0.).unwrap();
+        let tables2 = TableCollection::new_from_bytes(&bytes).unwrap();
+        assert!(tables.equals(&tables2, TableEqualityOptions::default()));
+
+        let tables3 = TableCollection::new_from_reader(std::io::Cursor::new(&bytes)).unwrap();
+        assert!(tables.equals(&tables3, TableEqualityOptions::default()));
+
+        assert!(TableCollection::new_from_bytes(&[]).is_err());
     }
 
     #[test]
-    #[should_panic]
-    fn test_negative_sequence_length() {
-        let _ = TableCollection::new(-1.).unwrap();
+    fn test_nbytes() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        let empty = tables.nbytes();
+        assert_eq!(tables.edges().nbytes(), std::mem::size_of::<tsk_size_t>());
+
+        tables.add_edge(0., 1000., 0, 1).unwrap();
+        // left, right, parent, child, and one new metadata offset
+        let edge_bytes = 2 * std::mem::size_of::<f64>()
+            + 2 * std::mem::size_of::<tsk_id_t>()
+            + std::mem::size_of::<tsk_size_t>();
+        assert_eq!(tables.nbytes(), empty + edge_bytes);
+
+        let empty_sites = tables.sites().nbytes();
+        tables.add_site(1., Some(b"A")).unwrap();
+        // position, ancestral state, and one new offset for each ragged column
+        let site_bytes = std::mem::size_of::<f64>() + 1 + 2 * std::mem::size_of::<tsk_size_t>();
+        assert_eq!(tables.sites().nbytes(), empty_sites + site_bytes);
+        assert_eq!(tables.nbytes(), empty + edge_bytes + site_bytes);
     }
 
     #[test]
-    fn test_add_edges() {
+    fn test_clear() {
         let mut tables = TableCollection::new(1000.).unwrap();
         for i in 0..5 {
             let _ = tables.add_edge(0., 1000., i, 2 * i).unwrap();
         }
-        let edges = tables.edges();
-        for i in 0..5 {
-            assert_eq!(edges.parent(i).unwrap(), i);
-            assert_eq!(edges.child(i).unwrap(), 2 * i);
-        }
+        assert_eq!(tables.edges().num_rows(), 5);
+        tables.clear(TableClearOptions::default()).unwrap();
+        assert_eq!(tables.edges().num_rows(), 0);
     }
 
     #[test]
-    fn test_mutable_node_access() {
-        let tables = TableCollection::new(1000.).unwrap();
-        let mut nodes = tables.nodes();
-        let f = nodes.flags_array_mut();
-        for i in f {
-            *i = NodeFlags::from(11);
-        }
-
-        for t in nodes.time_array_mut() {
-            *t = Time::from(-33.0);
-        }
-
-        for i in tables.nodes_iter() {
-            assert_eq!(i.flags.bits(), 11);
-            assert_eq!(f64::from(i.time) as i64, -33);
-        }
+    fn test_free() {
+        let mut tables = TableCollection::new(1000.).unwrap();
+        tables.free().unwrap();
     }
 
     #[test]
-    fn test_node_iteration() {
+    fn test_deepcopy() {
         let tables = make_small_table_collection();
-        for (i, row) in tables.nodes().iter().enumerate() {
-            assert!(close_enough(
-                tables.nodes().time(i as tsk_id_t).unwrap(),
-                row.time
-            ));
-            assert_eq!(tables.nodes().flags(i as tsk_id_t).unwrap(), row.flags);
-            assert_eq!(
-                tables.nodes().population(i as tsk_id_t).unwrap(),
-                row.population
-            );
-            assert_eq!(
-                tables.nodes().individual(i as tsk_id_t).unwrap(),
-                row.individual
-            );
-            assert!(row.metadata.is_none());
-        }
-
-        for row in tables.nodes_iter() {
-            assert!(close_enough(tables.nodes().time(row.id).unwrap(), row.time));
-            assert_eq!(tables.nodes().flags(row.id).unwrap(), row.flags);
-            assert_eq!(tables.nodes().population(row.id).unwrap(), row.population);
-            assert_eq!(tables.nodes().individual(row.id).unwrap(), row.individual);
-            assert!(row.metadata.is_none());
-        }
+        let dumps = tables.deepcopy().unwrap();
+        assert!(tables.equals(&dumps, TableEqualityOptions::default()));
     }
 
     #[test]
-    fn test_edge_iteration() {
+    fn test_edge_table_row_equality() {
         let tables = make_small_table_collection();
-        for (i, row) in tables.edges().iter().enumerate() {
-            assert!(close_enough(
-                tables.edges().left(i as tsk_id_t).unwrap(),
-                row.left
-            ));
-            assert!(close_enough(
-                tables.edges().right(i as tsk_id_t).unwrap(),
-                row.right
-            ));
-            assert_eq!(tables.edges().parent(i as tsk_id_t).unwrap(), row.parent);
-            assert_eq!(tables.edges().child(i as tsk_id_t).unwrap(), row.child);
-            assert!(row.metadata.is_none());
-        }
-        for row in tables.edges_iter() {
-            assert!(close_enough(tables.edges().left(row.id).unwrap(), row.left));
-            assert!(close_enough(
-                tables.edges().right(row.id).unwrap(),
-                row.right
-            ));
-            assert_eq!(tables.edges().parent(row.id).unwrap(), row.parent);
-            assert_eq!(tables.edges().child(row.id).unwrap(), row.child);
-            assert!(row.metadata.is_none());
+        for (i, row) in tables.edges_iter().enumerate() {
+            assert!(row.id == i as tsk_id_t);
+            assert!(row == tables.edges().row(i as tsk_id_t).unwrap());
+            assert!(!(row != tables.edges().row(i as tsk_id_t).unwrap()));
+            if i > 0 {
+                assert!(row != tables.edges().row(i as tsk_id_t - 1).unwrap());
+            }
         }
     }
 
     #[test]
-    fn test_edge_index_access() {
+    fn test_node_table_row_equality() {
         let tables = make_small_table_collection();
-        assert!(tables.is_indexed());
-        assert_eq!(
-            tables.edge_insertion_order().unwrap().len(),
-            tables.edges().num_rows().try_into().unwrap()
-        );
-        assert_eq!(
-            tables.edge_removal_order().unwrap().len(),
-            tables.edges().num_rows().try_into().unwrap()
-        );
-
-        for i in tables.edge_insertion_order().unwrap() {
-            assert!(*i >= 0);
-            assert!(*i < tables.edges().num_rows());
+        for (i, row) in tables.nodes_iter().enumerate() {
+            assert!(row.id.0 == i as tsk_id_t);
+            assert!(row == tables.nodes().row(i as tsk_id_t).unwrap());
+            assert!(!(row != tables.nodes().row(i as tsk_id_t).unwrap()));
         }
+        assert!(tables.nodes().row(0).unwrap() != tables.nodes().row(1).unwrap());
+        assert!(tables.nodes().row(1).unwrap() != tables.nodes().row(2).unwrap());
+    }
 
-        for i in tables.edge_removal_order().unwrap() {
-            assert!(*i >= 0);
-            assert!(*i < tables.edges().num_rows());
+    #[test]
+    fn test_add_individual_many_ways() {
+        {
+            let mut tables = TableCollection::new(1.).unwrap();
+            let location = vec![0., 1., 2.];
+            let parents = [0, 1, 2, 3, 4];
+            tables.add_individual(0, location, parents).unwrap();
         }
-
-        // The "transparent" casts are such black magic that we
-        // should probably test against what C thinks is going on :)
-        let input = unsafe {
-            std::slice::from_raw_parts(
-                (*tables.inner).indexes.edge_insertion_order,
-                (*tables.inner).indexes.num_edges as usize,
-            )
-        };
-
-        assert!(!input.is_empty());
-
-        let tables_input = tables.edge_insertion_order().unwrap();
-
-        assert_eq!(input.len(), tables_input.len());
-
-        for i in 0..input.len() {
-            assert_eq!(EdgeId::from(input[i]), tables_input[i]);
+        {
+            let mut tables = TableCollection::new(1.).unwrap();
+            let location = vec![0., 1., 2.];
+            let parents = [0, 1, 2, 3, 4];
+            tables
+                .add_individual(0, location.as_slice(), parents.as_slice())
+                .unwrap();
         }
-
-        let output = unsafe {
-            std::slice::from_raw_parts(
-                (*tables.inner).indexes.edge_removal_order,
-                (*tables.inner).indexes.num_edges as usize,
-            )
-        };
-        assert!(!output.is_empty());
-
-        let tables_output = tables.edge_removal_order().unwrap();
-
-        assert_eq!(output.len(), tables_output.len());
-
-        for i in 0..output.len() {
-            assert_eq!(EdgeId::from(output[i]), tables_output[i]);
+        {
+            let mut tables = TableCollection::new(1.).unwrap();
+            let location = [0., 1., 2.];
+            let parents = vec![0, 1, 2, 3, 4];
+            tables.add_individual(0, location, parents).unwrap();
+        }
+        {
+            let mut tables = TableCollection::new(1.).unwrap();
+            let location = [0., 1., 2.];
+            let parents = vec![0, 1, 2, 3, 4];
+            tables
+                .add_individual(0, location.as_slice(), parents.as_slice())
+                .unwrap();
         }
     }
+}
 
-    #[test]
-    fn test_add_site() {
-        let mut tables = TableCollection::new(1000.).unwrap();
-        tables.add_site(0.3, Some(b"Eggnog")).unwrap();
-        tables.add_site(0.5, None).unwrap(); // No ancestral_state specified!!!
-        let longer_metadata = "Hot Toddy";
-        tables
-            .add_site(0.9, Some(longer_metadata.as_bytes()))
-            .unwrap();
+#[cfg(test)]
+mod test_subset {
+    use crate::*;
 
-        let sites = tables.sites();
-        assert!(close_enough(sites.position(0).unwrap(), 0.3));
-        assert!(close_enough(sites.position(1).unwrap(), 0.5));
-        assert!(close_enough(sites.position(2).unwrap(), 0.9));
+    #[test]
+    fn test_subset_populations() {
+        let mut tables = TableCollection::new(10.).unwrap();
+        let pop0 = tables.add_population().unwrap();
+        let pop1 = tables.add_population().unwrap();
+        tables.add_node(0, 0.0, pop0, -1).unwrap();
+        let n1 = tables.add_node(0, 0.0, pop1, -1).unwrap();
+        let mut copy = tables.deepcopy().unwrap();
+
+        tables.subset(&[n1], SubsetFlags::default()).unwrap();
+        assert_eq!(tables.populations().num_rows(), 1);
+        assert_eq!(tables.nodes().population(0).unwrap(), 0);
 
-        match sites.ancestral_state(0).unwrap() {
-            Some(astate) => assert_eq!(astate, b"Eggnog"),
-            None => panic!(),
-        };
+        copy.subset(&[n1], SubsetFlags::NO_CHANGE_POPULATIONS)
+            .unwrap();
+        assert_eq!(copy.populations().num_rows(), 2);
+        assert_eq!(copy.nodes().population(0).unwrap(), pop1);
 
-        if sites.ancestral_state(1).unwrap().is_some() {
-            panic!()
-        }
+        assert!(copy
+            .subset(&[NodeId::from(5)], SubsetFlags::default())
+            .is_err());
+    }
+}
 
-        match sites.ancestral_state(2).unwrap() {
-            Some(astate) => assert_eq!(astate, longer_metadata.as_bytes()),
-            None => panic!(),
-        };
+#[cfg(test)]
+mod test_union {
+    use crate::*;
 
-        // NOTE: this is a useful test as not all rows have ancestral_state
-        let mut no_anc_state = 0;
-        for (i, row) in sites.iter().enumerate() {
-            assert!(close_enough(
-                sites.position(i as tsk_id_t).unwrap(),
-                row.position
-            ));
-            if row.ancestral_state.is_some() {
-                if i == 0 {
-                    assert_eq!(row.ancestral_state.unwrap(), b"Eggnog");
-                } else if i == 2 {
-                    assert_eq!(row.ancestral_state.unwrap(), longer_metadata.as_bytes());
-                }
-            } else {
-                no_anc_state += 1;
-            }
-        }
-        assert_eq!(no_anc_state, 1);
-        no_anc_state = 0;
-        for row in tables.sites_iter() {
-            assert!(close_enough(sites.position(row.id).unwrap(), row.position));
-            if row.ancestral_state.is_some() {
-                if row.id == 0 {
-                    assert_eq!(row.ancestral_state.unwrap(), b"Eggnog");
-                } else if row.id == 2 {
-                    assert_eq!(row.ancestral_state.unwrap(), longer_metadata.as_bytes());
-                }
-            } else {
-                no_anc_state += 1;
-            }
-        }
-        assert_eq!(no_anc_state, 1);
+    #[test]
+    fn test_union_errors() {
+        let mut tables = TableCollection::new(10.).unwrap();
+        tables.add_node(0, 1.0, -1, -1).unwrap();
+        let mut other = TableCollection::new(10.).unwrap();
+        other.add_node(0, 2.0, -1, -1).unwrap();
+        assert!(matches!(
+            tables.union(&other, &[], UnionFlags::default()),
+            Err(TskitError::ValueError { .. })
+        ));
+        // The shared node differs in time
+        assert!(tables
+            .union(&other, &[NodeId::from(0)], UnionFlags::default())
+            .is_err());
+        tables
+            .union(&other, &[NodeId::from(0)], UnionFlags::NO_CHECK_SHARED)
+            .unwrap();
+        assert_eq!(tables.nodes().num_rows(), 1);
     }
+}
 
-    fn close_enough<L: Into<f64>, R: Into<f64>>(a: L, b: R) -> bool {
-        (a.into() - b.into()).abs() < f64::EPSILON
+#[cfg(test)]
+mod test_link_ancestors {
+    use crate::test_fixtures::make_small_table_collection_two_trees;
+    use crate::*;
+
+    #[test]
+    fn test_link_ancestors_two_trees() {
+        let tables = make_small_table_collection_two_trees();
+        let edges = tables.link_ancestors(&[2, 3, 4, 5], &[0]).unwrap();
+        // Every sample links to the root over the full sequence
+        for row in edges.iter() {
+            assert_eq!(row.parent, 0);
+        }
+        let mut children = edges.child_slice().to_vec();
+        children.sort();
+        children.dedup();
+        assert_eq!(children, vec![2, 3, 4, 5]);
+
+        assert!(matches!(
+            tables.link_ancestors(&[6], &[0]),
+            Err(TskitError::IndexError)
+        ));
     }
+}
+
+#[cfg(test)]
+mod test_truncate {
+    use crate::*;
 
     #[test]
-    fn test_add_mutation() {
-        let mut tables = TableCollection::new(1000.).unwrap();
+    fn test_truncate() {
+        let mut tables = TableCollection::new(10.).unwrap();
+        tables.add_node(0, 1.0, -1, -1).unwrap();
+        tables.add_site(1., None).unwrap();
+        let bookmark = tables.record_num_rows();
+        assert_eq!(bookmark.offsets.nodes, 1);
+        assert_eq!(bookmark.offsets.sites, 1);
+        tables.add_node(0, 0.0, -1, -1).unwrap();
+        tables.add_edge(0., 10., 0, 1).unwrap();
+        tables.add_mutation(0, 1, -1, 0.5, None).unwrap();
+        tables.truncate(&bookmark).unwrap();
+        assert_eq!(tables.nodes().num_rows(), 1);
+        assert_eq!(tables.edges().num_rows(), 0);
+        assert_eq!(tables.sites().num_rows(), 1);
+        assert_eq!(tables.mutations().num_rows(), 0);
+
+        let mut too_many = tables.record_num_rows();
+        too_many.offsets.nodes = 2;
+        assert!(tables.truncate(&too_many).is_err());
+
+        let mut edges = OwnedEdgeTable::default();
+        edges.add_row(0., 1., 0, 1).unwrap();
+        edges.add_row(1., 2., 0, 1).unwrap();
+        edges.truncate(1_u64).unwrap();
+        assert_eq!(edges.num_rows(), 1);
+        assert!(edges.truncate(2_u64).is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_intervals {
+    use crate::*;
 
+    fn make_tables() -> TableCollection {
+        let mut tables = TableCollection::new(100.).unwrap();
+        tables.add_population().unwrap();
+        tables.add_node(0, 2.0, 0, -1).unwrap();
+        tables.add_node(0, 1.0, 0, -1).unwrap();
+        tables.add_node(TSK_NODE_IS_SAMPLE, 0.0, 0, -1).unwrap();
+        tables.add_node(TSK_NODE_IS_SAMPLE, 0.0, 0, -1).unwrap();
+        tables.add_edge(0., 100., 1, 2).unwrap();
+        tables.add_edge(0., 40., 1, 3).unwrap();
+        tables.add_edge(40., 100., 0, 3).unwrap();
+        tables.add_edge(0., 100., 0, 1).unwrap();
+        tables.add_migration((20., 60.), 1, (0, 0), 1.5).unwrap();
+        for position in [10., 30., 50., 70.] {
+            tables.add_site(position, Some(b"A")).unwrap();
+        }
+        tables.add_mutation(1, 2, -1, 0.5, Some(b"G")).unwrap();
+        tables.add_mutation(1, 2, 0, 0.25, Some(b"T")).unwrap();
+        tables.add_mutation(2, 3, -1, 0.5, Some(b"C")).unwrap();
+        tables.add_mutation(3, 3, -1, 0.5, Some(b"C")).unwrap();
         tables
-            .add_mutation(0, 0, MutationId::NULL, 1.123, Some(b"pajamas"))
+    }
+
+    #[test]
+    fn test_keep_intervals() {
+        let mut tables = make_tables();
+        tables
+            .keep_intervals([(25., 35.), (45., 55.)], false, false)
             .unwrap();
+        assert_eq!(tables.edges().num_rows(), 6);
+        for row in tables.edges().iter() {
+            assert!((row.left >= 25. && row.right <= 35.) || (row.left >= 45. && row.right <= 55.));
+        }
+        assert_eq!(tables.migrations().num_rows(), 2);
+        assert_eq!(tables.sites().position_slice(), &[30., 50.]);
+        assert_eq!(tables.mutations().num_rows(), 3);
+        assert_eq!(tables.mutations().site_slice(), &[0, 0, 1]);
+        assert_eq!(tables.mutations().parent(1).unwrap(), 0);
         tables
-            .add_mutation(1, 1, MutationId::NULL, 2.123, None)
+            .check_integrity(TableIntegrityCheckFlags::CHECK_TREES)
+            .unwrap_err();
+        tables.build_index().unwrap();
+        tables
+            .check_integrity(TableIntegrityCheckFlags::CHECK_TREES)
             .unwrap();
+    }
+
+    #[test]
+    fn test_delete_intervals_and_simplify() {
+        let mut tables = make_tables();
+        // Simplification does not support migrations.
+        assert!(tables.delete_intervals([(0., 50.)], true, false).is_err());
+        let mut tables = make_tables();
         tables
-            .add_mutation(2, 2, MutationId::NULL, 3.123, Some(b"more pajamas"))
+            .set_migrations(&OwnedMigrationTable::default())
             .unwrap();
-        let mutations = tables.mutations();
-        assert!(close_enough(mutations.time(0).unwrap(), 1.123));
-        assert!(close_enough(mutations.time(1).unwrap(), 2.123));
-        assert!(close_enough(mutations.time(2).unwrap(), 3.123));
-        assert_eq!(mutations.node(0).unwrap(), 0);
-        assert_eq!(mutations.node(1).unwrap(), 1);
-        assert_eq!(mutations.node(2).unwrap(), 2);
-        assert_eq!(mutations.parent(0).unwrap(), MutationId::NULL);
-        assert_eq!(mutations.parent(1).unwrap(), MutationId::NULL);
-        assert_eq!(mutations.parent(2).unwrap(), MutationId::NULL);
-        assert_eq!(mutations.derived_state(0).unwrap().unwrap(), b"pajamas");
+        tables.delete_intervals([(0., 50.)], true, false).unwrap();
+        assert_eq!(tables.sequence_length(), 100.);
+        // Node 1 is unary on [50, 100) and is simplified away.
+        assert_eq!(tables.nodes().num_rows(), 3);
+        assert_eq!(tables.edges().num_rows(), 2);
+        assert_eq!(tables.sites().position_slice(), &[50., 70.]);
+        assert_eq!(tables.mutations().num_rows(), 2);
+    }
 
-        if mutations.derived_state(1).unwrap().is_some() {
-            panic!()
+    #[test]
+    fn test_bad_intervals() {
+        let mut tables = make_tables();
+        for intervals in [
+            vec![(10., 5.)],
+            vec![(-1., 5.)],
+            vec![(10., 101.)],
+            vec![(10., 20.), (15., 30.)],
+            vec![(10., 20.), (0., 5.)],
+        ] {
+            assert!(matches!(
+                tables.keep_intervals(intervals, false, false),
+                Err(TskitError::ValueError { .. })
+            ));
         }
+        assert_eq!(tables.edges().num_rows(), 4);
+    }
+
+    #[test]
+    fn test_intervals_with_bad_ids() {
+        let mut tables = make_tables();
+        tables.add_mutation(10, 3, -1, 0.5, Some(b"C")).unwrap();
+        assert!(tables.keep_intervals([(25., 35.)], false, false).is_err());
+        let mut tables = make_tables();
+        tables.add_mutation(0, 3, 10, 0.5, Some(b"C")).unwrap();
+        assert!(tables.delete_intervals([(25., 35.)], false, false).is_err());
+        assert_eq!(tables.mutations().num_rows(), 5);
+    }
 
+    #[cfg(feature = "provenance")]
+    #[test]
+    fn test_record_provenance() {
+        let mut tables = make_tables();
+        tables.delete_intervals([(0., 50.)], false, true).unwrap();
+        assert_eq!(tables.provenances().num_rows(), 1);
+        let record: serde_json::Value =
+            serde_json::from_str(&tables.provenances().record(0).unwrap()).unwrap();
+        assert_eq!(record["software"]["name"], "tskit-rust");
+        assert_eq!(record["parameters"]["command"], "delete_intervals");
         assert_eq!(
-            mutations.derived_state(2).unwrap().unwrap(),
-            b"more pajamas"
+            record["parameters"]["intervals"],
+            serde_json::json!([[0.0, 50.0]])
         );
+        assert_eq!(record["parameters"]["simplify"], false);
+    }
+}
 
-        let mut nmuts = 0;
-        for (i, row) in tables.mutations().iter().enumerate() {
-            assert_eq!(row.site, tables.mutations().site(i as tsk_id_t).unwrap());
-            assert_eq!(row.node, tables.mutations().node(i as tsk_id_t).unwrap());
-            assert_eq!(
-                row.parent,
-                tables.mutations().parent(i as tsk_id_t).unwrap()
-            );
-            assert!(close_enough(
-                row.time,
-                tables.mutations().time(i as tsk_id_t).unwrap()
-            ));
-            assert!(row.metadata.is_none());
-            nmuts += 1;
-        }
-        assert_eq!(nmuts, tables.mutations().num_rows());
-        assert_eq!(nmuts, 3);
+#[cfg(test)]
+mod test_set_columns {
+    use crate::*;
 
-        nmuts = 0;
-        for row in tables.mutations_iter() {
-            assert_eq!(row.site, tables.mutations().site(row.id).unwrap());
-            assert_eq!(row.node, tables.mutations().node(row.id).unwrap());
-            assert_eq!(row.parent, tables.mutations().parent(row.id).unwrap());
-            assert!(close_enough(
-                row.time,
-                tables.mutations().time(row.id).unwrap()
-            ));
-            assert!(row.metadata.is_none());
-            nmuts += 1;
-        }
-        assert_eq!(nmuts, tables.mutations().num_rows());
-        assert_eq!(nmuts, 3);
-        for row in tables.mutations().iter() {
-            assert!(row.metadata.is_none());
-        }
+    #[test]
+    fn test_set_all_columns() {
+        let mut tables = TableCollection::new(10.).unwrap();
+        tables
+            .set_node_columns(
+                &[NodeFlags::default(), NodeFlags::new_sample()],
+                &[Time::from(1.), Time::from(0.)],
+                &[PopulationId::NULL; 2],
+                &[IndividualId::NULL, IndividualId::from(0)],
+            )
+            .unwrap();
+        tables
+            .set_edge_columns(
+                &[Position::from(0.)],
+                &[Position::from(10.)],
+                &[NodeId::from(0)],
+                &[NodeId::from(1)],
+            )
+            .unwrap();
+        tables
+            .set_individual_columns(
+                &[IndividualFlags::default()],
+                &[vec![1., 2.]],
+                &[Option::<&[IndividualId]>::None],
+            )
+            .unwrap();
+        tables
+            .set_site_columns(
+                &[Position::from(2.), Position::from(5.)],
+                &[Some(b"A".as_slice()), None],
+            )
+            .unwrap();
+        tables
+            .set_mutation_columns(
+                &[SiteId::from(0), SiteId::from(1)],
+                &[NodeId::from(1); 2],
+                &[MutationId::NULL; 2],
+                &[Time::from(0.5); 2],
+                &[Some(b"GT".as_slice()), Some(b"C".as_slice())],
+            )
+            .unwrap();
+        tables
+            .set_migration_columns(
+                (&[Position::from(0.)], &[Position::from(1.)]),
+                &[NodeId::from(1)],
+                (&[PopulationId::from(0)], &[PopulationId::from(1)]),
+                &[Time::from(0.5)],
+            )
+            .unwrap();
 
-        nmuts = 0;
-        for _ in tables.mutations().iter().skip(1) {
-            nmuts += 1;
-        }
+        assert_eq!(tables.nodes().num_rows(), 2);
+        assert_eq!(tables.nodes().individual(1).unwrap(), 0);
+        assert_eq!(tables.edges().child(0).unwrap(), 1);
+        assert_eq!(tables.individuals().location(0).unwrap().unwrap().len(), 2);
+        assert!(tables.individuals().parents(0).unwrap().is_none());
         assert_eq!(
-            crate::SizeType::try_from(nmuts + 1).unwrap(),
-            tables.mutations().num_rows()
+            tables.sites().ancestral_state(0).unwrap(),
+            Some(b"A".as_slice())
         );
-    }
+        assert!(tables.sites().ancestral_state(1).unwrap().is_none());
+        assert_eq!(
+            tables.mutations().derived_state(0).unwrap(),
+            Some(b"GT".as_slice())
+        );
+        assert_eq!(
+            tables.mutations().derived_state(1).unwrap(),
+            Some(b"C".as_slice())
+        );
+        assert_eq!(tables.migrations().dest(0).unwrap(), 1);
 
-    struct F {
-        x: i32,
-        y: u32,
+        assert_eq!(
+            tables.nodes().time_slice(),
+            &[Time::from(1.), Time::from(0.)]
+        );
+        assert_eq!(tables.nodes().individual_slice()[1], 0);
+        assert!(tables.nodes().flags_slice()[1].is_sample());
+        assert_eq!(tables.edges().parent_slice(), &[NodeId::from(0)]);
+        assert_eq!(tables.sites().position_slice(), &[2., 5.]);
+        assert_eq!(tables.mutations().site_slice(), &[0, 1]);
+        assert_eq!(tables.migrations().dest_slice(), &[1]);
+        assert_eq!(tables.individuals().flags_slice().len(), 1);
+
+        // Loading again replaces the existing rows
+        tables.set_edge_columns(&[], &[], &[], &[]).unwrap();
+        assert_eq!(tables.edges().num_rows(), 0);
+        assert!(tables.edges().left_slice().is_empty());
     }
 
-    impl MetadataRoundtrip for F {
-        fn encode(&self) -> Result<Vec<u8>, MetadataError> {
-            let mut rv = vec![];
-            rv.extend(self.x.to_le_bytes().iter().copied());
-            rv.extend(self.y.to_le_bytes().iter().copied());
-            Ok(rv)
-        }
-        fn decode(md: &[u8]) -> Result<Self, MetadataError> {
-            let (x_int_bytes, rest) = md.split_at(std::mem::size_of::<i32>());
-            let (y_int_bytes, _) = rest.split_at(std::mem::size_of::<u32>());
-            Ok(Self {
-                x: i32::from_le_bytes(x_int_bytes.try_into().unwrap()),
-                y: u32::from_le_bytes(y_int_bytes.try_into().unwrap()),
-            })
-        }
+    #[test]
+    fn test_set_columns_length_mismatch() {
+        let mut tables = TableCollection::new(10.).unwrap();
+        assert!(matches!(
+            tables.set_site_columns(&[Position::from(2.)], &[]),
+            Err(TskitError::ValueError { .. })
+        ));
+        assert_eq!(tables.sites().num_rows(), 0);
     }
+}
 
-    impl MutationMetadata for F {}
+#[cfg(test)]
+mod test_bad_metadata {
+    use super::*;
+    use crate::test_fixtures::bad_metadata::*;
+    use crate::MutationId;
 
     #[test]
-    fn test_add_mutation_with_metadata() {
-        let mut tables = TableCollection::new(1000.).unwrap();
+    fn test_bad_mutation_metadata_roundtrip() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        let md = F { x: 1, y: 11 };
         tables
-            .add_mutation_with_metadata(0, 0, MutationId::NULL, 1.123, None, &F { x: -3, y: 666 })
+            .add_mutation_with_metadata(0, 0, MutationId::NULL, 0.0, None, &md)
             .unwrap();
-        // The double unwrap is to first check for error
-        // and then to process the Option.
-        let md = tables.mutations().metadata::<F>(0.into()).unwrap().unwrap();
-        assert_eq!(md.x, -3);
-        assert_eq!(md.y, 666);
-
-        for row in tables.mutations().iter() {
-            assert!(row.metadata.is_some());
-            let md = F::decode(&row.metadata.unwrap()).unwrap();
-            assert_eq!(md.x, -3);
-            assert_eq!(md.y, 666);
+        if tables.mutations().metadata::<Ff>(0).is_ok() {
+            panic!("expected an error!!");
         }
     }
+}
+
+#[cfg(test)]
+mod test_max_increments {
+    use super::*;
 
     #[test]
-    fn test_add_mutation_with_metadata_for_some_columns() {
-        let mut tables = TableCollection::new(1000.).unwrap();
+    fn test_site_increments() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables.set_site_max_rows_increment(2000_usize).unwrap();
         tables
-            .add_mutation_with_metadata(0, 0, MutationId::NULL, 1.123, None, &F { x: -3, y: 666 })
+            .set_site_max_ancestral_state_length_increment(3000_usize)
             .unwrap();
+        // The initial allocation holds a single row.
+        tables.add_site(0.25, None).unwrap();
+        tables.add_site(0.5, Some(b"ACGT")).unwrap();
+        assert_eq!(tables.inner.sites.max_rows, 2001);
+        assert!(tables.inner.sites.max_ancestral_state_length >= 3000);
+    }
+}
 
-        tables
-            .add_mutation(1, 2, MutationId::NULL, 2.0, None)
-            .unwrap();
+#[cfg(test)]
+mod test_clear_table {
+    use super::*;
+    use crate::test_fixtures::make_small_table_collection;
 
-        let mut num_with_metadata = 0;
-        let mut num_without_metadata = 0;
-        for i in 0..usize::try_from(tables.mutations().num_rows()).unwrap() {
-            match tables
-                .mutations()
-                .metadata::<F>((i as tsk_id_t).into())
-                .unwrap()
-            {
-                Some(x) => {
-                    num_with_metadata += 1;
-                    assert_eq!(x.x, -3);
-                    assert_eq!(x.y, 666);
-                }
-                None => {
-                    num_without_metadata += 1;
-                }
-            }
-        }
-        assert_eq!(num_with_metadata, 1);
-        assert_eq!(num_without_metadata, 1);
+    #[test]
+    fn test_clear_edges() {
+        let mut tables = make_small_table_collection();
+        assert!(tables.is_indexed());
+        let num_nodes = tables.nodes().num_rows();
+        tables.clear_table(TableKind::Edges).unwrap();
+        assert_eq!(tables.edges().num_rows(), 0);
+        assert_eq!(tables.nodes().num_rows(), num_nodes);
+        assert!(!tables.is_indexed());
     }
 
     #[test]
-    fn test_add_population() {
-        let mut tables = TableCollection::new(1000.).unwrap();
-        let pop_id = tables.add_population().unwrap();
-        assert_eq!(pop_id, 0);
-        assert_eq!(tables.populations().num_rows(), 1);
-
-        tables
-            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, pop_id, IndividualId::NULL)
-            .unwrap();
-
-        match tables.nodes().row(NodeId::from(0)) {
-            Ok(x) => match x.population {
-                PopulationId(0) => (),
-                _ => panic!("expected PopulationId(0)"),
-            },
-            Err(_) => panic!("expected Ok(_)"),
-        };
+    fn test_clear_keeps_metadata_schema() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables.add_population().unwrap();
+        tables.set_population_metadata_schema("{}").unwrap();
+        tables.clear_table(TableKind::Populations).unwrap();
+        assert_eq!(tables.populations().num_rows(), 0);
+        assert_eq!(tables.populations().metadata_schema(), Some("{}"));
     }
+}
 
-    #[test]
-    fn test_dump_tables() {
-        let treefile = "trees.trees";
-        let mut tables = TableCollection::new(1000.).unwrap();
-        let pop_id = tables.add_population().unwrap();
-        tables
-            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, pop_id, IndividualId::NULL)
+#[cfg(test)]
+mod test_append {
+    use super::*;
+
+    fn make_tables() -> TableCollection {
+        let mut tables = TableCollection::new(100.).unwrap();
+        let pop = tables.add_population().unwrap();
+        let i0 = tables.add_individual(0, None, None).unwrap();
+        tables.add_individual(0, None, [i0]).unwrap();
+        let parent = tables.add_node(0, 1.0, pop, i0).unwrap();
+        let child = tables.add_node(0, 0.0, pop, -1).unwrap();
+        tables.add_edge(0., 100., parent, child).unwrap();
+        let site = tables.add_site(10., Some(b"A")).unwrap();
+        let m = tables
+            .add_mutation(site, child, MutationId::NULL, 0.5, Some(b"C"))
             .unwrap();
         tables
-            .add_node(crate::TSK_NODE_IS_SAMPLE, 1.0, pop_id, IndividualId::NULL)
+            .add_mutation(site, child, m, 0.25, Some(b"G"))
             .unwrap();
-        tables.add_edge(0., tables.sequence_length(), 1, 0).unwrap();
         tables
-            .dump(treefile, TableOutputOptions::default())
+            .add_migration((0., 100.), child, (pop, pop), 0.5)
             .unwrap();
-
-        let tables2 = TableCollection::new_from_file(treefile).unwrap();
-        assert!(tables.equals(&tables2, TableEqualityOptions::default()));
-
-        std::fs::remove_file(&treefile).unwrap();
+        tables
     }
 
     #[test]
-    fn test_clear() {
-        let mut tables = TableCollection::new(1000.).unwrap();
-        for i in 0..5 {
-            let _ = tables.add_edge(0., 1000., i, 2 * i).unwrap();
-        }
-        assert_eq!(tables.edges().num_rows(), 5);
-        tables.clear(TableClearOptions::default()).unwrap();
-        assert_eq!(tables.edges().num_rows(), 0);
+    fn test_append_offsets_ids() {
+        let mut tables = make_tables();
+        let other = make_tables();
+        tables.append(&other).unwrap();
+
+        assert_eq!(tables.populations().num_rows(), 2);
+        assert_eq!(tables.individuals().parents(3).unwrap().unwrap(), &[2]);
+        assert!(tables.individuals().parents(2).unwrap().is_none());
+        assert_eq!(tables.nodes().population(2).unwrap(), 1);
+        assert_eq!(tables.nodes().individual(2).unwrap(), 2);
+        assert_eq!(tables.nodes().individual(3).unwrap(), IndividualId::NULL);
+        assert_eq!(tables.edges().parent(1).unwrap(), 2);
+        assert_eq!(tables.edges().child(1).unwrap(), 3);
+        assert_eq!(tables.sites().ancestral_state(1).unwrap().unwrap(), b"A");
+        assert_eq!(tables.mutations().site(2).unwrap(), 1);
+        assert_eq!(tables.mutations().node(3).unwrap(), 3);
+        assert_eq!(tables.mutations().parent(2).unwrap(), MutationId::NULL);
+        assert_eq!(tables.mutations().parent(3).unwrap(), 2);
+        assert_eq!(tables.mutations().derived_state(3).unwrap().unwrap(), b"G");
+        assert_eq!(tables.migrations().node(1).unwrap(), 3);
+        assert_eq!(tables.migrations().source(1).unwrap(), 1);
+        assert_eq!(tables.migrations().dest(1).unwrap(), 1);
+
+        tables.full_sort(TableSortOptions::default()).unwrap();
+        tables.build_index().unwrap();
+        assert!(tables
+            .check_integrity(TableIntegrityCheckFlags::CHECK_MUTATION_ORDERING)
+            .is_ok());
     }
 
     #[test]
-    fn test_free() {
-        let mut tables = TableCollection::new(1000.).unwrap();
-        tables.free().unwrap();
+    fn test_append_empty() {
+        let mut tables = make_tables();
+        let other = TableCollection::new(1.).unwrap();
+        tables.append(&other).unwrap();
+        assert_eq!(tables.nodes().num_rows(), 2);
+        assert_eq!(tables.mutations().num_rows(), 2);
     }
 
     #[test]
-    fn test_deepcopy() {
-        let tables = make_small_table_collection();
-        let dumps = tables.deepcopy().unwrap();
-        assert!(tables.equals(&dumps, TableEqualityOptions::default()));
+    fn test_append_longer_sequence() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        let other = make_tables();
+        assert!(matches!(
+            tables.append(&other),
+            Err(TskitError::ValueError {
+                got: _,
+                expected: _
+            })
+        ));
     }
+}
+
+#[cfg(test)]
+mod test_takeset_tables {
+    use super::*;
+    use crate::test_fixtures::make_small_table_collection;
 
     #[test]
-    fn test_edge_table_row_equality() {
-        let tables = make_small_table_collection();
-        for (i, row) in tables.edges_iter().enumerate() {
-            assert!(row.id == i as tsk_id_t);
-            assert!(row == tables.edges().row(i as tsk_id_t).unwrap());
-            assert!(!(row != tables.edges().row(i as tsk_id_t).unwrap()));
-            if i > 0 {
-                assert!(row != tables.edges().row(i as tsk_id_t - 1).unwrap());
-            }
+    fn test_takeset_edges() {
+        let mut tables = make_small_table_collection();
+        assert!(tables.is_indexed());
+        let mut edges = crate::OwnedEdgeTable::default();
+        for e in tables.edges().iter().take(2) {
+            edges.add_row(e.left, e.right, e.parent, e.child).unwrap();
         }
+        edges.set_metadata_schema("{}").unwrap();
+        tables.takeset_edges(edges).unwrap();
+        assert_eq!(tables.edges().num_rows(), 2);
+        assert_eq!(tables.edges().metadata_schema(), Some("{}"));
+        assert!(!tables.is_indexed());
+        tables.build_index().unwrap();
+        assert!(tables.is_indexed());
     }
 
     #[test]
-    fn test_node_table_row_equality() {
-        let tables = make_small_table_collection();
-        for (i, row) in tables.nodes_iter().enumerate() {
-            assert!(row.id.0 == i as tsk_id_t);
-            assert!(row == tables.nodes().row(i as tsk_id_t).unwrap());
-            assert!(!(row != tables.nodes().row(i as tsk_id_t).unwrap()));
-        }
-        assert!(tables.nodes().row(0).unwrap() != tables.nodes().row(1).unwrap());
-        assert!(tables.nodes().row(1).unwrap() != tables.nodes().row(2).unwrap());
+    fn test_takeset_populations() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables.add_population().unwrap();
+        let populations = crate::OwnedPopulationTable::default();
+        tables.takeset_populations(populations).unwrap();
+        assert_eq!(tables.populations().num_rows(), 0);
+        tables.add_population().unwrap();
+        assert_eq!(tables.populations().num_rows(), 1);
     }
+}
+
+#[cfg(test)]
+mod test_packset_metadata {
+    use super::*;
+    use crate::metadata::MetadataRoundtrip;
+    use crate::test_fixtures::bad_metadata::*;
 
     #[test]
-    fn test_add_individual_many_ways() {
-        {
-            let mut tables = TableCollection::new(1.).unwrap();
-            let location = vec![0., 1., 2.];
-            let parents = [0, 1, 2, 3, 4];
-            tables.add_individual(0, location, parents).unwrap();
-        }
-        {
-            let mut tables = TableCollection::new(1.).unwrap();
-            let location = vec![0., 1., 2.];
-            let parents = [0, 1, 2, 3, 4];
+    fn test_packset_mutation_metadata() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables.add_site(0.5, None).unwrap();
+        for _ in 0..100 {
             tables
-                .add_individual(0, location.as_slice(), parents.as_slice())
+                .add_mutation(0, 0, MutationId::NULL, 0.0, None)
                 .unwrap();
         }
-        {
-            let mut tables = TableCollection::new(1.).unwrap();
-            let location = [0., 1., 2.];
-            let parents = vec![0, 1, 2, 3, 4];
-            tables.add_individual(0, location, parents).unwrap();
-        }
-        {
-            let mut tables = TableCollection::new(1.).unwrap();
-            let location = [0., 1., 2.];
-            let parents = vec![0, 1, 2, 3, 4];
-            tables
-                .add_individual(0, location.as_slice(), parents.as_slice())
+        let encoded = (0..100)
+            .map(|i| {
+                F {
+                    x: i,
+                    y: 2 * i as u32,
+                }
+                .encode()
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        tables.packset_mutation_metadata(&encoded).unwrap();
+        for i in 0..100 {
+            let md = tables
+                .mutations()
+                .metadata::<F>(MutationId::from(i))
+                .unwrap()
                 .unwrap();
+            assert_eq!(md.x, i);
+            assert_eq!(md.y, 2 * i as u32);
         }
+
+        // Shrinking the column clears the metadata
+        let empty = vec![vec![]; 100];
+        tables.packset_mutation_metadata(&empty).unwrap();
+        assert!(tables
+            .mutations()
+            .metadata::<F>(MutationId::from(0))
+            .unwrap()
+            .is_none());
+        assert!(tables.packset_mutation_metadata(&empty[1..]).is_err());
+    }
+
+    #[test]
+    fn test_packset_empty_table() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables.packset_edge_metadata::<&[u8]>(&[]).unwrap();
+        assert!(tables.packset_edge_metadata(&[b"x"]).is_err());
     }
 }
 
 #[cfg(test)]
-mod test_bad_metadata {
+mod test_top_level_metadata {
     use super::*;
     use crate::test_fixtures::bad_metadata::*;
-    use crate::MutationId;
 
     #[test]
-    fn test_bad_mutation_metadata_roundtrip() {
+    fn test_metadata_roundtrip() {
         let mut tables = TableCollection::new(1.).unwrap();
-        let md = F { x: 1, y: 11 };
+        tables.set_metadata(&F { x: 1, y: 11 }).unwrap();
         tables
-            .add_mutation_with_metadata(0, 0, MutationId::NULL, 0.0, None, &md)
+            .set_metadata_schema("{\"codec\":\"struct\"}")
             .unwrap();
-        if tables.mutations().metadata::<Ff>(0.into()).is_ok() {
-            panic!("expected an error!!");
-        }
+        let decoded = tables.metadata::<F>().unwrap().unwrap();
+        assert_eq!(decoded.x, 1);
+        assert_eq!(decoded.y, 11);
+        assert!(tables.metadata::<Ff>().is_err());
+
+        let treeseq = tables
+            .tree_sequence(TreeSequenceFlags::BUILD_INDEXES)
+            .unwrap();
+        assert_eq!(treeseq.metadata::<F>().unwrap().unwrap().y, 11);
+        assert_eq!(treeseq.metadata_schema(), Some("{\"codec\":\"struct\"}"));
     }
 }
 
@@ -2284,3 +5658,557 @@ mod test_adding_migrations {
         }
     }
 }
+
+#[cfg(test)]
+mod test_migrate_metadata {
+    use super::*;
+    use crate::metadata::{Bytes, MetadataError, MetadataRoundtrip, PopulationMetadata};
+
+    struct Count(u32);
+
+    impl MetadataRoundtrip for Count {
+        fn encode(&self) -> Result<Vec<u8>, MetadataError> {
+            Ok(self.0.to_le_bytes().to_vec())
+        }
+
+        fn decode(md: &[u8]) -> Result<Self, MetadataError> {
+            match <[u8; 4]>::try_from(md) {
+                Ok(bytes) => Ok(Self(u32::from_le_bytes(bytes))),
+                Err(e) => Err(MetadataError::RoundtripError { value: Box::new(e) }),
+            }
+        }
+    }
+
+    impl PopulationMetadata for Count {}
+
+    #[test]
+    fn test_migrate_population_metadata() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables.add_population_with_metadata(&Count(1)).unwrap();
+        tables.add_population().unwrap();
+        tables.add_population_with_metadata(&Count(3)).unwrap();
+        tables
+            .migrate_population_metadata("", "count*2", |c: Count| Count(2 * c.0))
+            .unwrap();
+        let populations = tables.populations();
+        let migrated = populations
+            .metadata_iter::<Count>()
+            .map(|m| m.unwrap().map(|c| c.0))
+            .collect::<Vec<_>>();
+        assert_eq!(migrated, vec![Some(2), None, Some(6)]);
+        assert_eq!(populations.metadata_schema(), Some("count*2"));
+    }
+
+    #[test]
+    fn test_migrate_failure_leaves_table_unchanged() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables.add_population_with_metadata(&Count(1)).unwrap();
+        tables
+            .add_population_with_metadata(&Bytes(vec![1, 2]))
+            .unwrap();
+        assert!(tables
+            .migrate_population_metadata("", "new", |c: Count| Count(c.0 + 1))
+            .is_err());
+        let populations = tables.populations();
+        assert_eq!(populations.metadata_schema(), None);
+        assert_eq!(
+            populations.row(0).unwrap().metadata,
+            Some(Count(1).encode().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_migrate_schema_mismatch() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables.add_population_with_metadata(&Count(1)).unwrap();
+        tables.set_population_metadata_schema("old").unwrap();
+        assert!(matches!(
+            tables.migrate_population_metadata("other", "new", |c: Count| c),
+            Err(TskitError::ValueError { .. })
+        ));
+        assert_eq!(tables.populations().metadata_schema(), Some("old"));
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "serde_json")]
+mod test_equals_normalized_metadata {
+    use super::*;
+    use crate::metadata::Bytes;
+
+    fn make_tables(schema: &str, metadata: &[u8]) -> TableCollection {
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables.set_node_metadata_schema(schema).unwrap();
+        tables
+            .add_node_with_metadata(0, 0.0, -1, -1, &Bytes::from(metadata))
+            .unwrap();
+        tables.add_node(0, 1.0, -1, -1).unwrap();
+        tables
+    }
+
+    #[test]
+    fn test_json_metadata() {
+        let tables = make_tables(r#"{"codec": "json"}"#, br#"{"x": [1, 2], "y": "a"}"#);
+        let same = make_tables(r#"{"codec":"json"}"#, br#"{"y":"a","x":[1,2]}"#);
+        let different = make_tables(r#"{"codec": "json"}"#, br#"{"x": [2, 1], "y": "a"}"#);
+        assert!(!tables.equals(&same, TableEqualityOptions::default()));
+        assert!(tables.equals_normalized_metadata(&same, TableEqualityOptions::default()));
+        assert!(!tables.equals_normalized_metadata(&different, TableEqualityOptions::default()));
+        assert!(
+            tables.equals_normalized_metadata(&different, TableEqualityOptions::IGNORE_METADATA)
+        );
+    }
+
+    #[test]
+    fn test_empty_json_metadata_is_empty_object() {
+        let tables = make_tables(r#"{"codec": "json"}"#, b"");
+        let other = make_tables(r#"{"codec": "json"}"#, b"{}");
+        assert!(tables.equals_normalized_metadata(&other, TableEqualityOptions::default()));
+    }
+
+    #[test]
+    fn test_other_codecs_compare_bytes() {
+        let tables = make_tables(r#"{"codec": "other"}"#, br#"{"x": 1, "y": 2}"#);
+        let other = make_tables(r#"{"codec": "other"}"#, br#"{"y": 2, "x": 1}"#);
+        assert!(!tables.equals_normalized_metadata(&other, TableEqualityOptions::default()));
+    }
+
+    #[test]
+    fn test_different_data() {
+        let tables = make_tables(r#"{"codec": "json"}"#, b"{}");
+        let mut other = make_tables(r#"{"codec": "json"}"#, b"{}");
+        other.add_node(0, 2.0, -1, -1).unwrap();
+        assert!(!tables.equals_normalized_metadata(&other, TableEqualityOptions::default()));
+    }
+
+    #[test]
+    fn test_top_level_metadata() {
+        let mut tables = make_tables(r#"{"codec": "json"}"#, b"{}");
+        let mut other = make_tables(r#"{"codec": "json"}"#, b"{}");
+        tables.set_metadata_schema(r#"{"codec": "json"}"#).unwrap();
+        other.set_metadata_schema(r#"{"codec": "json"}"#).unwrap();
+        tables
+            .set_metadata(&Bytes::from(br#"{"a": 1, "b": 2}"#.to_vec()))
+            .unwrap();
+        other
+            .set_metadata(&Bytes::from(br#"{"b": 2, "a": 1}"#.to_vec()))
+            .unwrap();
+        assert!(tables.equals_normalized_metadata(&other, TableEqualityOptions::default()));
+        other
+            .set_metadata(&Bytes::from(br#"{"b": 1, "a": 1}"#.to_vec()))
+            .unwrap();
+        assert!(!tables.equals_normalized_metadata(&other, TableEqualityOptions::default()));
+        assert!(tables.equals_normalized_metadata(&other, TableEqualityOptions::IGNORE_TS_METADATA));
+    }
+}
+
+#[cfg(test)]
+mod test_row_views {
+    use super::*;
+
+    #[test]
+    fn test_individual_and_mutation_views() {
+        let mut tables = TableCollection::new(10.).unwrap();
+        let i0 = tables.add_individual(0, [1.0, 2.0], None).unwrap();
+        tables.add_individual(0, None, [i0]).unwrap();
+        let n = tables.add_node(0, 0.0, -1, i0).unwrap();
+        let s = tables.add_site(1.0, Some(b"A")).unwrap();
+        tables
+            .add_mutation(s, n, MutationId::NULL, 0.5, Some(b"G"))
+            .unwrap();
+
+        let individuals = tables.individuals();
+        let mut views = individuals.iter_views();
+        let view = views.next().unwrap();
+        assert_eq!(
+            view.location,
+            Some(&[crate::Location::from(1.0), crate::Location::from(2.0)][..])
+        );
+        assert!(view.parents.is_none());
+        let view = views.next().unwrap();
+        assert!(view.location.is_none());
+        assert_eq!(view.parents, Some(&[i0][..]));
+        assert!(views.next().is_none());
+
+        let mutations = tables.mutations();
+        let view = mutations.get(0).unwrap();
+        assert_eq!(view.site, s);
+        assert_eq!(view.node, n);
+        assert!(view.parent.is_null());
+        assert_eq!(view.derived_state, Some(&b"G"[..]));
+        assert_eq!(tables.sites().iter_views().count(), 1);
+        assert_eq!(
+            tables.sites().iter_views().next().unwrap().ancestral_state,
+            Some(&b"A"[..])
+        );
+        assert_eq!(tables.edges().iter_views().count(), 0);
+    }
+}
+
+#[cfg(test)]
+mod test_out_of_range_ids {
+    use crate::metadata::Bytes;
+    use crate::TableCollection;
+
+    #[test]
+    fn test_no_panics() {
+        let mut tables = TableCollection::new(10.).unwrap();
+        tables.add_node(0, 0.0, -1, -1).unwrap();
+        for id in [-1, 1, i32::MAX, i32::MIN] {
+            assert!(tables.nodes().row(id).is_err());
+            assert!(tables.nodes().time(id).is_err());
+            assert!(tables.nodes().metadata::<Bytes>(id).is_err());
+            assert!(tables.nodes().get(id).is_none());
+            assert!(tables.edges().row(id).is_err());
+            assert!(tables.edges().parent(id).is_err());
+            assert!(tables.sites().row(id).is_err());
+            assert!(tables.sites().ancestral_state(id).is_err());
+            assert!(tables.mutations().row(id).is_err());
+            assert!(tables.mutations().derived_state(id).is_err());
+            assert!(tables.migrations().row(id).is_err());
+            assert!(tables.populations().row(id).is_err());
+            assert!(tables.individuals().row(id).is_err());
+            assert!(tables.individuals().location(id).is_err());
+            #[cfg(feature = "provenance")]
+            assert!(tables.provenances().row(id).is_err());
+        }
+    }
+
+    fn is_index_error<T>(r: Result<T, crate::TskitError>) -> bool {
+        matches!(r, Err(crate::TskitError::IndexError))
+    }
+
+    #[test]
+    fn test_index_errors() {
+        let mut tables = TableCollection::new(10.).unwrap();
+        tables
+            .add_node(crate::NodeFlags::IS_SAMPLE, 0.0, -1, -1)
+            .unwrap();
+        tables.add_site(0., Some(b"A")).unwrap();
+        tables.add_individual(0, [1.0], None).unwrap();
+        tables.build_index().unwrap();
+        for id in [-2, 1, i32::MAX, i32::MIN] {
+            assert!(is_index_error(tables.nodes().row(id)));
+            assert!(is_index_error(tables.nodes().time(id)));
+            assert!(is_index_error(tables.sites().ancestral_state(id)));
+            assert!(is_index_error(tables.individuals().location(id)));
+            assert!(is_index_error(tables.individuals().parents(id)));
+            assert!(is_index_error(tables.mutations().derived_state(id)));
+        }
+        let ts = tables
+            .tree_sequence(crate::TreeSequenceFlags::default())
+            .unwrap();
+        let mut trees = ts.tree_iterator(crate::TreeFlags::SAMPLE_LISTS).unwrap();
+        let tree = streaming_iterator::StreamingIterator::next(&mut trees).unwrap();
+        for id in [-2, 2, i32::MAX, i32::MIN] {
+            assert!(is_index_error(tree.parent(id)));
+            assert!(is_index_error(tree.left_child(id)));
+            assert!(is_index_error(tree.parents(id).map(|_| ())));
+            assert!(is_index_error(tree.children(id).map(|_| ())));
+            assert!(is_index_error(tree.samples(id).map(|_| ())));
+            assert!(is_index_error(tree.num_tracked_samples(id)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_row_builders {
+    use crate::TableCollection;
+    use crate::TskitError;
+
+    fn is_value_error<T: std::fmt::Debug>(r: Result<T, TskitError>) -> bool {
+        matches!(r, Err(TskitError::ValueError { .. }))
+    }
+
+    #[test]
+    fn test_edge_row_builder() {
+        let mut tables = TableCollection::new(10.).unwrap();
+        let id = tables
+            .add_edge_defaults()
+            .interval(0., 10.)
+            .parent(0)
+            .child(1)
+            .build()
+            .unwrap();
+        assert_eq!(id, 0);
+        assert!(is_value_error(
+            tables.add_edge_defaults().parent(0).child(1).build()
+        ));
+        for (left, right) in [(-1., 1.), (2., 1.), (0., f64::INFINITY), (f64::NAN, 1.)] {
+            assert!(is_value_error(
+                tables
+                    .add_edge_defaults()
+                    .interval(left, right)
+                    .parent(0)
+                    .child(1)
+                    .build()
+            ));
+        }
+        assert!(is_value_error(
+            tables.add_edge_defaults().interval(0., 1.).child(1).build()
+        ));
+        assert!(is_value_error(
+            tables
+                .add_edge_defaults()
+                .interval(0., 1.)
+                .parent(1)
+                .child(1)
+                .build()
+        ));
+        assert_eq!(tables.edges().num_rows(), 1);
+    }
+
+    #[test]
+    fn test_mutation_row_builder() {
+        let mut tables = TableCollection::new(10.).unwrap();
+        let unknown = tables
+            .add_mutation_defaults()
+            .site(0)
+            .node(0)
+            .build()
+            .unwrap();
+        assert!(f64::from(tables.mutations().time(unknown).unwrap()).is_nan());
+        let known = tables
+            .add_mutation_defaults()
+            .site(0)
+            .node(0)
+            .time(2.0)
+            .build()
+            .unwrap();
+        assert!(is_value_error(
+            tables.add_mutation_defaults().node(0).build()
+        ));
+        assert!(is_value_error(
+            tables.add_mutation_defaults().site(0).build()
+        ));
+        assert!(is_value_error(
+            tables
+                .add_mutation_defaults()
+                .site(0)
+                .node(0)
+                .time(f64::NAN)
+                .build()
+        ));
+        // mixing known and unknown times
+        assert!(is_value_error(
+            tables
+                .add_mutation_defaults()
+                .site(0)
+                .node(0)
+                .parent(unknown)
+                .time(1.0)
+                .build()
+        ));
+        assert!(is_value_error(
+            tables
+                .add_mutation_defaults()
+                .site(0)
+                .node(0)
+                .parent(known)
+                .unknown_time()
+                .build()
+        ));
+        // child older than parent
+        assert!(is_value_error(
+            tables
+                .add_mutation_defaults()
+                .site(0)
+                .node(0)
+                .parent(known)
+                .time(3.0)
+                .build()
+        ));
+        // parent at another site, or not yet in the table
+        assert!(is_value_error(
+            tables
+                .add_mutation_defaults()
+                .site(1)
+                .node(0)
+                .parent(known)
+                .time(1.0)
+                .build()
+        ));
+        assert!(is_value_error(
+            tables
+                .add_mutation_defaults()
+                .site(0)
+                .node(0)
+                .parent(2)
+                .build()
+        ));
+        let child = tables
+            .add_mutation_defaults()
+            .site(0)
+            .node(0)
+            .parent(known)
+            .time(1.0)
+            .derived_state("T")
+            .build()
+            .unwrap();
+        assert_eq!(
+            tables.mutations().derived_state(child).unwrap(),
+            Some(&b"T"[..])
+        );
+        assert_eq!(tables.mutations().num_rows(), 3);
+    }
+
+    #[test]
+    fn test_individual_row_builder() {
+        let mut tables = TableCollection::new(10.).unwrap();
+        let id = tables.add_individual_defaults().build().unwrap();
+        assert_eq!(id, 0);
+        assert!(tables.individuals().location(id).unwrap().is_none());
+        assert!(tables.individuals().parents(id).unwrap().is_none());
+        let id = tables
+            .add_individual_defaults()
+            .location(&[1.0, 2.0])
+            .parents(&[0, -1])
+            .build()
+            .unwrap();
+        assert_eq!(
+            tables.individuals().location(id).unwrap().unwrap(),
+            [1.0, 2.0]
+        );
+        assert!(is_value_error(
+            tables
+                .add_individual_defaults()
+                .location(&[f64::NAN])
+                .build()
+        ));
+        assert!(is_value_error(
+            tables.add_individual_defaults().parents(&[-2]).build()
+        ));
+        assert!(is_value_error(
+            tables.add_individual_defaults().parents(&[2]).build()
+        ));
+        assert_eq!(tables.individuals().num_rows(), 2);
+    }
+}
+
+#[cfg(test)]
+mod test_table_equality {
+    use crate::metadata::Bytes;
+    use crate::OwnedEdgeTable;
+    use crate::TableCollection;
+    use crate::TableEqualityOptions;
+
+    #[test]
+    fn test_single_table_equality() {
+        let mut a = TableCollection::new(10.).unwrap();
+        let mut b = TableCollection::new(10.).unwrap();
+        a.add_edge(0., 10., 0, 1).unwrap();
+        b.add_edge_with_metadata(0., 10., 0, 1, &Bytes::from(vec![1]))
+            .unwrap();
+        a.add_node(0, 0.0, -1, -1).unwrap();
+        assert!(a.edges() != b.edges());
+        assert!(a
+            .edges()
+            .equals(&b.edges(), TableEqualityOptions::IGNORE_METADATA));
+        assert!(a.sites() == b.sites());
+        assert!(a.nodes() != b.nodes());
+        assert!(!a.equals(&b, TableEqualityOptions::IGNORE_METADATA));
+
+        let mut owned = OwnedEdgeTable::default();
+        assert!(owned == OwnedEdgeTable::default());
+        owned.add_row(0., 10., 0, 1).unwrap();
+        assert!(*owned == a.edges());
+        let mut other = OwnedEdgeTable::default();
+        other.add_row(0., 10., 0, 1).unwrap();
+        assert!(owned == other);
+        other.add_row(0., 10., 0, 2).unwrap();
+        assert!(owned != other);
+    }
+}
+
+#[cfg(test)]
+mod test_table_columns {
+    use crate::types::{ColumnType, TableKind};
+    use crate::TableCollection;
+    use crate::TableColumns;
+
+    #[test]
+    fn test_columns() {
+        let mut tables = TableCollection::new(10.).unwrap();
+        tables
+            .add_individual(0, [1.0, 2.0, 3.0], [crate::IndividualId::NULL])
+            .unwrap();
+        tables.add_individual(0, None, None).unwrap();
+        let individuals = tables.individuals();
+        assert_eq!(individuals.table_kind(), TableKind::Individuals);
+        let columns = individuals.columns();
+        assert_eq!(columns.len(), 4);
+        assert_eq!(columns[0].name, "flags");
+        assert_eq!(columns[0].column_type, ColumnType::UInt32);
+        assert_eq!(columns[0].len, 2);
+        assert!(!columns[0].is_ragged());
+        let location = individuals.column("location").unwrap();
+        assert_eq!(location.column_type, ColumnType::Float64);
+        assert_eq!(location.len, 3);
+        assert_eq!(location.offsets_len.unwrap(), 3);
+        let parents = individuals.column("parents").unwrap();
+        assert_eq!(parents.column_type, ColumnType::Int32);
+        assert_eq!(parents.len, 1);
+        assert!(individuals.column("time").is_none());
+
+        assert_eq!(tables.nodes().columns().len(), 5);
+        assert_eq!(tables.edges().columns().len(), 5);
+        assert_eq!(tables.sites().columns().len(), 3);
+        assert_eq!(tables.mutations().columns().len(), 6);
+        assert_eq!(tables.migrations().columns().len(), 7);
+        assert_eq!(tables.populations().columns().len(), 1);
+
+        let mut nodes = crate::OwnedNodeTable::default();
+        nodes.add_row(0, 0.0, -1, -1).unwrap();
+        assert_eq!(nodes.column("time").unwrap().len, 1);
+    }
+}
+
+#[cfg(test)]
+mod test_iter_views {
+    use crate::metadata::Bytes;
+    use crate::TableCollection;
+    use crate::{EdgeTableRow, IndividualTableRow, MutationTableRow, NodeTableRow, SiteTableRow};
+
+    #[test]
+    fn test_views_match_rows() {
+        let mut tables = TableCollection::new(10.).unwrap();
+        tables
+            .add_node_with_metadata(0, 1.0, -1, -1, &Bytes::from(vec![1, 2]))
+            .unwrap();
+        tables.add_node(0, 0.0, -1, -1).unwrap();
+        tables.add_edge(0., 10., 0, 1).unwrap();
+        tables.add_site(1., Some(b"A")).unwrap();
+        tables
+            .add_mutation(0, 1, crate::MutationId::NULL, 0.5, Some(b"T"))
+            .unwrap();
+        tables.add_individual(0, [1.0], [-1, -1]).unwrap();
+        tables.add_individual(0, None, None).unwrap();
+
+        let nodes = tables.nodes();
+        let view = nodes.iter_views().next().unwrap();
+        assert_eq!(view.metadata, Some(&[1_u8, 2][..]));
+        assert!(nodes.iter_views().map(NodeTableRow::from).eq(nodes.iter()));
+        assert!(tables
+            .edges()
+            .iter_views()
+            .map(EdgeTableRow::from)
+            .eq(tables.edges().iter()));
+        assert!(tables
+            .sites()
+            .iter_views()
+            .map(SiteTableRow::from)
+            .eq(tables.sites().iter()));
+        assert!(tables
+            .mutations()
+            .iter_views()
+            .map(MutationTableRow::from)
+            .eq(tables.mutations().iter()));
+        assert!(tables
+            .individuals()
+            .iter_views()
+            .map(IndividualTableRow::from)
+            .eq(tables.individuals().iter()));
+        assert_eq!(tables.migrations().iter_views().count(), 0);
+        assert_eq!(tables.populations().iter_views().count(), 0);
+    }
+}
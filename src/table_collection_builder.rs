@@ -0,0 +1,297 @@
+use crate::IndividualFlags;
+use crate::IndividualId;
+use crate::IndividualLocation;
+use crate::IndividualParents;
+use crate::Location;
+use crate::MutationId;
+use crate::NodeFlags;
+use crate::NodeId;
+use crate::PopulationId;
+use crate::Position;
+use crate::SiteId;
+use crate::TableCollection;
+use crate::TableSortOptions;
+use crate::Time;
+use crate::TreeSequence;
+use crate::TreeSequenceFlags;
+use crate::TskitError;
+
+type MutationRow = (SiteId, NodeId, MutationId, Time, Option<Vec<u8>>);
+type MigrationRow = (Position, Position, NodeId, PopulationId, PopulationId, Time);
+
+/// Build a [`TreeSequence`] from rows in a single expression.
+///
+/// Rows are stored by the builder and only added to
+/// tables when [`TableCollectionBuilder::build`] is called.
+/// The row ids are assigned in the order that rows are added
+/// to each table, starting from zero.
+///
+/// Created by [`TableCollection::builder`].
+///
+/// # Examples
+///
+/// ```
+/// let treeseq = tskit::TableCollection::builder()
+///     .sequence_length(100.)
+///     .add_node(0, 1.0, -1, -1)
+///     .add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, -1, -1)
+///     .add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, -1, -1)
+///     // Edges need not be sorted
+///     .add_edge(0., 100., 0, 2)
+///     .add_edge(0., 100., 0, 1)
+///     .build()
+///     .unwrap();
+/// assert_eq!(treeseq.num_trees(), 1);
+/// assert_eq!(treeseq.num_samples(), 2);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TableCollectionBuilder {
+    sequence_length: Option<Position>,
+    nodes: Vec<(NodeFlags, Time, PopulationId, IndividualId)>,
+    edges: Vec<(Position, Position, NodeId, NodeId)>,
+    individuals: Vec<(IndividualFlags, Vec<Location>, Vec<IndividualId>)>,
+    num_populations: usize,
+    sites: Vec<(Position, Option<Vec<u8>>)>,
+    mutations: Vec<MutationRow>,
+    migrations: Vec<MigrationRow>,
+}
+
+impl TableCollectionBuilder {
+    /// Set the sequence length.
+    ///
+    /// This value is required.
+    pub fn sequence_length<P: Into<Position>>(mut self, sequence_length: P) -> Self {
+        self.sequence_length = Some(sequence_length.into());
+        self
+    }
+
+    /// Add a row to the node table.
+    ///
+    /// See [`TableCollection::add_node`].
+    pub fn add_node<F, T, P, I>(mut self, flags: F, time: T, population: P, individual: I) -> Self
+    where
+        F: Into<NodeFlags>,
+        T: Into<Time>,
+        P: Into<PopulationId>,
+        I: Into<IndividualId>,
+    {
+        self.nodes.push((
+            flags.into(),
+            time.into(),
+            population.into(),
+            individual.into(),
+        ));
+        self
+    }
+
+    /// Add a row to the edge table.
+    ///
+    /// See [`TableCollection::add_edge`].
+    pub fn add_edge<L, R, P, C>(mut self, left: L, right: R, parent: P, child: C) -> Self
+    where
+        L: Into<Position>,
+        R: Into<Position>,
+        P: Into<NodeId>,
+        C: Into<NodeId>,
+    {
+        self.edges
+            .push((left.into(), right.into(), parent.into(), child.into()));
+        self
+    }
+
+    /// Add a row to the individual table.
+    ///
+    /// See [`TableCollection::add_individual`].
+    pub fn add_individual<F, L, P>(mut self, flags: F, location: L, parents: P) -> Self
+    where
+        F: Into<IndividualFlags>,
+        L: IndividualLocation,
+        P: IndividualParents,
+    {
+        self.individuals.push((
+            flags.into(),
+            location.get_slice().to_vec(),
+            parents.get_slice().to_vec(),
+        ));
+        self
+    }
+
+    /// Add a row to the population table.
+    ///
+    /// See [`TableCollection::add_population`].
+    pub fn add_population(mut self) -> Self {
+        self.num_populations += 1;
+        self
+    }
+
+    /// Add a row to the site table.
+    ///
+    /// See [`TableCollection::add_site`].
+    pub fn add_site<P: Into<Position>>(
+        mut self,
+        position: P,
+        ancestral_state: Option<&[u8]>,
+    ) -> Self {
+        self.sites
+            .push((position.into(), ancestral_state.map(|s| s.to_vec())));
+        self
+    }
+
+    /// Add a row to the mutation table.
+    ///
+    /// See [`TableCollection::add_mutation`].
+    pub fn add_mutation<S, N, P, T>(
+        mut self,
+        site: S,
+        node: N,
+        parent: P,
+        time: T,
+        derived_state: Option<&[u8]>,
+    ) -> Self
+    where
+        S: Into<SiteId>,
+        N: Into<NodeId>,
+        P: Into<MutationId>,
+        T: Into<Time>,
+    {
+        self.mutations.push((
+            site.into(),
+            node.into(),
+            parent.into(),
+            time.into(),
+            derived_state.map(|s| s.to_vec()),
+        ));
+        self
+    }
+
+    /// Add a row to the migration table.
+    ///
+    /// See [`TableCollection::add_migration`].
+    pub fn add_migration<LEFT, RIGHT, N, SOURCE, DEST, T>(
+        mut self,
+        span: (LEFT, RIGHT),
+        node: N,
+        source_dest: (SOURCE, DEST),
+        time: T,
+    ) -> Self
+    where
+        LEFT: Into<Position>,
+        RIGHT: Into<Position>,
+        N: Into<NodeId>,
+        SOURCE: Into<PopulationId>,
+        DEST: Into<PopulationId>,
+        T: Into<Time>,
+    {
+        self.migrations.push((
+            span.0.into(),
+            span.1.into(),
+            node.into(),
+            source_dest.0.into(),
+            source_dest.1.into(),
+            time.into(),
+        ));
+        self
+    }
+
+    /// Add all rows to a new [`TableCollection`],
+    /// which is then sorted and indexed.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::ValueError`] if the sequence length was not set.
+    /// * [`TskitError`] if any row cannot be added, or if sorting
+    ///   or indexing fails.
+    pub fn build_tables(self) -> Result<TableCollection, TskitError> {
+        let sequence_length = self.sequence_length.ok_or_else(|| TskitError::ValueError {
+            got: "no sequence length".to_string(),
+            expected: "a call to TableCollectionBuilder::sequence_length".to_string(),
+        })?;
+        let mut tables = TableCollection::new(sequence_length)?;
+        for _ in 0..self.num_populations {
+            tables.add_population()?;
+        }
+        for (flags, location, parents) in &self.individuals {
+            tables.add_individual(*flags, location, parents)?;
+        }
+        for &(flags, time, population, individual) in &self.nodes {
+            tables.add_node(flags, time, population, individual)?;
+        }
+        for &(left, right, parent, child) in &self.edges {
+            tables.add_edge(left, right, parent, child)?;
+        }
+        for (position, ancestral_state) in &self.sites {
+            tables.add_site(*position, ancestral_state.as_deref())?;
+        }
+        for (site, node, parent, time, derived_state) in &self.mutations {
+            tables.add_mutation(*site, *node, *parent, *time, derived_state.as_deref())?;
+        }
+        for &(left, right, node, source, dest, time) in &self.migrations {
+            tables.add_migration((left, right), node, (source, dest), time)?;
+        }
+        tables.full_sort(TableSortOptions::default())?;
+        tables.build_index()?;
+        Ok(tables)
+    }
+
+    /// Build the tables and create a [`TreeSequence`] from them.
+    ///
+    /// # Errors
+    ///
+    /// See [`TableCollectionBuilder::build_tables`] and [`TreeSequence::new`].
+    pub fn build(self) -> Result<TreeSequence, TskitError> {
+        TreeSequence::new(self.build_tables()?, TreeSequenceFlags::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_sequence_length() {
+        let builder = TableCollection::builder().add_node(0, 0.0, -1, -1);
+        assert!(matches!(
+            builder.build(),
+            Err(TskitError::ValueError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_build_tables() {
+        let tables = TableCollection::builder()
+            .sequence_length(10.)
+            .add_population()
+            .add_individual(0, [0.5, 1.5], None)
+            .add_node(0, 1.0, 0, -1)
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, 0, 0)
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, 0, 0)
+            .add_edge(5., 10., 0, 2)
+            .add_edge(0., 10., 0, 1)
+            .add_edge(0., 5., 0, 2)
+            .add_site(7., Some(b"A"))
+            .add_site(2., Some(b"G"))
+            .add_mutation(0, 1, -1, 0.5, Some(b"T"))
+            .add_mutation(1, 2, -1, 0.5, Some(b"C"))
+            .build_tables()
+            .unwrap();
+        assert_eq!(tables.populations().num_rows(), 1);
+        assert_eq!(tables.individuals().num_rows(), 1);
+        assert_eq!(tables.nodes().num_rows(), 3);
+        assert_eq!(tables.edges().num_rows(), 3);
+        assert!(tables.is_indexed());
+        // Sorting reorders the sites and remaps the mutations.
+        assert_eq!(tables.sites().position(0).unwrap(), 2.);
+        assert_eq!(tables.mutations().site(0).unwrap(), 0);
+        assert_eq!(tables.mutations().node(0).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_invalid_row() {
+        let result = TableCollection::builder()
+            .sequence_length(10.)
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, -1, -1)
+            .add_edge(0., 10., 1, 0)
+            .build();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,368 @@
+//! `serde` support for [`TableCollection`].
+//!
+//! A collection is represented as a map containing the sequence length,
+//! time units, top-level metadata and schema, and one sequence of rows per
+//! table.
+//! Rows use the representation of the `*TableRow` types, including their
+//! ids, which must match the position of each row in its table.
+//! Table metadata schemas, the reference sequence, and edge indexes
+//! are not part of the representation.
+
+use crate::bindings as ll_bindings;
+use crate::EdgeTableRow;
+use crate::IndividualTableRow;
+use crate::MigrationTableRow;
+use crate::MutationTableRow;
+use crate::NodeTableRow;
+use crate::PopulationTableRow;
+use crate::Position;
+use crate::SiteTableRow;
+use crate::TableCollection;
+use crate::TskitError;
+use crate::TskitTypeAccess;
+use ll_bindings::{tsk_id_t, tsk_size_t};
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ProvenanceRow {
+    timestamp: String,
+    record: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct TableCollectionRepr {
+    sequence_length: Position,
+    #[serde(default = "unknown_time_units")]
+    time_units: String,
+    #[serde(default)]
+    metadata: Vec<u8>,
+    #[serde(default)]
+    metadata_schema: String,
+    #[serde(default)]
+    individuals: Vec<IndividualTableRow>,
+    #[serde(default)]
+    nodes: Vec<NodeTableRow>,
+    #[serde(default)]
+    edges: Vec<EdgeTableRow>,
+    #[serde(default)]
+    migrations: Vec<MigrationTableRow>,
+    #[serde(default)]
+    sites: Vec<SiteTableRow>,
+    #[serde(default)]
+    mutations: Vec<MutationTableRow>,
+    #[serde(default)]
+    populations: Vec<PopulationTableRow>,
+    #[serde(default)]
+    provenances: Vec<ProvenanceRow>,
+}
+
+// Matches the time units set by tsk_table_collection_init.
+fn unknown_time_units() -> String {
+    String::from_utf8_lossy(ll_bindings::TSK_TIME_UNITS_UNKNOWN)
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+fn string(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+fn len(bytes: &[u8]) -> tsk_size_t {
+    crate::SizeType::from(bytes.len()).into()
+}
+
+fn provenance_rows(table: &ll_bindings::tsk_provenance_table_t) -> Vec<ProvenanceRow> {
+    let timestamp = table_column!(@bytes table, timestamp, table.timestamp_length);
+    let timestamp_offset = table_column!(table, timestamp_offset, table.num_rows + 1);
+    let record = table_column!(@bytes table, record, table.record_length);
+    let record_offset = table_column!(table, record_offset, table.num_rows + 1);
+    let ragged = |data: &[u8], offset: &[tsk_size_t], i: usize| {
+        string(&data[offset[i] as usize..offset[i + 1] as usize])
+    };
+    (0..table.num_rows as usize)
+        .map(|i| ProvenanceRow {
+            timestamp: ragged(timestamp, timestamp_offset, i),
+            record: ragged(record, record_offset, i),
+        })
+        .collect()
+}
+
+impl TableCollectionRepr {
+    fn new(tables: &TableCollection) -> Self {
+        let raw = unsafe { &*tables.as_ptr() };
+        Self {
+            sequence_length: tables.sequence_length(),
+            time_units: string(table_column!(@bytes raw, time_units, raw.time_units_length)),
+            metadata: table_column!(@bytes raw, metadata, raw.metadata_length).to_vec(),
+            metadata_schema: tables.metadata_schema().unwrap_or_default().to_string(),
+            individuals: tables.individuals().iter().collect(),
+            nodes: tables.nodes().iter().collect(),
+            edges: tables.edges().iter().collect(),
+            migrations: tables.migrations().iter().collect(),
+            sites: tables.sites().iter().collect(),
+            mutations: tables.mutations().iter().collect(),
+            populations: tables.populations().iter().collect(),
+            provenances: provenance_rows(&raw.provenances),
+        }
+    }
+
+    fn into_tables(self) -> Result<TableCollection, TskitError> {
+        let mut tables = TableCollection::new(self.sequence_length)?;
+
+        fn check(table: &str, id: i32, expected: usize, rv: tsk_id_t) -> Result<(), TskitError> {
+            if rv < 0 {
+                return Err(TskitError::ErrorCode { code: rv });
+            }
+            if id < 0 || id as usize != expected {
+                return Err(TskitError::ValueError {
+                    got: format!("{} row with id {}", table, id),
+                    expected: format!("id {}", expected),
+                });
+            }
+            Ok(())
+        }
+
+        fn metadata(md: &Option<Vec<u8>>) -> &[u8] {
+            md.as_deref().unwrap_or(&[])
+        }
+
+        let raw = unsafe { &mut *tables.as_mut_ptr() };
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_set_time_units(
+                raw,
+                self.time_units.as_ptr().cast::<libc::c_char>(),
+                len(self.time_units.as_bytes()),
+            )
+        };
+        check("time units", 0, 0, rv)?;
+        tables.set_metadata_schema(&self.metadata_schema)?;
+        let raw = unsafe { &mut *tables.as_mut_ptr() };
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_set_metadata(
+                raw,
+                self.metadata.as_ptr().cast::<libc::c_char>(),
+                len(&self.metadata),
+            )
+        };
+        check("metadata", 0, 0, rv)?;
+
+        for (i, row) in self.individuals.iter().enumerate() {
+            let location = row.location.as_deref().unwrap_or(&[]);
+            let parents = row.parents.as_deref().unwrap_or(&[]);
+            let md = metadata(&row.metadata);
+            let rv = unsafe {
+                ll_bindings::tsk_individual_table_add_row(
+                    &mut raw.individuals,
+                    row.flags.bits(),
+                    location.as_ptr().cast::<f64>(),
+                    crate::SizeType::from(location.len()).into(),
+                    parents.as_ptr().cast::<tsk_id_t>(),
+                    crate::SizeType::from(parents.len()).into(),
+                    md.as_ptr().cast::<libc::c_char>(),
+                    len(md),
+                )
+            };
+            check("individual", row.id.into(), i, rv)?;
+        }
+        for (i, row) in self.nodes.iter().enumerate() {
+            let md = metadata(&row.metadata);
+            let rv = unsafe {
+                ll_bindings::tsk_node_table_add_row(
+                    &mut raw.nodes,
+                    row.flags.bits(),
+                    row.time.into(),
+                    row.population.into(),
+                    row.individual.into(),
+                    md.as_ptr().cast::<libc::c_char>(),
+                    len(md),
+                )
+            };
+            check("node", row.id.into(), i, rv)?;
+        }
+        for (i, row) in self.edges.iter().enumerate() {
+            let md = metadata(&row.metadata);
+            let rv = unsafe {
+                ll_bindings::tsk_edge_table_add_row(
+                    &mut raw.edges,
+                    row.left.into(),
+                    row.right.into(),
+                    row.parent.into(),
+                    row.child.into(),
+                    md.as_ptr().cast::<libc::c_char>(),
+                    len(md),
+                )
+            };
+            check("edge", row.id.into(), i, rv)?;
+        }
+        for (i, row) in self.migrations.iter().enumerate() {
+            let md = metadata(&row.metadata);
+            let rv = unsafe {
+                ll_bindings::tsk_migration_table_add_row(
+                    &mut raw.migrations,
+                    row.left.into(),
+                    row.right.into(),
+                    row.node.into(),
+                    row.source.into(),
+                    row.dest.into(),
+                    row.time.into(),
+                    md.as_ptr().cast::<libc::c_char>(),
+                    len(md),
+                )
+            };
+            check("migration", row.id.into(), i, rv)?;
+        }
+        for (i, row) in self.sites.iter().enumerate() {
+            let state = metadata(&row.ancestral_state);
+            let md = metadata(&row.metadata);
+            let rv = unsafe {
+                ll_bindings::tsk_site_table_add_row(
+                    &mut raw.sites,
+                    row.position.into(),
+                    state.as_ptr().cast::<libc::c_char>(),
+                    len(state),
+                    md.as_ptr().cast::<libc::c_char>(),
+                    len(md),
+                )
+            };
+            check("site", row.id.into(), i, rv)?;
+        }
+        for (i, row) in self.mutations.iter().enumerate() {
+            let state = metadata(&row.derived_state);
+            let md = metadata(&row.metadata);
+            let rv = unsafe {
+                ll_bindings::tsk_mutation_table_add_row(
+                    &mut raw.mutations,
+                    row.site.into(),
+                    row.node.into(),
+                    row.parent.into(),
+                    row.time.into(),
+                    state.as_ptr().cast::<libc::c_char>(),
+                    len(state),
+                    md.as_ptr().cast::<libc::c_char>(),
+                    len(md),
+                )
+            };
+            check("mutation", row.id.into(), i, rv)?;
+        }
+        for (i, row) in self.populations.iter().enumerate() {
+            let md = metadata(&row.metadata);
+            let rv = unsafe {
+                ll_bindings::tsk_population_table_add_row(
+                    &mut raw.populations,
+                    md.as_ptr().cast::<libc::c_char>(),
+                    len(md),
+                )
+            };
+            check("population", row.id.into(), i, rv)?;
+        }
+        for (i, row) in self.provenances.iter().enumerate() {
+            let rv = unsafe {
+                ll_bindings::tsk_provenance_table_add_row(
+                    &mut raw.provenances,
+                    row.timestamp.as_ptr().cast::<libc::c_char>(),
+                    len(row.timestamp.as_bytes()),
+                    row.record.as_ptr().cast::<libc::c_char>(),
+                    len(row.record.as_bytes()),
+                )
+            };
+            check("provenance", i as i32, i, rv)?;
+        }
+        Ok(tables)
+    }
+}
+
+/// Requires the `serde` feature.
+///
+/// See the [`serde` representation](TableCollection#serde-representation).
+impl serde::Serialize for TableCollection {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TableCollectionRepr::new(self).serialize(serializer)
+    }
+}
+
+/// Requires the `serde` feature.
+///
+/// See the [`serde` representation](TableCollection#serde-representation).
+impl<'de> serde::Deserialize<'de> for TableCollection {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        TableCollectionRepr::deserialize(deserializer)?
+            .into_tables()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TableEqualityOptions;
+
+    fn make_tables() -> TableCollection {
+        let mut tables = TableCollection::builder()
+            .sequence_length(10.)
+            .add_population()
+            .add_individual(0, [0.5, 1.5], None)
+            .add_node(0, 1.0, 0, -1)
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, 0, 0)
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, 0, 0)
+            .add_edge(0., 10., 0, 1)
+            .add_edge(0., 10., 0, 2)
+            .add_site(5., Some(b"A"))
+            .add_mutation(0, 1, -1, 0.5, Some(b"G"))
+            .build_tables()
+            .unwrap();
+        tables
+            .packset_node_metadata(&[&b"root"[..], b"", b""])
+            .unwrap();
+        tables.set_metadata_schema("{\"codec\":\"json\"}").unwrap();
+        #[cfg(feature = "provenance")]
+        tables
+            .add_provenance_with_timestamp("2022-01-01T00:00:00Z", "test")
+            .unwrap();
+        tables
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let tables = make_tables();
+        let json = serde_json::to_string(&tables).unwrap();
+        let decoded: TableCollection = serde_json::from_str(&json).unwrap();
+        assert!(tables.equals(&decoded, TableEqualityOptions::default()));
+    }
+
+    #[test]
+    fn test_hand_written_fixture() {
+        let json = r#"{
+            "sequence_length": 1.0,
+            "nodes": [
+                {"id": 0, "time": 1.0, "flags": 0, "population": -1, "individual": -1, "metadata": null},
+                {"id": 1, "time": 0.0, "flags": 1, "population": -1, "individual": -1, "metadata": null}
+            ],
+            "edges": [
+                {"id": 0, "left": 0.0, "right": 1.0, "parent": 0, "child": 1, "metadata": null}
+            ]
+        }"#;
+        let tables: TableCollection = serde_json::from_str(json).unwrap();
+        assert_eq!(tables.nodes().num_rows(), 2);
+        assert_eq!(tables.edges().num_rows(), 1);
+        assert_eq!(
+            tables.nodes().flags(1).unwrap(),
+            crate::NodeFlags::IS_SAMPLE
+        );
+    }
+
+    #[test]
+    fn test_minimal_document_equals_new() {
+        let tables: TableCollection = serde_json::from_str(r#"{"sequence_length": 1.0}"#).unwrap();
+        let expected = TableCollection::new(1.0).unwrap();
+        assert!(tables.equals(&expected, TableEqualityOptions::default()));
+        assert_eq!(tables.time_units().unwrap(), "unknown");
+    }
+
+    #[test]
+    fn test_out_of_order_ids() {
+        let json = r#"{
+            "sequence_length": 1.0,
+            "populations": [{"id": 1, "metadata": null}]
+        }"#;
+        assert!(serde_json::from_str::<TableCollection>(json).is_err());
+    }
+}
@@ -0,0 +1,687 @@
+//! Row-by-row comparison of table collections.
+//!
+//! [`TableCollection::equals`](crate::TableCollection::equals) only
+//! reports whether two collections differ.
+//! The functions here find where they differ, applying the same
+//! [`TableEqualityOptions`](crate::TableEqualityOptions) as the `C` API.
+
+use crate::bindings as ll_bindings;
+use crate::types::TableKind;
+use crate::Position;
+use crate::SizeType;
+use ll_bindings::tsk_size_t;
+
+/// A single difference found by
+/// [`TableCollection::diff`](crate::TableCollection::diff).
+///
+/// # Examples
+///
+/// ```
+/// use tskit::types::TableKind;
+/// use tskit::TableDifference;
+///
+/// let mut a = tskit::TableCollection::new(10.).unwrap();
+/// a.add_node(0, 1.0, -1, -1).unwrap();
+/// a.add_node(0, 0.0, -1, -1).unwrap();
+/// let mut b = a.deepcopy().unwrap();
+/// b.add_node(0, 0.0, -1, -1).unwrap();
+/// b.update_node(0, 0, 2.0, -1, -1).unwrap();
+///
+/// let diff = a.diff(&b, tskit::TableEqualityOptions::default());
+/// assert_eq!(diff.len(), 2);
+/// assert!(matches!(
+///     diff[0],
+///     TableDifference::NumRows { table: TableKind::Nodes, .. }
+/// ));
+/// assert_eq!(diff[1].to_string(), "node table, row 0: time differs");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum TableDifference {
+    /// The sequence lengths differ.
+    SequenceLength { left: Position, right: Position },
+    /// The time units differ.
+    TimeUnits,
+    /// The top-level metadata differ.
+    Metadata,
+    /// The metadata schemas differ.
+    /// `table` is `None` for the top-level schema.
+    MetadataSchema { table: Option<TableKind> },
+    /// The reference sequences differ.
+    ReferenceSequence,
+    /// A table has a different number of rows.
+    /// Rows beyond the shorter of the two tables
+    /// are not compared.
+    NumRows {
+        table: TableKind,
+        left: SizeType,
+        right: SizeType,
+    },
+    /// A column differs in a given row.
+    Column {
+        table: TableKind,
+        row: SizeType,
+        column: &'static str,
+    },
+    /// The metadata of a given row differ.
+    RowMetadata { table: TableKind, row: SizeType },
+}
+
+impl std::fmt::Display for TableDifference {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TableDifference::SequenceLength { left, right } => {
+                write!(f, "sequence length differs: {} != {}", left, right)
+            }
+            TableDifference::TimeUnits => write!(f, "time units differ"),
+            TableDifference::Metadata => write!(f, "top-level metadata differ"),
+            TableDifference::MetadataSchema { table: None } => {
+                write!(f, "top-level metadata schema differs")
+            }
+            TableDifference::MetadataSchema { table: Some(table) } => {
+                write!(f, "{} table: metadata schema differs", table)
+            }
+            TableDifference::ReferenceSequence => write!(f, "reference sequence differs"),
+            TableDifference::NumRows { table, left, right } => write!(
+                f,
+                "{} table: number of rows differs: {} != {}",
+                table,
+                u64::from(*left),
+                u64::from(*right)
+            ),
+            TableDifference::Column { table, row, column } => write!(
+                f,
+                "{} table, row {}: {} differs",
+                table,
+                u64::from(*row),
+                column
+            ),
+            TableDifference::RowMetadata { table, row } => {
+                write!(
+                    f,
+                    "{} table, row {}: metadata differ",
+                    table,
+                    u64::from(*row)
+                )
+            }
+        }
+    }
+}
+
+// Returns true if row i of a column is the same in both tables.
+type RowComparison<'a> = Box<dyn Fn(usize) -> bool + 'a>;
+
+fn fixed<'a, T: PartialEq>(left: &'a [T], right: &'a [T]) -> RowComparison<'a> {
+    Box::new(move |i| left[i] == right[i])
+}
+
+// Compares bit patterns, so that NaN values
+// (such as unknown mutation times) are equal.
+fn fixed_f64<'a>(left: &'a [f64], right: &'a [f64]) -> RowComparison<'a> {
+    Box::new(move |i| left[i].to_bits() == right[i].to_bits())
+}
+
+struct RaggedColumn<'a, T> {
+    data: &'a [T],
+    offset: &'a [tsk_size_t],
+}
+
+impl<'a, T> RaggedColumn<'a, T> {
+    fn row(&self, i: usize) -> &'a [T] {
+        &self.data[self.offset[i] as usize..self.offset[i + 1] as usize]
+    }
+}
+
+fn ragged<'a, T: PartialEq>(
+    left: RaggedColumn<'a, T>,
+    right: RaggedColumn<'a, T>,
+) -> RowComparison<'a> {
+    Box::new(move |i| left.row(i) == right.row(i))
+}
+
+macro_rules! ragged_column {
+    ($table: expr, $column: ident, $offset: ident, $length: ident) => {
+        RaggedColumn {
+            data: table_column!($table, $column, $table.$length),
+            offset: table_column!($table, $offset, $table.num_rows + 1),
+        }
+    };
+}
+
+macro_rules! ragged_char_column {
+    ($table: expr, $column: ident, $offset: ident, $length: ident) => {
+        RaggedColumn {
+            data: table_column!(@bytes $table, $column, $table.$length),
+            offset: table_column!($table, $offset, $table.num_rows + 1),
+        }
+    };
+}
+
+macro_rules! metadata_column {
+    ($table: expr) => {
+        ragged_char_column!($table, metadata, metadata_offset, metadata_length)
+    };
+}
+
+struct Differ {
+    options: u32,
+    differences: Vec<TableDifference>,
+}
+
+impl Differ {
+    fn ignore(&self, flag: u32) -> bool {
+        self.options & flag != 0
+    }
+
+    fn ignore_metadata(&self) -> bool {
+        self.ignore(ll_bindings::TSK_CMP_IGNORE_METADATA)
+    }
+
+    fn bytes(&mut self, left: &[u8], right: &[u8], difference: TableDifference) {
+        if left != right {
+            self.differences.push(difference);
+        }
+    }
+
+    fn table(
+        &mut self,
+        table: TableKind,
+        num_rows: (tsk_size_t, tsk_size_t),
+        schemas: (&[u8], &[u8]),
+        columns: Vec<(&'static str, RowComparison)>,
+    ) {
+        if num_rows.0 != num_rows.1 {
+            self.differences.push(TableDifference::NumRows {
+                table,
+                left: num_rows.0.into(),
+                right: num_rows.1.into(),
+            });
+        }
+        let has_metadata = table != TableKind::Provenances;
+        if has_metadata && !self.ignore_metadata() {
+            self.bytes(
+                schemas.0,
+                schemas.1,
+                TableDifference::MetadataSchema { table: Some(table) },
+            );
+        }
+        for i in 0..num_rows.0.min(num_rows.1) as usize {
+            for (name, equal) in &columns {
+                if equal(i) {
+                    continue;
+                }
+                let row = SizeType::from(i);
+                if *name == "metadata" {
+                    self.differences
+                        .push(TableDifference::RowMetadata { table, row });
+                } else {
+                    self.differences.push(TableDifference::Column {
+                        table,
+                        row,
+                        column: name,
+                    });
+                }
+            }
+        }
+    }
+
+    fn with_metadata<'a>(
+        &self,
+        mut columns: Vec<(&'static str, RowComparison<'a>)>,
+        metadata: RowComparison<'a>,
+    ) -> Vec<(&'static str, RowComparison<'a>)> {
+        if !self.ignore_metadata() {
+            columns.push(("metadata", metadata));
+        }
+        columns
+    }
+}
+
+pub(crate) fn diff(
+    left: &ll_bindings::tsk_table_collection_t,
+    right: &ll_bindings::tsk_table_collection_t,
+    options: u32,
+) -> Vec<TableDifference> {
+    let mut differ = Differ {
+        options,
+        differences: vec![],
+    };
+
+    if left.sequence_length.to_bits() != right.sequence_length.to_bits() {
+        differ.differences.push(TableDifference::SequenceLength {
+            left: left.sequence_length.into(),
+            right: right.sequence_length.into(),
+        });
+    }
+    differ.bytes(
+        table_column!(@bytes left, time_units, left.time_units_length),
+        table_column!(@bytes right, time_units, right.time_units_length),
+        TableDifference::TimeUnits,
+    );
+    // TSK_CMP_IGNORE_TS_METADATA is implied by TSK_CMP_IGNORE_METADATA
+    if !differ.ignore_metadata() && !differ.ignore(ll_bindings::TSK_CMP_IGNORE_TS_METADATA) {
+        differ.bytes(
+            table_column!(@bytes left, metadata, left.metadata_length),
+            table_column!(@bytes right, metadata, right.metadata_length),
+            TableDifference::Metadata,
+        );
+        differ.bytes(
+            table_column!(@bytes left, metadata_schema, left.metadata_schema_length),
+            table_column!(@bytes right, metadata_schema, right.metadata_schema_length),
+            TableDifference::MetadataSchema { table: None },
+        );
+    }
+    if !differ.ignore(ll_bindings::TSK_CMP_IGNORE_REFERENCE_SEQUENCE) {
+        let equal = unsafe {
+            ll_bindings::tsk_reference_sequence_equals(
+                &left.reference_sequence,
+                &right.reference_sequence,
+                options,
+            )
+        };
+        if !equal {
+            differ.differences.push(TableDifference::ReferenceSequence);
+        }
+    }
+    if !differ.ignore(ll_bindings::TSK_CMP_IGNORE_TABLES) {
+        diff_tables(&mut differ, left, right);
+    }
+    differ.differences
+}
+
+fn diff_tables(
+    differ: &mut Differ,
+    left: &ll_bindings::tsk_table_collection_t,
+    right: &ll_bindings::tsk_table_collection_t,
+) {
+    let (l, r) = (&left.individuals, &right.individuals);
+    let columns = differ.with_metadata(
+        vec![
+            (
+                "flags",
+                fixed(table_column!(l, flags), table_column!(r, flags)),
+            ),
+            (
+                "location",
+                ragged(
+                    ragged_column!(l, location, location_offset, location_length),
+                    ragged_column!(r, location, location_offset, location_length),
+                ),
+            ),
+            (
+                "parents",
+                ragged(
+                    ragged_column!(l, parents, parents_offset, parents_length),
+                    ragged_column!(r, parents, parents_offset, parents_length),
+                ),
+            ),
+        ],
+        ragged(metadata_column!(l), metadata_column!(r)),
+    );
+    differ.table(
+        TableKind::Individuals,
+        (l.num_rows, r.num_rows),
+        (
+            table_column!(@bytes l, metadata_schema, l.metadata_schema_length),
+            table_column!(@bytes r, metadata_schema, r.metadata_schema_length),
+        ),
+        columns,
+    );
+
+    let (l, r) = (&left.nodes, &right.nodes);
+    let columns = differ.with_metadata(
+        vec![
+            (
+                "flags",
+                fixed(table_column!(l, flags), table_column!(r, flags)),
+            ),
+            (
+                "time",
+                fixed_f64(table_column!(l, time), table_column!(r, time)),
+            ),
+            (
+                "population",
+                fixed(table_column!(l, population), table_column!(r, population)),
+            ),
+            (
+                "individual",
+                fixed(table_column!(l, individual), table_column!(r, individual)),
+            ),
+        ],
+        ragged(metadata_column!(l), metadata_column!(r)),
+    );
+    differ.table(
+        TableKind::Nodes,
+        (l.num_rows, r.num_rows),
+        (
+            table_column!(@bytes l, metadata_schema, l.metadata_schema_length),
+            table_column!(@bytes r, metadata_schema, r.metadata_schema_length),
+        ),
+        columns,
+    );
+
+    let (l, r) = (&left.edges, &right.edges);
+    let columns = differ.with_metadata(
+        vec![
+            (
+                "left",
+                fixed_f64(table_column!(l, left), table_column!(r, left)),
+            ),
+            (
+                "right",
+                fixed_f64(table_column!(l, right), table_column!(r, right)),
+            ),
+            (
+                "parent",
+                fixed(table_column!(l, parent), table_column!(r, parent)),
+            ),
+            (
+                "child",
+                fixed(table_column!(l, child), table_column!(r, child)),
+            ),
+        ],
+        ragged(metadata_column!(l), metadata_column!(r)),
+    );
+    differ.table(
+        TableKind::Edges,
+        (l.num_rows, r.num_rows),
+        (
+            table_column!(@bytes l, metadata_schema, l.metadata_schema_length),
+            table_column!(@bytes r, metadata_schema, r.metadata_schema_length),
+        ),
+        columns,
+    );
+
+    let (l, r) = (&left.migrations, &right.migrations);
+    let columns = differ.with_metadata(
+        vec![
+            (
+                "left",
+                fixed_f64(table_column!(l, left), table_column!(r, left)),
+            ),
+            (
+                "right",
+                fixed_f64(table_column!(l, right), table_column!(r, right)),
+            ),
+            (
+                "node",
+                fixed(table_column!(l, node), table_column!(r, node)),
+            ),
+            (
+                "source",
+                fixed(table_column!(l, source), table_column!(r, source)),
+            ),
+            (
+                "dest",
+                fixed(table_column!(l, dest), table_column!(r, dest)),
+            ),
+            (
+                "time",
+                fixed_f64(table_column!(l, time), table_column!(r, time)),
+            ),
+        ],
+        ragged(metadata_column!(l), metadata_column!(r)),
+    );
+    differ.table(
+        TableKind::Migrations,
+        (l.num_rows, r.num_rows),
+        (
+            table_column!(@bytes l, metadata_schema, l.metadata_schema_length),
+            table_column!(@bytes r, metadata_schema, r.metadata_schema_length),
+        ),
+        columns,
+    );
+
+    let (l, r) = (&left.sites, &right.sites);
+    let columns = differ.with_metadata(
+        vec![
+            (
+                "position",
+                fixed_f64(table_column!(l, position), table_column!(r, position)),
+            ),
+            (
+                "ancestral_state",
+                ragged(
+                    ragged_char_column!(
+                        l,
+                        ancestral_state,
+                        ancestral_state_offset,
+                        ancestral_state_length
+                    ),
+                    ragged_char_column!(
+                        r,
+                        ancestral_state,
+                        ancestral_state_offset,
+                        ancestral_state_length
+                    ),
+                ),
+            ),
+        ],
+        ragged(metadata_column!(l), metadata_column!(r)),
+    );
+    differ.table(
+        TableKind::Sites,
+        (l.num_rows, r.num_rows),
+        (
+            table_column!(@bytes l, metadata_schema, l.metadata_schema_length),
+            table_column!(@bytes r, metadata_schema, r.metadata_schema_length),
+        ),
+        columns,
+    );
+
+    let (l, r) = (&left.mutations, &right.mutations);
+    let columns = differ.with_metadata(
+        vec![
+            (
+                "site",
+                fixed(table_column!(l, site), table_column!(r, site)),
+            ),
+            (
+                "node",
+                fixed(table_column!(l, node), table_column!(r, node)),
+            ),
+            (
+                "parent",
+                fixed(table_column!(l, parent), table_column!(r, parent)),
+            ),
+            (
+                "time",
+                fixed_f64(table_column!(l, time), table_column!(r, time)),
+            ),
+            (
+                "derived_state",
+                ragged(
+                    ragged_char_column!(
+                        l,
+                        derived_state,
+                        derived_state_offset,
+                        derived_state_length
+                    ),
+                    ragged_char_column!(
+                        r,
+                        derived_state,
+                        derived_state_offset,
+                        derived_state_length
+                    ),
+                ),
+            ),
+        ],
+        ragged(metadata_column!(l), metadata_column!(r)),
+    );
+    differ.table(
+        TableKind::Mutations,
+        (l.num_rows, r.num_rows),
+        (
+            table_column!(@bytes l, metadata_schema, l.metadata_schema_length),
+            table_column!(@bytes r, metadata_schema, r.metadata_schema_length),
+        ),
+        columns,
+    );
+
+    let (l, r) = (&left.populations, &right.populations);
+    let columns = differ.with_metadata(vec![], ragged(metadata_column!(l), metadata_column!(r)));
+    differ.table(
+        TableKind::Populations,
+        (l.num_rows, r.num_rows),
+        (
+            table_column!(@bytes l, metadata_schema, l.metadata_schema_length),
+            table_column!(@bytes r, metadata_schema, r.metadata_schema_length),
+        ),
+        columns,
+    );
+
+    // TSK_CMP_IGNORE_TABLES implies TSK_CMP_IGNORE_PROVENANCE
+    if !differ.ignore(ll_bindings::TSK_CMP_IGNORE_PROVENANCE) {
+        let (l, r) = (&left.provenances, &right.provenances);
+        let mut columns: Vec<(&'static str, RowComparison)> = vec![];
+        if !differ.ignore(ll_bindings::TSK_CMP_IGNORE_TIMESTAMPS) {
+            columns.push((
+                "timestamp",
+                ragged(
+                    ragged_char_column!(l, timestamp, timestamp_offset, timestamp_length),
+                    ragged_char_column!(r, timestamp, timestamp_offset, timestamp_length),
+                ),
+            ));
+        }
+        columns.push((
+            "record",
+            ragged(
+                ragged_char_column!(l, record, record_offset, record_length),
+                ragged_char_column!(r, record, record_offset, record_length),
+            ),
+        ));
+        differ.table(
+            TableKind::Provenances,
+            (l.num_rows, r.num_rows),
+            (&[], &[]),
+            columns,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TableCollection;
+    use crate::TableEqualityOptions;
+
+    fn make_tables() -> TableCollection {
+        TableCollection::builder()
+            .sequence_length(10.)
+            .add_node(0, 1.0, -1, -1)
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, -1, -1)
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, -1, -1)
+            .add_edge(0., 10., 0, 1)
+            .add_edge(0., 10., 0, 2)
+            .add_site(5., Some(b"A"))
+            .add_mutation(0, 1, -1, 0.5, Some(b"G"))
+            .build_tables()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_equal_tables() {
+        let tables = make_tables();
+        let copy = tables.deepcopy().unwrap();
+        assert!(tables
+            .diff(&copy, TableEqualityOptions::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_unknown_mutation_times_are_equal() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        tables.add_site(0.5, None).unwrap();
+        tables
+            .add_mutation(0, 0, -1, crate::Time::from(f64::NAN), None)
+            .unwrap();
+        let copy = tables.deepcopy().unwrap();
+        assert!(tables.equals(&copy, TableEqualityOptions::default()));
+        assert!(tables
+            .diff(&copy, TableEqualityOptions::default())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_column_differences() {
+        let tables = make_tables();
+        let mut other = tables.deepcopy().unwrap();
+        other.update_edge(1, 0., 5., 0, 2).unwrap();
+        other.update_site(0, 5., Some(b"C")).unwrap();
+        let diff = tables.diff(&other, TableEqualityOptions::default());
+        assert!(!tables.equals(&other, TableEqualityOptions::default()));
+        assert_eq!(
+            diff,
+            vec![
+                TableDifference::Column {
+                    table: TableKind::Edges,
+                    row: SizeType::from(1_usize),
+                    column: "right"
+                },
+                TableDifference::Column {
+                    table: TableKind::Sites,
+                    row: SizeType::from(0_usize),
+                    column: "ancestral_state"
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_metadata_differences() {
+        let tables = make_tables();
+        let mut other = tables.deepcopy().unwrap();
+        other.packset_node_metadata(&[&b""[..], b"x", b""]).unwrap();
+        other.set_metadata_schema("{}").unwrap();
+        other.set_node_metadata_schema("{}").unwrap();
+        let diff = tables.diff(&other, TableEqualityOptions::default());
+        assert_eq!(
+            diff,
+            vec![
+                TableDifference::MetadataSchema { table: None },
+                TableDifference::MetadataSchema {
+                    table: Some(TableKind::Nodes)
+                },
+                TableDifference::RowMetadata {
+                    table: TableKind::Nodes,
+                    row: SizeType::from(1_usize)
+                },
+            ]
+        );
+        let diff = tables.diff(&other, TableEqualityOptions::IGNORE_TS_METADATA);
+        assert_eq!(diff.len(), 2);
+        assert!(tables.equals(&other, TableEqualityOptions::IGNORE_METADATA));
+        assert!(tables
+            .diff(&other, TableEqualityOptions::IGNORE_METADATA)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_num_rows_and_sequence_length() {
+        let tables = make_tables();
+        let mut other = TableCollection::new(20.).unwrap();
+        other.add_node(0, 1.0, -1, -1).unwrap();
+        let diff = tables.diff(&other, TableEqualityOptions::default());
+        assert_eq!(
+            diff[0],
+            TableDifference::SequenceLength {
+                left: 10.0.into(),
+                right: 20.0.into()
+            }
+        );
+        assert!(diff.contains(&TableDifference::NumRows {
+            table: TableKind::Nodes,
+            left: SizeType::from(3_usize),
+            right: SizeType::from(1_usize)
+        }));
+        assert!(diff.contains(&TableDifference::NumRows {
+            table: TableKind::Edges,
+            left: SizeType::from(2_usize),
+            right: SizeType::from(0_usize)
+        }));
+        // Only the first row of the node tables is compared.
+        assert!(!diff
+            .iter()
+            .any(|d| matches!(d, TableDifference::Column { .. })));
+    }
+}
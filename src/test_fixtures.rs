@@ -192,4 +192,6 @@ pub mod bad_metadata {
 
     impl crate::metadata::MutationMetadata for F {}
     impl crate::metadata::MutationMetadata for Ff {}
+    impl crate::metadata::TreeSequenceMetadata for F {}
+    impl crate::metadata::TreeSequenceMetadata for Ff {}
 }
@@ -5,7 +5,6 @@ mod tests {
     use crate::NodeFlags;
     use crate::NodeId;
     use crate::SimplificationOptions;
-    use crate::TableAccess;
 
     #[test]
     fn test_simplify_tables() {
@@ -40,4 +39,111 @@ mod tests {
             assert_ne!(idmap[usize::from(i)], NodeId::NULL);
         }
     }
+
+    #[test]
+    fn test_simplify_with_id_maps() {
+        use crate::metadata::MetadataRoundtrip;
+        use crate::{IndividualId, MutationId, PopulationId, SiteId};
+
+        struct Tag(u8);
+
+        impl MetadataRoundtrip for Tag {
+            fn encode(&self) -> Result<Vec<u8>, crate::metadata::MetadataError> {
+                Ok(vec![self.0])
+            }
+
+            fn decode(md: &[u8]) -> Result<Self, crate::metadata::MetadataError> {
+                Ok(Self(md[0]))
+            }
+        }
+        impl crate::metadata::SiteMetadata for Tag {}
+        impl crate::metadata::MutationMetadata for Tag {}
+
+        let mut tables = crate::TableCollection::new(10.).unwrap();
+        let p0 = tables.add_population().unwrap();
+        let p1 = tables.add_population().unwrap();
+        let i0 = tables.add_individual(0, None, None).unwrap();
+        let i1 = tables.add_individual(0, None, None).unwrap();
+        let root = tables.add_node(0, 2.0, p1, i0).unwrap();
+        let s0 = tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, p0, i1)
+            .unwrap();
+        let s1 = tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, p0, i1)
+            .unwrap();
+        tables.add_edge(0., 10., root, s0).unwrap();
+        tables.add_edge(0., 10., root, s1).unwrap();
+        // Only the mutations on s1 remain after simplifying w.r.t. s1
+        let site0 = tables.add_site_with_metadata(1., None, &Tag(0)).unwrap();
+        let site1 = tables.add_site_with_metadata(2., None, &Tag(1)).unwrap();
+        tables
+            .add_mutation_with_metadata(site0, s0, MutationId::NULL, 0.5, None, &Tag(2))
+            .unwrap();
+        tables
+            .add_mutation_with_metadata(site1, s0, MutationId::NULL, 0.5, None, &Tag(3))
+            .unwrap();
+        tables
+            .add_mutation_with_metadata(site1, s1, MutationId::NULL, 0.5, None, &Tag(4))
+            .unwrap();
+        tables
+            .full_sort(crate::TableSortOptions::default())
+            .unwrap();
+        tables.build_index().unwrap();
+        let ts = tables
+            .tree_sequence(crate::TreeSequenceFlags::default())
+            .unwrap();
+
+        let options = SimplificationOptions::FILTER_SITES
+            | SimplificationOptions::FILTER_POPULATIONS
+            | SimplificationOptions::FILTER_INDIVIDUALS;
+        let (simplified, maps) = ts.simplify_with_id_maps(&[s1], options).unwrap();
+        let (_, node_map) = ts.simplify(&[s1], options, true).unwrap();
+
+        assert_eq!(maps.nodes, node_map.unwrap());
+        assert_eq!(maps.nodes, [NodeId::NULL, NodeId::NULL, NodeId::from(0)]);
+        assert_eq!(
+            maps.populations,
+            [PopulationId::from(0), PopulationId::NULL]
+        );
+        assert_eq!(
+            maps.individuals,
+            [IndividualId::NULL, IndividualId::from(0)]
+        );
+        assert_eq!(maps.sites, [SiteId::NULL, SiteId::from(0)]);
+        assert_eq!(
+            maps.mutations,
+            [MutationId::NULL, MutationId::NULL, MutationId::from(0)]
+        );
+
+        // The output metadata are those of the input rows
+        assert_eq!(simplified.sites().metadata::<Tag>(0).unwrap().unwrap().0, 1);
+        assert_eq!(
+            simplified
+                .mutations()
+                .metadata::<Tag>(0)
+                .unwrap()
+                .unwrap()
+                .0,
+            4
+        );
+
+        // Without filtering, site, individual and population
+        // maps are the identity.
+        let (_, maps) = ts
+            .simplify_with_id_maps(&[s1], SimplificationOptions::default())
+            .unwrap();
+        assert_eq!(
+            maps.populations,
+            [PopulationId::from(0), PopulationId::from(1)]
+        );
+        assert_eq!(
+            maps.individuals,
+            [IndividualId::from(0), IndividualId::from(1)]
+        );
+        assert_eq!(maps.sites, [SiteId::from(0), SiteId::from(1)]);
+        assert_eq!(
+            maps.mutations,
+            [MutationId::NULL, MutationId::NULL, MutationId::from(0)]
+        );
+    }
 }
@@ -0,0 +1,664 @@
+//! Reading and writing tables in the `tskit` text format.
+//!
+//! Each table is a tab-separated file with a header line
+//! naming the columns.
+//! The format is the same as that of `tskit.load_text`
+//! and `TreeSequence.dump_text` from the `Python` API.
+//! Metadata are base64-encoded.
+//! States are written as text, with backslashes, control characters,
+//! whitespace other than interior spaces, and bytes that are not
+//! valid `UTF-8` escaped as `\xHH`.
+
+use crate::bindings as ll_bindings;
+use crate::tsk_id_t;
+use crate::util::{base64_decode, base64_encode};
+use crate::TableCollection;
+use crate::TskitError;
+use crate::TskitTypeAccess;
+use std::io::{BufRead, Write};
+
+/// Sources or destinations for the tables of a
+/// [`TableCollection`] in the `tskit` text format.
+///
+/// Tables that are `None` are skipped.
+///
+/// See [`TableCollection::dump_text`] and [`TableCollection::load_text`].
+pub struct TextTables<T> {
+    pub nodes: Option<T>,
+    pub edges: Option<T>,
+    pub sites: Option<T>,
+    pub mutations: Option<T>,
+    pub individuals: Option<T>,
+    pub populations: Option<T>,
+    pub migrations: Option<T>,
+}
+
+impl<T> Default for TextTables<T> {
+    fn default() -> Self {
+        Self {
+            nodes: None,
+            edges: None,
+            sites: None,
+            mutations: None,
+            individuals: None,
+            populations: None,
+            migrations: None,
+        }
+    }
+}
+
+fn encode_metadata(metadata: &Option<Vec<u8>>) -> String {
+    metadata.as_deref().map(base64_encode).unwrap_or_default()
+}
+
+fn escape_byte(encoded: &mut String, byte: u8) {
+    encoded.push_str(&format!("\\x{:02x}", byte));
+}
+
+fn encode_state(state: &Option<Vec<u8>>) -> String {
+    let state = state.as_deref().unwrap_or(&[]);
+    let mut encoded = String::with_capacity(state.len());
+    let mut offset = 0;
+    for chunk in state.utf8_chunks() {
+        for (i, c) in chunk.valid().char_indices() {
+            // Fields are trimmed on reading, so only interior spaces are kept as is
+            let pos = offset + i;
+            let interior_space = c == ' ' && pos > 0 && pos + 1 < state.len();
+            if c == '\\' || c.is_control() || (c.is_whitespace() && !interior_space) {
+                let mut buf = [0; 4];
+                for &b in c.encode_utf8(&mut buf).as_bytes() {
+                    escape_byte(&mut encoded, b);
+                }
+            } else {
+                encoded.push(c);
+            }
+        }
+        for &b in chunk.invalid() {
+            escape_byte(&mut encoded, b);
+        }
+        offset += chunk.valid().len() + chunk.invalid().len();
+    }
+    encoded
+}
+
+fn encode_list<T: std::fmt::Display>(values: impl Iterator<Item = T>) -> String {
+    values.map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+}
+
+fn encode_time(time: f64) -> String {
+    if unsafe { ll_bindings::tsk_is_unknown_time(time) } {
+        "unknown".to_string()
+    } else {
+        time.to_string()
+    }
+}
+
+pub(crate) fn dump_text<W: Write>(
+    tables: &TableCollection,
+    writers: &mut TextTables<W>,
+) -> Result<(), TskitError> {
+    if let Some(w) = writers.nodes.as_mut() {
+        writeln!(w, "id\tis_sample\ttime\tpopulation\tindividual\tmetadata")?;
+        for row in tables.nodes().iter() {
+            writeln!(
+                w,
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                tsk_id_t::from(row.id),
+                i32::from(row.flags.is_sample()),
+                f64::from(row.time),
+                tsk_id_t::from(row.population),
+                tsk_id_t::from(row.individual),
+                encode_metadata(&row.metadata)
+            )?;
+        }
+    }
+    if let Some(w) = writers.edges.as_mut() {
+        writeln!(w, "id\tleft\tright\tparent\tchild\tmetadata")?;
+        for row in tables.edges().iter() {
+            writeln!(
+                w,
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                tsk_id_t::from(row.id),
+                f64::from(row.left),
+                f64::from(row.right),
+                tsk_id_t::from(row.parent),
+                tsk_id_t::from(row.child),
+                encode_metadata(&row.metadata)
+            )?;
+        }
+    }
+    if let Some(w) = writers.sites.as_mut() {
+        writeln!(w, "id\tposition\tancestral_state\tmetadata")?;
+        for row in tables.sites().iter() {
+            writeln!(
+                w,
+                "{}\t{}\t{}\t{}",
+                tsk_id_t::from(row.id),
+                f64::from(row.position),
+                encode_state(&row.ancestral_state),
+                encode_metadata(&row.metadata)
+            )?;
+        }
+    }
+    if let Some(w) = writers.mutations.as_mut() {
+        writeln!(w, "id\tsite\tnode\ttime\tderived_state\tparent\tmetadata")?;
+        for row in tables.mutations().iter() {
+            writeln!(
+                w,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                tsk_id_t::from(row.id),
+                tsk_id_t::from(row.site),
+                tsk_id_t::from(row.node),
+                encode_time(f64::from(row.time)),
+                encode_state(&row.derived_state),
+                tsk_id_t::from(row.parent),
+                encode_metadata(&row.metadata)
+            )?;
+        }
+    }
+    if let Some(w) = writers.individuals.as_mut() {
+        writeln!(w, "id\tflags\tlocation\tparents\tmetadata")?;
+        for row in tables.individuals().iter() {
+            writeln!(
+                w,
+                "{}\t{}\t{}\t{}\t{}",
+                tsk_id_t::from(row.id),
+                row.flags.bits(),
+                encode_list(row.location.iter().flatten().map(|&l| f64::from(l))),
+                encode_list(row.parents.iter().flatten().map(|&p| tsk_id_t::from(p))),
+                encode_metadata(&row.metadata)
+            )?;
+        }
+    }
+    if let Some(w) = writers.populations.as_mut() {
+        writeln!(w, "id\tmetadata")?;
+        for row in tables.populations().iter() {
+            writeln!(
+                w,
+                "{}\t{}",
+                tsk_id_t::from(row.id),
+                encode_metadata(&row.metadata)
+            )?;
+        }
+    }
+    if let Some(w) = writers.migrations.as_mut() {
+        writeln!(w, "id\tleft\tright\tnode\tsource\tdest\ttime\tmetadata")?;
+        for row in tables.migrations().iter() {
+            writeln!(
+                w,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                tsk_id_t::from(row.id),
+                f64::from(row.left),
+                f64::from(row.right),
+                tsk_id_t::from(row.node),
+                tsk_id_t::from(row.source),
+                tsk_id_t::from(row.dest),
+                f64::from(row.time),
+                encode_metadata(&row.metadata)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// One row of a text table, with access to fields by column name.
+struct TextRow<'a> {
+    table: &'a str,
+    header: &'a [String],
+    fields: Vec<&'a str>,
+    line: usize,
+}
+
+impl<'a> TextRow<'a> {
+    fn get(&self, column: &str) -> Option<&'a str> {
+        let i = self.header.iter().position(|h| h == column)?;
+        Some(self.fields.get(i).copied().unwrap_or(""))
+    }
+
+    fn error(&self, column: &str, value: &str, expected: &str) -> TskitError {
+        TskitError::ValueError {
+            got: format!(
+                "{:?} in column {} of the {} table on line {}",
+                value, column, self.table, self.line
+            ),
+            expected: expected.to_string(),
+        }
+    }
+
+    fn parse<T: std::str::FromStr>(&self, column: &str, default: T) -> Result<T, TskitError> {
+        match self.get(column) {
+            None | Some("") => Ok(default),
+            Some(value) => value
+                .parse::<T>()
+                .map_err(|_| self.error(column, value, std::any::type_name::<T>())),
+        }
+    }
+
+    fn parse_required<T: std::str::FromStr>(&self, column: &str) -> Result<T, TskitError> {
+        match self.get(column) {
+            None => Err(TskitError::ValueError {
+                got: format!("no {} column in the {} table", column, self.table),
+                expected: format!("a {} column", column),
+            }),
+            Some(value) => value
+                .parse::<T>()
+                .map_err(|_| self.error(column, value, std::any::type_name::<T>())),
+        }
+    }
+
+    fn parse_list<T: std::str::FromStr>(&self, column: &str) -> Result<Vec<T>, TskitError> {
+        match self.get(column) {
+            None | Some("") => Ok(vec![]),
+            Some(value) => value
+                .split(',')
+                .map(|v| {
+                    v.trim()
+                        .parse::<T>()
+                        .map_err(|_| self.error(column, value, "a comma-separated list"))
+                })
+                .collect(),
+        }
+    }
+
+    fn state(&self, column: &str) -> Result<Vec<u8>, TskitError> {
+        let value = self.parse_required::<String>(column)?;
+        let mut state = Vec::with_capacity(value.len());
+        let mut rest = value.as_bytes();
+        while let Some((&b, tail)) = rest.split_first() {
+            if b == b'\\' {
+                let byte = tail
+                    .strip_prefix(b"x")
+                    .and_then(|h| h.get(..2))
+                    .filter(|h| h.iter().all(u8::is_ascii_hexdigit))
+                    .and_then(|h| u8::from_str_radix(std::str::from_utf8(h).ok()?, 16).ok())
+                    .ok_or_else(|| self.error(column, &value, "a state with \\xHH escapes"))?;
+                state.push(byte);
+                rest = &tail[3..];
+            } else {
+                state.push(b);
+                rest = tail;
+            }
+        }
+        Ok(state)
+    }
+
+    fn metadata(&self) -> Result<Vec<u8>, TskitError> {
+        match self.get("metadata") {
+            None | Some("") => Ok(vec![]),
+            Some(value) => {
+                base64_decode(value).ok_or_else(|| self.error("metadata", value, "base64"))
+            }
+        }
+    }
+}
+
+fn read_text_table<R: BufRead>(
+    reader: R,
+    table: &str,
+    mut add_row: impl FnMut(&TextRow) -> Result<i32, TskitError>,
+) -> Result<(), TskitError> {
+    let mut lines = reader.lines();
+    let header = match lines.next() {
+        Some(line) => line?
+            .split('\t')
+            .map(|h| h.trim().to_string())
+            .collect::<Vec<_>>(),
+        None => return Ok(()),
+    };
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row = TextRow {
+            table,
+            header: &header,
+            fields: line.split('\t').map(|f| f.trim()).collect(),
+            line: i + 2,
+        };
+        let rv = add_row(&row)?;
+        if rv < 0 {
+            return Err(TskitError::ErrorCode { code: rv });
+        }
+    }
+    Ok(())
+}
+
+fn parse_time(row: &TextRow, column: &str) -> Result<f64, TskitError> {
+    match row.get(column) {
+        None | Some("") | Some("unknown") => Ok(f64::from_bits(ll_bindings::TSK_UNKNOWN_TIME_HEX)),
+        Some(_) => row.parse_required::<f64>(column),
+    }
+}
+
+fn len(bytes: &[u8]) -> ll_bindings::tsk_size_t {
+    crate::SizeType::from(bytes.len()).into()
+}
+
+pub(crate) fn load_text<R: BufRead>(
+    sequence_length: crate::Position,
+    readers: TextTables<R>,
+) -> Result<TableCollection, TskitError> {
+    let infer_length = sequence_length == 0.;
+    let mut tables = if infer_length {
+        TableCollection::new(1.)?
+    } else {
+        TableCollection::new(sequence_length)?
+    };
+    let raw = unsafe { &mut *tables.as_mut_ptr() };
+
+    if let Some(r) = readers.populations {
+        read_text_table(r, "population", |row| {
+            let md = row.metadata()?;
+            Ok(unsafe {
+                ll_bindings::tsk_population_table_add_row(
+                    &mut raw.populations,
+                    md.as_ptr().cast::<i8>(),
+                    len(&md),
+                )
+            })
+        })?;
+    }
+    if let Some(r) = readers.individuals {
+        read_text_table(r, "individual", |row| {
+            let flags = row.parse::<u32>("flags", 0)?;
+            let location = row.parse_list::<f64>("location")?;
+            let parents = row.parse_list::<i32>("parents")?;
+            let md = row.metadata()?;
+            Ok(unsafe {
+                ll_bindings::tsk_individual_table_add_row(
+                    &mut raw.individuals,
+                    flags,
+                    location.as_ptr(),
+                    crate::SizeType::from(location.len()).into(),
+                    parents.as_ptr(),
+                    crate::SizeType::from(parents.len()).into(),
+                    md.as_ptr().cast::<i8>(),
+                    len(&md),
+                )
+            })
+        })?;
+    }
+    if let Some(r) = readers.nodes {
+        read_text_table(r, "node", |row| {
+            let is_sample = row.parse_required::<i32>("is_sample")?;
+            let flags = if is_sample != 0 {
+                ll_bindings::TSK_NODE_IS_SAMPLE
+            } else {
+                0
+            };
+            let time = row.parse_required::<f64>("time")?;
+            let population = row.parse::<i32>("population", -1)?;
+            let individual = row.parse::<i32>("individual", -1)?;
+            let md = row.metadata()?;
+            Ok(unsafe {
+                ll_bindings::tsk_node_table_add_row(
+                    &mut raw.nodes,
+                    flags,
+                    time,
+                    population,
+                    individual,
+                    md.as_ptr().cast::<i8>(),
+                    len(&md),
+                )
+            })
+        })?;
+    }
+    if let Some(r) = readers.edges {
+        read_text_table(r, "edge", |row| {
+            let md = row.metadata()?;
+            Ok(unsafe {
+                ll_bindings::tsk_edge_table_add_row(
+                    &mut raw.edges,
+                    row.parse_required::<f64>("left")?,
+                    row.parse_required::<f64>("right")?,
+                    row.parse_required::<i32>("parent")?,
+                    row.parse_required::<i32>("child")?,
+                    md.as_ptr().cast::<i8>(),
+                    len(&md),
+                )
+            })
+        })?;
+    }
+    if let Some(r) = readers.sites {
+        read_text_table(r, "site", |row| {
+            let position = row.parse_required::<f64>("position")?;
+            let state = row.state("ancestral_state")?;
+            let md = row.metadata()?;
+            Ok(unsafe {
+                ll_bindings::tsk_site_table_add_row(
+                    &mut raw.sites,
+                    position,
+                    state.as_ptr().cast::<i8>(),
+                    len(&state),
+                    md.as_ptr().cast::<i8>(),
+                    len(&md),
+                )
+            })
+        })?;
+    }
+    if let Some(r) = readers.mutations {
+        read_text_table(r, "mutation", |row| {
+            let site = row.parse_required::<i32>("site")?;
+            let node = row.parse_required::<i32>("node")?;
+            let parent = row.parse::<i32>("parent", -1)?;
+            let time = parse_time(row, "time")?;
+            let state = row.state("derived_state")?;
+            let md = row.metadata()?;
+            Ok(unsafe {
+                ll_bindings::tsk_mutation_table_add_row(
+                    &mut raw.mutations,
+                    site,
+                    node,
+                    parent,
+                    time,
+                    state.as_ptr().cast::<i8>(),
+                    len(&state),
+                    md.as_ptr().cast::<i8>(),
+                    len(&md),
+                )
+            })
+        })?;
+    }
+    if let Some(r) = readers.migrations {
+        read_text_table(r, "migration", |row| {
+            let md = row.metadata()?;
+            Ok(unsafe {
+                ll_bindings::tsk_migration_table_add_row(
+                    &mut raw.migrations,
+                    row.parse_required::<f64>("left")?,
+                    row.parse_required::<f64>("right")?,
+                    row.parse_required::<i32>("node")?,
+                    row.parse_required::<i32>("source")?,
+                    row.parse_required::<i32>("dest")?,
+                    row.parse_required::<f64>("time")?,
+                    md.as_ptr().cast::<i8>(),
+                    len(&md),
+                )
+            })
+        })?;
+    }
+
+    if infer_length {
+        let edges = tables.edges();
+        let migrations = tables.migrations();
+        let length = edges
+            .right_slice()
+            .iter()
+            .chain(migrations.right_slice())
+            .map(|&r| f64::from(r))
+            .fold(0., f64::max);
+        if length <= 0. {
+            return Err(TskitError::ValueError {
+                got: "sequence_length == 0.0 and no edges or migrations".to_string(),
+                expected: "a sequence length".to_string(),
+            });
+        }
+        unsafe { (*tables.as_mut_ptr()).sequence_length = length };
+    }
+    Ok(tables)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::make_small_table_collection_two_trees;
+    use crate::util::{base64_decode, base64_encode};
+
+    #[test]
+    fn test_base64_round_trip() {
+        for input in [&b""[..], b"f", b"fo", b"foo", b"foob", b"fooba", b"foobar"] {
+            let encoded = base64_encode(input);
+            assert_eq!(base64_decode(&encoded).unwrap(), input);
+        }
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert!(base64_decode("Zm8").is_none());
+        assert!(base64_decode("Z=8=").is_none());
+        assert!(base64_decode("Zm8!").is_none());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut tables = make_small_table_collection_two_trees();
+        tables.add_population().unwrap();
+        tables.add_individual(0, [1.5, 2.], None).unwrap();
+        tables.add_site(10., Some(b"A")).unwrap();
+        tables
+            .add_mutation(
+                0,
+                2,
+                crate::MutationId::NULL,
+                f64::from_bits(ll_bindings::TSK_UNKNOWN_TIME_HEX),
+                Some(b"T"),
+            )
+            .unwrap();
+        tables.add_migration((0., 10.), 2, (0, 0), 0.5).unwrap();
+        unsafe {
+            let md = b"{\"x\": 1}";
+            let rv = ll_bindings::tsk_node_table_add_row(
+                &mut (*tables.as_mut_ptr()).nodes,
+                0,
+                3.0,
+                -1,
+                0,
+                md.as_ptr().cast::<i8>(),
+                md.len() as ll_bindings::tsk_size_t,
+            );
+            assert!(rv > 0);
+        }
+
+        let mut writers = TextTables::<Vec<u8>> {
+            nodes: Some(vec![]),
+            edges: Some(vec![]),
+            sites: Some(vec![]),
+            mutations: Some(vec![]),
+            individuals: Some(vec![]),
+            populations: Some(vec![]),
+            migrations: Some(vec![]),
+        };
+        tables.dump_text(&mut writers).unwrap();
+        let readers = TextTables {
+            nodes: writers.nodes.as_deref(),
+            edges: writers.edges.as_deref(),
+            sites: writers.sites.as_deref(),
+            mutations: writers.mutations.as_deref(),
+            individuals: writers.individuals.as_deref(),
+            populations: writers.populations.as_deref(),
+            migrations: writers.migrations.as_deref(),
+        };
+        let loaded = TableCollection::load_text(tables.sequence_length(), readers).unwrap();
+        assert!(tables.equals(&loaded, crate::TableEqualityOptions::IGNORE_PROVENANCE));
+    }
+
+    #[test]
+    fn test_state_round_trip() {
+        let states: [&[u8]; 7] = [
+            b"A",
+            b"A\tB",
+            b"line\nbreak",
+            b" a b ",
+            b"\\x41",
+            &[0xff, b'A', 0xfe],
+            "\u{e9}\u{a0}".as_bytes(),
+        ];
+        let mut tables = TableCollection::new(10.).unwrap();
+        tables.add_node(0, 0.0, -1, -1).unwrap();
+        for (i, state) in states.iter().enumerate() {
+            let site = tables.add_site(i as f64, Some(state)).unwrap();
+            tables
+                .add_mutation(site, 0, crate::MutationId::NULL, 0.0, Some(state))
+                .unwrap();
+        }
+        assert_eq!(encode_state(&Some(b"A".to_vec())), "A");
+        assert_eq!(encode_state(&Some(b"A\tB".to_vec())), "A\\x09B");
+        assert_eq!(encode_state(&Some(b" a b ".to_vec())), "\\x20a b\\x20");
+
+        let mut writers = TextTables::<Vec<u8>> {
+            nodes: Some(vec![]),
+            sites: Some(vec![]),
+            mutations: Some(vec![]),
+            ..Default::default()
+        };
+        tables.dump_text(&mut writers).unwrap();
+        let sites = writers.sites.as_deref().unwrap();
+        assert_eq!(
+            sites.iter().filter(|&&b| b == b'\n').count(),
+            states.len() + 1
+        );
+        let readers = TextTables {
+            nodes: writers.nodes.as_deref(),
+            sites: Some(sites),
+            mutations: writers.mutations.as_deref(),
+            ..Default::default()
+        };
+        let loaded = TableCollection::load_text(tables.sequence_length(), readers).unwrap();
+        assert!(tables.equals(&loaded, crate::TableEqualityOptions::IGNORE_PROVENANCE));
+
+        for bad in ["\\", "\\x4", "\\xZZ", "\\y41"] {
+            let sites = format!("position\tancestral_state\n0\t{}\n", bad);
+            let readers = TextTables {
+                sites: Some(sites.as_bytes()),
+                ..Default::default()
+            };
+            assert!(matches!(
+                TableCollection::load_text(1., readers),
+                Err(TskitError::ValueError { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_load_hand_written() {
+        let nodes = "is_sample\ttime\n0\t1\n1\t0\n1\t0\n";
+        let edges = "left\tright\tparent\tchild\n0\t10\t0\t1,2\n";
+        let readers = TextTables {
+            nodes: Some(nodes.as_bytes()),
+            edges: Some(edges.as_bytes()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            TableCollection::load_text(0., readers),
+            Err(TskitError::ValueError { .. })
+        ));
+
+        let edges = "left\tright\tparent\tchild\n0\t5\t0\t1\n0\t5\t0\t2\n";
+        let readers = TextTables {
+            nodes: Some(nodes.as_bytes()),
+            edges: Some(edges.as_bytes()),
+            ..Default::default()
+        };
+        let tables = TableCollection::load_text(0., readers).unwrap();
+        assert_eq!(tables.sequence_length(), 5.);
+        assert_eq!(tables.nodes().num_rows(), 3);
+        assert_eq!(tables.edges().num_rows(), 2);
+        assert!(tables.nodes().flags(1).unwrap().is_sample());
+        assert_eq!(tables.nodes().population(1).unwrap(), -1);
+
+        let readers = TextTables {
+            nodes: Some("time\n1\n".as_bytes()),
+            ..Default::default()
+        };
+        assert!(TableCollection::load_text(1., readers).is_err());
+    }
+}
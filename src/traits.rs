@@ -10,6 +10,16 @@ use crate::PopulationTable;
 use crate::SiteTable;
 
 /// Provide pointer access to underlying C types
+///
+/// The pointers let client code pass the safe types to functions in
+/// [`bindings`](crate::bindings) that this crate does not wrap.
+/// The pointed-to data remain owned by `self`.
+/// They must not be freed or re-initialized, and must not be used
+/// after `self` is dropped.
+/// Changes made through [`as_mut_ptr`](TskitTypeAccess::as_mut_ptr)
+/// must leave the C object in a valid state.
+/// For example, the tables of a [`TreeSequence`](crate::TreeSequence)
+/// must never be modified.
 pub trait TskitTypeAccess<T> {
     /// Return const pointer
     fn as_ptr(&self) -> *const T;
@@ -38,6 +48,36 @@ pub trait TskitTypeAccess<T> {
 /// let n = tables.nodes();
 /// ```
 ///
+/// [`TableCollection`](crate::TableCollection) and
+/// [`TreeSequence`](crate::TreeSequence) also provide the functions
+/// of this trait as inherent functions, so they can be called
+/// without importing the trait.
+///
+/// The trait is object-safe and is implemented for references,
+/// so generic code can accept anything with tables:
+///
+/// ```
+/// use tskit::TableAccess;
+///
+/// fn num_nodes(tables: &dyn TableAccess) -> u64 {
+///     tables.nodes().num_rows().into()
+/// }
+///
+/// fn num_edges<T: TableAccess>(tables: T) -> u64 {
+///     tables.edges().num_rows().into()
+/// }
+///
+/// let mut tables = tskit::TableCollection::new(1.).unwrap();
+/// tables.add_node(0, 1.0, -1, -1).unwrap();
+/// assert_eq!(num_nodes(&tables), 1);
+/// assert_eq!(num_edges(&tables), 0);
+///
+/// // NodeListGenerator is implemented for all such types.
+/// use tskit::NodeListGenerator;
+/// let generator: &dyn NodeListGenerator = &tables;
+/// assert!(generator.samples_as_vector().is_empty());
+/// ```
+///
 /// The borrow checker will keep you from getting in trouble:
 ///
 /// ```compile_fail
@@ -128,8 +168,46 @@ pub trait TableAccess {
     }
 }
 
+impl<T: TableAccess + ?Sized> TableAccess for &T {
+    fn edges(&self) -> EdgeTable<'_> {
+        (**self).edges()
+    }
+
+    fn nodes(&self) -> NodeTable<'_> {
+        (**self).nodes()
+    }
+
+    fn mutations(&self) -> MutationTable<'_> {
+        (**self).mutations()
+    }
+
+    fn sites(&self) -> SiteTable<'_> {
+        (**self).sites()
+    }
+
+    fn populations(&self) -> PopulationTable<'_> {
+        (**self).populations()
+    }
+
+    fn migrations(&self) -> MigrationTable<'_> {
+        (**self).migrations()
+    }
+
+    fn individuals(&self) -> IndividualTable<'_> {
+        (**self).individuals()
+    }
+
+    #[cfg(any(feature = "provenance", doc))]
+    fn provenances(&self) -> crate::provenance::ProvenanceTable<'_> {
+        (**self).provenances()
+    }
+}
+
 /// Interface for returning lists of node ids from
 /// types implementing [`TableAccess`].
+///
+/// This trait is implemented for all types implementing
+/// [`TableAccess`].
 pub trait NodeListGenerator: TableAccess {
     /// Obtain a vector containing the indexes ("ids")
     /// of all nodes for which [`crate::TSK_NODE_IS_SAMPLE`]
@@ -204,11 +282,59 @@ pub trait NodeListGenerator: TableAccess {
     fn create_node_id_vector(
         &self,
         f: impl FnMut(&crate::NodeTableRow) -> bool,
-    ) -> Vec<crate::NodeId> {
+    ) -> Vec<crate::NodeId>
+    where
+        Self: Sized,
+    {
         self.nodes().create_node_id_vector(f)
     }
 }
 
+impl<T: TableAccess + ?Sized> NodeListGenerator for T {}
+
+/// List the columns of a table.
+///
+/// This trait allows code to work with any table type,
+/// for example to print tables or to convert them to other formats.
+///
+/// # Examples
+///
+/// ```
+/// use tskit::TableColumns;
+///
+/// let mut tables = tskit::TableCollection::new(10.).unwrap();
+/// tables
+///     .add_mutation(0, 0, tskit::MutationId::NULL, 1.0, Some(b"AT"))
+///     .unwrap();
+///
+/// let mutations = tables.mutations();
+/// assert_eq!(mutations.table_kind(), tskit::types::TableKind::Mutations);
+/// let names = mutations.columns().iter().map(|c| c.name).collect::<Vec<_>>();
+/// assert_eq!(
+///     names,
+///     ["site", "node", "parent", "time", "derived_state", "metadata"]
+/// );
+///
+/// let derived_state = mutations.column("derived_state").unwrap();
+/// assert_eq!(derived_state.column_type, tskit::types::ColumnType::UInt8);
+/// assert_eq!(derived_state.len, 2);
+/// assert_eq!(derived_state.offsets_len.unwrap(), 2);
+/// assert!(!mutations.column("time").unwrap().is_ragged());
+/// ```
+pub trait TableColumns {
+    /// The kind of table.
+    fn table_kind(&self) -> crate::types::TableKind;
+
+    /// Describe each column, in the order used by the tskit C API.
+    fn columns(&self) -> Vec<crate::types::ColumnInfo>;
+
+    /// Describe the column called `name`, or return `None`
+    /// if there is no such column.
+    fn column(&self, name: &str) -> Option<crate::types::ColumnInfo> {
+        self.columns().into_iter().find(|c| c.name == name)
+    }
+}
+
 /// Abstraction of individual location.
 ///
 /// This trait exists to streamline the API of
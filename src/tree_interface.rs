@@ -296,8 +296,13 @@ impl TreeInterface {
     fn left_sample(&self, u: NodeId) -> Result<NodeId, TskitError> {
         err_if_not_tracking_samples!(
             self.flags,
-            unsafe_tsk_column_access!(u.0, 0, self.num_nodes, (*self.as_ptr()).left_sample, NodeId)
-                .unwrap()
+            unsafe_tsk_column_access!(
+                u.0,
+                0,
+                self.num_nodes,
+                (*self.as_ptr()).left_sample,
+                NodeId
+            )?
         )
     }
 
@@ -310,24 +315,20 @@ impl TreeInterface {
                 self.num_nodes,
                 (*self.as_ptr()).right_sample,
                 NodeId
-            )
-            .unwrap()
+            )?
         )
     }
 
     /// Return the `[left, right)` coordinates of the tree.
-    pub fn interval(&self) -> (Position, Position) {
-        (
-            unsafe { (*self.as_ptr()).interval }.left.into(),
-            unsafe { (*self.as_ptr()).interval }.right.into(),
-        )
+    pub fn interval(&self) -> crate::GenomicInterval {
+        let interval = unsafe { (*self.as_ptr()).interval };
+        (interval.left, interval.right).into()
     }
 
     /// Return the length of the genome for which this
     /// tree is the ancestry.
     pub fn span(&self) -> Position {
-        let i = self.interval();
-        i.1 - i.0
+        self.interval().span()
     }
 
     /// Get the parent of node `u`.
@@ -335,7 +336,8 @@ impl TreeInterface {
     /// # Errors
     ///
     /// [`TskitError`] if `u` is out of range.
-    pub fn parent(&self, u: NodeId) -> Result<NodeId, TskitError> {
+    pub fn parent<N: Into<NodeId> + Copy>(&self, u: N) -> Result<NodeId, TskitError> {
+        let u = u.into();
         unsafe_tsk_column_access!(u.0, 0, self.array_len, (*self.as_ptr()).parent, NodeId)
     }
 
@@ -344,7 +346,8 @@ impl TreeInterface {
     /// # Errors
     ///
     /// [`TskitError`] if `u` is out of range.
-    pub fn left_child(&self, u: NodeId) -> Result<NodeId, TskitError> {
+    pub fn left_child<N: Into<NodeId> + Copy>(&self, u: N) -> Result<NodeId, TskitError> {
+        let u = u.into();
         unsafe_tsk_column_access!(u.0, 0, self.array_len, (*self.as_ptr()).left_child, NodeId)
     }
 
@@ -353,7 +356,8 @@ impl TreeInterface {
     /// # Errors
     ///
     /// [`TskitError`] if `u` is out of range.
-    pub fn right_child(&self, u: NodeId) -> Result<NodeId, TskitError> {
+    pub fn right_child<N: Into<NodeId> + Copy>(&self, u: N) -> Result<NodeId, TskitError> {
+        let u = u.into();
         unsafe_tsk_column_access!(u.0, 0, self.array_len, (*self.as_ptr()).right_child, NodeId)
     }
 
@@ -362,7 +366,8 @@ impl TreeInterface {
     /// # Errors
     ///
     /// [`TskitError`] if `u` is out of range.
-    pub fn left_sib(&self, u: NodeId) -> Result<NodeId, TskitError> {
+    pub fn left_sib<N: Into<NodeId> + Copy>(&self, u: N) -> Result<NodeId, TskitError> {
+        let u = u.into();
         unsafe_tsk_column_access!(u.0, 0, self.array_len, (*self.as_ptr()).left_sib, NodeId)
     }
 
@@ -371,10 +376,70 @@ impl TreeInterface {
     /// # Errors
     ///
     /// [`TskitError::IndexError`] if `u` is out of range.
-    pub fn right_sib(&self, u: NodeId) -> Result<NodeId, TskitError> {
+    pub fn right_sib<N: Into<NodeId> + Copy>(&self, u: N) -> Result<NodeId, TskitError> {
+        let u = u.into();
         unsafe_tsk_column_access!(u.0, 0, self.array_len, (*self.as_ptr()).right_sib, NodeId)
     }
 
+    /// Get the parent of node `u`, or `None` if it is `NULL`.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if `u` is out of range.
+    pub fn parent_opt<N: Into<NodeId> + Copy>(&self, u: N) -> Result<Option<NodeId>, TskitError> {
+        let u = u.into();
+        Ok(self.parent(u)?.to_option())
+    }
+
+    /// Get the left child of node `u`, or `None` if it is `NULL`.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if `u` is out of range.
+    pub fn left_child_opt<N: Into<NodeId> + Copy>(
+        &self,
+        u: N,
+    ) -> Result<Option<NodeId>, TskitError> {
+        let u = u.into();
+        Ok(self.left_child(u)?.to_option())
+    }
+
+    /// Get the right child of node `u`, or `None` if it is `NULL`.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if `u` is out of range.
+    pub fn right_child_opt<N: Into<NodeId> + Copy>(
+        &self,
+        u: N,
+    ) -> Result<Option<NodeId>, TskitError> {
+        let u = u.into();
+        Ok(self.right_child(u)?.to_option())
+    }
+
+    /// Get the left sib of node `u`, or `None` if it is `NULL`.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if `u` is out of range.
+    pub fn left_sib_opt<N: Into<NodeId> + Copy>(&self, u: N) -> Result<Option<NodeId>, TskitError> {
+        let u = u.into();
+        Ok(self.left_sib(u)?.to_option())
+    }
+
+    /// Get the right sib of node `u`, or `None` if it is `NULL`.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::IndexError`] if `u` is out of range.
+    pub fn right_sib_opt<N: Into<NodeId> + Copy>(
+        &self,
+        u: N,
+    ) -> Result<Option<NodeId>, TskitError> {
+        let u = u.into();
+        Ok(self.right_sib(u)?.to_option())
+    }
+
     /// Obtain the list of samples for the current tree/tree sequence
     /// as a vector.
     ///
@@ -410,7 +475,11 @@ impl TreeInterface {
     ///
     /// [`TskitError::IndexError`] if `u` is out of range.
     #[deprecated(since = "0.2.3", note = "Please use Tree::parents instead")]
-    pub fn path_to_root(&self, u: NodeId) -> Result<impl Iterator<Item = NodeId> + '_, TskitError> {
+    pub fn path_to_root<N: Into<NodeId> + Copy>(
+        &self,
+        u: N,
+    ) -> Result<impl Iterator<Item = NodeId> + '_, TskitError> {
+        let u = u.into();
         self.parents(u)
     }
 
@@ -420,7 +489,11 @@ impl TreeInterface {
     /// # Errors
     ///
     /// [`TskitError::IndexError`] if `u` is out of range.
-    pub fn parents(&self, u: NodeId) -> Result<impl Iterator<Item = NodeId> + '_, TskitError> {
+    pub fn parents<N: Into<NodeId> + Copy>(
+        &self,
+        u: N,
+    ) -> Result<impl Iterator<Item = NodeId> + '_, TskitError> {
+        let u = u.into();
         ParentsIterator::new(self, u)
     }
 
@@ -429,7 +502,11 @@ impl TreeInterface {
     /// # Errors
     ///
     /// [`TskitError::IndexError`] if `u` is out of range.
-    pub fn children(&self, u: NodeId) -> Result<impl Iterator<Item = NodeId> + '_, TskitError> {
+    pub fn children<N: Into<NodeId> + Copy>(
+        &self,
+        u: N,
+    ) -> Result<impl Iterator<Item = NodeId> + '_, TskitError> {
+        let u = u.into();
         ChildIterator::new(self, u)
     }
     /// Return an [`Iterator`] over the sample nodes descending from node `u`.
@@ -444,7 +521,11 @@ impl TreeInterface {
     ///
     /// [`TskitError::NotTrackingSamples`] if [`TreeFlags::SAMPLE_LISTS`] was not used
     /// to initialize `self`.
-    pub fn samples(&self, u: NodeId) -> Result<impl Iterator<Item = NodeId> + '_, TskitError> {
+    pub fn samples<N: Into<NodeId> + Copy>(
+        &self,
+        u: N,
+    ) -> Result<impl Iterator<Item = NodeId> + '_, TskitError> {
+        let u = u.into();
         SamplesIterator::new(self, u)
     }
 
@@ -490,8 +571,8 @@ impl TreeInterface {
     /// (and the tree sequence from which it came).
     ///
     /// This is a convenience function for accessing node times, etc..
-    pub fn node_table<'a>(&'a self) -> crate::NodeTable<'a> {
-        crate::NodeTable::<'a>::new_from_table(unsafe {
+    pub fn node_table(&self) -> crate::NodeTable<'_> {
+        crate::NodeTable::new_from_table(unsafe {
             &(*(*(*self.as_ptr()).tree_sequence).tables).nodes
         })
     }
@@ -525,8 +606,16 @@ impl TreeInterface {
     ///
     /// # Errors
     ///
+    /// * [`TskitError::IndexError`] if `u` is out of range.
     /// * [`TskitError`] if [`TreeFlags::NO_SAMPLE_COUNTS`].
-    pub fn num_tracked_samples(&self, u: NodeId) -> Result<SizeType, TskitError> {
+    pub fn num_tracked_samples<N: Into<NodeId> + Copy>(
+        &self,
+        u: N,
+    ) -> Result<SizeType, TskitError> {
+        let u = u.into();
+        if u < 0 || u.0 as tsk_size_t >= self.array_len {
+            return Err(TskitError::IndexError);
+        }
         let mut n = SizeType(tsk_size_t::MAX);
         let np: *mut tsk_size_t = &mut n.0;
         let code = unsafe { ll_bindings::tsk_tree_get_num_tracked_samples(self.as_ptr(), u.0, np) };
@@ -775,7 +864,7 @@ impl<'a> ParentsIterator<'a> {
                 )))
             }
         };
-        match u.0 >= num_nodes {
+        match u < NodeId::NULL || u.0 >= num_nodes {
             true => Err(TskitError::IndexError),
             false => Ok(ParentsIterator {
                 current_node: None,
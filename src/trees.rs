@@ -1,21 +1,27 @@
+use std::marker::PhantomData;
 use std::mem::MaybeUninit;
 use std::ops::Deref;
 use std::ops::DerefMut;
 
 use crate::bindings as ll_bindings;
 use crate::error::TskitError;
+use crate::EdgeId;
 use crate::EdgeTable;
+use crate::IndividualId;
 use crate::IndividualTable;
 use crate::MigrationTable;
+use crate::MutationId;
 use crate::MutationTable;
 use crate::NodeId;
 use crate::NodeTable;
 use crate::PopulationTable;
+use crate::Position;
 use crate::SimplificationOptions;
 use crate::SiteTable;
 use crate::SizeType;
 use crate::TableAccess;
 use crate::TableOutputOptions;
+use crate::Time;
 use crate::TreeFlags;
 use crate::TreeInterface;
 use crate::TreeSequenceFlags;
@@ -28,39 +34,103 @@ use std::ptr::NonNull;
 /// A Tree.
 ///
 /// Wrapper around `tsk_tree_t`.
-pub struct Tree {
-    pub(crate) inner: ll_bindings::tsk_tree_t,
+///
+/// A tree borrows the [`TreeSequence`] that it was created from.
+/// Each tree owns its own `tsk_tree_t`, so any number of trees
+/// can move independently along the same tree sequence,
+/// including from different threads.
+///
+/// # Examples
+///
+/// Process each half of the genome in its own thread:
+///
+/// ```
+/// use streaming_iterator::StreamingIterator;
+///
+/// let mut tables = tskit::TableCollection::new(100.).unwrap();
+/// tables.add_node(0, 1.0, -1, -1).unwrap();
+/// tables.add_node(tskit::NodeFlags::IS_SAMPLE, 0.0, -1, -1).unwrap();
+/// tables.add_edge(0., 50., 0, 1).unwrap();
+/// tables.add_edge(50., 100., 0, 1).unwrap();
+/// tables.build_index().unwrap();
+/// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+///
+/// let spans = std::thread::scope(|s| {
+///     let handles = [0., 50.]
+///         .iter()
+///         .map(|&start| {
+///             let treeseq = &treeseq;
+///             s.spawn(move || {
+///                 let mut tree = treeseq.tree_iterator(tskit::TreeFlags::default()).unwrap();
+///                 tree.seek(start).unwrap();
+///                 tree.span()
+///             })
+///         })
+///         .collect::<Vec<_>>();
+///     handles.into_iter().map(|h| h.join().unwrap()).collect::<Vec<_>>()
+/// });
+/// assert_eq!(spans, [50., 50.]);
+/// ```
+pub struct Tree<'treeseq> {
+    pub(crate) inner: mbox::MBox<ll_bindings::tsk_tree_t>,
     api: TreeInterface,
     current_tree: i32,
     advanced: bool,
+    treeseq: PhantomData<&'treeseq TreeSequence>,
 }
 
-impl Drop for Tree {
+// Safety: the tree owns its tsk_tree_t and only reads the
+// tree sequence, which is Sync.
+unsafe impl Send for Tree<'_> {}
+unsafe impl Sync for Tree<'_> {}
+
+impl Drop for Tree<'_> {
     fn drop(&mut self) {
-        let rv = unsafe { tsk_tree_free(&mut self.inner) };
+        let rv = unsafe { tsk_tree_free(&mut *self.inner) };
         assert_eq!(rv, 0);
     }
 }
 
-impl Deref for Tree {
+impl Deref for Tree<'_> {
     type Target = TreeInterface;
     fn deref(&self) -> &Self::Target {
         &self.api
     }
 }
 
-impl DerefMut for Tree {
+impl DerefMut for Tree<'_> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.api
     }
 }
 
-impl Tree {
-    fn new<F: Into<TreeFlags>>(ts: &TreeSequence, flags: F) -> Result<Self, TskitError> {
+impl<'treeseq> Tree<'treeseq> {
+    fn new<F: Into<TreeFlags>>(ts: &'treeseq TreeSequence, flags: F) -> Result<Self, TskitError> {
         let flags = flags.into();
-        let mut tree = MaybeUninit::<ll_bindings::tsk_tree_t>::uninit();
-        let mut rv =
-            unsafe { ll_bindings::tsk_tree_init(tree.as_mut_ptr(), ts.as_ptr(), flags.bits()) };
+        // The tsk_tree_t is allocated on the heap so that the
+        // pointer held by the TreeInterface stays valid when
+        // the Tree is moved.
+        let temp = unsafe {
+            libc::malloc(std::mem::size_of::<ll_bindings::tsk_tree_t>())
+                as *mut ll_bindings::tsk_tree_t
+        };
+        let non_owned_pointer = match NonNull::new(temp) {
+            Some(x) => x,
+            None => panic!("out of memory"),
+        };
+        let mut rv = unsafe { ll_bindings::tsk_tree_init(temp, ts.as_ptr(), flags.bits()) };
+        let inner = unsafe { mbox::MBox::from_non_null_raw(non_owned_pointer) };
+        let num_nodes = unsafe { (*(*ts.as_ptr()).tables).nodes.num_rows };
+        let api = TreeInterface::new(non_owned_pointer, num_nodes, num_nodes + 1, flags);
+        // Once initialized, drop must free the tree even if a
+        // later step fails.
+        let mut tree = Tree {
+            inner,
+            current_tree: 0,
+            advanced: false,
+            api,
+            treeseq: PhantomData,
+        };
         if rv < 0 {
             return Err(TskitError::ErrorCode { code: rv });
         }
@@ -68,33 +138,36 @@ impl Tree {
         if !flags.contains(TreeFlags::NO_SAMPLE_COUNTS) {
             rv = unsafe {
                 ll_bindings::tsk_tree_set_tracked_samples(
-                    tree.as_mut_ptr(),
+                    &mut *tree.inner,
                     ts.num_samples().into(),
-                    (*tree.as_ptr()).samples,
+                    tree.inner.samples,
                 )
             };
         }
+        handle_tsk_return_value!(rv, tree)
+    }
 
-        let mut tree = unsafe { tree.assume_init() };
-        let ptr = &mut tree as *mut ll_bindings::tsk_tree_t;
-
-        let num_nodes = unsafe { (*(*ts.as_ptr()).tables).nodes.num_rows };
-        let non_owned_pointer = unsafe { NonNull::new_unchecked(ptr) };
-        let api = TreeInterface::new(non_owned_pointer, num_nodes, num_nodes + 1, flags);
-        handle_tsk_return_value!(
-            rv,
-            Tree {
-                inner: tree,
-                current_tree: 0,
-                advanced: false,
-                api
-            }
-        )
+    /// Move to the tree covering `position`.
+    ///
+    /// Afterwards, `self` is the tree at `position`, and
+    /// advancing the iterator moves to the following tree.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] if `position` is not in `[0, sequence_length)`.
+    pub fn seek<P: Into<Position>>(&mut self, position: P) -> Result<(), TskitError> {
+        let rv = unsafe { ll_bindings::tsk_tree_seek(&mut *self.inner, position.into().into(), 0) };
+        if rv < 0 {
+            return Err(TskitError::ErrorCode { code: rv });
+        }
+        self.current_tree = self.inner.index + 1;
+        self.advanced = true;
+        Ok(())
     }
 }
 
-impl streaming_iterator::StreamingIterator for Tree {
-    type Item = Tree;
+impl<'treeseq> streaming_iterator::StreamingIterator for Tree<'treeseq> {
+    type Item = Tree<'treeseq>;
     fn advance(&mut self) {
         let rv = if self.current_tree == 0 {
             unsafe { ll_bindings::tsk_tree_first(self.as_mut_ptr()) }
@@ -112,7 +185,7 @@ impl streaming_iterator::StreamingIterator for Tree {
         }
     }
 
-    fn get(&self) -> Option<&Tree> {
+    fn get(&self) -> Option<&Self::Item> {
         match self.advanced {
             true => Some(self),
             false => None,
@@ -120,7 +193,7 @@ impl streaming_iterator::StreamingIterator for Tree {
     }
 }
 
-impl streaming_iterator::DoubleEndedStreamingIterator for Tree {
+impl streaming_iterator::DoubleEndedStreamingIterator for Tree<'_> {
     fn advance_back(&mut self) {
         let rv = if self.current_tree == 0 {
             unsafe { ll_bindings::tsk_tree_last(self.as_mut_ptr()) }
@@ -139,6 +212,41 @@ impl streaming_iterator::DoubleEndedStreamingIterator for Tree {
     }
 }
 
+/// Summary of the contents of a [`TreeSequence`].
+///
+/// See [`TreeSequence::summary`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeSequenceSummary {
+    pub num_trees: SizeType,
+    pub num_edges: SizeType,
+    pub num_nodes: SizeType,
+    pub num_sites: SizeType,
+    pub num_mutations: SizeType,
+    pub num_individuals: SizeType,
+    pub num_migrations: SizeType,
+    pub sequence_length: Position,
+    /// `None` if the time units are not set.
+    pub time_units: Option<String>,
+}
+
+impl std::fmt::Display for TreeSequenceSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "sequence_length: {}", f64::from(self.sequence_length))?;
+        writeln!(
+            f,
+            "time_units: {}",
+            self.time_units.as_deref().unwrap_or("unknown")
+        )?;
+        writeln!(f, "num_trees: {}", u64::from(self.num_trees))?;
+        writeln!(f, "num_edges: {}", u64::from(self.num_edges))?;
+        writeln!(f, "num_nodes: {}", u64::from(self.num_nodes))?;
+        writeln!(f, "num_sites: {}", u64::from(self.num_sites))?;
+        writeln!(f, "num_mutations: {}", u64::from(self.num_mutations))?;
+        writeln!(f, "num_individuals: {}", u64::from(self.num_individuals))?;
+        write!(f, "num_migrations: {}", u64::from(self.num_migrations))
+    }
+}
+
 /// A tree sequence.
 ///
 /// This is a thin wrapper around the C type `tsk_treeseq_t`.
@@ -162,10 +270,38 @@ impl streaming_iterator::DoubleEndedStreamingIterator for Tree {
 /// // tables gets moved into our treeseq variable:
 /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
 /// ```
+///
+/// ## Sharing between threads
+///
+/// A `TreeSequence` is [`Send`] and [`Sync`], so it can be shared
+/// behind an [`Arc`](std::sync::Arc) for read-only analyses:
+///
+/// ```
+/// # let mut tables = tskit::TableCollection::new(1000.).unwrap();
+/// # tables.add_node(0, 1.0, -1, -1).unwrap();
+/// # tables.add_node(tskit::NodeFlags::IS_SAMPLE, 0.0, -1, -1).unwrap();
+/// # tables.add_edge(0., 1000., 0, 1).unwrap();
+/// # tables.build_index().unwrap();
+/// let treeseq = std::sync::Arc::new(
+///     tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap(),
+/// );
+/// let handles = (0..2)
+///     .map(|_| {
+///         let treeseq = std::sync::Arc::clone(&treeseq);
+///         std::thread::spawn(move || treeseq.nodes().num_rows())
+///     })
+///     .collect::<Vec<_>>();
+/// for h in handles {
+///     assert_eq!(h.join().unwrap(), 2);
+/// }
+/// ```
 pub struct TreeSequence {
     pub(crate) inner: ll_bindings::tsk_treeseq_t,
 }
 
+// Safety: the tree sequence owns its tables, the tskit C library
+// keeps no global or thread-local state, and the C functions called
+// from methods taking `&self` do not modify the tree sequence.
 unsafe impl Send for TreeSequence {}
 unsafe impl Sync for TreeSequence {}
 
@@ -186,6 +322,47 @@ impl Drop for TreeSequence {
     }
 }
 
+/// Prints a summary of the tree sequence rather than its contents.
+///
+/// # Examples
+///
+/// ```
+/// let mut tables = tskit::TableCollection::new(10.).unwrap();
+/// tables.add_node(tskit::NodeFlags::IS_SAMPLE, 0.0, -1, -1).unwrap();
+/// tables.build_index().unwrap();
+/// let ts = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+/// assert_eq!(
+///     format!("{:?}", ts),
+///     "TreeSequence { sequence_length: 10.0, num_trees: 1, num_samples: 1, \
+///      num_nodes: 1, num_edges: 0, num_sites: 0, num_mutations: 0, \
+///      num_individuals: 0, num_populations: 0, num_migrations: 0, num_provenances: 0 }"
+/// );
+/// ```
+impl std::fmt::Debug for TreeSequence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let tables = unsafe { &*self.inner.tables };
+        let mut d = f.debug_struct("TreeSequence");
+        d.field("sequence_length", &tables.sequence_length)
+            .field("num_trees", &self.inner.num_trees)
+            .field("num_samples", &self.inner.num_samples);
+        crate::table_collection::debug_num_rows(&mut d, tables);
+        d.finish()
+    }
+}
+
+/// Cloning makes a deep copy of the tables.
+/// Use an [`Arc`](std::sync::Arc) to share a tree sequence without copying.
+///
+/// # Panics
+///
+/// Panics if the copy fails, which only happens if memory runs out.
+/// See [`TreeSequence::deepcopy`] for a fallible version.
+impl Clone for TreeSequence {
+    fn clone(&self) -> Self {
+        self.deepcopy().unwrap()
+    }
+}
+
 impl TreeSequence {
     /// Create a tree sequence from a [`TableCollection`].
     /// In general, [`TableCollection::tree_sequence`] may be preferred.
@@ -277,6 +454,26 @@ impl TreeSequence {
         Self::new(tables, TreeSequenceFlags::default())
     }
 
+    /// Load from an in-memory buffer.
+    ///
+    /// This function calls [`TableCollection::new_from_bytes`] with
+    /// [`TreeSequenceFlags::default`].
+    pub fn load_from_bytes(bytes: &[u8]) -> Result<Self, TskitError> {
+        let tables = TableCollection::new_from_bytes(bytes)?;
+
+        Self::new(tables, TreeSequenceFlags::default())
+    }
+
+    /// Load from any type implementing [`std::io::Read`].
+    ///
+    /// This function calls [`TableCollection::new_from_reader`] with
+    /// [`TreeSequenceFlags::default`].
+    pub fn load_from_reader<R: std::io::Read>(reader: R) -> Result<Self, TskitError> {
+        let tables = TableCollection::new_from_reader(reader)?;
+
+        Self::new(tables, TreeSequenceFlags::default())
+    }
+
     /// Obtain a copy of the [`TableCollection`].
     /// The result is a "deep" copy of the tables.
     ///
@@ -295,6 +492,68 @@ impl TreeSequence {
         handle_tsk_return_value!(rv, unsafe { TableCollection::new_from_mbox(inner) })
     }
 
+    /// Return a "deep" copy of the tree sequence.
+    ///
+    /// The copy owns a copy of the tables, so it is
+    /// independent of `self`.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] will be raised if the underlying C library returns an error code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_node(tskit::NodeFlags::IS_SAMPLE, 0.0, -1, -1).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let copy = treeseq.deepcopy().unwrap();
+    /// drop(treeseq);
+    /// assert_eq!(copy.num_samples(), 1);
+    /// ```
+    pub fn deepcopy(&self) -> Result<Self, TskitError> {
+        Self::new(self.dump_tables()?, TreeSequenceFlags::default())
+    }
+
+    /// Obtain a copy of the [`TableCollection`].
+    ///
+    /// This is an alias for [`TreeSequence::dump_tables`].
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] will be raised if the underlying C library returns an error code.
+    pub fn tables(&self) -> Result<TableCollection, TskitError> {
+        self.dump_tables()
+    }
+
+    /// Consume the tree sequence and return its [`TableCollection`].
+    ///
+    /// Unlike [`TreeSequence::dump_tables`], the tables are not copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tskit::TableAccess;
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_node(0, 0.0, tskit::PopulationId::NULL, tskit::IndividualId::NULL).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let mut tables = treeseq.into_tables();
+    /// tables.add_node(0, 1.0, tskit::PopulationId::NULL, tskit::IndividualId::NULL).unwrap();
+    /// assert_eq!(tables.nodes().num_rows(), 2);
+    /// ```
+    pub fn into_tables(mut self) -> TableCollection {
+        let tables = self.inner.tables;
+        // tsk_treeseq_free skips NULL tables, so the
+        // remaining memory is cleaned up when self drops.
+        self.inner.tables = std::ptr::null_mut();
+        assert!(!tables.is_null());
+        // SAFETY: the tree sequence owns the tables (TSK_TAKE_OWNERSHIP),
+        // which were allocated via MBox in TableCollection::into_raw.
+        unsafe { TableCollection::new_from_mbox(mbox::MBox::from_raw(tables)) }
+    }
+
     /// Create an iterator over trees.
     ///
     /// # Parameters
@@ -335,7 +594,7 @@ impl TreeSequence {
     /// while let Some(tree) = tree_sequence.tree_iterator(tskit::TreeFlags::default()).unwrap().next() {
     /// }
     /// ```
-    pub fn tree_iterator<F: Into<TreeFlags>>(&self, flags: F) -> Result<Tree, TskitError> {
+    pub fn tree_iterator<F: Into<TreeFlags>>(&self, flags: F) -> Result<Tree<'_>, TskitError> {
         let tree = Tree::new(self, flags)?;
 
         Ok(tree)
@@ -369,6 +628,194 @@ impl TreeSequence {
         tree_array_slice!(self, samples, num_samples)
     }
 
+    /// Get the list of sample nodes as a slice.
+    ///
+    /// This is equivalent to [`TreeSequence::sample_nodes`].
+    pub fn samples(&self) -> &[NodeId] {
+        self.sample_nodes()
+    }
+
+    /// Get the nodes of individual `individual` as a slice.
+    ///
+    /// Returns `None` if `individual` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(1.).unwrap();
+    /// let individual = tables.add_individual(0, None, None).unwrap();
+    /// let n0 = tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, -1, individual).unwrap();
+    /// let n1 = tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, -1, individual).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// assert_eq!(treeseq.individual_nodes(individual).unwrap(), &[n0, n1]);
+    /// assert!(treeseq.individual_nodes(1).is_none());
+    /// ```
+    pub fn individual_nodes<I: Into<IndividualId>>(&self, individual: I) -> Option<&[NodeId]> {
+        let individual = individual.into();
+        let num_individuals = unsafe { (*self.inner.tables).individuals.num_rows };
+        let i = usize::try_from(individual.0).ok()?;
+        if i >= usize::try_from(num_individuals).ok()? {
+            return None;
+        }
+        let length = unsafe { *self.inner.individual_nodes_length.add(i) };
+        if length == 0 {
+            return Some(&[]);
+        }
+        Some(unsafe {
+            std::slice::from_raw_parts(
+                (*self.inner.individual_nodes.add(i)).cast::<NodeId>(),
+                usize::try_from(length).unwrap(),
+            )
+        })
+    }
+
+    /// Return an [`Iterator`] over the nodes of each individual.
+    ///
+    /// The `i`-th item contains the nodes of
+    /// individual `i`, in increasing order of node id.
+    pub fn individuals_nodes(&self) -> impl Iterator<Item = &[NodeId]> + '_ {
+        let num_individuals = unsafe { (*self.inner.tables).individuals.num_rows };
+        (0..num_individuals).map(move |i| {
+            // All indexes are in range, so unwrap is ok.
+            self.individual_nodes(tsk_id_t::try_from(i).unwrap())
+                .unwrap()
+        })
+    }
+
+    /// Return an [`Iterator`] over sites and their mutations.
+    ///
+    /// Each item contains a view of a site and an iterator over
+    /// views of the mutations at that site, in the order of the
+    /// mutation table.
+    /// The views borrow from the tree sequence, so nothing is copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let node = tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, tskit::PopulationId::NULL, tskit::IndividualId::NULL).unwrap();
+    /// tables.add_site(10., None).unwrap();
+    /// let site = tables.add_site(20., None).unwrap();
+    /// tables.add_mutation(site, node, tskit::MutationId::NULL, 1.0, None).unwrap();
+    /// tables.add_mutation(site, node, tskit::MutationId::NULL, 0.5, None).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let counts = treeseq
+    ///     .sites_with_mutations()
+    ///     .map(|(_, mutations)| mutations.len())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(counts, vec![0, 2]);
+    /// ```
+    pub fn sites_with_mutations(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            crate::SiteTableRowView<'_>,
+            impl ExactSizeIterator<Item = crate::MutationTableRowView<'_>> + '_,
+        ),
+    > + '_ {
+        let tables = unsafe { &*self.inner.tables };
+        let site_mutations_length =
+            table_column!(&self.inner, site_mutations_length, tables.sites.num_rows);
+        let mut offset: tsk_id_t = 0;
+        (0..).zip(site_mutations_length).map(move |(i, &length)| {
+            // Mutations at a site are contiguous and the counts
+            // sum to the number of mutations, so the ids are in range.
+            let start = offset;
+            offset += tsk_id_t::try_from(length).unwrap();
+            let mutations = (start..offset)
+                .map(move |m| crate::mutation_table::mutation_table_row_view(&tables.mutations, m));
+            (
+                crate::site_table::site_table_row_view(&tables.sites, i),
+                mutations,
+            )
+        })
+    }
+
+    /// Return the chain of mutations from `mutation` back to
+    /// the first mutation at its site.
+    ///
+    /// The chain is obtained by following the `parent`
+    /// column of the mutation table.  The first element
+    /// is `mutation` and the last element is a mutation
+    /// whose parent is [`MutationId::NULL`].
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::IndexError`] if `mutation` is out of range.
+    /// * [`TskitError::ValueError`] if a parent is at a different site
+    ///   or does not precede its child in the mutation table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// let node = tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, tskit::PopulationId::NULL, tskit::IndividualId::NULL).unwrap();
+    /// let site = tables.add_site(20., None).unwrap();
+    /// let first = tables.add_mutation(site, node, tskit::MutationId::NULL, 1.0, None).unwrap();
+    /// let back = tables.add_mutation(site, node, first, 0.5, None).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// assert_eq!(treeseq.mutation_chain(back).unwrap(), vec![back, first]);
+    /// ```
+    pub fn mutation_chain<M: Into<MutationId>>(
+        &self,
+        mutation: M,
+    ) -> Result<Vec<MutationId>, TskitError> {
+        let mutations = self.mutations();
+        let mut current = mutation.into();
+        let site = mutations.site(current)?;
+        let mut chain = vec![current];
+        loop {
+            let parent = mutations.parent(current)?;
+            if parent == MutationId::NULL {
+                break;
+            }
+            if parent >= current || mutations.site(parent)? != site {
+                return Err(TskitError::ValueError {
+                    got: format!("parent {} of mutation {}", parent, current),
+                    expected: format!("an earlier mutation at site {}", site),
+                });
+            }
+            chain.push(parent);
+            current = parent;
+        }
+        Ok(chain)
+    }
+
+    /// Return the edge insertion order of the table indexes.
+    ///
+    /// Edge ids are sorted by left coordinate and then
+    /// by increasing time of the parent node.
+    /// See [`TableCollection::edge_insertion_order`].
+    pub fn edge_insertion_order(&self) -> &[EdgeId] {
+        let tables = unsafe { &*self.inner.tables };
+        // SAFETY: a tree sequence is always indexed.
+        unsafe {
+            std::slice::from_raw_parts(
+                tables.indexes.edge_insertion_order.cast::<EdgeId>(),
+                usize::try_from(tables.indexes.num_edges).unwrap(),
+            )
+        }
+    }
+
+    /// Return the edge removal order of the table indexes.
+    ///
+    /// Edge ids are sorted by right coordinate and then
+    /// by decreasing time of the parent node.
+    /// See [`TableCollection::edge_removal_order`].
+    pub fn edge_removal_order(&self) -> &[EdgeId] {
+        let tables = unsafe { &*self.inner.tables };
+        // SAFETY: a tree sequence is always indexed.
+        unsafe {
+            std::slice::from_raw_parts(
+                tables.indexes.edge_removal_order.cast::<EdgeId>(),
+                usize::try_from(tables.indexes.num_edges).unwrap(),
+            )
+        }
+    }
+
     /// Get the number of trees.
     pub fn num_trees(&self) -> SizeType {
         unsafe { ll_bindings::tsk_treeseq_get_num_trees(self.as_ptr()) }.into()
@@ -399,6 +846,235 @@ impl TreeSequence {
         unsafe { ll_bindings::tsk_treeseq_get_num_samples(self.as_ptr()) }.into()
     }
 
+    /// Return `true` if all genomic coordinates in the tree sequence
+    /// are discrete integer values.
+    pub fn discrete_genome(&self) -> bool {
+        unsafe { ll_bindings::tsk_treeseq_get_discrete_genome(self.as_ptr()) }
+    }
+
+    /// Return `true` if all times in the tree sequence
+    /// are discrete integer values.
+    pub fn discrete_time(&self) -> bool {
+        unsafe { ll_bindings::tsk_treeseq_get_discrete_time(self.as_ptr()) }
+    }
+
+    /// Return the minimum time of all nodes and of all mutations
+    /// whose times are known.
+    ///
+    /// Returns `None` if there are no nodes and no mutations with known times.
+    pub fn min_time(&self) -> Option<Time> {
+        self.known_times().reduce(f64::min).map(Time::from)
+    }
+
+    /// Return the maximum time of all nodes and of all mutations
+    /// whose times are known.
+    ///
+    /// Returns `None` if there are no nodes and no mutations with known times.
+    pub fn max_time(&self) -> Option<Time> {
+        self.known_times().reduce(f64::max).map(Time::from)
+    }
+
+    fn known_times(&self) -> impl Iterator<Item = f64> + '_ {
+        let tables = unsafe { &*self.inner.tables };
+        let node_times = tables.nodes.time;
+        let num_nodes = usize::try_from(tables.nodes.num_rows).unwrap();
+        let mutation_times = tables.mutations.time;
+        let num_mutations = usize::try_from(tables.mutations.num_rows).unwrap();
+        (0..num_nodes)
+            .map(move |i| unsafe { *node_times.add(i) })
+            .chain(
+                (0..num_mutations)
+                    .map(move |i| unsafe { *mutation_times.add(i) })
+                    .filter(|t| !unsafe { ll_bindings::tsk_is_unknown_time(*t) }),
+            )
+    }
+
+    /// Return a new tree sequence with all mutation times computed.
+    ///
+    /// See [`TableCollection::compute_mutation_times`] for details.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError`] will be raised if the underlying C library returns an error code.
+    pub fn compute_mutation_times(&self) -> Result<Self, TskitError> {
+        let mut tables = self.dump_tables()?;
+        tables.compute_mutation_times()?;
+        Self::new(tables, TreeSequenceFlags::default())
+    }
+
+    /// Decode the top-level metadata of the tree sequence.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(T))` if there is metadata that decodes successfully.
+    /// * `Ok(None)` if there is no metadata.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::MetadataDecodeError`] if decoding fails.
+    pub fn metadata<T: crate::metadata::TreeSequenceMetadata>(
+        &self,
+    ) -> Result<Option<T>, TskitError> {
+        let tables = unsafe { &*self.inner.tables };
+        let buffer = unsafe {
+            crate::metadata::char_array_to_slice(self, tables.metadata, tables.metadata_length)
+        };
+        decode_metadata_row!(T, buffer)
+    }
+
+    /// Decode the top-level metadata of the tree sequence,
+    /// treating the absence of metadata as an error.
+    ///
+    /// # Errors
+    ///
+    /// * [`TskitError::UnexpectedEmptyMetadata`] if there is no metadata.
+    /// * [`TskitError::MetadataDecodeError`] if decoding fails.
+    pub fn metadata_or_err<T: crate::metadata::TreeSequenceMetadata>(
+        &self,
+    ) -> Result<T, TskitError> {
+        self.metadata()?
+            .ok_or(TskitError::UnexpectedEmptyMetadata { row: None })
+    }
+
+    /// Return the top-level metadata schema of the tree sequence.
+    ///
+    /// Returns `None` if there is no schema or if the schema
+    /// is not valid UTF-8.
+    pub fn metadata_schema(&self) -> Option<&str> {
+        let tables = unsafe { &*self.inner.tables };
+        unsafe {
+            crate::metadata::char_array_to_slice(
+                self,
+                tables.metadata_schema,
+                tables.metadata_schema_length,
+            )
+        }
+        .and_then(|s| std::str::from_utf8(s).ok())
+    }
+
+    /// Return the time units of the tree sequence.
+    ///
+    /// Returns `None` if the time units are not set or
+    /// are not valid UTF-8.
+    pub fn time_units(&self) -> Option<&str> {
+        let tables = unsafe { &*self.inner.tables };
+        unsafe {
+            crate::metadata::char_array_to_slice(self, tables.time_units, tables.time_units_length)
+        }
+        .and_then(|s| std::str::from_utf8(s).ok())
+    }
+
+    /// Return `time` with the time units of the tree sequence.
+    ///
+    /// See [`TimeWithUnits`](crate::TimeWithUnits).
+    pub fn time_with_units<T: Into<Time>>(&self, time: T) -> crate::TimeWithUnits<'_> {
+        crate::TimeWithUnits::new(time, self.time_units())
+    }
+
+    /// Summarize the contents of the tree sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(100.).unwrap();
+    /// tables.add_node(0, 0.0, tskit::PopulationId::NULL, tskit::IndividualId::NULL).unwrap();
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let summary = treeseq.summary();
+    /// assert_eq!(summary.num_trees, 1);
+    /// assert_eq!(summary.num_nodes, 1);
+    /// assert_eq!(summary.sequence_length, 100.);
+    /// println!("{}", summary);
+    /// ```
+    pub fn summary(&self) -> TreeSequenceSummary {
+        TreeSequenceSummary {
+            num_trees: self.num_trees(),
+            num_edges: self.edges().num_rows(),
+            num_nodes: self.nodes().num_rows(),
+            num_sites: self.sites().num_rows(),
+            num_mutations: self.mutations().num_rows(),
+            num_individuals: self.individuals().num_rows(),
+            num_migrations: self.migrations().num_rows(),
+            sequence_length: unsafe { (*self.inner.tables).sequence_length }.into(),
+            time_units: self.time_units().map(|s| s.to_owned()),
+        }
+    }
+
+    /// Draw a random subset of the sample nodes.
+    ///
+    /// # Parameters
+    ///
+    /// * `n`: the number of samples to draw.
+    ///   If `by_population` is `true`, `n` samples are drawn
+    ///   from each population, with nodes not assigned to a population
+    ///   treated as one more population.
+    /// * `rng_seed`: seed for the random number generator.
+    ///   The same seed always gives the same subset.
+    /// * `by_population`: whether to stratify the draw by population.
+    ///
+    /// # Returns
+    ///
+    /// The sampled nodes, sorted by id, which is the form
+    /// expected by [`TreeSequence::simplify`].
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`] if fewer than `n` samples
+    /// are available (in any population, if `by_population` is `true`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let mut tables = tskit::TableCollection::new(1.).unwrap();
+    /// for _ in 0..10 {
+    ///     tables.add_node(tskit::TSK_NODE_IS_SAMPLE, 0.0, -1, -1).unwrap();
+    /// }
+    /// tables.build_index().unwrap();
+    /// let treeseq = tables.tree_sequence(tskit::TreeSequenceFlags::default()).unwrap();
+    /// let subset = treeseq.sample_subset(3, 42, false).unwrap();
+    /// assert_eq!(subset.len(), 3);
+    /// assert_eq!(subset, treeseq.sample_subset(3, 42, false).unwrap());
+    /// assert!(treeseq.sample_subset(11, 42, false).is_err());
+    /// ```
+    pub fn sample_subset(
+        &self,
+        n: usize,
+        rng_seed: u64,
+        by_population: bool,
+    ) -> Result<Vec<NodeId>, TskitError> {
+        let mut rng = crate::util::SplitMix64::new(rng_seed);
+        let mut groups: Vec<Vec<NodeId>> = if by_population {
+            let nodes = self.nodes();
+            let mut groups = std::collections::BTreeMap::<crate::PopulationId, Vec<NodeId>>::new();
+            for &u in self.sample_nodes() {
+                groups.entry(nodes.population(u)?).or_default().push(u);
+            }
+            groups.into_values().collect()
+        } else {
+            vec![self.sample_nodes().to_vec()]
+        };
+        let mut subset = Vec::with_capacity(n * groups.len());
+        for group in groups.iter_mut() {
+            if group.len() < n {
+                return Err(TskitError::ValueError {
+                    got: format!("{} samples", group.len()),
+                    expected: format!("at least {} samples", n),
+                });
+            }
+            rng.partial_shuffle(group, n);
+            subset.extend_from_slice(&group[..n]);
+        }
+        subset.sort();
+        Ok(subset)
+    }
+
+    /// Return the number of bytes used by the data in the tables.
+    ///
+    /// See [`TableCollection::nbytes`] for details.
+    pub fn nbytes(&self) -> usize {
+        crate::table_collection::table_collection_nbytes(unsafe { &*self.inner.tables })
+    }
+
     /// Simplify tables and return a new tree sequence.
     ///
     /// # Parameters
@@ -454,13 +1130,30 @@ impl TreeSequence {
         )
     }
 
+    /// Simplify tables and return a new tree sequence
+    /// along with the id maps of all tables that simplification
+    /// may modify.
+    ///
+    /// See [`TableCollection::simplify_with_id_maps`] for details.
+    pub fn simplify_with_id_maps<O: Into<SimplificationOptions>>(
+        &self,
+        samples: &[NodeId],
+        options: O,
+    ) -> Result<(Self, crate::SimplificationIdMaps), TskitError> {
+        let mut tables = self.dump_tables()?;
+        let maps = tables.simplify_with_id_maps(samples, options)?;
+        tables.build_index()?;
+        Ok((Self::new(tables, TreeSequenceFlags::default())?, maps))
+    }
+
     #[cfg(any(feature = "provenance", doc))]
+    provenance_table_add_row!(
     /// Add provenance record with a time stamp.
     ///
     /// All implementation of this trait provided by `tskit` use
     /// an `ISO 8601` format time stamp
     /// written using the [RFC 3339](https://tools.ietf.org/html/rfc3339)
-    /// specification.
+    /// specification, with microsecond precision as in the `Python` API.
     /// This formatting approach has been the most straightforward method
     /// for supporting round trips to/from a [`crate::provenance::ProvenanceTable`].
     /// The implementations used here use the [`humantime`](https://docs.rs/humantime/latest/humantime/) crate.
@@ -488,19 +1181,14 @@ impl TreeSequence {
     /// println!("utc = {}", dt_utc);
     /// # }
     /// ```
-    pub fn add_provenance(&mut self, record: &str) -> Result<crate::ProvenanceId, TskitError> {
-        let timestamp = humantime::format_rfc3339(std::time::SystemTime::now()).to_string();
-        let rv = unsafe {
-            ll_bindings::tsk_provenance_table_add_row(
-                &mut (*self.inner.tables).provenances,
-                timestamp.as_ptr() as *mut i8,
-                timestamp.len() as tsk_size_t,
-                record.as_ptr() as *mut i8,
-                record.len() as tsk_size_t,
-            )
-        };
-        handle_tsk_return_value!(rv, crate::ProvenanceId::from(rv))
-    }
+    => add_provenance, self, (*self.inner.tables).provenances);
+
+    #[cfg(any(feature = "provenance", doc))]
+    provenance_table_add_row_with_timestamp!(
+    /// Add provenance record with a given time stamp.
+    ///
+    /// See [`TableCollection::add_provenance_with_timestamp`].
+    => add_provenance_with_timestamp, self, (*self.inner.tables).provenances);
 }
 
 impl TryFrom<TableCollection> for TreeSequence {
@@ -548,7 +1236,7 @@ impl TableAccess for TreeSequence {
     }
 }
 
-impl crate::traits::NodeListGenerator for TreeSequence {}
+impl_table_access_inherent!(TreeSequence);
 
 #[cfg(test)]
 pub(crate) mod test_trees {
@@ -610,6 +1298,22 @@ pub(crate) mod test_trees {
         assert_eq!(ntrees, 1);
     }
 
+    #[test]
+    fn test_tree_opt_accessors() {
+        let tables = make_small_table_collection();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
+        let tree = tree_iter.next().unwrap();
+        assert_eq!(tree.parent_opt(1).unwrap(), Some(0.into()));
+        assert!(tree.parent_opt(0).unwrap().is_none());
+        assert!(tree.left_child_opt(1).unwrap().is_none());
+        assert_eq!(tree.left_child_opt(0).unwrap(), Some(1.into()));
+        assert_eq!(tree.right_child_opt(0).unwrap(), Some(2.into()));
+        assert_eq!(tree.right_sib_opt(1).unwrap(), Some(2.into()));
+        assert!(tree.left_sib_opt(1).unwrap().is_none());
+        assert!(tree.parent_opt(NodeId::NULL).is_err());
+    }
+
     #[test]
     fn test_iterate_no_roots() {
         let mut tables = TableCollection::new(100.).unwrap();
@@ -645,9 +1349,9 @@ pub(crate) mod test_trees {
         assert_eq!(treeseq.num_samples(), 2);
         let mut tree_iter = treeseq.tree_iterator(TreeFlags::default()).unwrap();
         if let Some(tree) = tree_iter.next() {
-            assert_eq!(tree.num_tracked_samples(2.into()).unwrap(), 1);
-            assert_eq!(tree.num_tracked_samples(1.into()).unwrap(), 1);
-            assert_eq!(tree.num_tracked_samples(0.into()).unwrap(), 2);
+            assert_eq!(tree.num_tracked_samples(2).unwrap(), 1);
+            assert_eq!(tree.num_tracked_samples(1).unwrap(), 1);
+            assert_eq!(tree.num_tracked_samples(0).unwrap(), 2);
         }
     }
 
@@ -658,9 +1362,9 @@ pub(crate) mod test_trees {
         assert_eq!(treeseq.num_samples(), 2);
         let mut tree_iter = treeseq.tree_iterator(TreeFlags::NO_SAMPLE_COUNTS).unwrap();
         if let Some(tree) = tree_iter.next() {
-            assert_eq!(tree.num_tracked_samples(2.into()).unwrap(), 0);
-            assert_eq!(tree.num_tracked_samples(1.into()).unwrap(), 0);
-            assert_eq!(tree.num_tracked_samples(0.into()).unwrap(), 0);
+            assert_eq!(tree.num_tracked_samples(2).unwrap(), 0);
+            assert_eq!(tree.num_tracked_samples(1).unwrap(), 0);
+            assert_eq!(tree.num_tracked_samples(0).unwrap(), 0);
         }
     }
 
@@ -674,27 +1378,27 @@ pub(crate) mod test_trees {
             assert!(!tree.flags().contains(TreeFlags::NO_SAMPLE_COUNTS));
             assert!(tree.flags().contains(TreeFlags::SAMPLE_LISTS));
             let mut s = vec![];
-            for i in tree.samples(0.into()).unwrap() {
+            for i in tree.samples(0).unwrap() {
                 s.push(i);
             }
             assert_eq!(s.len(), 2);
             assert_eq!(
                 s.len(),
-                usize::try_from(tree.num_tracked_samples(0.into()).unwrap()).unwrap()
+                usize::try_from(tree.num_tracked_samples(0).unwrap()).unwrap()
             );
             assert_eq!(s[0], 1);
             assert_eq!(s[1], 2);
 
             for u in 1..3 {
                 let mut s = vec![];
-                for i in tree.samples(u.into()).unwrap() {
+                for i in tree.samples(u).unwrap() {
                     s.push(i);
                 }
                 assert_eq!(s.len(), 1);
                 assert_eq!(s[0], u);
                 assert_eq!(
                     s.len(),
-                    usize::try_from(tree.num_tracked_samples(u.into()).unwrap()).unwrap()
+                    usize::try_from(tree.num_tracked_samples(u).unwrap()).unwrap()
                 );
             }
         } else {
@@ -790,6 +1494,353 @@ pub(crate) mod test_trees {
         assert!(tables_copy.equals(&dumped, crate::TableEqualityOptions::default()));
     }
 
+    #[test]
+    fn test_sites_with_mutations() {
+        let mut tables = make_small_table_collection_two_trees();
+        tables.add_site(0.25, None).unwrap();
+        tables.add_site(0.5, None).unwrap();
+        tables.add_site(0.75, None).unwrap();
+        tables
+            .add_mutation(0, 2, MutationId::NULL, 0.5, None)
+            .unwrap();
+        tables
+            .add_mutation(2, 3, MutationId::NULL, 0.5, None)
+            .unwrap();
+        tables
+            .add_mutation(2, 3, MutationId::NULL, 0.25, None)
+            .unwrap();
+        let ts = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        let grouped = ts
+            .sites_with_mutations()
+            .map(|(site, mutations)| (site, mutations.collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        assert_eq!(grouped.len(), 3);
+        for (site, mutations) in &grouped {
+            assert!(mutations.iter().all(|m| m.site == site.id));
+        }
+        let ids = grouped
+            .iter()
+            .map(|(_, m)| m.iter().map(|m| m.id).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            vec![
+                vec![MutationId::from(0)],
+                vec![],
+                vec![MutationId::from(1), MutationId::from(2)]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mutation_chain() {
+        let mut tables = make_small_table_collection_two_trees();
+        tables.add_site(0.25, None).unwrap();
+        tables.add_site(0.5, None).unwrap();
+        let m0 = tables
+            .add_mutation(0, 0, MutationId::NULL, 3.0, None)
+            .unwrap();
+        let m1 = tables.add_mutation(0, 2, m0, 0.5, None).unwrap();
+        let m2 = tables.add_mutation(0, 2, m1, 0.25, None).unwrap();
+        let m3 = tables
+            .add_mutation(1, 3, MutationId::NULL, 0.5, None)
+            .unwrap();
+        let ts = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        assert_eq!(ts.mutation_chain(m2).unwrap(), vec![m2, m1, m0]);
+        assert_eq!(ts.mutation_chain(m0).unwrap(), vec![m0]);
+        assert_eq!(ts.mutation_chain(m3).unwrap(), vec![m3]);
+        assert!(matches!(ts.mutation_chain(4), Err(TskitError::IndexError)));
+    }
+
+    #[test]
+    fn test_edge_index_orders() {
+        let tables = make_small_table_collection_two_trees();
+        let insertion = tables.edge_insertion_order().unwrap().to_vec();
+        let removal = tables.edge_removal_order().unwrap().to_vec();
+        let ts = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        assert_eq!(ts.edge_insertion_order(), insertion.as_slice());
+        assert_eq!(ts.edge_removal_order(), removal.as_slice());
+        assert_eq!(
+            ts.edge_insertion_order().len(),
+            usize::try_from(ts.edges().num_rows()).unwrap()
+        );
+        // Insertion order is sorted by left coordinate
+        let lefts = ts
+            .edge_insertion_order()
+            .iter()
+            .map(|&e| ts.edges().left(e).unwrap())
+            .collect::<Vec<_>>();
+        assert!(lefts.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut tables = make_small_table_collection_two_trees();
+        tables.add_site(0.5, None).unwrap();
+        tables
+            .add_mutation(0, 2, MutationId::NULL, 0.5, None)
+            .unwrap();
+        let units = "generations";
+        let rv = unsafe {
+            ll_bindings::tsk_table_collection_set_time_units(
+                tables.as_mut_ptr(),
+                units.as_ptr().cast::<i8>(),
+                units.len() as tsk_size_t,
+            )
+        };
+        assert_eq!(rv, 0);
+        let ts = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        let summary = ts.summary();
+        assert_eq!(summary.num_trees, ts.num_trees());
+        assert_eq!(summary.num_edges, ts.edges().num_rows());
+        assert_eq!(summary.num_nodes, ts.nodes().num_rows());
+        assert_eq!(summary.num_sites, 1);
+        assert_eq!(summary.num_mutations, 1);
+        assert_eq!(summary.num_individuals, 0);
+        assert_eq!(summary.num_migrations, 0);
+        assert_eq!(summary.sequence_length, 1000.);
+        assert_eq!(summary.time_units.as_deref(), Some(units));
+        let display = format!("{}", summary);
+        assert!(display.contains("time_units: generations"));
+        assert!(display.contains("num_mutations: 1"));
+    }
+
+    #[test]
+    fn test_tables_round_trip() {
+        let tables = make_small_table_collection_two_trees();
+        let tables_copy = tables.deepcopy().unwrap();
+        let ts = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        assert!(tables_copy.equals(
+            &ts.tables().unwrap(),
+            crate::TableEqualityOptions::default()
+        ));
+        let mut recovered = ts.into_tables();
+        assert!(tables_copy.equals(&recovered, crate::TableEqualityOptions::default()));
+        recovered.add_site(0.5, None).unwrap();
+        let ts = recovered
+            .tree_sequence(TreeSequenceFlags::default())
+            .unwrap();
+        assert_eq!(ts.tables().unwrap().sites().num_rows(), 1);
+    }
+
+    #[test]
+    fn test_load_from_bytes_and_reader() {
+        let treefile = "load_from_bytes.trees";
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        treeseq
+            .dump(treefile, crate::TableOutputOptions::default())
+            .unwrap();
+        let bytes = std::fs::read(treefile).unwrap();
+        std::fs::remove_file(treefile).unwrap();
+
+        let from_bytes = TreeSequence::load_from_bytes(&bytes).unwrap();
+        assert_eq!(from_bytes.num_trees(), treeseq.num_trees());
+
+        let from_reader = TreeSequence::load_from_reader(bytes.as_slice()).unwrap();
+        assert_eq!(from_reader.num_trees(), treeseq.num_trees());
+
+        assert!(TreeSequence::load_from_bytes(&bytes[..bytes.len() / 2]).is_err());
+    }
+
+    #[test]
+    fn test_top_level_metadata() {
+        struct Metadata(Vec<u8>);
+
+        impl crate::metadata::MetadataRoundtrip for Metadata {
+            fn encode(&self) -> Result<Vec<u8>, crate::metadata::MetadataError> {
+                Ok(self.0.clone())
+            }
+
+            fn decode(md: &[u8]) -> Result<Self, crate::metadata::MetadataError> {
+                Ok(Self(md.to_vec()))
+            }
+        }
+
+        impl crate::metadata::TreeSequenceMetadata for Metadata {}
+
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        assert!(treeseq.metadata::<Metadata>().unwrap().is_none());
+        assert!(treeseq.metadata_schema().is_none());
+
+        let mut tables = make_small_table_collection_two_trees();
+        let metadata = b"metadata";
+        let schema = "{\"codec\":\"json\"}";
+        unsafe {
+            let rv = ll_bindings::tsk_table_collection_set_metadata(
+                tables.as_mut_ptr(),
+                metadata.as_ptr().cast::<libc::c_char>(),
+                metadata.len() as tsk_size_t,
+            );
+            assert_eq!(rv, 0);
+            let rv = ll_bindings::tsk_table_collection_set_metadata_schema(
+                tables.as_mut_ptr(),
+                schema.as_ptr().cast::<libc::c_char>(),
+                schema.len() as tsk_size_t,
+            );
+            assert_eq!(rv, 0);
+        }
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        let decoded = treeseq.metadata::<Metadata>().unwrap().unwrap();
+        assert_eq!(decoded.0, metadata);
+        assert_eq!(treeseq.metadata_schema(), Some(schema));
+    }
+
+    #[test]
+    fn test_discreteness_and_time_bounds() {
+        let treeseq = treeseq_from_small_table_collection_two_trees();
+        assert!(treeseq.discrete_genome());
+        assert!(treeseq.discrete_time());
+        let min_time = treeseq.min_time().unwrap();
+        let max_time = treeseq.max_time().unwrap();
+        assert!(min_time <= max_time);
+        for row in treeseq.nodes_iter() {
+            assert!(row.time >= min_time);
+            assert!(row.time <= max_time);
+        }
+
+        let mut tables = TableCollection::new(10.).unwrap();
+        tables.add_node(0, 0.5, -1, -1).unwrap();
+        tables.add_node(0, 1.0, -1, -1).unwrap();
+        let site = tables.add_site(1.5, None).unwrap();
+        tables
+            .add_mutation(site, 0, crate::MutationId::NULL, 10.0, None)
+            .unwrap();
+        let site = tables.add_site(2.5, None).unwrap();
+        tables
+            .add_mutation(
+                site,
+                0,
+                crate::MutationId::NULL,
+                f64::from_bits(ll_bindings::TSK_UNKNOWN_TIME_HEX),
+                None,
+            )
+            .unwrap();
+        tables.build_index().unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        assert!(!treeseq.discrete_genome());
+        assert!(!treeseq.discrete_time());
+        assert_eq!(treeseq.min_time().unwrap(), 0.5);
+        assert_eq!(treeseq.max_time().unwrap(), 10.0);
+
+        let mut tables = TableCollection::new(10.).unwrap();
+        tables.build_index().unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        assert!(treeseq.min_time().is_none());
+        assert!(treeseq.max_time().is_none());
+    }
+
+    #[test]
+    fn test_compute_mutation_times() {
+        let unknown = f64::from_bits(ll_bindings::TSK_UNKNOWN_TIME_HEX);
+        let mut tables = TableCollection::new(10.).unwrap();
+        let parent = tables.add_node(0, 3.0, -1, -1).unwrap();
+        let child = tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, -1, -1)
+            .unwrap();
+        tables.add_edge(0., 10., parent, child).unwrap();
+        let site = tables.add_site(5., None).unwrap();
+        let m0 = tables
+            .add_mutation(site, child, crate::MutationId::NULL, unknown, None)
+            .unwrap();
+        tables.add_mutation(site, child, m0, unknown, None).unwrap();
+        tables.build_index().unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        // mutations with unknown times are not counted
+        assert_eq!(treeseq.max_time().unwrap(), 3.0);
+
+        let computed = treeseq.compute_mutation_times().unwrap();
+        let times = computed
+            .mutations_iter()
+            .map(|row| f64::from(row.time))
+            .collect::<Vec<_>>();
+        assert_eq!(times, vec![2.0, 1.0]);
+        // the input is unchanged
+        assert!(treeseq.mutations_iter().all(|row| row.time.0.is_nan()));
+    }
+
+    #[test]
+    fn test_individuals_nodes() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        let i0 = tables.add_individual(0, None, None).unwrap();
+        let i1 = tables.add_individual(0, None, None).unwrap();
+        let i2 = tables.add_individual(0, None, None).unwrap();
+        let n0 = tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, -1, i2)
+            .unwrap();
+        let n1 = tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, -1, i0)
+            .unwrap();
+        tables.add_node(0, 1.0, -1, -1).unwrap();
+        let n3 = tables
+            .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, -1, i2)
+            .unwrap();
+        tables.build_index().unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+
+        assert_eq!(treeseq.samples(), &[n0, n1, n3]);
+        assert_eq!(treeseq.individual_nodes(i0).unwrap(), &[n1]);
+        assert!(treeseq.individual_nodes(i1).unwrap().is_empty());
+        assert_eq!(treeseq.individual_nodes(i2).unwrap(), &[n0, n3]);
+        assert!(treeseq.individual_nodes(IndividualId::NULL).is_none());
+        assert!(treeseq.individual_nodes(3).is_none());
+
+        let grouped = treeseq.individuals_nodes().collect::<Vec<_>>();
+        assert_eq!(grouped.len(), 3);
+        assert_eq!(grouped[0], &[n1]);
+        assert!(grouped[1].is_empty());
+        assert_eq!(grouped[2], &[n0, n3]);
+    }
+
+    #[test]
+    fn test_sample_subset() {
+        let mut tables = TableCollection::new(1.).unwrap();
+        let p0 = tables.add_population().unwrap();
+        let p1 = tables.add_population().unwrap();
+        for i in 0..20 {
+            let pop = if i % 4 == 0 { p1 } else { p0 };
+            tables
+                .add_node(crate::TSK_NODE_IS_SAMPLE, 0.0, pop, -1)
+                .unwrap();
+        }
+        tables.add_node(0, 1.0, p1, -1).unwrap();
+        tables.build_index().unwrap();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+
+        let subset = treeseq.sample_subset(10, 101, false).unwrap();
+        assert_eq!(subset.len(), 10);
+        assert!(subset.windows(2).all(|w| w[0] < w[1]));
+        assert!(subset.iter().all(|u| treeseq.sample_nodes().contains(u)));
+        assert_eq!(subset, treeseq.sample_subset(10, 101, false).unwrap());
+        assert_ne!(subset, treeseq.sample_subset(10, 202, false).unwrap());
+        assert_eq!(
+            treeseq.sample_subset(20, 1, false).unwrap(),
+            treeseq.sample_nodes()
+        );
+        assert!(treeseq.sample_subset(21, 1, false).is_err());
+
+        let stratified = treeseq.sample_subset(5, 101, true).unwrap();
+        assert_eq!(stratified.len(), 10);
+        let nodes = treeseq.nodes();
+        for pop in [p0, p1] {
+            let count = stratified
+                .iter()
+                .filter(|&&u| nodes.population(u).unwrap() == pop)
+                .count();
+            assert_eq!(count, 5);
+        }
+        // p1 only has 5 samples
+        assert!(treeseq.sample_subset(6, 101, true).is_err());
+        assert!(treeseq.sample_subset(6, 101, false).is_ok());
+    }
+
+    #[test]
+    fn test_nbytes() {
+        let tables = make_small_table_collection_two_trees();
+        let nbytes = tables.nbytes();
+        let treeseq = tables.tree_sequence(TreeSequenceFlags::default()).unwrap();
+        assert_eq!(treeseq.nbytes(), nbytes);
+    }
+
     #[test]
     fn test_reverse_tree_iteration() {
         let treeseq = treeseq_from_small_table_collection_two_trees();
@@ -800,8 +1851,8 @@ pub(crate) mod test_trees {
         let mut stops_rev = vec![];
         while let Some(tree) = tree_iter.next() {
             let interval = tree.interval();
-            starts_fwd.push(interval.0);
-            stops_fwd.push(interval.1);
+            starts_fwd.push(interval.left);
+            stops_fwd.push(interval.right);
         }
         assert_eq!(stops_fwd.len(), 2);
         assert_eq!(stops_fwd.len(), 2);
@@ -809,8 +1860,8 @@ pub(crate) mod test_trees {
         // NOTE: we do NOT need to create a new iterator.
         while let Some(tree) = tree_iter.next_back() {
             let interval = tree.interval();
-            starts_rev.push(interval.0);
-            stops_rev.push(interval.1);
+            starts_rev.push(interval.left);
+            stops_rev.push(interval.right);
         }
         assert_eq!(starts_fwd.len(), starts_rev.len());
         assert_eq!(stops_fwd.len(), stops_rev.len());
@@ -855,4 +1906,114 @@ mod test_treeeseq_send_sync {
         let ntrees = join_handle.join().unwrap();
         assert_eq!(ntrees, 2);
     }
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_types_are_send_sync() {
+        assert_send_sync::<crate::TreeSequence>();
+        assert_send_sync::<crate::TableCollection>();
+        assert_send_sync::<crate::EdgeTable>();
+        assert_send_sync::<crate::IndividualTable>();
+        assert_send_sync::<crate::MigrationTable>();
+        assert_send_sync::<crate::MutationTable>();
+        assert_send_sync::<crate::NodeTable>();
+        assert_send_sync::<crate::PopulationTable>();
+        assert_send_sync::<crate::SiteTable>();
+        assert_send_sync::<crate::OwnedEdgeTable>();
+        assert_send_sync::<crate::OwnedIndividualTable>();
+        assert_send_sync::<crate::OwnedMigrationTable>();
+        assert_send_sync::<crate::OwnedMutationTable>();
+        assert_send_sync::<crate::OwnedNodeTable>();
+        assert_send_sync::<crate::OwnedPopulationTable>();
+        assert_send_sync::<crate::OwnedSiteTable>();
+        #[cfg(feature = "provenance")]
+        {
+            assert_send_sync::<crate::provenance::ProvenanceTable>();
+            assert_send_sync::<crate::provenance::OwnedProvenanceTable>();
+        }
+    }
+
+    #[test]
+    fn test_scoped_threads_share_tables() {
+        let t = treeseq_from_small_table_collection_two_trees();
+        let nodes = t.nodes();
+        let total: f64 = thread::scope(|s| {
+            let handles = (0..4)
+                .map(|_| {
+                    s.spawn(|| {
+                        nodes
+                            .time_slice()
+                            .iter()
+                            .map(|&t| f64::from(t))
+                            .sum::<f64>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles.into_iter().map(|h| h.join().unwrap()).sum()
+        });
+        let expected: f64 = nodes.time_slice().iter().map(|&t| f64::from(t)).sum();
+        assert_eq!(total, 4. * expected);
+    }
+
+    #[test]
+    fn test_independent_tree_cursors() {
+        use streaming_iterator::StreamingIterator;
+        let t = treeseq_from_small_table_collection_two_trees();
+        let mut intervals = vec![];
+        let mut tree = t.tree_iterator(crate::TreeFlags::default()).unwrap();
+        while let Some(tree) = tree.next() {
+            intervals.push(tree.interval());
+        }
+        assert_eq!(intervals.len(), 2);
+
+        let mut tree = t.tree_iterator(crate::TreeFlags::default()).unwrap();
+        tree.seek(intervals[1].left).unwrap();
+        assert_eq!(tree.interval(), intervals[1]);
+        assert!(tree.next().is_none());
+        assert_eq!(
+            tree.seek(t.tables().unwrap().sequence_length())
+                .unwrap_err()
+                .code(),
+            Some(crate::bindings::TSK_ERR_SEEK_OUT_OF_BOUNDS)
+        );
+
+        // Each tree is moved to its own thread.
+        let trees = intervals
+            .iter()
+            .map(|interval| {
+                let mut tree = t.tree_iterator(crate::TreeFlags::default()).unwrap();
+                tree.seek(interval.left).unwrap();
+                tree
+            })
+            .collect::<Vec<_>>();
+        let found = thread::scope(|s| {
+            let handles = trees
+                .into_iter()
+                .map(|tree| s.spawn(move || tree.interval()))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(found, intervals);
+    }
+
+    #[test]
+    fn test_clone() {
+        let t = treeseq_from_small_table_collection_two_trees();
+        let c = t.clone();
+        drop(t);
+        assert_eq!(c.num_trees(), 2);
+        let copy_handle = thread::spawn({
+            let c = c.clone();
+            move || c.num_trees()
+        });
+        assert_eq!(copy_handle.join().unwrap(), c.num_trees());
+        assert!(c
+            .dump_tables()
+            .unwrap()
+            .equals(&c.clone().dump_tables().unwrap(), 0));
+    }
 }
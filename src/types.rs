@@ -12,6 +12,10 @@ use crate::bindings as ll_bindings;
 /// the first row from which to begin.
 /// The names of the fields are the same
 /// names as tables in a TableCollection.
+///
+/// A bookmark of the current table sizes can be obtained
+/// from [``record_num_rows``](crate::TableCollection::record_num_rows).
+#[derive(Clone, Copy, Debug)]
 pub struct Bookmark {
     pub offsets: ll_bindings::tsk_bookmark_t,
 }
@@ -33,6 +37,299 @@ impl Bookmark {
     }
 }
 
+impl Default for Bookmark {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Identifies one of the tables of a
+/// [`TableCollection`](crate::TableCollection).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TableKind {
+    Nodes,
+    Edges,
+    Sites,
+    Mutations,
+    Migrations,
+    Individuals,
+    Populations,
+    Provenances,
+}
+
+impl std::fmt::Display for TableKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            TableKind::Nodes => "node",
+            TableKind::Edges => "edge",
+            TableKind::Sites => "site",
+            TableKind::Mutations => "mutation",
+            TableKind::Migrations => "migration",
+            TableKind::Individuals => "individual",
+            TableKind::Populations => "population",
+            TableKind::Provenances => "provenance",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The type of the values stored in a table column.
+///
+/// See [`TableColumns`](crate::TableColumns).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColumnType {
+    /// 32-bit signed integers, used for row ids
+    /// ([`tsk_id_t`](crate::tsk_id_t)).
+    Int32,
+    /// 32-bit unsigned integers, used for flags
+    /// ([`tsk_flags_t`](crate::tsk_flags_t)).
+    UInt32,
+    /// 64-bit floating point values, used for times,
+    /// positions and locations.
+    Float64,
+    /// Bytes, used for metadata, states and provenance text.
+    UInt8,
+}
+
+/// Description of one column of a table.
+///
+/// See [`TableColumns`](crate::TableColumns).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColumnInfo {
+    /// The name of the column, as used by the tskit C API.
+    pub name: &'static str,
+    pub column_type: ColumnType,
+    /// The number of values in the column.
+    ///
+    /// This equals the number of rows, except for ragged columns.
+    pub len: crate::SizeType,
+    /// The length of the offset column of a ragged column,
+    /// which is one more than the number of rows.
+    ///
+    /// `None` if the column is not ragged.
+    pub offsets_len: Option<crate::SizeType>,
+}
+
+impl ColumnInfo {
+    /// Return `true` if rows of the column hold a variable number of values.
+    pub fn is_ragged(&self) -> bool {
+        self.offsets_len.is_some()
+    }
+}
+
+/// A half-open interval `[left, right)` of the genome.
+///
+/// # Examples
+///
+/// ```
+/// use tskit::GenomicInterval;
+/// let a = GenomicInterval::new(0., 10.).unwrap();
+/// let b = GenomicInterval::from((5., 20.));
+/// assert_eq!(a.span(), 10.);
+/// assert!(a.contains(0.));
+/// assert!(!a.contains(10.));
+/// assert!(a.overlaps(&b));
+/// assert_eq!(a.intersection(&b), Some(GenomicInterval::from((5., 10.))));
+/// assert_eq!(a.to_string(), "[0, 10)");
+/// assert!(GenomicInterval::new(10., 0.).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GenomicInterval {
+    pub left: crate::Position,
+    pub right: crate::Position,
+}
+
+impl GenomicInterval {
+    /// Create a new interval.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::ValueError`](crate::TskitError::ValueError)
+    /// unless `0 <= left < right` and `right` is finite.
+    pub fn new<L: Into<crate::Position>, R: Into<crate::Position>>(
+        left: L,
+        right: R,
+    ) -> Result<Self, crate::TskitError> {
+        let interval = Self::from((left, right));
+        let (left, right) = (f64::from(interval.left), f64::from(interval.right));
+        if left >= 0. && left < right && right.is_finite() {
+            Ok(interval)
+        } else {
+            Err(crate::TskitError::ValueError {
+                got: interval.to_string(),
+                expected: "an interval with 0 <= left < right < infinity".to_string(),
+            })
+        }
+    }
+
+    /// The length of the interval.
+    pub fn span(&self) -> crate::Position {
+        self.right - self.left
+    }
+
+    /// Returns `true` if `position` is in the interval.
+    pub fn contains<P: Into<crate::Position>>(&self, position: P) -> bool {
+        let position = position.into();
+        self.left <= position && position < self.right
+    }
+
+    /// Returns `true` if all of `other` is within `self`.
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        self.left <= other.left && other.right <= self.right
+    }
+
+    /// Returns `true` if `self` and `other` share any part of the genome.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.left < other.right && other.left < self.right
+    }
+
+    /// The part of the genome within both `self` and `other`,
+    /// or `None` if they do not overlap.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if self.overlaps(other) {
+            Some(Self {
+                left: f64::max(self.left.into(), other.left.into()).into(),
+                right: f64::min(self.right.into(), other.right.into()).into(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl<L: Into<crate::Position>, R: Into<crate::Position>> From<(L, R)> for GenomicInterval {
+    fn from(value: (L, R)) -> Self {
+        Self {
+            left: value.0.into(),
+            right: value.1.into(),
+        }
+    }
+}
+
+impl From<GenomicInterval> for (crate::Position, crate::Position) {
+    fn from(value: GenomicInterval) -> Self {
+        (value.left, value.right)
+    }
+}
+
+impl std::fmt::Display for GenomicInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "[{}, {})", f64::from(self.left), f64::from(self.right))
+    }
+}
+
+/// A [`Time`](crate::Time) together with the time units
+/// declared by the object that it came from.
+///
+/// Comparing or combining two values is an error if both
+/// declare units and the units differ.
+/// Units that are not set, or are `"unknown"`, match any units.
+///
+/// # Examples
+///
+/// ```
+/// let mut tables = tskit::TableCollection::new(10.).unwrap();
+/// tables.set_time_units("generations").unwrap();
+/// let mut other = tskit::TableCollection::new(10.).unwrap();
+/// other.set_time_units("years").unwrap();
+///
+/// let a = tables.time_with_units(10.);
+/// let b = tables.time_with_units(1.);
+/// assert_eq!(a.try_sub(b).unwrap().time(), 9.);
+/// assert!(a.try_partial_cmp(b).unwrap() == Some(std::cmp::Ordering::Greater));
+///
+/// let c = other.time_with_units(1.);
+/// assert!(a.try_sub(c).is_err());
+/// assert!(a.try_partial_cmp(c).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeWithUnits<'a> {
+    time: crate::Time,
+    units: Option<&'a str>,
+}
+
+impl<'a> TimeWithUnits<'a> {
+    pub fn new<T: Into<crate::Time>>(time: T, units: Option<&'a str>) -> Self {
+        Self {
+            time: time.into(),
+            units,
+        }
+    }
+
+    pub fn time(&self) -> crate::Time {
+        self.time
+    }
+
+    /// The declared units, or `None` if the units are
+    /// not set or are `"unknown"`.
+    pub fn units(&self) -> Option<&'a str> {
+        self.units.filter(|&u| u != "unknown")
+    }
+
+    /// Return the units shared by `self` and `other`.
+    ///
+    /// # Errors
+    ///
+    /// [`TskitError::TimeUnitsMismatch`](crate::TskitError::TimeUnitsMismatch)
+    /// if both declare units and the units differ.
+    pub fn common_units(&self, other: Self) -> Result<Option<&'a str>, crate::TskitError> {
+        match (self.units(), other.units()) {
+            (Some(left), Some(right)) if left != right => {
+                Err(crate::TskitError::TimeUnitsMismatch {
+                    left: left.to_string(),
+                    right: right.to_string(),
+                })
+            }
+            (left, right) => Ok(left.or(right)),
+        }
+    }
+
+    /// Add two times.
+    ///
+    /// # Errors
+    ///
+    /// See [`TimeWithUnits::common_units`].
+    pub fn try_add(&self, other: Self) -> Result<Self, crate::TskitError> {
+        let units = self.common_units(other)?;
+        Ok(Self::new(self.time + other.time, units))
+    }
+
+    /// Subtract `other` from `self`.
+    ///
+    /// # Errors
+    ///
+    /// See [`TimeWithUnits::common_units`].
+    pub fn try_sub(&self, other: Self) -> Result<Self, crate::TskitError> {
+        let units = self.common_units(other)?;
+        Ok(Self::new(self.time - other.time, units))
+    }
+
+    /// Compare two times.
+    ///
+    /// # Errors
+    ///
+    /// See [`TimeWithUnits::common_units`].
+    pub fn try_partial_cmp(
+        &self,
+        other: Self,
+    ) -> Result<Option<std::cmp::Ordering>, crate::TskitError> {
+        self.common_units(other)?;
+        Ok(self.time.partial_cmp(&other.time))
+    }
+}
+
+impl std::fmt::Display for TimeWithUnits<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            f64::from(self.time),
+            self.units().unwrap_or("(unknown units)")
+        )
+    }
+}
+
 #[cfg(test)]
 mod test {
 
@@ -52,4 +349,38 @@ mod test {
         b.offsets.nodes = 3;
         assert_eq!(b.offsets.nodes, 3);
     }
+
+    #[test]
+    fn test_genomic_interval() {
+        let a = GenomicInterval::new(0., 10.).unwrap();
+        let b = GenomicInterval::new(10., 20.).unwrap();
+        assert!(!a.overlaps(&b));
+        assert!(a.intersection(&b).is_none());
+        assert!(GenomicInterval::new(0., 20.).unwrap().contains_interval(&a));
+        assert!(!a.contains_interval(&GenomicInterval::new(5., 15.).unwrap()));
+        assert!(GenomicInterval::new(-1., 1.).is_err());
+        assert!(GenomicInterval::new(0., f64::INFINITY).is_err());
+        assert!(GenomicInterval::new(f64::NAN, 1.).is_err());
+        let (left, right) = a.into();
+        assert_eq!((left, right), (0.0.into(), 10.0.into()));
+    }
+
+    #[test]
+    fn test_time_with_units() {
+        let a = TimeWithUnits::new(2., Some("generations"));
+        let unknown = TimeWithUnits::new(1., Some("unknown"));
+        let unset = TimeWithUnits::new(1., None);
+        assert_eq!(a.try_add(unknown).unwrap().units(), Some("generations"));
+        assert_eq!(unset.try_add(a).unwrap().units(), Some("generations"));
+        assert_eq!(unset.try_add(unknown).unwrap().units(), None);
+        assert_eq!(a.to_string(), "2 generations");
+        assert_eq!(unset.to_string(), "1 (unknown units)");
+        match a.try_add(TimeWithUnits::new(1., Some("years"))) {
+            Err(crate::TskitError::TimeUnitsMismatch { left, right }) => {
+                assert_eq!(left, "generations");
+                assert_eq!(right, "years");
+            }
+            _ => panic!("expected TimeUnitsMismatch"),
+        }
+    }
 }
@@ -1,3 +1,218 @@
 pub(crate) fn partial_cmp_equal<T: PartialOrd>(lhs: &T, rhs: &T) -> bool {
     matches!(lhs.partial_cmp(rhs), Some(std::cmp::Ordering::Equal))
 }
+
+/// View `length` values starting at `data` as a slice
+/// borrowed for as long as `owner`.
+///
+/// Returns an empty slice if `length` is zero or `data` is null,
+/// as the C API does not allocate the columns of empty tables.
+///
+/// This is usually called through the `table_column!` macro.
+///
+/// # Safety
+///
+/// Unless the slice is empty, `data` must point to `length`
+/// initialized values that belong to `owner` and are not
+/// modified while the slice is borrowed.
+pub(crate) unsafe fn column_slice<O: ?Sized, T>(
+    _owner: &O,
+    data: *const T,
+    length: crate::tsk_size_t,
+) -> &[T] {
+    if length == 0 || data.is_null() {
+        &[]
+    } else {
+        std::slice::from_raw_parts(data, usize::try_from(length).unwrap())
+    }
+}
+
+/// Number of bytes occupied by `length` elements of type `T`.
+pub(crate) fn column_nbytes<T>(length: crate::tsk_size_t) -> usize {
+    std::mem::size_of::<T>() * usize::try_from(length).unwrap()
+}
+
+/// Number of bytes occupied by a ragged column of `length` elements
+/// of type `T` plus its `num_rows + 1` offsets.
+pub(crate) fn ragged_column_nbytes<T>(
+    length: crate::tsk_size_t,
+    num_rows: crate::tsk_size_t,
+) -> usize {
+    column_nbytes::<T>(length) + column_nbytes::<crate::tsk_size_t>(num_rows + 1)
+}
+
+// Not a method of ProvenanceTable so that TableCollection
+// can use it when the provenance feature is not enabled.
+pub(crate) fn provenance_table_nbytes(table: &crate::bindings::tsk_provenance_table_t) -> usize {
+    ragged_column_nbytes::<u8>(table.timestamp_length, table.num_rows)
+        + ragged_column_nbytes::<u8>(table.record_length, table.num_rows)
+}
+
+/// Number of rows described by a set of column lengths,
+/// which must all be equal.
+pub(crate) fn num_rows_from_column_lengths(
+    lengths: &[usize],
+) -> Result<crate::tsk_size_t, crate::TskitError> {
+    let num_rows = lengths.first().copied().unwrap_or(0);
+    if lengths.iter().any(|&l| l != num_rows) {
+        return Err(crate::TskitError::ValueError {
+            got: format!("columns of lengths {:?}", lengths),
+            expected: "columns of equal length".to_string(),
+        });
+    }
+    Ok(crate::SizeType::from(num_rows).into())
+}
+
+/// Flatten per-row data into the data and offset
+/// arrays of a ragged column.
+pub(crate) fn flatten_ragged_column<'r, T: Copy + 'r>(
+    rows: impl Iterator<Item = &'r [T]>,
+) -> (Vec<T>, Vec<crate::tsk_size_t>) {
+    let mut data = vec![];
+    let mut offsets: Vec<crate::tsk_size_t> = vec![0];
+    for row in rows {
+        data.extend_from_slice(row);
+        offsets.push(crate::SizeType::from(data.len()).into());
+    }
+    (data, offsets)
+}
+
+/// Displays optional ragged row data, such as
+/// individual locations, as a list.
+pub(crate) struct DisplayRagged<'a, T>(pub &'a Option<Vec<T>>);
+
+impl<T: std::fmt::Display> std::fmt::Display for DisplayRagged<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.0 {
+            None => write!(f, "None"),
+            Some(values) => {
+                write!(f, "[")?;
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", v)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// Displays optional ancestral/derived states as (lossy) text.
+pub(crate) struct DisplayState<'a>(pub &'a Option<Vec<u8>>);
+
+impl std::fmt::Display for DisplayState<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.0 {
+            None => write!(f, "None"),
+            Some(state) => write!(f, "{:?}", String::from_utf8_lossy(state)),
+        }
+    }
+}
+
+/// Displays optional row metadata by its size,
+/// as it is only meaningful once decoded.
+pub(crate) struct DisplayMetadata<'a>(pub &'a Option<Vec<u8>>);
+
+impl std::fmt::Display for DisplayMetadata<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.0 {
+            None => write!(f, "None"),
+            Some(md) => write!(f, "{} bytes", md.len()),
+        }
+    }
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard (padded) base64 encoding.
+pub(crate) fn base64_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(4 * bytes.len().div_ceil(3));
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(char::from(
+                    BASE64_ALPHABET[((n >> (18 - 6 * i)) & 63) as usize],
+                ));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Decode standard (padded) base64.
+/// Returns `None` if the input is not valid base64.
+pub(crate) fn base64_decode(encoded: &str) -> Option<Vec<u8>> {
+    let encoded = encoded.as_bytes();
+    if !encoded.len().is_multiple_of(4) {
+        return None;
+    }
+    let mut decoded = Vec::with_capacity(3 * encoded.len() / 4);
+    for (c, chunk) in encoded.chunks(4).enumerate() {
+        let last = c + 1 == encoded.len() / 4;
+        let padding = chunk.iter().rev().take_while(|&&b| b == b'=').count();
+        if padding > 2 || (padding > 0 && !last) {
+            return None;
+        }
+        let mut n = 0_u32;
+        for &b in &chunk[..4 - padding] {
+            let value = BASE64_ALPHABET.iter().position(|&a| a == b)? as u32;
+            n = (n << 6) | value;
+        }
+        n <<= 6 * padding;
+        decoded.extend_from_slice(&n.to_be_bytes()[1..4 - padding]);
+    }
+    Some(decoded)
+}
+
+/// A small, seedable pseudo-random number generator (SplitMix64).
+///
+/// Used where the crate needs reproducible randomness
+/// without depending on an external crate.
+pub(crate) struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `[0, n)`, using rejection sampling to avoid bias.
+    pub(crate) fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0);
+        let zone = u64::MAX - (u64::MAX % n);
+        loop {
+            let x = self.next_u64();
+            if x < zone {
+                return x % n;
+            }
+        }
+    }
+
+    /// Move a uniformly-chosen subset of `n` elements to
+    /// the front of `values` via a partial Fisher-Yates shuffle.
+    pub(crate) fn partial_shuffle<T>(&mut self, values: &mut [T], n: usize) {
+        for i in 0..n.min(values.len()) {
+            let remaining = (values.len() - i) as u64;
+            let j = i + self.below(remaining) as usize;
+            values.swap(i, j);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use tskit::{NodeFlags, RawFlags, SimplificationOptions};
+use tskit::prelude::*;
 
 fn clip_invalid_flags() {
     // This value contains bits set to 1
@@ -23,10 +23,16 @@ fn clip_invalid_flags() {
 fn example_node_flags() {
     let f: RawFlags = 1000000;
 
-    // Node flags allow user-specified values,
-    // so ::from accepts input as-is.
-    let mut node_flags = NodeFlags::from(f);
+    // Node flags allow user-specified values in bits 16 and higher.
+    // This value also sets bits in the lower 16 bits, which are
+    // reserved for tskit, so ::from unsets those.
+    let node_flags = NodeFlags::from(f);
+    assert_eq!(node_flags.bits(), f & NodeFlags::USER_BITS);
+    assert!(node_flags.is_valid());
 
+    // Bits 16 and higher are free for user-defined values.
+    let f: RawFlags = 1 << 16 | 1 << 20;
+    let mut node_flags = NodeFlags::from(f);
     assert_eq!(node_flags.bits(), f);
     assert!(node_flags.is_valid());
 
@@ -2,8 +2,6 @@
 
 mod experimental_features {
 
-    use tskit::TableAccess;
-
     // Goal: proc macro this up
     // Design notes for future:
     // * We can probably drop this trait.
@@ -133,7 +131,7 @@ mod experimental_features {
             &self,
             row: M,
         ) -> Result<Option<Self::Item>, tskit::TskitError> {
-            self.mutations().metadata::<Self::Item>(row.into())
+            self.mutations().metadata::<Self::Item>(row)
         }
     }
 
@@ -143,8 +141,7 @@ mod experimental_features {
             &self,
             row: impl Into<tskit::MutationId>,
         ) -> Result<Option<MutationMetadataType>, tskit::TskitError> {
-            self.mutations()
-                .metadata::<MutationMetadataType>(row.into())
+            self.mutations().metadata::<MutationMetadataType>(row)
         }
     }
 
@@ -154,8 +151,7 @@ mod experimental_features {
             &self,
             row: impl Into<tskit::IndividualId>,
         ) -> Result<Option<IndividualMetadataType>, tskit::TskitError> {
-            self.individuals()
-                .metadata::<IndividualMetadataType>(row.into())
+            self.individuals().metadata::<IndividualMetadataType>(row)
         }
     }
 
@@ -189,7 +185,7 @@ mod experimental_features {
         // current API requires
         let decoded = tables
             .mutations()
-            .metadata::<MutationMetadataType>(0.into())
+            .metadata::<MutationMetadataType>(0)
             .unwrap()
             .unwrap();
         assert_eq!(decoded.effect_size, 0.10);
@@ -225,8 +221,6 @@ mod experimental_features_refined {
     // * What kind of proc-macro can we use to implement that,
     //   again "easily"?
 
-    use tskit::TableAccess;
-
     trait AsTableCollection {
         fn as_tables(&self) -> &tskit::TableCollection;
     }
@@ -242,9 +236,7 @@ mod experimental_features_refined {
             &self,
             row: M,
         ) -> Result<Option<Self::Item>, tskit::TskitError> {
-            self.as_tables()
-                .mutations()
-                .metadata::<Self::Item>(row.into())
+            self.as_tables().mutations().metadata::<Self::Item>(row)
         }
     }
 
@@ -289,7 +281,7 @@ mod experimental_features_refined {
         let decoded = tables
             .0
             .mutations()
-            .metadata::<MutationMetadataType>(0.into())
+            .metadata::<MutationMetadataType>(0)
             .unwrap()
             .unwrap();
         assert_eq!(decoded.effect_size, 0.10);
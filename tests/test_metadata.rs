@@ -196,3 +196,93 @@ build_metadata_registration_test!(
     GenericMetadata,
     PopulationMetadata
 );
+
+#[cfg(feature = "derive")]
+build_metadata_registration_test!(
+    test_struct_metadata_registration,
+    test_register_mutation_metadata,
+    "struct",
+    GenericMetadata,
+    MutationMetadata
+);
+
+#[cfg(feature = "derive")]
+build_metadata_registration_test!(
+    test_struct_node_registration,
+    test_register_node_metadata,
+    "struct",
+    GenericMetadata,
+    NodeMetadata
+);
+
+#[cfg(feature = "derive")]
+build_metadata_registration_test!(
+    test_struct_site_registration,
+    test_register_site_metadata,
+    "struct",
+    GenericMetadata,
+    SiteMetadata
+);
+
+#[cfg(feature = "derive")]
+build_metadata_registration_test!(
+    test_struct_edge_registration,
+    test_register_edge_metadata,
+    "struct",
+    GenericMetadata,
+    EdgeMetadata
+);
+
+#[cfg(feature = "derive")]
+build_metadata_registration_test!(
+    test_struct_individual_registration,
+    test_register_individual_metadata,
+    "struct",
+    GenericMetadata,
+    IndividualMetadata
+);
+
+#[cfg(feature = "derive")]
+build_metadata_registration_test!(
+    test_struct_migration_registration,
+    test_register_migration_metadata,
+    "struct",
+    GenericMetadata,
+    MigrationMetadata
+);
+
+#[cfg(feature = "derive")]
+build_metadata_registration_test!(
+    test_struct_population_registration,
+    test_register_population_metadata,
+    "struct",
+    GenericMetadata,
+    PopulationMetadata
+);
+
+#[cfg(feature = "derive")]
+build_metadata_registration_test!(
+    test_json_tree_sequence_registration,
+    test_register_tree_sequence_metadata,
+    "serde_json",
+    GenericMetadata,
+    TreeSequenceMetadata
+);
+
+#[cfg(feature = "derive")]
+build_metadata_registration_test!(
+    test_bincode_tree_sequence_registration,
+    test_register_tree_sequence_metadata,
+    "bincode",
+    GenericMetadata,
+    TreeSequenceMetadata
+);
+
+#[cfg(feature = "derive")]
+build_metadata_registration_test!(
+    test_struct_tree_sequence_registration,
+    test_register_tree_sequence_metadata,
+    "struct",
+    GenericMetadata,
+    TreeSequenceMetadata
+);
@@ -50,6 +50,20 @@ fn impl_serde_bincode_roundtrip(name: &syn::Ident) -> TokenStream {
     gen.into()
 }
 
+fn impl_struct_roundtrip(name: &syn::Ident) -> TokenStream {
+    let gen = quote::quote!(
+        impl ::tskit::metadata::MetadataRoundtrip for #name {
+            fn encode(&self) -> Result<Vec<u8>, ::tskit::metadata::MetadataError> {
+                ::tskit::metadata::struct_encode(self)
+            }
+            fn decode(md: &[u8]) -> Result<Self, ::tskit::metadata::MetadataError> {
+                ::tskit::metadata::struct_decode(md)
+            }
+        }
+    );
+    gen.into()
+}
+
 fn impl_metadata_roundtrip_macro(ast: &syn::DeriveInput) -> Result<TokenStream, syn::Error> {
     let name = &ast.ident;
     let attrs = &ast.attrs;
@@ -63,6 +77,8 @@ fn impl_metadata_roundtrip_macro(ast: &syn::DeriveInput) -> Result<TokenStream,
                 return Ok(impl_serde_json_roundtrip(name));
             } else if &serializer == "bincode" {
                 return Ok(impl_serde_bincode_roundtrip(name));
+            } else if &serializer == "struct" {
+                return Ok(impl_struct_roundtrip(name));
             } else {
                 proc_macro_error::abort!(serializer, "is not a supported protocol.");
             }
@@ -103,3 +119,15 @@ make_derive_metadata_tag!(population_metadata_derive, PopulationMetadata);
 make_derive_metadata_tag!(node_metadata_derive, NodeMetadata);
 make_derive_metadata_tag!(edge_metadata_derive, EdgeMetadata);
 make_derive_metadata_tag!(migration_metadata_derive, MigrationMetadata);
+make_derive_metadata_tag!(tree_sequence_metadata_derive, TreeSequenceMetadata);
+
+#[proc_macro_error::proc_macro_error]
+#[proc_macro_derive(ProvenanceMetadata, attributes(serializer))]
+/// Provenance tables have no metadata column, so deriving
+/// this is a compile-time error.
+pub fn provenance_metadata_derive(_input: TokenStream) -> TokenStream {
+    proc_macro_error::abort_call_site!(
+        "provenance tables do not support metadata";
+        help = "store the data in the provenance record instead"
+    )
+}